@@ -95,7 +95,7 @@ impl<T> Handle<T> {
     }
 
     /// Convert a `usize` index into a `Handle<T>`.
-    fn from_usize(index: usize) -> Self {
+    pub(crate) fn from_usize(index: usize) -> Self {
         use std::convert::TryFrom;
 
         let handle_index = u32::try_from(index + 1)
@@ -125,6 +125,28 @@ pub struct Range<T> {
     marker: PhantomData<T>,
 }
 
+impl<T> Range<T> {
+    /// Return the first and last handles included in this range, if any.
+    pub(crate) fn first_and_last(&self) -> Option<(Handle<T>, Handle<T>)> {
+        if self.inner.start < self.inner.end {
+            Some((
+                Handle::from_usize(self.inner.start as usize),
+                Handle::from_usize(self.inner.end as usize - 1),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Construct a range including the handles from `first` to `last`.
+    pub(crate) fn new_from_bounds(first: Handle<T>, last: Handle<T>) -> Self {
+        Range {
+            inner: first.index() as u32..last.index() as u32 + 1,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<T> Clone for Range<T> {
     fn clone(&self) -> Self {
         Range {
@@ -155,12 +177,91 @@ impl<T> Iterator for Range<T> {
     }
 }
 
+/// A mapping from the handles of an arena to the handles of the same items
+/// in another arena, or in the same arena after some items were removed.
+///
+/// Passes that remove, reorder or merge the items of an [`Arena`] or
+/// [`UniqueArena`] use this to adjust the handles referring to them.
+pub struct HandleMap<T> {
+    new_handles: Vec<Option<Handle<T>>>,
+}
+
+impl<T> HandleMap<T> {
+    /// Create a map of an arena of `len` items, none of which is mapped yet.
+    pub fn new(len: usize) -> Self {
+        HandleMap {
+            new_handles: vec![None; len],
+        }
+    }
+
+    /// Create the map of an arena of `len` items from which the ones that
+    /// `kept` returns `false` for are removed, and the others shifted down
+    /// over them, like [`Arena::retain_mut`] does.
+    pub fn from_kept<F: FnMut(Handle<T>) -> bool>(len: usize, mut kept: F) -> Self {
+        let mut next_index = 0;
+        let new_handles = (0..len)
+            .map(|index| {
+                if kept(Handle::from_usize(index)) {
+                    let handle = Handle::from_usize(next_index);
+                    next_index += 1;
+                    Some(handle)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        HandleMap { new_handles }
+    }
+
+    /// Map `old` to `new`.
+    pub fn insert(&mut self, old: Handle<T>, new: Handle<T>) {
+        self.new_handles[old.index()] = Some(new);
+    }
+
+    /// Return the handle that `old` is mapped to, if any.
+    pub fn get(&self, old: Handle<T>) -> Option<Handle<T>> {
+        self.new_handles.get(old.index()).cloned().flatten()
+    }
+
+    /// Return `true` if `old` is mapped.
+    pub fn contains(&self, old: Handle<T>) -> bool {
+        self.get(old).is_some()
+    }
+
+    /// Replace `handle` with the handle it is mapped to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` isn't mapped.
+    pub fn adjust(&self, handle: &mut Handle<T>) {
+        *handle = self.get(*handle).expect("handle to a removed item");
+    }
+
+    /// Like [`adjust`](HandleMap::adjust), if `handle` is `Some`.
+    pub fn adjust_option(&self, handle: &mut Option<Handle<T>>) {
+        if let Some(ref mut handle) = *handle {
+            self.adjust(handle);
+        }
+    }
+
+    /// Adjust a range of handles, all of which must be mapped, and mapped
+    /// to a contiguous range in the same order.
+    pub fn adjust_range(&self, range: &mut Range<T>) {
+        if let Some((mut first, mut last)) = range.first_and_last() {
+            self.adjust(&mut first);
+            self.adjust(&mut last);
+            *range = Range::new_from_bounds(first, last);
+        }
+    }
+}
+
 /// An arena holding some kind of component (e.g., type, constant,
 /// instruction, etc.) that can be referenced.
 ///
 /// Adding new items to the arena produces a strongly-typed [`Handle`].
 /// The arena can be indexed using the given handle to obtain
 /// a reference to the stored item.
+#[derive(Clone)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "serialize", serde(transparent))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -297,6 +398,32 @@ impl<T> Arena<T> {
         self.data.clear()
     }
 
+    /// Keep only the elements for which `predicate` returns `true`, along
+    /// with their spans, and preserve their order.
+    ///
+    /// `predicate` receives the handle of each element before the removal,
+    /// and may modify the elements it keeps. Handles to the kept elements
+    /// are shifted down over the removed ones, so the caller is responsible
+    /// for adjusting them, for example with a [`HandleMap`].
+    pub fn retain_mut<P>(&mut self, mut predicate: P)
+    where
+        P: FnMut(Handle<T>, &mut T) -> bool,
+    {
+        let data = std::mem::take(&mut self.data);
+        #[cfg(feature = "span")]
+        let span_info = std::mem::take(&mut self.span_info);
+
+        for (index, mut value) in data.into_iter().enumerate() {
+            let handle = unsafe { Handle::from_usize_unchecked(index) };
+            if predicate(handle, &mut value) {
+                self.data.push(value);
+                #[cfg(feature = "span")]
+                self.span_info
+                    .push(span_info.get(index).cloned().unwrap_or_default());
+            }
+        }
+    }
+
     pub fn get_span(&self, handle: Handle<T>) -> Span {
         #[cfg(feature = "span")]
         {
@@ -395,6 +522,21 @@ mod tests {
         assert!(t1 != t2);
         assert!(arena[t1] != arena[t2]);
     }
+
+    #[test]
+    fn retain_and_map() {
+        let mut arena: Arena<u8> = Arena::new();
+        let handles: Vec<_> = (0..4).map(|i| arena.append(i, Span::default())).collect();
+        let map = HandleMap::from_kept(arena.len(), |handle| arena[handle] % 2 == 1);
+        arena.retain_mut(|handle, value| {
+            *value *= 10;
+            map.contains(handle)
+        });
+        assert!(!map.contains(handles[0]));
+        let mut handle = handles[3];
+        map.adjust(&mut handle);
+        assert_eq!(arena[handle], 30);
+    }
 }
 
 /// An arena whose elements are guaranteed to be unique.
@@ -407,10 +549,11 @@ mod tests {
 /// The element type must implement `Eq` and `Hash`. Insertions of equivalent
 /// elements, according to `Eq`, all return the same `Handle`.
 ///
-/// Once inserted, elements may not be mutated.
+/// Once inserted, elements may only be mutated by [`UniqueArena::retain_mut`].
 ///
 /// `UniqueArena` is similar to [`Arena`]: If `Arena` is vector-like,
 /// `UniqueArena` is `HashSet`-like.
+#[derive(Clone)]
 pub struct UniqueArena<T> {
     set: IndexSet<T>,
 
@@ -451,6 +594,19 @@ impl<T> UniqueArena<T> {
         self.span_info.clear();
     }
 
+    /// Consume the arena, returning its elements in order along with their
+    /// spans.
+    pub(crate) fn into_iter_with_spans(self) -> impl Iterator<Item = (T, Span)> {
+        #[cfg(feature = "span")]
+        let span_info = self.span_info;
+        #[cfg(not(feature = "span"))]
+        let span_info: Vec<Span> = Vec::new();
+        self.set
+            .into_iter()
+            .enumerate()
+            .map(move |(index, value)| (value, span_info.get(index).cloned().unwrap_or_default()))
+    }
+
     /// Return the span associated with `handle`.
     ///
     /// If a value has been inserted multiple times, the span returned is the
@@ -518,6 +674,29 @@ impl<T: Eq + hash::Hash> UniqueArena<T> {
         Handle::from_usize(index)
     }
 
+    /// Keep only the elements for which `predicate` returns `true`, along
+    /// with their spans, and preserve their order.
+    ///
+    /// Like [`Arena::retain_mut`], `predicate` receives the handle of each
+    /// element before the removal, and may modify the elements it keeps.
+    /// The kept elements must remain distinct once modified.
+    pub fn retain_mut<P>(&mut self, mut predicate: P)
+    where
+        P: FnMut(Handle<T>, &mut T) -> bool,
+    {
+        let old = std::mem::take(self);
+        for (index, (mut value, span)) in old.into_iter_with_spans().enumerate() {
+            if predicate(Handle::from_usize(index), &mut value) {
+                let (_, added) = self.set.insert_full(value);
+                debug_assert!(added, "kept elements are no longer distinct");
+                #[cfg(feature = "span")]
+                self.span_info.push(span);
+                #[cfg(not(feature = "span"))]
+                let _ = span;
+            }
+        }
+    }
+
     /// Return this arena's handle for `value`, if present.
     ///
     /// If this arena already contains an element equal to `value`,
@@ -110,6 +110,23 @@ impl StatementGraph {
                     self.dependencies.push((id, value, "value"));
                     "ImageStore"
                 }
+                S::ImageAtomic {
+                    image,
+                    coordinate,
+                    array_index,
+                    fun: _,
+                    value,
+                    result,
+                } => {
+                    self.emits.push((id, result));
+                    self.dependencies.push((id, image, "image"));
+                    self.dependencies.push((id, coordinate, "coordinate"));
+                    if let Some(expr) = array_index {
+                        self.dependencies.push((id, expr, "array_index"));
+                    }
+                    self.dependencies.push((id, value, "value"));
+                    "ImageAtomic"
+                }
                 S::Call {
                     function,
                     ref arguments,
@@ -138,6 +155,88 @@ impl StatementGraph {
                     }
                     "Atomic"
                 }
+                S::SubgroupBallot { result, predicate } => {
+                    if let Some(predicate) = predicate {
+                        self.dependencies.push((id, predicate, "predicate"));
+                    }
+                    self.emits.push((id, result));
+                    "SubgroupBallot"
+                }
+                S::SubgroupElect { result } => {
+                    self.emits.push((id, result));
+                    "SubgroupElect"
+                }
+                S::SubgroupGather {
+                    mode,
+                    argument,
+                    result,
+                } => {
+                    match mode {
+                        crate::GatherMode::BroadcastFirst => {}
+                        crate::GatherMode::Broadcast(index)
+                        | crate::GatherMode::Shuffle(index)
+                        | crate::GatherMode::ShuffleDown(index)
+                        | crate::GatherMode::ShuffleUp(index)
+                        | crate::GatherMode::ShuffleXor(index) => {
+                            self.dependencies.push((id, index, "index"))
+                        }
+                    }
+                    self.dependencies.push((id, argument, "arg"));
+                    self.emits.push((id, result));
+                    "SubgroupGather"
+                }
+                S::SubgroupCollectiveOperation {
+                    op: _,
+                    collective_op: _,
+                    argument,
+                    result,
+                } => {
+                    self.dependencies.push((id, argument, "arg"));
+                    self.emits.push((id, result));
+                    "SubgroupCollectiveOperation"
+                }
+                S::RayQuery { query, ref fun } => {
+                    self.dependencies.push((id, query, "query"));
+                    match *fun {
+                        crate::RayQueryFunction::Initialize {
+                            acceleration_structure,
+                            descriptor,
+                        } => {
+                            self.dependencies.push((
+                                id,
+                                acceleration_structure,
+                                "acceleration_structure",
+                            ));
+                            self.dependencies.push((id, descriptor, "descriptor"));
+                            "RayQueryInitialize"
+                        }
+                        crate::RayQueryFunction::Proceed { result } => {
+                            self.emits.push((id, result));
+                            "RayQueryProceed"
+                        }
+                    }
+                }
+                S::MeshFunction(ref fun) => match *fun {
+                    crate::MeshFunction::SetMeshOutputs {
+                        vertex_count,
+                        primitive_count,
+                    } => {
+                        self.dependencies.push((id, vertex_count, "vertex_count"));
+                        self.dependencies
+                            .push((id, primitive_count, "primitive_count"));
+                        "SetMeshOutputs"
+                    }
+                    crate::MeshFunction::SetVertex { index, value } => {
+                        self.dependencies.push((id, index, "index"));
+                        self.dependencies.push((id, value, "value"));
+                        "SetVertex"
+                    }
+                    crate::MeshFunction::SetPrimitive { index, value } => {
+                        self.dependencies.push((id, index, "index"));
+                        self.dependencies.push((id, value, "value"));
+                        "SetPrimitive"
+                    }
+                },
             };
         }
         root
@@ -356,10 +455,21 @@ fn write_fun(
             }
             E::CallResult(_function) => ("CallResult".into(), 4),
             E::AtomicResult { .. } => ("AtomicResult".into(), 4),
+            E::SubgroupBallotResult => ("SubgroupBallotResult".into(), 4),
+            E::SubgroupOperationResult { .. } => ("SubgroupOperationResult".into(), 4),
+            E::RayQueryProceedResult => ("RayQueryProceedResult".into(), 4),
+            E::RayQueryGetCommittedIntersection { query, .. } => {
+                edges.insert("", query);
+                ("RayQueryGetCommittedIntersection".into(), 4)
+            }
             E::ArrayLength(expr) => {
                 edges.insert("", expr);
                 ("ArrayLength".into(), 7)
             }
+            E::NonUniform(value) => {
+                edges.insert("", value);
+                ("NonUniform".into(), 7)
+            }
         };
 
         // give uniform expressions an outline
@@ -3,7 +3,7 @@ use crate::{
     Binding, Bytes, Expression, Handle, ImageClass, ImageDimension, Interpolation, MathFunction,
     Sampling, ScalarKind, ShaderStage, StorageClass, StorageFormat, Type, TypeInner,
 };
-use std::fmt::Write;
+use std::{fmt::Write, iter};
 
 bitflags::bitflags! {
     /// Structure used to encode a set of additions to glsl that aren't supported by all versions
@@ -35,6 +35,18 @@ bitflags::bitflags! {
         const MULTI_VIEW = 1 << 17;
         /// Adds support for fused multiply-add
         const FMA = 1 << 18;
+        /// 2 byte floats
+        const FLOAT16 = 1 << 19;
+        /// 8 byte integers
+        const INT64_TYPE = 1 << 20;
+        /// Atomic operations on 8 byte integers
+        const INT64_ATOMICS = 1 << 21;
+        /// Subgroup operations and built-ins
+        const SUBGROUP_OPERATIONS = 1 << 22;
+        /// Fragment outputs with a blend source index
+        const DUAL_SOURCE_BLENDING = 1 << 23;
+        /// Atomic operations on storage images
+        const IMAGE_ATOMICS = 1 << 24;
     }
 }
 
@@ -101,6 +113,12 @@ impl FeaturesManager {
         check_feature!(DYNAMIC_ARRAY_SIZE, 430, 310);
         check_feature!(MULTI_VIEW, 140, 310);
         check_feature!(FMA, 400, 310);
+        check_feature!(FLOAT16, 450, 310);
+        check_feature!(INT64_TYPE, 400);
+        check_feature!(INT64_ATOMICS, 400);
+        check_feature!(SUBGROUP_OPERATIONS, 430, 310);
+        check_feature!(DUAL_SOURCE_BLENDING, 330, 300);
+        check_feature!(IMAGE_ATOMICS, 420, 310);
 
         // Return an error if there are missing features
         if missing.is_empty() {
@@ -209,6 +227,52 @@ impl FeaturesManager {
             writeln!(out, "#extension GL_EXT_gpu_shader5 : require")?;
         }
 
+        if self.0.contains(Features::FLOAT16) {
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_shader_explicit_arithmetic_types.txt
+            writeln!(
+                out,
+                "#extension GL_EXT_shader_explicit_arithmetic_types_float16 : require"
+            )?;
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_shader_16bit_storage.txt
+            writeln!(out, "#extension GL_EXT_shader_16bit_storage : require")?;
+        }
+
+        if self.0.contains(Features::INT64_TYPE) {
+            // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_gpu_shader_int64.txt
+            writeln!(out, "#extension GL_ARB_gpu_shader_int64 : require")?;
+        }
+
+        if self.0.contains(Features::INT64_ATOMICS) {
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_shader_atomic_int64.txt
+            writeln!(out, "#extension GL_EXT_shader_atomic_int64 : require")?;
+        }
+
+        if self.0.contains(Features::IMAGE_ATOMICS) && version < Version::Embedded(320) {
+            // https://www.khronos.org/registry/OpenGL/extensions/OES/OES_shader_image_atomic.txt
+            writeln!(out, "#extension GL_OES_shader_image_atomic : require")?;
+        }
+
+        if self.0.contains(Features::DUAL_SOURCE_BLENDING) && version.is_es() {
+            // https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_blend_func_extended.txt
+            writeln!(out, "#extension GL_EXT_blend_func_extended : require")?;
+        }
+
+        if self.0.contains(Features::SUBGROUP_OPERATIONS) {
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/khr/GL_KHR_shader_subgroup.txt
+            writeln!(out, "#extension GL_KHR_shader_subgroup_basic : require")?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_vote : require")?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_ballot : require")?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_shuffle : require")?;
+            writeln!(
+                out,
+                "#extension GL_KHR_shader_subgroup_shuffle_relative : require"
+            )?;
+            writeln!(
+                out,
+                "#extension GL_KHR_shader_subgroup_arithmetic : require"
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -251,6 +315,12 @@ impl<'a, W> Writer<'a, W> {
                 TypeInner::Matrix { width, .. } => {
                     self.scalar_required_features(ScalarKind::Float, width)
                 }
+                TypeInner::Atomic { kind, width } => {
+                    self.scalar_required_features(kind, width);
+                    if width == 8 {
+                        self.features.request(Features::INT64_ATOMICS);
+                    }
+                }
                 TypeInner::Array { base, size, .. } => {
                     if let TypeInner::Array { .. } = self.module.types[base].inner {
                         self.features.request(Features::ARRAY_OF_ARRAYS)
@@ -362,6 +432,10 @@ impl<'a, W> Writer<'a, W> {
             }
         }
 
+        if self.uses_ray_queries() {
+            return Err(Error::Custom("ray queries are not supported".to_string()));
+        }
+
         if self.options.version.supports_fma_function() {
             let has_fma = self
                 .module
@@ -383,13 +457,85 @@ impl<'a, W> Writer<'a, W> {
             }
         }
 
+        let uses_subgroup = self.any_statement(&|statement| match *statement {
+            crate::Statement::SubgroupBallot { .. }
+            | crate::Statement::SubgroupElect { .. }
+            | crate::Statement::SubgroupGather { .. }
+            | crate::Statement::SubgroupCollectiveOperation { .. } => true,
+            crate::Statement::Barrier(flags) => flags.contains(crate::Barrier::SUB_GROUP),
+            _ => false,
+        });
+        if uses_subgroup {
+            self.features.request(Features::SUBGROUP_OPERATIONS);
+        }
+
+        let uses_image_atomics = self
+            .any_statement(&|statement| matches!(*statement, crate::Statement::ImageAtomic { .. }));
+        if uses_image_atomics {
+            self.features.request(Features::IMAGE_ATOMICS);
+        }
+
         self.features.check_availability(self.options.version)
     }
 
+    /// Helper method that checks if any statement of any function satisfies `predicate`
+    fn any_statement(&self, predicate: &impl Fn(&crate::Statement) -> bool) -> bool {
+        self.module
+            .functions
+            .iter()
+            .map(|(_, f)| f)
+            .chain(self.module.entry_points.iter().map(|e| &e.function))
+            .any(|f| block_any_statement(&f.body, predicate))
+    }
+
+    /// Helper method that checks if the global variables or the functions
+    /// written for the current entry point use ray queries
+    fn uses_ray_queries(&self) -> bool {
+        let ep_info = self.info.get_entry_point(self.entry_point_idx as usize);
+        let types = &self.module.types;
+
+        let uses_acceleration_structure =
+            self.module.global_variables.iter().any(|(handle, global)| {
+                !ep_info[handle].is_empty()
+                    && matches!(types[global.ty].inner, TypeInner::AccelerationStructure)
+            });
+
+        // functions using globals the entry point doesn't are skipped
+        let uses_ray_query = self
+            .module
+            .functions
+            .iter()
+            .filter(|&(handle, _)| ep_info.dominates_global_use(&self.info[handle]))
+            .map(|(_, f)| f)
+            .chain(iter::once(&self.entry_point.function))
+            .any(|f| {
+                f.local_variables
+                    .iter()
+                    .any(|(_, local)| matches!(types[local.ty].inner, TypeInner::RayQuery))
+                    || f.expressions.iter().any(|(_, e)| {
+                        matches!(
+                            *e,
+                            Expression::RayQueryProceedResult
+                                | Expression::RayQueryGetCommittedIntersection { .. }
+                        )
+                    })
+                    || block_any_statement(&f.body, &|statement| {
+                        matches!(*statement, crate::Statement::RayQuery { .. })
+                    })
+            });
+
+        uses_acceleration_structure || uses_ray_query
+    }
+
     /// Helper method that checks the [`Features`](Features) needed by a scalar
     fn scalar_required_features(&mut self, kind: ScalarKind, width: Bytes) {
-        if kind == ScalarKind::Float && width == 8 {
-            self.features.request(Features::DOUBLE_TYPE);
+        match (kind, width) {
+            (ScalarKind::Float, 2) => self.features.request(Features::FLOAT16),
+            (ScalarKind::Float, 8) => self.features.request(Features::DOUBLE_TYPE),
+            (ScalarKind::Sint, 8) | (ScalarKind::Uint, 8) => {
+                self.features.request(Features::INT64_TYPE)
+            }
+            _ => {}
         }
     }
 
@@ -416,12 +562,16 @@ impl<'a, W> Writer<'a, W> {
                             crate::BuiltIn::ViewIndex => {
                                 self.features.request(Features::MULTI_VIEW)
                             }
+                            crate::BuiltIn::SubgroupSize | crate::BuiltIn::SubgroupInvocationId => {
+                                self.features.request(Features::SUBGROUP_OPERATIONS)
+                            }
                             _ => {}
                         },
                         Binding::Location {
                             location: _,
                             interpolation,
                             sampling,
+                            blend_src,
                         } => {
                             if interpolation == Some(Interpolation::Linear) {
                                 self.features.request(Features::NOPERSPECTIVE_QUALIFIER);
@@ -429,6 +579,9 @@ impl<'a, W> Writer<'a, W> {
                             if sampling == Some(Sampling::Sample) {
                                 self.features.request(Features::SAMPLE_QUALIFIER);
                             }
+                            if blend_src.is_some() {
+                                self.features.request(Features::DUAL_SOURCE_BLENDING);
+                            }
                         }
                     }
                 }
@@ -436,3 +589,37 @@ impl<'a, W> Writer<'a, W> {
         }
     }
 }
+
+/// Helper function that checks if a block (or any of its nested blocks) contains a statement
+/// satisfying `predicate`
+fn block_any_statement(
+    block: &crate::Block,
+    predicate: &impl Fn(&crate::Statement) -> bool,
+) -> bool {
+    use crate::Statement as S;
+
+    block.iter().any(|statement| {
+        predicate(statement)
+            || match *statement {
+                S::Block(ref block) => block_any_statement(block, predicate),
+                S::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    block_any_statement(accept, predicate) || block_any_statement(reject, predicate)
+                }
+                S::Switch { ref cases, .. } => cases
+                    .iter()
+                    .any(|case| block_any_statement(&case.body, predicate)),
+                S::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    block_any_statement(body, predicate)
+                        || block_any_statement(continuing, predicate)
+                }
+                _ => false,
+            }
+    })
+}
@@ -78,7 +78,7 @@ impl crate::AtomicFunction {
             Self::Min => "Min",
             Self::Max => "Max",
             Self::Exchange { compare: None } => "Exchange",
-            Self::Exchange { compare: Some(_) } => "", //TODO
+            Self::Exchange { compare: Some(_) } => "CompSwap",
         }
     }
 }
@@ -297,7 +297,8 @@ impl IdGenerator {
 /// - Varyings with builtin bindings get the from [`glsl_built_in`](glsl_built_in).
 /// - Varyings with location bindings are named `_S_location_X` where `S` is a
 ///   prefix identifying which pipeline stage the varying connects, and `X` is
-///   the location.
+///   the location. Outputs with a blend source index `I` get an `_indexI`
+///   suffix.
 struct VaryingName<'a> {
     binding: &'a crate::Binding,
     stage: ShaderStage,
@@ -306,9 +307,15 @@ struct VaryingName<'a> {
 impl fmt::Display for VaryingName<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self.binding {
-            crate::Binding::Location { location, .. } => {
+            crate::Binding::Location {
+                location,
+                blend_src,
+                ..
+            } => {
                 let prefix = match (self.stage, self.output) {
-                    (ShaderStage::Compute, _) => unreachable!(),
+                    (ShaderStage::Compute, _) | (ShaderStage::Task, _) | (ShaderStage::Mesh, _) => {
+                        unreachable!()
+                    }
                     // pipeline to vertex
                     (ShaderStage::Vertex, false) => "p2vs",
                     // vertex to fragment
@@ -316,7 +323,11 @@ impl fmt::Display for VaryingName<'_> {
                     // fragment to pipeline
                     (ShaderStage::Fragment, true) => "fs2p",
                 };
-                write!(f, "_{}_location{}", prefix, location,)
+                write!(f, "_{}_location{}", prefix, location,)?;
+                match blend_src {
+                    Some(index) => write!(f, "_index{}", index),
+                    None => Ok(()),
+                }
             }
             crate::Binding::BuiltIn(built_in) => {
                 write!(f, "{}", glsl_built_in(built_in, self.output))
@@ -331,6 +342,8 @@ impl ShaderStage {
             ShaderStage::Compute => "cs",
             ShaderStage::Fragment => "fs",
             ShaderStage::Vertex => "vs",
+            ShaderStage::Task => "ts",
+            ShaderStage::Mesh => "ms",
         }
     }
 }
@@ -441,6 +454,11 @@ impl<'a, W: Write> Writer<'a, W> {
                 pipeline_options.shader_stage == ep.stage && pipeline_options.entry_point == ep.name
             })
             .ok_or(Error::EntryPointNotFound)?;
+        if let ShaderStage::Task | ShaderStage::Mesh = module.entry_points[ep_idx].stage {
+            return Err(Error::Custom(
+                "task and mesh shaders are not supported".to_string(),
+            ));
+        }
 
         // Generate a map with names required to write the module
         let mut names = crate::FastHashMap::default();
@@ -794,7 +812,10 @@ impl<'a, W: Write> Writer<'a, W> {
             TypeInner::Pointer { .. }
             | TypeInner::Struct { .. }
             | TypeInner::Image { .. }
-            | TypeInner::Sampler { .. } => {
+            | TypeInner::Sampler { .. }
+            | TypeInner::BindingArray { .. }
+            | TypeInner::AccelerationStructure
+            | TypeInner::RayQuery => {
                 return Err(Error::Custom(format!("Unable to write type {:?}", inner)))
             }
         }
@@ -1056,12 +1077,19 @@ impl<'a, W: Write> Writer<'a, W> {
                 }
             }
             _ => {
-                let (location, interpolation, sampling) = match binding {
+                let (location, interpolation, sampling, blend_src) = match binding {
                     Some(&crate::Binding::Location {
                         location,
                         interpolation,
                         sampling,
-                    }) => (location, interpolation, sampling),
+                        blend_src,
+                    }) => (location, interpolation, sampling, blend_src),
+                    Some(&crate::Binding::BuiltIn(
+                        built_in @ crate::BuiltIn::Position { invariant: true },
+                    )) if output => {
+                        writeln!(self.out, "invariant {};", glsl_built_in(built_in, output))?;
+                        return Ok(());
+                    }
                     _ => return Ok(()),
                 };
 
@@ -1079,7 +1107,14 @@ impl<'a, W: Write> Writer<'a, W> {
                 if self.options.version.supports_explicit_locations()
                     || !emit_interpolation_and_auxiliary
                 {
-                    write!(self.out, "layout(location = {}) ", location)?;
+                    match blend_src {
+                        Some(index) => write!(
+                            self.out,
+                            "layout(location = {}, index = {}) ",
+                            location, index
+                        )?,
+                        None => write!(self.out, "layout(location = {}) ", location)?,
+                    }
                 }
 
                 // Write the interpolation qualifier.
@@ -1116,6 +1151,7 @@ impl<'a, W: Write> Writer<'a, W> {
                         location,
                         interpolation: None,
                         sampling: None,
+                        blend_src,
                     },
                     stage: self.entry_point.stage,
                     output,
@@ -1359,19 +1395,22 @@ impl<'a, W: Write> Writer<'a, W> {
         use crate::ScalarValue as Sv;
 
         match self.module.constants[handle].inner {
-            crate::ConstantInner::Scalar {
-                width: _,
-                ref value,
-            } => match *value {
-                // Signed integers don't need anything special
+            crate::ConstantInner::Scalar { width, ref value } => match *value {
+                // Signed integers don't need anything special, except for the `l` suffix of
+                // 64-bit integers
+                Sv::Sint(int) if width == 8 => write!(self.out, "{}l", int)?,
                 Sv::Sint(int) => write!(self.out, "{}", int)?,
                 // Unsigned integers need a `u` at the end
                 //
                 // While `core` doesn't necessarily need it, it's allowed and since `es` needs it we
                 // always write it as the extra branch wouldn't have any benefit in readability
+                Sv::Uint(int) if width == 8 => write!(self.out, "{}ul", int)?,
                 Sv::Uint(int) => write!(self.out, "{}u", int)?,
                 // Floats are written using `Debug` instead of `Display` because it always appends the
                 // decimal part even it's zero which is needed for a valid glsl float constant
+                //
+                // Half precision floats need the `hf` suffix
+                Sv::Float(float) if width == 2 => write!(self.out, "{:?}hf", float)?,
                 Sv::Float(float) => write!(self.out, "{:?}", float)?,
                 // Booleans are either `true` or `false` so nothing special needs to be done
                 Sv::Bool(boolean) => write!(self.out, "{}", boolean)?,
@@ -1773,7 +1812,15 @@ impl<'a, W: Write> Writer<'a, W> {
                     writeln!(self.out, "{}memoryBarrierShared();", level)?;
                 }
 
-                writeln!(self.out, "{}barrier();", level)?;
+                if flags.contains(crate::Barrier::SUB_GROUP) {
+                    writeln!(self.out, "{}subgroupMemoryBarrier();", level)?;
+                }
+
+                if flags == crate::Barrier::SUB_GROUP {
+                    writeln!(self.out, "{}subgroupBarrier();", level)?;
+                } else {
+                    writeln!(self.out, "{}barrier();", level)?;
+                }
             }
             // Stores in glsl are just variable assignments written as `pointer = value;`
             Statement::Store { pointer, value } => {
@@ -1805,6 +1852,39 @@ impl<'a, W: Write> Writer<'a, W> {
                 self.write_expr(value, ctx)?;
                 writeln!(self.out, ");")?;
             }
+            Statement::ImageAtomic {
+                image,
+                coordinate,
+                array_index,
+                ref fun,
+                value,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", super::BAKE_PREFIX, result.index());
+                let res_ty = ctx.info[result].ty.inner_with(&self.module.types);
+                self.write_value_type(res_ty)?;
+                write!(self.out, " {} = ", res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                // This will only panic if the module is invalid
+                let dim = match *ctx.info[image].ty.inner_with(&self.module.types) {
+                    TypeInner::Image { dim, .. } => dim,
+                    _ => unreachable!(),
+                };
+
+                write!(self.out, "imageAtomic{}(", fun.to_glsl())?;
+                self.write_expr(image, ctx)?;
+                write!(self.out, ", ")?;
+                self.write_texture_coordinates(coordinate, array_index, dim, ctx)?;
+                write!(self.out, ", ")?;
+                if let crate::AtomicFunction::Subtract = *fun {
+                    // we just wrote `imageAtomicAdd`, so negate the argument
+                    write!(self.out, "-")?;
+                }
+                self.write_expr(value, ctx)?;
+                writeln!(self.out, ");")?;
+            }
             // A `Call` is written `name(arguments)` where `arguments` is a comma separated expressions list
             Statement::Call {
                 function,
@@ -1834,6 +1914,45 @@ impl<'a, W: Write> Writer<'a, W> {
                 self.write_slice(&arguments, |this, _, arg| this.write_expr(*arg, ctx))?;
                 writeln!(self.out, ");")?
             }
+            Statement::Atomic {
+                pointer,
+                fun: crate::AtomicFunction::Exchange { compare: Some(cmp) },
+                value,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", super::BAKE_PREFIX, result.index());
+                let res_ty = match ctx.info[result].ty {
+                    proc::TypeResolution::Handle(handle) => handle,
+                    proc::TypeResolution::Value(_) => unreachable!(),
+                };
+                self.write_type(res_ty)?;
+                writeln!(self.out, " {};", res_name)?;
+
+                // `atomicCompSwap` only returns the original value, so
+                // compare it ourselves to tell if the exchange happened.
+                let old_value = format!(
+                    "{}.{}",
+                    res_name,
+                    self.names[&NameKey::StructMember(res_ty, 0)]
+                );
+                let exchanged = format!(
+                    "{}.{}",
+                    res_name,
+                    self.names[&NameKey::StructMember(res_ty, 1)]
+                );
+                write!(self.out, "{}{} = atomicCompSwap(", level, old_value)?;
+                self.write_expr(pointer, ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(cmp, ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(value, ctx)?;
+                writeln!(self.out, ");")?;
+                write!(self.out, "{}{} = ({} == ", level, exchanged, old_value)?;
+                self.write_expr(cmp, ctx)?;
+                writeln!(self.out, ");")?;
+                self.named_expressions.insert(result, res_name);
+            }
             Statement::Atomic {
                 pointer,
                 ref fun,
@@ -1851,27 +1970,107 @@ impl<'a, W: Write> Writer<'a, W> {
                 write!(self.out, "atomic{}(", fun_str)?;
                 self.write_expr(pointer, ctx)?;
                 write!(self.out, ", ")?;
-                // handle the special cases
-                match *fun {
-                    crate::AtomicFunction::Subtract => {
-                        // we just wrote `InterlockedAdd`, so negate the argument
-                        write!(self.out, "-")?;
-                    }
-                    crate::AtomicFunction::Exchange { compare: Some(_) } => {
-                        return Err(Error::Custom(
-                            "atomic CompareExchange is not implemented".to_string(),
-                        ));
-                    }
-                    _ => {}
+                if let crate::AtomicFunction::Subtract = *fun {
+                    // we just wrote `atomicAdd`, so negate the argument
+                    write!(self.out, "-")?;
                 }
                 self.write_expr(value, ctx)?;
                 writeln!(self.out, ");")?;
             }
+            Statement::SubgroupBallot { result, predicate } => {
+                self.write_subgroup_result(result, ctx, level)?;
+                write!(self.out, "subgroupBallot(")?;
+                match predicate {
+                    Some(predicate) => self.write_expr(predicate, ctx)?,
+                    None => write!(self.out, "true")?,
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupElect { result } => {
+                self.write_subgroup_result(result, ctx, level)?;
+                writeln!(self.out, "subgroupElect();")?;
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                self.write_subgroup_result(result, ctx, level)?;
+                let (fun_str, index) = match mode {
+                    crate::GatherMode::BroadcastFirst => ("BroadcastFirst", None),
+                    crate::GatherMode::Broadcast(index) => ("Broadcast", Some(index)),
+                    crate::GatherMode::Shuffle(index) => ("Shuffle", Some(index)),
+                    crate::GatherMode::ShuffleDown(index) => ("ShuffleDown", Some(index)),
+                    crate::GatherMode::ShuffleUp(index) => ("ShuffleUp", Some(index)),
+                    crate::GatherMode::ShuffleXor(index) => ("ShuffleXor", Some(index)),
+                };
+                write!(self.out, "subgroup{}(", fun_str)?;
+                self.write_expr(argument, ctx)?;
+                if let Some(index) = index {
+                    write!(self.out, ", ")?;
+                    self.write_expr(index, ctx)?;
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                self.write_subgroup_result(result, ctx, level)?;
+                let collective_str = match collective_op {
+                    crate::CollectiveOperation::Reduce => "",
+                    crate::CollectiveOperation::InclusiveScan => "Inclusive",
+                    crate::CollectiveOperation::ExclusiveScan => "Exclusive",
+                };
+                let op_str = match op {
+                    crate::SubgroupOperation::All => "All",
+                    crate::SubgroupOperation::Any => "Any",
+                    crate::SubgroupOperation::Add => "Add",
+                    crate::SubgroupOperation::Mul => "Mul",
+                    crate::SubgroupOperation::Min => "Min",
+                    crate::SubgroupOperation::Max => "Max",
+                    crate::SubgroupOperation::And => "And",
+                    crate::SubgroupOperation::Or => "Or",
+                    crate::SubgroupOperation::Xor => "Xor",
+                };
+                write!(self.out, "subgroup{}{}(", collective_str, op_str)?;
+                self.write_expr(argument, ctx)?;
+                writeln!(self.out, ");")?;
+            }
+            Statement::MeshFunction(_) => {
+                return Err(Error::Custom(
+                    "task and mesh shaders are not supported".to_string(),
+                ))
+            }
+            Statement::RayQuery { .. } => {
+                return Err(Error::Custom("ray queries are not supported".to_string()))
+            }
         }
 
         Ok(())
     }
 
+    /// Helper method to write the declaration of a subgroup operation result
+    ///
+    /// # Notes
+    /// Writes `{level}{type} {name} = ` and registers the name of the result expression
+    fn write_subgroup_result(
+        &mut self,
+        result: Handle<crate::Expression>,
+        ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> BackendResult {
+        write!(self.out, "{}", level)?;
+        let res_name = format!("{}{}", super::BAKE_PREFIX, result.index());
+        let res_ty = ctx.info[result].ty.inner_with(&self.module.types);
+        self.write_value_type(res_ty)?;
+        write!(self.out, " {} = ", res_name)?;
+        self.named_expressions.insert(result, res_name);
+        Ok(())
+    }
+
     /// Helper method to write expressions
     ///
     /// # Notes
@@ -2696,13 +2895,22 @@ impl<'a, W: Write> Writer<'a, W> {
                 }
             }
             // These expressions never show up in `Emit`.
-            Expression::CallResult(_) | Expression::AtomicResult { .. } => unreachable!(),
+            Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. }
+            | Expression::RayQueryProceedResult => unreachable!(),
+            Expression::RayQueryGetCommittedIntersection { .. } => {
+                return Err(Error::Custom("ray queries are not supported".to_string()))
+            }
             // `ArrayLength` is written as `expr.length()` and we convert it to a uint
             Expression::ArrayLength(expr) => {
                 write!(self.out, "uint(")?;
                 self.write_expr(expr, ctx)?;
                 write!(self.out, ".length())")?
             }
+            // binding arrays aren't supported, so there is nothing to mark
+            Expression::NonUniform(value) => self.write_expr(value, ctx)?,
         }
 
         Ok(())
@@ -2941,7 +3149,7 @@ struct ScalarString<'a> {
 /// Check [`ScalarString`](ScalarString) for the information provided
 ///
 /// # Errors
-/// If a [`Float`](crate::ScalarKind::Float) with an width that isn't 4 or 8
+/// If a [`Float`](crate::ScalarKind::Float) with an width that isn't 2, 4 or 8
 fn glsl_scalar(
     kind: crate::ScalarKind,
     width: crate::Bytes,
@@ -2949,15 +3157,31 @@ fn glsl_scalar(
     use crate::ScalarKind as Sk;
 
     Ok(match kind {
-        Sk::Sint => ScalarString {
-            prefix: "i",
-            full: "int",
+        Sk::Sint => match width {
+            8 => ScalarString {
+                prefix: "i64",
+                full: "int64_t",
+            },
+            _ => ScalarString {
+                prefix: "i",
+                full: "int",
+            },
         },
-        Sk::Uint => ScalarString {
-            prefix: "u",
-            full: "uint",
+        Sk::Uint => match width {
+            8 => ScalarString {
+                prefix: "u64",
+                full: "uint64_t",
+            },
+            _ => ScalarString {
+                prefix: "u",
+                full: "uint",
+            },
         },
         Sk::Float => match width {
+            2 => ScalarString {
+                prefix: "f16",
+                full: "float16_t",
+            },
             4 => ScalarString {
                 prefix: "",
                 full: "float",
@@ -2980,7 +3204,7 @@ fn glsl_built_in(built_in: crate::BuiltIn, output: bool) -> &'static str {
     use crate::BuiltIn as Bi;
 
     match built_in {
-        Bi::Position => {
+        Bi::Position { .. } => {
             if output {
                 "gl_Position"
             } else {
@@ -3015,6 +3239,15 @@ fn glsl_built_in(built_in: crate::BuiltIn, output: bool) -> &'static str {
         Bi::WorkGroupId => "gl_WorkGroupID",
         Bi::WorkGroupSize => "gl_WorkGroupSize",
         Bi::NumWorkGroups => "gl_NumWorkGroups",
+        // subgroup
+        Bi::SubgroupSize => "gl_SubgroupSize",
+        Bi::SubgroupInvocationId => "gl_SubgroupInvocationID",
+        // mesh
+        Bi::MeshTaskSize
+        | Bi::PointIndex
+        | Bi::LineIndices
+        | Bi::TriangleIndices
+        | Bi::CullPrimitive => unreachable!(),
     }
 }
 
@@ -15,10 +15,18 @@ impl crate::ScalarKind {
     /// <https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-scalar>
     pub(super) fn to_hlsl_str(self, width: crate::Bytes) -> Result<&'static str, Error> {
         match self {
-            Self::Sint => Ok("int"),
-            Self::Uint => Ok("uint"),
+            Self::Sint => match width {
+                4 => Ok("int"),
+                8 => Ok("int64_t"),
+                _ => Err(Error::UnsupportedScalar(self, width)),
+            },
+            Self::Uint => match width {
+                4 => Ok("uint"),
+                8 => Ok("uint64_t"),
+                _ => Err(Error::UnsupportedScalar(self, width)),
+            },
             Self::Float => match width {
-                2 => Ok("half"),
+                2 => Ok("float16_t"),
                 4 => Ok("float"),
                 8 => Ok("double"),
                 _ => Err(Error::UnsupportedScalar(self, width)),
@@ -71,12 +79,22 @@ impl crate::StorageFormat {
             | Self::Rgba32Sint => "int4",
         }
     }
+
+    /// The scalar type of the texels of images used atomically, which
+    /// `Interlocked*` functions require.
+    pub(super) fn to_hlsl_atomic_str(self) -> Option<&'static str> {
+        match self {
+            Self::R32Uint => Some("uint"),
+            Self::R32Sint => Some("int"),
+            _ => None,
+        }
+    }
 }
 
 impl crate::BuiltIn {
     pub(super) fn to_hlsl_str(self) -> Result<&'static str, Error> {
         Ok(match self {
-            Self::Position => "SV_Position",
+            Self::Position { .. } => "SV_Position",
             // vertex
             Self::ClipDistance => "SV_ClipDistance",
             Self::CullDistance => "SV_CullDistance",
@@ -103,6 +121,25 @@ impl crate::BuiltIn {
             Self::BaseInstance | Self::BaseVertex | Self::WorkGroupSize => {
                 return Err(Error::Unimplemented(format!("builtin {:?}", self)))
             }
+            // These have no semantic. Entry point arguments bound to them are
+            // skipped, and their uses replaced with intrinsic calls in
+            // `Writer::write_expr`.
+            Self::SubgroupSize | Self::SubgroupInvocationId => {
+                return Err(Error::Unimplemented(format!(
+                    "builtin {:?} as a struct member",
+                    self
+                )))
+            }
+            // mesh
+            Self::CullPrimitive => "SV_CullPrimitive",
+            // These have no semantic. The task result is passed to `DispatchMesh`,
+            // and the indices are written to a separate mesh shader output.
+            Self::MeshTaskSize | Self::PointIndex | Self::LineIndices | Self::TriangleIndices => {
+                return Err(Error::Unimplemented(format!(
+                    "builtin {:?} as a struct member",
+                    self
+                )))
+            }
             Self::ViewIndex => {
                 return Err(Error::Custom(format!("Unsupported builtin {:?}", self)))
             }
@@ -143,7 +180,7 @@ impl crate::AtomicFunction {
             Self::Min => "Min",
             Self::Max => "Max",
             Self::Exchange { compare: None } => "Exchange",
-            Self::Exchange { .. } => "", //TODO
+            Self::Exchange { .. } => "CompareExchange",
         }
     }
 }
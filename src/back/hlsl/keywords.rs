@@ -72,6 +72,9 @@ pub const RESERVED: &[&str] = &[
     "PointStream",
     "precise",
     "RasterizerState",
+    "RayDesc",
+    "RayQuery",
+    "RaytracingAccelerationStructure",
     "RenderTargetView",
     "return",
     "register",
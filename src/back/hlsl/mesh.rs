@@ -0,0 +1,281 @@
+//! Helpers for task and mesh shaders in the HLSL backend.
+//!
+//! HLSL mesh shaders write their outputs to `out` array arguments, with the
+//! primitive indices kept apart from the other per-primitive values:
+//!
+//! ```hlsl
+//! [numthreads(1, 1, 1)]
+//! [outputtopology("triangle")]
+//! void main(out vertices Vertex vertices[3], out indices uint3 indices[1],
+//!     out primitives PrimitiveOutput_main primitives[1]) { ... }
+//! ```
+//!
+//! Task shaders are written as amplification shaders, which launch the mesh
+//! workgroups with `DispatchMesh` instead of returning their count.
+
+use super::{
+    writer::{EntryPointBinding, EpStructMember, Io},
+    BackendResult, Error, ShaderModel,
+};
+use crate::{arena::Handle, back, proc::NameKey, Module, ShaderStage, TypeInner};
+use std::fmt::Write;
+
+/// `DispatchMesh` requires a payload, even if the mesh shader ignores it.
+const TASK_PAYLOAD_TYPE: &str = "NagaTaskPayload";
+const TASK_PAYLOAD_VAR: &str = "_naga_task_payload";
+
+/// Output arguments of a mesh shader entry point.
+pub(super) struct MeshInterface {
+    vertices: String,
+    indices: String,
+    /// Index of the primitive struct member holding the vertex indices.
+    indices_member: u32,
+    /// If `Some`, the per-primitive values other than the indices are
+    /// gathered in a special struct.
+    primitives: Option<EntryPointBinding>,
+}
+
+/// Return true if `func_ctx` is a task entry point.
+pub(super) fn is_task_entry_point(module: &Module, func_ctx: &back::FunctionCtx<'_>) -> bool {
+    match func_ctx.ty {
+        back::FunctionType::EntryPoint(index) => {
+            module.entry_points[index as usize].stage == ShaderStage::Task
+        }
+        back::FunctionType::Function(_) => false,
+    }
+}
+
+impl crate::MeshOutputTopology {
+    fn to_hlsl_str(self) -> &'static str {
+        match self {
+            Self::Points => "point",
+            Self::Lines => "line",
+            Self::Triangles => "triangle",
+        }
+    }
+}
+
+impl<'a, W: Write> super::Writer<'a, W> {
+    /// Check that the target shader model supports mesh shaders.
+    pub(super) fn require_mesh_shader(&self) -> BackendResult {
+        if self.options.shader_model < ShaderModel::V6_5 {
+            return Err(Error::Custom(
+                "task and mesh shaders require shader model 6.5".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Write the task payload, if `module` has a task shader.
+    pub(super) fn write_task_payload(&mut self, module: &Module) -> BackendResult {
+        if module
+            .entry_points
+            .iter()
+            .any(|ep| ep.stage == ShaderStage::Task)
+        {
+            writeln!(self.out, "struct {} {{", TASK_PAYLOAD_TYPE)?;
+            writeln!(self.out, "{}uint dummy;", back::INDENT)?;
+            writeln!(self.out, "}};")?;
+            writeln!(
+                self.out,
+                "groupshared {} {};",
+                TASK_PAYLOAD_TYPE, TASK_PAYLOAD_VAR
+            )?;
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+
+    /// Write the per-primitive output struct of a mesh shader, and return
+    /// the names of its output arguments.
+    pub(super) fn write_mesh_interface(
+        &mut self,
+        module: &Module,
+        mesh_info: &crate::MeshStageInfo,
+        entry_point_name: &str,
+    ) -> Result<MeshInterface, Error> {
+        let indices_built_in = mesh_info.topology.indices_built_in();
+        let mut indices_member = 0;
+        let mut fake_members = Vec::new();
+        if let TypeInner::Struct { ref members, .. } =
+            module.types[mesh_info.primitive_output_type].inner
+        {
+            for (index, member) in members.iter().enumerate() {
+                if member.binding == Some(crate::Binding::BuiltIn(indices_built_in)) {
+                    indices_member = index as u32;
+                    continue;
+                }
+                fake_members.push(EpStructMember {
+                    name: self.namer.call_or(&member.name, "member"),
+                    ty: member.ty,
+                    binding: member.binding.clone(),
+                    index: index as u32,
+                });
+            }
+        }
+
+        let primitives = if fake_members.is_empty() {
+            None
+        } else {
+            let struct_name = format!("PrimitiveOutput_{}", entry_point_name);
+            Some(self.write_interface_struct(
+                module,
+                (ShaderStage::Mesh, Io::Output),
+                struct_name,
+                fake_members,
+            )?)
+        };
+
+        Ok(MeshInterface {
+            vertices: self.namer.call("vertices"),
+            indices: self.namer.call("indices"),
+            indices_member,
+            primitives,
+        })
+    }
+
+    /// Write the attributes of a task or mesh entry point.
+    pub(super) fn write_mesh_attributes(&mut self, ep: &crate::EntryPoint) -> BackendResult {
+        self.require_mesh_shader()?;
+        if let Some(ref mesh_info) = ep.mesh_info {
+            writeln!(
+                self.out,
+                "[outputtopology(\"{}\")]",
+                mesh_info.topology.to_hlsl_str()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the output arguments of a mesh entry point, including the
+    /// leading separator.
+    pub(super) fn write_mesh_arguments(
+        &mut self,
+        module: &Module,
+        ep_index: u16,
+        separator: bool,
+    ) -> BackendResult {
+        let mesh_info = match module.entry_points[ep_index as usize].mesh_info {
+            Some(ref mesh_info) => mesh_info,
+            None => return Ok(()),
+        };
+        let mesh = match self.entry_point_io[ep_index as usize].mesh {
+            Some(ref mesh) => mesh,
+            None => return Ok(()),
+        };
+        let indices_ty = match module.types[mesh_info.primitive_output_type].inner {
+            TypeInner::Struct { ref members, .. } => members[mesh.indices_member as usize].ty,
+            _ => unreachable!(),
+        };
+        let vertices = format!(
+            "out vertices {} {}[{}]",
+            self.names[&NameKey::Type(mesh_info.vertex_output_type)],
+            mesh.vertices,
+            mesh_info.max_vertices
+        );
+        let indices = format!(" {}[{}]", mesh.indices, mesh_info.max_primitives);
+        let primitives = mesh.primitives.as_ref().map(|primitives| {
+            format!(
+                ", out primitives {} {}[{}]",
+                primitives.ty_name, primitives.arg_name, mesh_info.max_primitives
+            )
+        });
+
+        if separator {
+            write!(self.out, ", ")?;
+        }
+        write!(self.out, "{}, out indices ", vertices)?;
+        self.write_type(module, indices_ty)?;
+        write!(self.out, "{}", indices)?;
+        if let Some(primitives) = primitives {
+            write!(self.out, "{}", primitives)?;
+        }
+        Ok(())
+    }
+
+    /// Write a [`MeshFunction`](crate::MeshFunction) of the entry point
+    /// `ep_index`, whose value, if any, has already been baked.
+    pub(super) fn write_mesh_function(
+        &mut self,
+        module: &Module,
+        fun: &crate::MeshFunction,
+        ep_index: u16,
+        func_ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> BackendResult {
+        self.require_mesh_shader()?;
+        match *fun {
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count,
+                primitive_count,
+            } => {
+                write!(self.out, "{}SetMeshOutputCounts(", level)?;
+                self.write_expr(module, vertex_count, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, primitive_count, func_ctx)?;
+                writeln!(self.out, ");")?;
+            }
+            crate::MeshFunction::SetVertex { index, value } => {
+                let mesh = self.entry_point_io[ep_index as usize]
+                    .mesh
+                    .as_ref()
+                    .unwrap();
+                write!(self.out, "{}{}[", level, mesh.vertices)?;
+                self.write_expr(module, index, func_ctx)?;
+                write!(self.out, "] = ")?;
+                self.write_expr(module, value, func_ctx)?;
+                writeln!(self.out, ";")?;
+            }
+            crate::MeshFunction::SetPrimitive { index, value } => {
+                let ty = module.entry_points[ep_index as usize]
+                    .mesh_info
+                    .as_ref()
+                    .unwrap()
+                    .primitive_output_type;
+                let mesh = self.entry_point_io[ep_index as usize]
+                    .mesh
+                    .as_ref()
+                    .unwrap();
+                let mut targets = vec![(mesh.indices.clone(), None, mesh.indices_member)];
+                if let Some(ref primitives) = mesh.primitives {
+                    for m in primitives.members.iter() {
+                        targets.push((primitives.arg_name.clone(), Some(m.name.clone()), m.index));
+                    }
+                }
+
+                for (array, field, member_index) in targets {
+                    write!(self.out, "{}{}[", level, array)?;
+                    self.write_expr(module, index, func_ctx)?;
+                    write!(self.out, "]")?;
+                    if let Some(field) = field {
+                        write!(self.out, ".{}", field)?;
+                    }
+                    write!(self.out, " = ")?;
+                    self.write_expr(module, value, func_ctx)?;
+                    let member_name = &self.names[&NameKey::StructMember(ty, member_index)];
+                    writeln!(self.out, ".{};", member_name)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the return of a task entry point, which launches the mesh
+    /// workgroups. The workgroup count `value` has already been baked.
+    pub(super) fn write_task_return(
+        &mut self,
+        module: &Module,
+        value: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> BackendResult {
+        write!(self.out, "{}DispatchMesh(", level)?;
+        for component in ["x", "y", "z"].iter() {
+            self.write_expr(module, value, func_ctx)?;
+            write!(self.out, ".{}, ", component)?;
+        }
+        writeln!(self.out, "{});", TASK_PAYLOAD_VAR)?;
+        writeln!(self.out, "{}return;", level)?;
+        Ok(())
+    }
+}
@@ -20,6 +20,8 @@
 mod conv;
 mod help;
 mod keywords;
+mod mesh;
+mod ray;
 mod storage;
 mod writer;
 
@@ -48,6 +50,12 @@ pub enum ShaderModel {
     V5_0,
     V5_1,
     V6_0,
+    V6_1,
+    V6_2,
+    V6_3,
+    V6_4,
+    V6_5,
+    V6_6,
 }
 
 impl ShaderModel {
@@ -56,6 +64,12 @@ impl ShaderModel {
             Self::V5_0 => "5_0",
             Self::V5_1 => "5_1",
             Self::V6_0 => "6_0",
+            Self::V6_1 => "6_1",
+            Self::V6_2 => "6_2",
+            Self::V6_3 => "6_3",
+            Self::V6_4 => "6_4",
+            Self::V6_5 => "6_5",
+            Self::V6_6 => "6_6",
         }
     }
 }
@@ -66,6 +80,8 @@ impl crate::ShaderStage {
             Self::Vertex => "vs",
             Self::Fragment => "ps",
             Self::Compute => "cs",
+            Self::Task => "as",
+            Self::Mesh => "ms",
         }
     }
 }
@@ -185,4 +201,9 @@ pub struct Writer<'a, W> {
     named_expressions: crate::NamedExpressions,
     wrapped: Wrapped,
     temp_access_chain: Vec<storage::SubAccess>,
+    /// Storage images used by [`ImageAtomic`] statements, whose texels are
+    /// declared as scalars.
+    ///
+    /// [`ImageAtomic`]: crate::Statement::ImageAtomic
+    atomic_images: crate::FastHashSet<crate::Handle<crate::GlobalVariable>>,
 }
@@ -0,0 +1,147 @@
+//! Helpers for ray queries in the HLSL backend.
+//!
+//! The IR ray descriptor and intersection structures don't match the
+//! HLSL builtin `RayDesc` and the `RayQuery` accessors, so the backend
+//! writes a conversion function for each:
+//!
+//! ```hlsl
+//! RayDesc RayDescFromRayDesc_(RayDesc_ arg0) { ... }
+//! RayIntersection GetCommittedIntersection(RayQuery<RAY_FLAG_NONE> rq) { ... }
+//! ```
+
+use super::{BackendResult, Error, ShaderModel};
+use crate::{arena::Handle, proc::NameKey};
+use std::fmt::Write;
+
+pub(super) const RAY_QUERY_TYPE: &str = "RayQuery<RAY_FLAG_NONE>";
+const RAY_DESC_FUNCTION: &str = "RayDescFromRayDesc_";
+const COMMITTED_INTERSECTION_FUNCTION: &str = "GetCommittedIntersection";
+
+impl<'a, W: Write> super::Writer<'a, W> {
+    /// Check that the target shader model supports inline ray tracing.
+    pub(super) fn require_ray_query(&self) -> BackendResult {
+        if self.options.shader_model < ShaderModel::V6_5 {
+            return Err(Error::Custom(
+                "ray queries require shader model 6.5".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_ray_desc_function_name(&mut self) -> BackendResult {
+        write!(self.out, "{}", RAY_DESC_FUNCTION)?;
+        Ok(())
+    }
+
+    pub(super) fn write_committed_intersection_function_name(&mut self) -> BackendResult {
+        write!(self.out, "{}", COMMITTED_INTERSECTION_FUNCTION)?;
+        Ok(())
+    }
+
+    /// Write the helper functions needed by the ray queries in `module`.
+    pub(super) fn write_ray_query_functions(&mut self, module: &crate::Module) -> BackendResult {
+        let uses_ray_queries = module.types.iter().any(|(_, ty)| match ty.inner {
+            crate::TypeInner::AccelerationStructure | crate::TypeInner::RayQuery => true,
+            _ => false,
+        });
+        if !uses_ray_queries {
+            return Ok(());
+        }
+        self.require_ray_query()?;
+
+        for (handle, ty) in module.types.iter() {
+            if ty.inner.is_ray_desc(&module.types) {
+                self.write_ray_desc_function(handle)?;
+            } else if ty.inner.is_ray_intersection(&module.types) {
+                self.write_committed_intersection_function(handle)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a function converting the IR ray descriptor `ty` to an HLSL `RayDesc`.
+    fn write_ray_desc_function(&mut self, ty: Handle<crate::Type>) -> BackendResult {
+        use crate::back::INDENT;
+
+        // The `RayDesc` fields and the indices of the IR members they come from.
+        const FIELDS: [(&str, u32); 4] =
+            [("Origin", 4), ("TMin", 2), ("Direction", 5), ("TMax", 3)];
+
+        write!(self.out, "RayDesc ")?;
+        self.write_ray_desc_function_name()?;
+        writeln!(self.out, "({} arg0) {{", self.names[&NameKey::Type(ty)])?;
+        writeln!(self.out, "{}RayDesc ret = (RayDesc)0;", INDENT)?;
+        for &(field, index) in FIELDS.iter() {
+            writeln!(
+                self.out,
+                "{}ret.{} = arg0.{};",
+                INDENT,
+                field,
+                self.names[&NameKey::StructMember(ty, index)]
+            )?;
+        }
+        writeln!(self.out, "{}return ret;", INDENT)?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    /// Write a function reading the committed intersection of a ray query
+    /// into the IR ray intersection `ty`.
+    fn write_committed_intersection_function(&mut self, ty: Handle<crate::Type>) -> BackendResult {
+        use crate::back::INDENT;
+
+        // The `RayQuery` accessors for each IR member, in order.
+        const ACCESSORS: [&str; 11] = [
+            "CommittedStatus",
+            "CommittedRayT",
+            "CommittedInstanceID",
+            "CommittedInstanceIndex",
+            "CommittedInstanceContributionToHitGroupIndex",
+            "CommittedGeometryIndex",
+            "CommittedPrimitiveIndex",
+            "CommittedTriangleBarycentrics",
+            "CommittedTriangleFrontFace",
+            "CommittedObjectToWorld3x4",
+            "CommittedWorldToObject3x4",
+        ];
+        // Members only available for triangle hits.
+        const TRIANGLE_MEMBERS: std::ops::Range<usize> = 7..9;
+
+        let struct_name = self.names[&NameKey::Type(ty)].clone();
+        write!(self.out, "{} ", struct_name)?;
+        self.write_committed_intersection_function_name()?;
+        writeln!(self.out, "({} rq) {{", RAY_QUERY_TYPE)?;
+        writeln!(
+            self.out,
+            "{}{} ret = ({})0;",
+            INDENT, struct_name, struct_name
+        )?;
+        for (index, accessor) in ACCESSORS.iter().enumerate() {
+            if TRIANGLE_MEMBERS.contains(&index) {
+                continue;
+            }
+            let member = &self.names[&NameKey::StructMember(ty, index as u32)];
+            writeln!(self.out, "{}ret.{} = rq.{}();", INDENT, member, accessor)?;
+        }
+        writeln!(
+            self.out,
+            "{}if (ret.{} == COMMITTED_TRIANGLE_HIT) {{",
+            INDENT,
+            self.names[&NameKey::StructMember(ty, 0)]
+        )?;
+        for index in TRIANGLE_MEMBERS {
+            let member = &self.names[&NameKey::StructMember(ty, index as u32)];
+            writeln!(
+                self.out,
+                "{}{}ret.{} = rq.{}();",
+                INDENT, INDENT, member, ACCESSORS[index]
+            )?;
+        }
+        writeln!(self.out, "{}}}", INDENT)?;
+        writeln!(self.out, "{}return ret;", INDENT)?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@
 
 use super::{super::FunctionCtx, BackendResult, Error};
 use crate::{
-    proc::{NameKey, TypeResolution},
+    proc::{index::GuardedIndex, NameKey, TypeResolution},
     Handle,
 };
 
@@ -21,6 +21,14 @@ pub(super) enum SubAccess {
     },
 }
 
+/// The buffer an access chain starts from.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct StorageBuffer {
+    pub var_handle: Handle<crate::GlobalVariable>,
+    /// Index of the buffer, if the variable is a binding array of buffers.
+    pub index: Option<GuardedIndex>,
+}
+
 pub(super) enum StoreValue {
     Expression(Handle<crate::Expression>),
     TempIndex {
@@ -36,6 +44,27 @@ pub(super) enum StoreValue {
 }
 
 impl<W: fmt::Write> super::Writer<'_, W> {
+    /// Helper function to write down the `ByteAddressBuffer` of an access chain.
+    pub(super) fn write_storage_buffer(
+        &mut self,
+        module: &crate::Module,
+        buffer: StorageBuffer,
+        func_ctx: &FunctionCtx,
+    ) -> BackendResult {
+        let var_name = &self.names[&NameKey::GlobalVariable(buffer.var_handle)];
+        write!(self.out, "{}", var_name)?;
+        match buffer.index {
+            Some(GuardedIndex::Expression(index)) => {
+                write!(self.out, "[")?;
+                self.write_expr(module, index, func_ctx)?;
+                write!(self.out, "]")?;
+            }
+            Some(GuardedIndex::Known(index)) => write!(self.out, "[{}]", index)?,
+            None => {}
+        }
+        Ok(())
+    }
+
     pub(super) fn write_storage_address(
         &mut self,
         module: &crate::Module,
@@ -62,7 +91,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
     fn write_storage_load_sequence<I: Iterator<Item = (TypeResolution, u32)>>(
         &mut self,
         module: &crate::Module,
-        var_handle: Handle<crate::GlobalVariable>,
+        buffer: StorageBuffer,
         sequence: I,
         func_ctx: &FunctionCtx,
     ) -> BackendResult {
@@ -72,7 +101,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
             if i != 0 {
                 write!(self.out, ", ")?;
             };
-            self.write_storage_load(module, var_handle, ty_resolution, func_ctx)?;
+            self.write_storage_load(module, buffer, ty_resolution, func_ctx)?;
             self.temp_access_chain.pop();
         }
         Ok(())
@@ -82,17 +111,31 @@ impl<W: fmt::Write> super::Writer<'_, W> {
     pub(super) fn write_storage_load(
         &mut self,
         module: &crate::Module,
-        var_handle: Handle<crate::GlobalVariable>,
+        buffer: StorageBuffer,
         result_ty: TypeResolution,
         func_ctx: &FunctionCtx,
     ) -> BackendResult {
         match *result_ty.inner_with(&module.types) {
+            crate::TypeInner::Scalar { width, .. } | crate::TypeInner::Vector { width, .. }
+                if width != 4 =>
+            {
+                // Only 32-bit values can be bit-casted from the untyped loads,
+                // other widths need the templated load of SM 6.2.
+                let chain = mem::take(&mut self.temp_access_chain);
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Load<")?;
+                self.write_value_type(module, result_ty.inner_with(&module.types))?;
+                write!(self.out, ">(")?;
+                self.write_storage_address(module, &chain, func_ctx)?;
+                write!(self.out, ")")?;
+                self.temp_access_chain = chain;
+            }
             crate::TypeInner::Scalar { kind, width: _ } => {
                 // working around the borrow checker in `self.write_expr`
                 let chain = mem::take(&mut self.temp_access_chain);
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                let cast = kind.to_hlsl_cast();
-                write!(self.out, "{}({}.Load(", cast, var_name)?;
+                write!(self.out, "{}(", kind.to_hlsl_cast())?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Load(")?;
                 self.write_storage_address(module, &chain, func_ctx)?;
                 write!(self.out, "))")?;
                 self.temp_access_chain = chain;
@@ -104,9 +147,9 @@ impl<W: fmt::Write> super::Writer<'_, W> {
             } => {
                 // working around the borrow checker in `self.write_expr`
                 let chain = mem::take(&mut self.temp_access_chain);
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                let cast = kind.to_hlsl_cast();
-                write!(self.out, "{}({}.Load{}(", cast, var_name, size as u8)?;
+                write!(self.out, "{}(", kind.to_hlsl_cast())?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Load{}(", size as u8)?;
                 self.write_storage_address(module, &chain, func_ctx)?;
                 write!(self.out, "))")?;
                 self.temp_access_chain = chain;
@@ -119,7 +162,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                 write!(
                     self.out,
                     "{}{}x{}(",
-                    crate::ScalarKind::Float.to_hlsl_str(width)?,
+                    self.scalar_type_str(crate::ScalarKind::Float, width)?,
                     rows as u8,
                     columns as u8,
                 )?;
@@ -138,7 +181,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                     };
                     (TypeResolution::Value(ty_inner), i * row_stride)
                 });
-                self.write_storage_load_sequence(module, var_handle, iter, func_ctx)?;
+                self.write_storage_load_sequence(module, buffer, iter, func_ctx)?;
                 write!(self.out, ")")?;
             }
             crate::TypeInner::Array {
@@ -150,7 +193,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                 let count = module.constants[const_handle].to_array_length().unwrap();
                 let stride = module.types[base].inner.size(&module.constants);
                 let iter = (0..count).map(|i| (TypeResolution::Handle(base), stride * i));
-                self.write_storage_load_sequence(module, var_handle, iter, func_ctx)?;
+                self.write_storage_load_sequence(module, buffer, iter, func_ctx)?;
                 write!(self.out, "}}")?;
             }
             crate::TypeInner::Struct { ref members, .. } => {
@@ -158,7 +201,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                 let iter = members
                     .iter()
                     .map(|m| (TypeResolution::Handle(m.ty), m.offset));
-                self.write_storage_load_sequence(module, var_handle, iter, func_ctx)?;
+                self.write_storage_load_sequence(module, buffer, iter, func_ctx)?;
                 write!(self.out, "}}")?;
             }
             _ => unreachable!(),
@@ -195,7 +238,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
     pub(super) fn write_storage_store(
         &mut self,
         module: &crate::Module,
-        var_handle: Handle<crate::GlobalVariable>,
+        buffer: StorageBuffer,
         value: StoreValue,
         func_ctx: &FunctionCtx,
         level: crate::back::Level,
@@ -224,11 +267,29 @@ impl<W: fmt::Write> super::Writer<'_, W> {
             }
         };
         match *ty_resolution.inner_with(&module.types) {
+            crate::TypeInner::Scalar { width, .. } | crate::TypeInner::Vector { width, .. }
+                if width != 4 =>
+            {
+                // Only 32-bit values can be bit-casted for the untyped stores,
+                // other widths need the templated store of SM 6.2.
+                let chain = mem::take(&mut self.temp_access_chain);
+                write!(self.out, "{}", level)?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Store<")?;
+                self.write_value_type(module, ty_resolution.inner_with(&module.types))?;
+                write!(self.out, ">(")?;
+                self.write_storage_address(module, &chain, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_store_value(module, &value, func_ctx)?;
+                writeln!(self.out, ");")?;
+                self.temp_access_chain = chain;
+            }
             crate::TypeInner::Scalar { .. } => {
                 // working around the borrow checker in `self.write_expr`
                 let chain = mem::take(&mut self.temp_access_chain);
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                write!(self.out, "{}{}.Store(", level, var_name)?;
+                write!(self.out, "{}", level)?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Store(")?;
                 self.write_storage_address(module, &chain, func_ctx)?;
                 write!(self.out, ", asuint(")?;
                 self.write_store_value(module, &value, func_ctx)?;
@@ -238,8 +299,9 @@ impl<W: fmt::Write> super::Writer<'_, W> {
             crate::TypeInner::Vector { size, .. } => {
                 // working around the borrow checker in `self.write_expr`
                 let chain = mem::take(&mut self.temp_access_chain);
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                write!(self.out, "{}{}.Store{}(", level, var_name, size as u8)?;
+                write!(self.out, "{}", level)?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Store{}(", size as u8)?;
                 self.write_storage_address(module, &chain, func_ctx)?;
                 write!(self.out, ", asuint(")?;
                 self.write_store_value(module, &value, func_ctx)?;
@@ -258,7 +320,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                     self.out,
                     "{}{}{}x{} {}{} = ",
                     level.next(),
-                    crate::ScalarKind::Float.to_hlsl_str(width)?,
+                    self.scalar_type_str(crate::ScalarKind::Float, width)?,
                     rows as u8,
                     columns as u8,
                     STORE_TEMP_NAME,
@@ -288,7 +350,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                         index: i,
                         ty: TypeResolution::Value(ty_inner),
                     };
-                    self.write_storage_store(module, var_handle, sv, func_ctx, level.next())?;
+                    self.write_storage_store(module, buffer, sv, func_ctx, level.next())?;
                     self.temp_access_chain.pop();
                 }
                 // done
@@ -319,7 +381,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                         index: i,
                         ty: TypeResolution::Handle(base),
                     };
-                    self.write_storage_store(module, var_handle, sv, func_ctx, level.next())?;
+                    self.write_storage_store(module, buffer, sv, func_ctx, level.next())?;
                     self.temp_access_chain.pop();
                 }
                 // done
@@ -350,7 +412,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                         base: struct_ty,
                         member_index: i as u32,
                     };
-                    self.write_storage_store(module, var_handle, sv, func_ctx, level.next())?;
+                    self.write_storage_store(module, buffer, sv, func_ctx, level.next())?;
                     self.temp_access_chain.pop();
                 }
                 // done
@@ -366,7 +428,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
         module: &crate::Module,
         mut cur_expr: Handle<crate::Expression>,
         func_ctx: &FunctionCtx,
-    ) -> Result<Handle<crate::GlobalVariable>, Error> {
+    ) -> Result<StorageBuffer, Error> {
         enum AccessIndex {
             Expression(Handle<crate::Expression>),
             Constant(u32),
@@ -374,12 +436,19 @@ impl<W: fmt::Write> super::Writer<'_, W> {
         enum Parent<'a> {
             Array { stride: u32 },
             Struct(&'a [crate::StructMember]),
+            BindingArray,
         }
         self.temp_access_chain.clear();
+        let mut buffer_index = None;
 
         loop {
             let (next_expr, access_index) = match func_ctx.expressions[cur_expr] {
-                crate::Expression::GlobalVariable(handle) => return Ok(handle),
+                crate::Expression::GlobalVariable(handle) => {
+                    return Ok(StorageBuffer {
+                        var_handle: handle,
+                        index: buffer_index,
+                    })
+                }
                 crate::Expression::Access { base, index } => (base, AccessIndex::Expression(index)),
                 crate::Expression::AccessIndex { base, index } => {
                     (base, AccessIndex::Constant(index))
@@ -396,6 +465,7 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                 crate::TypeInner::Pointer { base, .. } => match module.types[base].inner {
                     crate::TypeInner::Struct { ref members, .. } => Parent::Struct(members),
                     crate::TypeInner::Array { stride, .. } => Parent::Array { stride },
+                    crate::TypeInner::BindingArray { .. } => Parent::BindingArray,
                     crate::TypeInner::Vector { width, .. } => Parent::Array {
                         stride: width as u32,
                     },
@@ -411,6 +481,17 @@ impl<W: fmt::Write> super::Writer<'_, W> {
             };
 
             let sub = match (parent, access_index) {
+                // selects the buffer instead of an offset within it
+                (Parent::BindingArray, AccessIndex::Expression(value)) => {
+                    buffer_index = Some(GuardedIndex::Expression(value));
+                    cur_expr = next_expr;
+                    continue;
+                }
+                (Parent::BindingArray, AccessIndex::Constant(index)) => {
+                    buffer_index = Some(GuardedIndex::Known(index));
+                    cur_expr = next_expr;
+                    continue;
+                }
                 (Parent::Array { stride }, AccessIndex::Expression(value)) => {
                     SubAccess::Index { value, stride }
                 }
@@ -1,7 +1,7 @@
 use super::{
     help::{MipLevelCoordinate, WrappedArrayLength, WrappedConstructor, WrappedImageQuery},
     storage::StoreValue,
-    BackendResult, Error, Options,
+    BackendResult, Error, Options, ShaderModel,
 };
 use crate::{
     back,
@@ -17,24 +17,24 @@ const SPECIAL_BASE_VERTEX: &str = "base_vertex";
 const SPECIAL_BASE_INSTANCE: &str = "base_instance";
 const SPECIAL_OTHER: &str = "other";
 
-struct EpStructMember {
-    name: String,
-    ty: Handle<crate::Type>,
+pub(super) struct EpStructMember {
+    pub(super) name: String,
+    pub(super) ty: Handle<crate::Type>,
     // technically, this should always be `Some`
-    binding: Option<crate::Binding>,
-    index: u32,
+    pub(super) binding: Option<crate::Binding>,
+    pub(super) index: u32,
 }
 
 /// Structure contains information required for generating
 /// wrapped structure of all entry points arguments
-struct EntryPointBinding {
+pub(super) struct EntryPointBinding {
     /// Name of the fake EP argument that contains the struct
     /// with all the flattened input data.
-    arg_name: String,
+    pub(super) arg_name: String,
     /// Generated structure name
-    ty_name: String,
+    pub(super) ty_name: String,
     /// Members of generated structure
-    members: Vec<EpStructMember>,
+    pub(super) members: Vec<EpStructMember>,
 }
 
 pub(super) struct EntryPointInterface {
@@ -47,6 +47,8 @@ pub(super) struct EntryPointInterface {
     /// The `EntryPointBinding::members` array is sorted by binding,
     /// So that we can walk it in `Statement::Return` handler.
     output: Option<EntryPointBinding>,
+    /// If `Some`, the entry point is a mesh shader with these output arguments.
+    pub(super) mesh: Option<super::mesh::MeshInterface>,
 }
 
 #[derive(Clone, Eq, PartialEq, PartialOrd, Ord)]
@@ -67,11 +69,65 @@ impl InterfaceKey {
 }
 
 #[derive(Copy, Clone, PartialEq)]
-enum Io {
+pub(super) enum Io {
     Input,
     Output,
 }
 
+/// Return true if the binding is a built-in that HLSL only exposes through
+/// intrinsics, and which therefore has no entry point argument.
+fn is_subgroup_builtin_binding(binding: &Option<crate::Binding>) -> bool {
+    match *binding {
+        Some(crate::Binding::BuiltIn(crate::BuiltIn::SubgroupSize))
+        | Some(crate::Binding::BuiltIn(crate::BuiltIn::SubgroupInvocationId)) => true,
+        _ => false,
+    }
+}
+
+/// Add to `images` the global storage images that [`ImageAtomic`] statements
+/// of `block`, or of its nested blocks, operate on.
+///
+/// [`ImageAtomic`]: crate::Statement::ImageAtomic
+fn collect_atomic_images(
+    block: &crate::Block,
+    function: &crate::Function,
+    images: &mut crate::FastHashSet<Handle<crate::GlobalVariable>>,
+) {
+    use crate::Statement as S;
+
+    for statement in block.iter() {
+        match *statement {
+            S::ImageAtomic { image, .. } => {
+                if let crate::Expression::GlobalVariable(handle) = function.expressions[image] {
+                    images.insert(handle);
+                }
+            }
+            S::Block(ref block) => collect_atomic_images(block, function, images),
+            S::If {
+                ref accept,
+                ref reject,
+                ..
+            } => {
+                collect_atomic_images(accept, function, images);
+                collect_atomic_images(reject, function, images);
+            }
+            S::Switch { ref cases, .. } => {
+                for case in cases.iter() {
+                    collect_atomic_images(&case.body, function, images);
+                }
+            }
+            S::Loop {
+                ref body,
+                ref continuing,
+            } => {
+                collect_atomic_images(body, function, images);
+                collect_atomic_images(continuing, function, images);
+            }
+            _ => {}
+        }
+    }
+}
+
 impl<'a, W: fmt::Write> super::Writer<'a, W> {
     pub fn new(out: W, options: &'a Options) -> Self {
         Self {
@@ -83,6 +139,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             named_expressions: crate::NamedExpressions::default(),
             wrapped: super::Wrapped::default(),
             temp_access_chain: Vec::new(),
+            atomic_images: crate::FastHashSet::default(),
         }
     }
 
@@ -93,6 +150,28 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         self.entry_point_io.clear();
         self.named_expressions.clear();
         self.wrapped.clear();
+        self.atomic_images.clear();
+        for function in module
+            .functions
+            .iter()
+            .map(|(_, f)| f)
+            .chain(module.entry_points.iter().map(|ep| &ep.function))
+        {
+            collect_atomic_images(&function.body, function, &mut self.atomic_images);
+        }
+    }
+
+    /// Return true if `image` is a storage image used atomically, declared
+    /// with scalar texels.
+    fn is_atomic_image(
+        &self,
+        image: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx,
+    ) -> bool {
+        match func_ctx.expressions[image] {
+            crate::Expression::GlobalVariable(handle) => self.atomic_images.contains(&handle),
+            _ => false,
+        }
     }
 
     pub fn write(
@@ -182,6 +261,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
         }
 
+        self.write_ray_query_functions(module)?;
+
         // Write all globals
         for (ty, _) in module.global_variables.iter() {
             self.write_global(module, ty)?;
@@ -192,9 +273,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             writeln!(self.out)?;
         }
 
+        self.write_task_payload(module)?;
+
         // Write all entry points wrapped structs
         for ep in module.entry_points.iter() {
-            let ep_io = self.write_ep_interface(module, &ep.function, ep.stage, &ep.name)?;
+            let ep_io = self.write_ep_interface(module, ep)?;
             self.entry_point_io.push(ep_io);
         }
 
@@ -278,7 +361,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             // before writing all statements and expressions.
             self.write_wrapped_functions(module, &ctx)?;
 
-            if ep.stage == ShaderStage::Compute {
+            if ep.stage.has_workgroups() {
                 // HLSL is calling workgroup size "num threads"
                 let num_threads = ep.workgroup_size;
                 writeln!(
@@ -287,6 +370,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     num_threads[0], num_threads[1], num_threads[2]
                 )?;
             }
+            if ep.stage == ShaderStage::Task || ep.stage == ShaderStage::Mesh {
+                self.write_mesh_attributes(ep)?;
+            }
 
             let name = self.names[&NameKey::EntryPoint(index as u16)].clone();
             self.write_function(module, &name, &ep.function, &ctx)?;
@@ -309,13 +395,25 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         stage: Option<(ShaderStage, Io)>,
     ) -> BackendResult {
         match *binding {
+            // The task result and the primitive indices are written
+            // without a semantic, see `mesh.rs`.
+            crate::Binding::BuiltIn(crate::BuiltIn::MeshTaskSize)
+            | crate::Binding::BuiltIn(crate::BuiltIn::PointIndex)
+            | crate::Binding::BuiltIn(crate::BuiltIn::LineIndices)
+            | crate::Binding::BuiltIn(crate::BuiltIn::TriangleIndices) => {}
             crate::Binding::BuiltIn(builtin) => {
                 let builtin_str = builtin.to_hlsl_str()?;
                 write!(self.out, " : {}", builtin_str)?;
             }
-            crate::Binding::Location { location, .. } => {
+            crate::Binding::Location {
+                location,
+                blend_src,
+                ..
+            } => {
                 if stage == Some((crate::ShaderStage::Fragment, Io::Output)) {
-                    write!(self.out, " : SV_Target{}", location)?;
+                    // The second blend source is bound to the next target.
+                    let target = location + blend_src.unwrap_or(0);
+                    write!(self.out, " : SV_Target{}", target)?;
                 } else {
                     write!(self.out, " : {}{}", LOCATION_SEMANTIC, location)?;
                 }
@@ -325,7 +423,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
-    fn write_interface_struct(
+    /// Writes the `precise` modifier of an invariant position, if `binding` is one.
+    fn write_invariant_modifier(&mut self, binding: Option<&crate::Binding>) -> BackendResult {
+        if let Some(&crate::Binding::BuiltIn(crate::BuiltIn::Position { invariant: true })) =
+            binding
+        {
+            write!(self.out, "precise ")?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_interface_struct(
         &mut self,
         module: &Module,
         shader_stage: (ShaderStage, Io),
@@ -341,6 +449,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         writeln!(self.out, " {{")?;
         for m in members.iter() {
             write!(self.out, "{}", back::INDENT)?;
+            self.write_invariant_modifier(m.binding.as_ref())?;
             self.write_type(module, m.ty)?;
             write!(self.out, " {}", &m.name)?;
             if let Some(ref binding) = m.binding {
@@ -382,6 +491,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
         let mut fake_members = Vec::new();
         for arg in func.arguments.iter() {
+            if is_subgroup_builtin_binding(&arg.binding) {
+                continue;
+            }
             match module.types[arg.ty].inner {
                 TypeInner::Struct { ref members, .. } => {
                     for member in members.iter() {
@@ -453,10 +565,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     fn write_ep_interface(
         &mut self,
         module: &Module,
-        func: &crate::Function,
-        stage: ShaderStage,
-        ep_name: &str,
+        ep: &crate::EntryPoint,
     ) -> Result<EntryPointInterface, Error> {
+        let (func, stage, ep_name) = (&ep.function, ep.stage, ep.name.as_str());
         Ok(EntryPointInterface {
             input: if !func.arguments.is_empty() && stage == ShaderStage::Fragment {
                 Some(self.write_ep_input_struct(module, func, stage, ep_name)?)
@@ -469,6 +580,10 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 }
                 _ => None,
             },
+            mesh: match ep.mesh_info {
+                Some(ref mesh_info) => Some(self.write_mesh_interface(module, mesh_info, ep_name)?),
+                None => None,
+            },
         })
     }
 
@@ -485,6 +600,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         };
         let mut fake_iter = ep_input.members.iter();
         for (arg_index, arg) in func.arguments.iter().enumerate() {
+            if is_subgroup_builtin_binding(&arg.binding) {
+                continue;
+            }
             write!(self.out, "{}", back::INDENT)?;
             self.write_type(module, arg.ty)?;
             let arg_name = &self.names[&NameKey::EntryPointArgument(ep_index, arg_index as u32)];
@@ -553,9 +671,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 ""
             }
             crate::StorageClass::Uniform => {
-                // constant buffer declarations are expected to be inlined, e.g.
-                // `cbuffer foo: register(b0) { field1: type1; }`
-                write!(self.out, "cbuffer")?;
+                if let TypeInner::BindingArray { base, .. } = *inner {
+                    // arrays of constant buffers can't be inlined, e.g.
+                    // `ConstantBuffer<type> foo[2]: register(b0);`
+                    write!(self.out, "ConstantBuffer<")?;
+                    self.write_type(module, base)?;
+                    write!(self.out, ">")?;
+                } else {
+                    // constant buffer declarations are expected to be inlined, e.g.
+                    // `cbuffer foo: register(b0) { field1: type1; }`
+                    write!(self.out, "cbuffer")?;
+                }
                 "b"
             }
             crate::StorageClass::Storage { access } => {
@@ -568,7 +694,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 register
             }
             crate::StorageClass::Handle => {
-                let register = match *inner {
+                let handle_inner = match *inner {
+                    TypeInner::BindingArray { base, .. } => &module.types[base].inner,
+                    ref other => other,
+                };
+                let register = match *handle_inner {
                     TypeInner::Sampler { .. } => "s",
                     // all storage textures are UAV, unconditionally
                     TypeInner::Image {
@@ -577,7 +707,26 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     } => "u",
                     _ => "t",
                 };
-                self.write_type(module, global.ty)?;
+                match *inner {
+                    // `Interlocked*` functions only operate on scalar texels
+                    TypeInner::Image {
+                        dim,
+                        arrayed,
+                        class: crate::ImageClass::Storage { format, .. },
+                    } if self.atomic_images.contains(&handle) => {
+                        let arrayed_str = if arrayed { "Array" } else { "" };
+                        write!(
+                            self.out,
+                            "RWTexture{}{}<{}>",
+                            dim.to_hlsl_str(),
+                            arrayed_str,
+                            format
+                                .to_hlsl_atomic_str()
+                                .unwrap_or_else(|| format.to_hlsl_str()),
+                        )?;
+                    }
+                    _ => self.write_type(module, global.ty)?,
+                }
                 register
             }
             crate::StorageClass::PushConstant => unimplemented!("Push constants"),
@@ -585,8 +734,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
         let name = &self.names[&NameKey::GlobalVariable(handle)];
         write!(self.out, " {}", name)?;
-        if let TypeInner::Array { size, .. } = module.types[global.ty].inner {
-            self.write_array_size(module, size)?;
+        match module.types[global.ty].inner {
+            TypeInner::Array { size, .. } => self.write_array_size(module, size)?,
+            TypeInner::BindingArray { size, .. } => match size {
+                crate::ArraySize::Constant(_) => self.write_array_size(module, size)?,
+                // unbounded resource arrays are declared with empty brackets
+                crate::ArraySize::Dynamic => write!(self.out, "[]")?,
+            },
+            _ => {}
         }
 
         if let Some(ref binding) = global.binding {
@@ -606,7 +761,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
         }
 
-        if global.class == crate::StorageClass::Uniform {
+        let is_binding_array = matches!(*inner, TypeInner::BindingArray { .. });
+        if global.class == crate::StorageClass::Uniform && !is_binding_array {
             write!(self.out, " {{ ")?;
             self.write_type(module, global.ty)?;
             let name = &self.names[&NameKey::GlobalVariable(handle)];
@@ -630,23 +786,21 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ) -> BackendResult {
         write!(self.out, "static const ")?;
         match *inner {
-            crate::ConstantInner::Scalar {
-                width: _,
-                ref value,
-            } => {
+            crate::ConstantInner::Scalar { width, ref value } => {
                 // Write type
-                let ty_str = match *value {
-                    crate::ScalarValue::Sint(_) => "int",
-                    crate::ScalarValue::Uint(_) => "uint",
-                    crate::ScalarValue::Float(_) => "float",
-                    crate::ScalarValue::Bool(_) => "bool",
-                };
+                let ty_str = self.scalar_type_str(value.scalar_kind(), width)?;
                 let name = &self.names[&NameKey::Constant(handle)];
                 write!(self.out, "{} {} = ", ty_str, name)?;
 
                 // Second match required to avoid heap allocation by `format!()`
                 match *value {
+                    crate::ScalarValue::Sint(value) if width == 8 => {
+                        write!(self.out, "{}L", value)?
+                    }
                     crate::ScalarValue::Sint(value) => write!(self.out, "{}", value)?,
+                    crate::ScalarValue::Uint(value) if width == 8 => {
+                        write!(self.out, "{}uL", value)?
+                    }
                     crate::ScalarValue::Uint(value) => write!(self.out, "{}", value)?,
                     crate::ScalarValue::Float(value) => {
                         // Floats are written using `Debug` instead of `Display` because it always appends the
@@ -747,6 +901,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         }
                     }
 
+                    self.write_invariant_modifier(member.binding.as_ref())?;
+
                     if let TypeInner::Matrix { .. } = module.types[member.ty].inner {
                         write!(self.out, "row_major ")?;
                     }
@@ -780,13 +936,38 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         match *inner {
             TypeInner::Struct { .. } => write!(self.out, "{}", self.names[&NameKey::Type(ty)])?,
             // hlsl array has the size separated from the base type
-            TypeInner::Array { base, .. } => self.write_type(module, base)?,
+            TypeInner::Array { base, .. } | TypeInner::BindingArray { base, .. } => {
+                self.write_type(module, base)?
+            }
             ref other => self.write_value_type(module, other)?,
         }
 
         Ok(())
     }
 
+    /// Helper method that returns the name of a scalar type
+    ///
+    /// # Notes
+    /// Native 16-bit floats require shader model 6.2, older shader models get the
+    /// `min16float` minimum precision type instead.
+    pub(super) fn scalar_type_str(
+        &self,
+        kind: crate::ScalarKind,
+        width: crate::Bytes,
+    ) -> Result<&'static str, Error> {
+        match (kind, width) {
+            (crate::ScalarKind::Float, 2) if self.options.shader_model < ShaderModel::V6_2 => {
+                Ok("min16float")
+            }
+            (crate::ScalarKind::Sint, 8) | (crate::ScalarKind::Uint, 8)
+                if self.options.shader_model < ShaderModel::V6_0 =>
+            {
+                Err(Error::UnsupportedScalar(kind, width))
+            }
+            _ => kind.to_hlsl_str(width),
+        }
+    }
+
     /// Helper method used to write value types
     ///
     /// # Notes
@@ -794,13 +975,13 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     pub(super) fn write_value_type(&mut self, module: &Module, inner: &TypeInner) -> BackendResult {
         match *inner {
             TypeInner::Scalar { kind, width } | TypeInner::Atomic { kind, width } => {
-                write!(self.out, "{}", kind.to_hlsl_str(width)?)?;
+                write!(self.out, "{}", self.scalar_type_str(kind, width)?)?;
             }
             TypeInner::Vector { size, kind, width } => {
                 write!(
                     self.out,
                     "{}{}",
-                    kind.to_hlsl_str(width)?,
+                    self.scalar_type_str(kind, width)?,
                     back::vector_size_str(size)
                 )?;
             }
@@ -816,7 +997,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 write!(
                     self.out,
                     "{}{}x{}",
-                    crate::ScalarKind::Float.to_hlsl_str(width)?,
+                    self.scalar_type_str(crate::ScalarKind::Float, width)?,
                     back::vector_size_str(rows),
                     back::vector_size_str(columns),
                 )?;
@@ -836,6 +1017,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 };
                 write!(self.out, "{}", sampler)?;
             }
+            TypeInner::AccelerationStructure => {
+                write!(self.out, "RaytracingAccelerationStructure")?;
+            }
+            TypeInner::RayQuery => {
+                write!(self.out, "{}", super::ray::RAY_QUERY_TYPE)?;
+            }
             // HLSL arrays are written as `type name[size]`
             // Current code is written arrays only as `[size]`
             // Base `type` and `name` should be written outside
@@ -863,8 +1050,18 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         func: &crate::Function,
         func_ctx: &back::FunctionCtx<'_>,
     ) -> BackendResult {
+        // Task shaders dispatch the mesh workgroups instead of returning their count
+        let result = match func_ctx.ty {
+            back::FunctionType::EntryPoint(index)
+                if module.entry_points[index as usize].stage == ShaderStage::Task =>
+            {
+                None
+            }
+            _ => func.result.as_ref(),
+        };
+
         // Function Declaration Syntax - https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-function-syntax
-        if let Some(ref result) = func.result {
+        if let Some(result) = result {
             match func_ctx.ty {
                 back::FunctionType::Function(_) => {
                     self.write_type(module, result.ty)?;
@@ -873,6 +1070,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     if let Some(ref ep_output) = self.entry_point_io[index as usize].output {
                         write!(self.out, "{}", ep_output.ty_name)?;
                     } else {
+                        self.write_invariant_modifier(result.binding.as_ref())?;
                         self.write_type(module, result.ty)?;
                     }
                 }
@@ -918,10 +1116,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     write!(self.out, "{} {}", ep_input.ty_name, ep_input.arg_name,)?;
                 } else {
                     let stage = module.entry_points[ep_index as usize].stage;
-                    for (index, arg) in func.arguments.iter().enumerate() {
-                        if index != 0 {
+                    let arguments = func
+                        .arguments
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, arg)| !is_subgroup_builtin_binding(&arg.binding));
+                    let mut separator = false;
+                    for (index, arg) in arguments {
+                        if separator {
                             write!(self.out, ", ")?;
                         }
+                        separator = true;
                         self.write_type(module, arg.ty)?;
 
                         let argument_name =
@@ -936,6 +1141,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                             self.write_semantic(binding, Some((stage, Io::Input)))?;
                         }
                     }
+                    self.write_mesh_arguments(module, ep_index, separator)?;
                 }
             }
         }
@@ -945,10 +1151,10 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         // Write semantic if it present
         if let back::FunctionType::EntryPoint(index) = func_ctx.ty {
             let stage = module.entry_points[index as usize].stage;
-            if let Some(crate::FunctionResult {
+            if let Some(&crate::FunctionResult {
                 binding: Some(ref binding),
                 ..
-            }) = func.result
+            }) = result
             {
                 self.write_semantic(binding, Some((stage, Io::Output)))?;
             }
@@ -976,6 +1182,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 self.write_array_size(module, size)?;
             }
 
+            // Ray queries can't be initialized, they are set up by `TraceRayInline`
+            if let TypeInner::RayQuery = module.types[local.ty].inner {
+                writeln!(self.out, ";")?;
+                continue;
+            }
+
             write!(self.out, " = ")?;
             // Write the local initializer if needed
             if let Some(init) = local.init {
@@ -1100,6 +1312,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             Statement::Return { value: None } => {
                 writeln!(self.out, "{}return;", level)?;
             }
+            Statement::Return { value: Some(expr) }
+                if super::mesh::is_task_entry_point(module, func_ctx) =>
+            {
+                // The workgroup count is used three times, so make sure it's evaluated once.
+                if !self.named_expressions.contains_key(&expr) {
+                    let name = format!("{}{}", back::BAKE_PREFIX, expr.index());
+                    write!(self.out, "{}", level)?;
+                    self.write_named_expr(module, expr, name, func_ctx)?;
+                }
+                self.write_task_return(module, expr, func_ctx, level)?;
+            }
             Statement::Return { value: Some(expr) } => {
                 let base_ty_res = &func_ctx.info[expr].ty;
                 let mut resolved = base_ty_res.inner_with(&module.types);
@@ -1168,10 +1391,10 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 };
 
                 if let Some(crate::StorageClass::Storage { .. }) = ty_inner.pointer_class() {
-                    let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
+                    let buffer = self.fill_access_chain(module, pointer, func_ctx)?;
                     self.write_storage_store(
                         module,
-                        var_handle,
+                        buffer,
                         StoreValue::Expression(value),
                         func_ctx,
                         level,
@@ -1235,6 +1458,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 if barrier.contains(crate::Barrier::WORK_GROUP) {
                     writeln!(self.out, "{}GroupMemoryBarrierWithGroupSync();", level)?;
                 }
+
+                // There is no subgroup barrier in HLSL, since the invocations
+                // of a wave are always executed together.
             }
             Statement::ImageStore {
                 image,
@@ -1259,9 +1485,58 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 write!(self.out, "]")?;
 
                 write!(self.out, " = ")?;
-                self.write_expr(module, value, func_ctx)?;
+                if self.is_atomic_image(image, func_ctx) {
+                    // images used atomically have scalar texels
+                    write!(self.out, "(")?;
+                    self.write_expr(module, value, func_ctx)?;
+                    write!(self.out, ").x")?;
+                } else {
+                    self.write_expr(module, value, func_ctx)?;
+                }
                 writeln!(self.out, ";")?;
             }
+            Statement::ImageAtomic {
+                image,
+                coordinate,
+                array_index,
+                ref fun,
+                value,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                match func_ctx.info[result].ty {
+                    proc::TypeResolution::Handle(handle) => self.write_type(module, handle)?,
+                    proc::TypeResolution::Value(ref value) => {
+                        self.write_value_type(module, value)?
+                    }
+                };
+                write!(
+                    self.out,
+                    " {}; Interlocked{}(",
+                    res_name,
+                    fun.to_hlsl_suffix()
+                )?;
+                self.write_expr(module, image, func_ctx)?;
+                write!(self.out, "[")?;
+                if let Some(index) = array_index {
+                    write!(self.out, "int3(")?;
+                    self.write_expr(module, coordinate, func_ctx)?;
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, index, func_ctx)?;
+                    write!(self.out, ")")?;
+                } else {
+                    self.write_expr(module, coordinate, func_ctx)?;
+                }
+                write!(self.out, "], ")?;
+                if let crate::AtomicFunction::Subtract = *fun {
+                    // we just wrote `InterlockedAdd`, so negate the argument
+                    write!(self.out, "-")?;
+                }
+                self.write_expr(module, value, func_ctx)?;
+                writeln!(self.out, ", {});", res_name)?;
+                self.named_expressions.insert(result, res_name);
+            }
             Statement::Call {
                 function,
                 ref arguments,
@@ -1308,17 +1583,26 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     }
                 };
 
-                let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
+                let buffer = self.fill_access_chain(module, pointer, func_ctx)?;
                 // working around the borrow checker in `self.write_expr`
                 let chain = mem::take(&mut self.temp_access_chain);
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
 
                 let fun_str = fun.to_hlsl_suffix();
-                write!(
-                    self.out,
-                    " {}; {}.Interlocked{}(",
-                    res_name, var_name, fun_str
-                )?;
+                // 64-bit atomics use the `InterlockedXxx64` methods (SM 6.6)
+                let width_str = match *func_ctx.info[value].ty.inner_with(&module.types) {
+                    TypeInner::Scalar { width: 8, .. } => {
+                        if self.options.shader_model < ShaderModel::V6_6 {
+                            return Err(Error::Custom(
+                                "64-bit atomics require shader model 6.6".to_string(),
+                            ));
+                        }
+                        "64"
+                    }
+                    _ => "",
+                };
+                write!(self.out, " {}; ", res_name)?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ".Interlocked{}{}(", fun_str, width_str)?;
                 self.write_storage_address(module, &chain, func_ctx)?;
                 write!(self.out, ", ")?;
                 // handle the special cases
@@ -1327,16 +1611,176 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         // we just wrote `InterlockedAdd`, so negate the argument
                         write!(self.out, "-")?;
                     }
-                    crate::AtomicFunction::Exchange { compare: Some(_) } => {
-                        return Err(Error::Unimplemented("atomic CompareExchange".to_string()));
+                    crate::AtomicFunction::Exchange { compare: Some(cmp) } => {
+                        self.write_expr(module, cmp, func_ctx)?;
+                        write!(self.out, ", ")?;
                     }
                     _ => {}
                 }
                 self.write_expr(module, value, func_ctx)?;
-                writeln!(self.out, ", {});", res_name)?;
+                match *fun {
+                    crate::AtomicFunction::Exchange { compare: Some(cmp) } => {
+                        // The original value is written to the first member of the
+                        // result, and the second one tells if the exchange happened.
+                        let ty = match func_ctx.info[result].ty {
+                            proc::TypeResolution::Handle(handle) => handle,
+                            proc::TypeResolution::Value(_) => unreachable!(),
+                        };
+                        let old_value = &self.names[&NameKey::StructMember(ty, 0)];
+                        let exchanged = &self.names[&NameKey::StructMember(ty, 1)];
+                        write!(
+                            self.out,
+                            ", {}.{}); {}.{} = ({}.{} == ",
+                            res_name, old_value, res_name, exchanged, res_name, old_value
+                        )?;
+                        self.write_expr(module, cmp, func_ctx)?;
+                        writeln!(self.out, ");")?;
+                    }
+                    _ => writeln!(self.out, ", {});", res_name)?,
+                }
                 self.temp_access_chain = chain;
                 self.named_expressions.insert(result, res_name);
             }
+            Statement::SubgroupBallot { result, predicate } => {
+                self.write_subgroup_result(module, result, func_ctx, level)?;
+                write!(self.out, "WaveActiveBallot(")?;
+                match predicate {
+                    Some(predicate) => self.write_expr(module, predicate, func_ctx)?,
+                    None => write!(self.out, "true")?,
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::RayQuery { query, ref fun } => {
+                self.require_ray_query()?;
+                match *fun {
+                    crate::RayQueryFunction::Initialize {
+                        acceleration_structure,
+                        descriptor,
+                    } => {
+                        // The descriptor is used three times, so make sure it's evaluated once.
+                        if !self.named_expressions.contains_key(&descriptor) {
+                            let name = format!("{}{}", back::BAKE_PREFIX, descriptor.index());
+                            write!(self.out, "{}", level)?;
+                            self.write_named_expr(module, descriptor, name, func_ctx)?;
+                        }
+                        write!(self.out, "{}", level)?;
+                        self.write_expr(module, query, func_ctx)?;
+                        write!(self.out, ".TraceRayInline(")?;
+                        self.write_expr(module, acceleration_structure, func_ctx)?;
+                        write!(self.out, ", ")?;
+                        self.write_expr(module, descriptor, func_ctx)?;
+                        write!(self.out, ".flags, ")?;
+                        self.write_expr(module, descriptor, func_ctx)?;
+                        write!(self.out, ".cull_mask, ")?;
+                        self.write_ray_desc_function_name()?;
+                        write!(self.out, "(")?;
+                        self.write_expr(module, descriptor, func_ctx)?;
+                        writeln!(self.out, "));")?;
+                    }
+                    crate::RayQueryFunction::Proceed { result } => {
+                        write!(self.out, "{}const bool ", level)?;
+                        let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                        write!(self.out, "{} = ", res_name)?;
+                        self.named_expressions.insert(result, res_name);
+                        self.write_expr(module, query, func_ctx)?;
+                        writeln!(self.out, ".Proceed();")?;
+                    }
+                }
+            }
+            Statement::MeshFunction(ref fun) => {
+                let ep_index = match func_ctx.ty {
+                    back::FunctionType::EntryPoint(index) => index,
+                    back::FunctionType::Function(_) => {
+                        return Err(Error::Unimplemented(
+                            "mesh functions outside of entry points".to_string(),
+                        ))
+                    }
+                };
+                // The primitive is split between several outputs, so make sure it's evaluated once.
+                if let crate::MeshFunction::SetPrimitive { value, .. } = *fun {
+                    if !self.named_expressions.contains_key(&value) {
+                        let name = format!("{}{}", back::BAKE_PREFIX, value.index());
+                        write!(self.out, "{}", level)?;
+                        self.write_named_expr(module, value, name, func_ctx)?;
+                    }
+                }
+                self.write_mesh_function(module, fun, ep_index, func_ctx, level)?;
+            }
+            Statement::SubgroupElect { result } => {
+                self.write_subgroup_result(module, result, func_ctx, level)?;
+                writeln!(self.out, "WaveIsFirstLane();")?;
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                self.write_subgroup_result(module, result, func_ctx, level)?;
+                match mode {
+                    crate::GatherMode::BroadcastFirst => {
+                        write!(self.out, "WaveReadLaneFirst(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                    }
+                    crate::GatherMode::Broadcast(index) | crate::GatherMode::Shuffle(index) => {
+                        write!(self.out, "WaveReadLaneAt(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ", ")?;
+                        self.write_expr(module, index, func_ctx)?;
+                    }
+                    crate::GatherMode::ShuffleDown(index)
+                    | crate::GatherMode::ShuffleUp(index)
+                    | crate::GatherMode::ShuffleXor(index) => {
+                        let op = match mode {
+                            crate::GatherMode::ShuffleDown(_) => "+",
+                            crate::GatherMode::ShuffleUp(_) => "-",
+                            _ => "^",
+                        };
+                        write!(self.out, "WaveReadLaneAt(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ", WaveGetLaneIndex() {} ", op)?;
+                        self.write_expr(module, index, func_ctx)?;
+                    }
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                use crate::{CollectiveOperation as Co, SubgroupOperation as So};
+
+                self.write_subgroup_result(module, result, func_ctx, level)?;
+                let fun_str = match (collective_op, op) {
+                    (Co::Reduce, So::All) => "WaveActiveAllTrue",
+                    (Co::Reduce, So::Any) => "WaveActiveAnyTrue",
+                    (Co::Reduce, So::Add) => "WaveActiveSum",
+                    (Co::Reduce, So::Mul) => "WaveActiveProduct",
+                    (Co::Reduce, So::Min) => "WaveActiveMin",
+                    (Co::Reduce, So::Max) => "WaveActiveMax",
+                    (Co::Reduce, So::And) => "WaveActiveBitAnd",
+                    (Co::Reduce, So::Or) => "WaveActiveBitOr",
+                    (Co::Reduce, So::Xor) => "WaveActiveBitXor",
+                    (_, So::Add) => "WavePrefixSum",
+                    (_, So::Mul) => "WavePrefixProduct",
+                    _ => {
+                        return Err(Error::Custom(format!(
+                            "unsupported {:?} {:?}",
+                            collective_op, op
+                        )))
+                    }
+                };
+                write!(self.out, "{}(", fun_str)?;
+                self.write_expr(module, argument, func_ctx)?;
+                write!(self.out, ")")?;
+                // HLSL only has exclusive scans
+                if collective_op == Co::InclusiveScan {
+                    write!(self.out, " {} ", if op == So::Add { "+" } else { "*" })?;
+                    self.write_expr(module, argument, func_ctx)?;
+                }
+                writeln!(self.out, ";")?;
+            }
             Statement::Switch {
                 selector,
                 ref cases,
@@ -1413,18 +1857,24 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ) -> BackendResult {
         use crate::Expression;
 
-        // Handle the special semantics for base vertex/instance
-        let ff_input = if self.options.special_constants_binding.is_some() {
-            func_ctx.is_fixed_function_input(expr, module)
-        } else {
-            None
-        };
+        // Handle the special semantics for base vertex/instance, and the
+        // built-ins that are only available through intrinsics
+        let ff_input = func_ctx.is_fixed_function_input(expr, module);
+        let has_special_constants = self.options.special_constants_binding.is_some();
         let closing_bracket = match ff_input {
-            Some(crate::BuiltIn::VertexIndex) => {
+            Some(crate::BuiltIn::SubgroupSize) => {
+                write!(self.out, "WaveGetLaneCount()")?;
+                return Ok(());
+            }
+            Some(crate::BuiltIn::SubgroupInvocationId) => {
+                write!(self.out, "WaveGetLaneIndex()")?;
+                return Ok(());
+            }
+            Some(crate::BuiltIn::VertexIndex) if has_special_constants => {
                 write!(self.out, "({}.{} + ", SPECIAL_CBUF_VAR, SPECIAL_BASE_VERTEX)?;
                 ")"
             }
-            Some(crate::BuiltIn::InstanceIndex) => {
+            Some(crate::BuiltIn::InstanceIndex) if has_special_constants => {
                 write!(
                     self.out,
                     "({}.{} + ",
@@ -1432,7 +1882,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 )?;
                 ")"
             }
-            Some(crate::BuiltIn::NumWorkGroups) => {
+            Some(crate::BuiltIn::NumWorkGroups) if has_special_constants => {
                 //Note: despite their names (`BASE_VERTEX` and `BASE_INSTANCE`),
                 // in compute shaders the special constants contain the number
                 // of workgroups, which we are using here.
@@ -1552,6 +2002,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         }
                         TypeInner::Matrix { .. }
                         | TypeInner::Array { .. }
+                        | TypeInner::BindingArray { .. }
                         | TypeInner::ValuePointer { .. } => write!(self.out, "[{}]", index)?,
                         TypeInner::Struct { .. } => {
                             // This will never panic in case the type is a `Struct`, this is not true
@@ -1693,7 +2144,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     TypeInner::Image { class, .. } => match class {
                         crate::ImageClass::Sampled { multi, .. }
                         | crate::ImageClass::Depth { multi } => (multi, false),
-                        crate::ImageClass::Storage { .. } => (false, true),
+                        crate::ImageClass::Storage { format, .. } => {
+                            // images used atomically have scalar texels, so
+                            // expand them to `(v, 0, 0, 1)` like other loads
+                            if self.is_atomic_image(image, func_ctx) {
+                                write!(self.out, "{}(", format.to_hlsl_str())?;
+                            }
+                            (false, true)
+                        }
                     },
                     _ => (false, false),
                 };
@@ -1726,6 +2184,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
                 // close bracket for Load function
                 write!(self.out, ")")?;
+                if storage && self.is_atomic_image(image, func_ctx) {
+                    write!(self.out, ", 0, 0, 1)")?;
+                }
 
                 // return x component if return type is scalar
                 if let TypeInner::Scalar { .. } = *func_ctx.info[expr].ty.inner_with(&module.types)
@@ -1750,9 +2211,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     .pointer_class()
                 {
                     Some(crate::StorageClass::Storage { .. }) => {
-                        let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
+                        let buffer = self.fill_access_chain(module, pointer, func_ctx)?;
                         let result_ty = func_ctx.info[expr].ty.clone();
-                        self.write_storage_load(module, var_handle, result_ty, func_ctx)?;
+                        self.write_storage_load(module, buffer, result_ty, func_ctx)?;
                     }
                     _ => {
                         self.write_expr(module, pointer, func_ctx)?;
@@ -1784,7 +2245,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         )));
                     }
                 };
-                let kind_str = kind.to_hlsl_str(convert.unwrap_or(src_width))?;
+                let kind_str = self.scalar_type_str(kind, convert.unwrap_or(src_width))?;
                 write!(self.out, "{}{}(", kind_str, size_str,)?;
                 self.write_expr(module, expr, func_ctx)?;
                 write!(self.out, ")")?;
@@ -1920,20 +2381,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     self.out.write_char(back::COMPONENTS[sc as usize])?;
                 }
             }
+            Expression::RayQueryGetCommittedIntersection { query, ty: _ } => {
+                self.write_committed_intersection_function_name()?;
+                write!(self.out, "(")?;
+                self.write_expr(module, query, func_ctx)?;
+                write!(self.out, ")")?;
+            }
             Expression::ArrayLength(expr) => {
-                let var_handle = match func_ctx.expressions[expr] {
-                    Expression::AccessIndex { base, index: _ } => {
-                        match func_ctx.expressions[base] {
-                            Expression::GlobalVariable(handle) => handle,
-                            _ => unreachable!(),
-                        }
-                    }
-                    Expression::GlobalVariable(handle) => handle,
-                    _ => unreachable!(),
-                };
+                // only the buffer is needed, the offset is the one of the last member
+                let buffer = self.fill_access_chain(module, expr, func_ctx)?;
 
-                let var = &module.global_variables[var_handle];
-                let (offset, stride) = match module.types[var.ty].inner {
+                let var = &module.global_variables[buffer.var_handle];
+                let var_ty = match module.types[var.ty].inner {
+                    TypeInner::BindingArray { base, .. } => base,
+                    _ => var.ty,
+                };
+                let (offset, stride) = match module.types[var_ty].inner {
                     TypeInner::Array { stride, .. } => (0, stride),
                     TypeInner::Struct { ref members, .. } => {
                         let last = members.last().unwrap();
@@ -1956,8 +2419,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
                 write!(self.out, "((")?;
                 self.write_wrapped_array_length_function_name(wrapped_array_length)?;
-                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                write!(self.out, "({}) - {}) / {})", var_name, offset, stride)?
+                write!(self.out, "(")?;
+                self.write_storage_buffer(module, buffer, func_ctx)?;
+                write!(self.out, ") - {}) / {})", offset, stride)?
+            }
+            Expression::NonUniform(value) => {
+                write!(self.out, "NonUniformResourceIndex(")?;
+                self.write_expr(module, value, func_ctx)?;
+                write!(self.out, ")")?;
             }
             Expression::Derivative { axis, expr } => {
                 use crate::DerivativeAxis as Da;
@@ -2015,7 +2484,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 write!(self.out, ")")?
             }
             // Nothing to do here, since call expression already cached
-            Expression::CallResult(_) | Expression::AtomicResult { .. } => {}
+            Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::RayQueryProceedResult
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => {}
         }
 
         if !closing_bracket.is_empty() {
@@ -2035,14 +2508,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ) -> BackendResult {
         let constant = &module.constants[handle];
         match constant.inner {
-            crate::ConstantInner::Scalar {
-                width: _,
-                ref value,
-            } => {
+            crate::ConstantInner::Scalar { width, ref value } => {
                 if constant.name.is_some() {
                     write!(self.out, "{}", &self.names[&NameKey::Constant(handle)])?;
                 } else {
-                    self.write_scalar_value(*value)?;
+                    self.write_scalar_value(*value, width)?;
                 }
             }
             crate::ConstantInner::Composite { ty, ref components } => {
@@ -2085,11 +2555,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ///
     /// # Notes
     /// Adds no trailing or leading whitespace
-    fn write_scalar_value(&mut self, value: crate::ScalarValue) -> BackendResult {
+    fn write_scalar_value(
+        &mut self,
+        value: crate::ScalarValue,
+        width: crate::Bytes,
+    ) -> BackendResult {
         use crate::ScalarValue as Sv;
 
         match value {
+            Sv::Sint(value) if width == 8 => write!(self.out, "{}L", value)?,
             Sv::Sint(value) => write!(self.out, "{}", value)?,
+            Sv::Uint(value) if width == 8 => write!(self.out, "{}uL", value)?,
             Sv::Uint(value) => write!(self.out, "{}u", value)?,
             // Floats are written using `Debug` instead of `Display` because it always appends the
             // decimal part even it's zero
@@ -2100,7 +2576,32 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
-    fn write_named_expr(
+    /// Helper method to start the declaration of the `result` of a subgroup
+    /// statement, up to the ` = ` sign
+    fn write_subgroup_result(
+        &mut self,
+        module: &Module,
+        result: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> BackendResult {
+        if self.options.shader_model < ShaderModel::V6_0 {
+            return Err(Error::Custom(
+                "subgroup operations require shader model 6.0".to_string(),
+            ));
+        }
+        write!(self.out, "{}const ", level)?;
+        match func_ctx.info[result].ty {
+            proc::TypeResolution::Handle(handle) => self.write_type(module, handle)?,
+            proc::TypeResolution::Value(ref value) => self.write_value_type(module, value)?,
+        };
+        let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+        write!(self.out, " {} = ", res_name)?;
+        self.named_expressions.insert(result, res_name);
+        Ok(())
+    }
+
+    pub(super) fn write_named_expr(
         &mut self,
         module: &Module,
         handle: Handle<crate::Expression>,
@@ -145,6 +145,8 @@ impl crate::Expression {
             // More info - https://github.com/gfx-rs/naga/pull/914
             // And https://github.com/gfx-rs/naga/issues/910
             crate::Expression::Load { .. } => 1,
+            // the intersection depends on the state of the query when it's read
+            crate::Expression::RayQueryGetCommittedIntersection { .. } => 1,
             // cache expressions that are referenced multiple times
             _ => 2,
         }
@@ -195,7 +197,9 @@ impl crate::TypeInner {
     #[allow(unused)]
     fn is_handle(&self) -> bool {
         match *self {
-            crate::TypeInner::Image { .. } | crate::TypeInner::Sampler { .. } => true,
+            crate::TypeInner::Image { .. }
+            | crate::TypeInner::Sampler { .. }
+            | crate::TypeInner::AccelerationStructure => true,
             _ => false,
         }
     }
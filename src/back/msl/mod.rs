@@ -55,6 +55,11 @@ pub struct BindTarget {
     pub texture: Option<Slot>,
     pub sampler: Option<BindSamplerTarget>,
     pub mutable: bool,
+    /// Number of resources in an unbounded binding array bound here.
+    ///
+    /// Functions taking the array are shared between the entry points,
+    /// so it has to be the same in every stage the array is bound in.
+    pub binding_array_size: Option<u32>,
 }
 
 // Using `BTreeMap` instead of `HashMap` so that we can hash itself.
@@ -83,6 +88,8 @@ pub struct PerStageMap {
     pub vs: PerStageResources,
     pub fs: PerStageResources,
     pub cs: PerStageResources,
+    pub ts: PerStageResources,
+    pub ms: PerStageResources,
 }
 
 impl ops::Index<crate::ShaderStage> for PerStageMap {
@@ -92,6 +99,8 @@ impl ops::Index<crate::ShaderStage> for PerStageMap {
             crate::ShaderStage::Vertex => &self.vs,
             crate::ShaderStage::Fragment => &self.fs,
             crate::ShaderStage::Compute => &self.cs,
+            crate::ShaderStage::Task => &self.ts,
+            crate::ShaderStage::Mesh => &self.ms,
         }
     }
 }
@@ -99,7 +108,10 @@ impl ops::Index<crate::ShaderStage> for PerStageMap {
 enum ResolvedBinding {
     BuiltIn(crate::BuiltIn),
     Attribute(u32),
-    Color(u32),
+    Color {
+        location: u32,
+        blend_src: Option<u32>,
+    },
     User {
         prefix: &'static str,
         index: u32,
@@ -217,9 +229,13 @@ impl Options {
                 location,
                 interpolation,
                 sampling,
+                blend_src,
             } => match mode {
                 LocationMode::VertexInput => Ok(ResolvedBinding::Attribute(location)),
-                LocationMode::FragmentOutput => Ok(ResolvedBinding::Color(location)),
+                LocationMode::FragmentOutput => Ok(ResolvedBinding::Color {
+                    location,
+                    blend_src,
+                }),
                 LocationMode::Intermediate => Ok(ResolvedBinding::User {
                     prefix: if self.spirv_cross_compatibility {
                         "locn"
@@ -263,21 +279,26 @@ impl Options {
         }
     }
 
+    fn resolve_binding_array_size(&self, res_binding: &crate::ResourceBinding) -> Option<u32> {
+        let map = &self.per_stage_map;
+        [&map.vs, &map.fs, &map.cs, &map.ts, &map.ms]
+            .iter()
+            .filter_map(|stage| stage.resources.get(res_binding))
+            .find_map(|target| target.binding_array_size)
+    }
+
     fn resolve_push_constants(
         &self,
         stage: crate::ShaderStage,
     ) -> Result<ResolvedBinding, EntryPointError> {
-        let slot = match stage {
-            crate::ShaderStage::Vertex => self.per_stage_map.vs.push_constant_buffer,
-            crate::ShaderStage::Fragment => self.per_stage_map.fs.push_constant_buffer,
-            crate::ShaderStage::Compute => self.per_stage_map.cs.push_constant_buffer,
-        };
+        let slot = self.per_stage_map[stage].push_constant_buffer;
         match slot {
             Some(slot) => Ok(ResolvedBinding::Resource(BindTarget {
                 buffer: Some(slot),
                 texture: None,
                 sampler: None,
                 mutable: false,
+                binding_array_size: None,
             })),
             None if self.fake_missing_bindings => Ok(ResolvedBinding::User {
                 prefix: "fake",
@@ -299,6 +320,7 @@ impl Options {
                 texture: None,
                 sampler: None,
                 mutable: false,
+                binding_array_size: None,
             })),
             None if self.fake_missing_bindings => Ok(ResolvedBinding::User {
                 prefix: "fake",
@@ -326,7 +348,7 @@ impl ResolvedBinding {
             Self::BuiltIn(built_in) => {
                 use crate::BuiltIn as Bi;
                 let name = match built_in {
-                    Bi::Position => "position",
+                    Bi::Position { .. } => "position",
                     // vertex
                     Bi::BaseInstance => "base_instance",
                     Bi::BaseVertex => "base_vertex",
@@ -347,14 +369,32 @@ impl ResolvedBinding {
                     Bi::WorkGroupId => "threadgroup_position_in_grid",
                     Bi::WorkGroupSize => "dispatch_threads_per_threadgroup",
                     Bi::NumWorkGroups => "threadgroups_per_grid",
-                    Bi::CullDistance | Bi::ViewIndex => {
-                        return Err(Error::UnsupportedBuiltIn(built_in))
-                    }
+                    // subgroup
+                    Bi::SubgroupSize => "threads_per_simdgroup",
+                    Bi::SubgroupInvocationId => "thread_index_in_simdgroup",
+                    // mesh
+                    Bi::CullPrimitive => "primitive_culled",
+                    // The task result and the primitive indices are passed to
+                    // `metal::mesh_grid_properties` and `metal::mesh` methods.
+                    Bi::MeshTaskSize
+                    | Bi::PointIndex
+                    | Bi::LineIndices
+                    | Bi::TriangleIndices
+                    | Bi::CullDistance
+                    | Bi::ViewIndex => return Err(Error::UnsupportedBuiltIn(built_in)),
                 };
                 write!(out, "{}", name)?;
             }
             Self::Attribute(index) => write!(out, "attribute({})", index)?,
-            Self::Color(index) => write!(out, "color({})", index)?,
+            Self::Color {
+                location,
+                blend_src,
+            } => {
+                write!(out, "color({})", location)?;
+                if let Some(blend_src) = blend_src {
+                    write!(out, ", index({})", blend_src)?;
+                }
+            }
             Self::User {
                 prefix,
                 index,
@@ -42,31 +42,37 @@ impl<'a> Display for TypeContext<'a> {
         }
 
         match ty.inner {
-            crate::TypeInner::Scalar { kind, .. } => {
+            crate::TypeInner::Scalar { kind, width } => {
                 match kind {
                     // work around Metal toolchain bug with `uint` typedef
-                    crate::ScalarKind::Uint => write!(out, "{}::uint", NAMESPACE),
-                    _ => write!(out, "{}", kind.to_msl_name()),
+                    crate::ScalarKind::Uint => {
+                        write!(out, "{}::{}", NAMESPACE, kind.to_msl_name(width))
+                    }
+                    _ => write!(out, "{}", kind.to_msl_name(width)),
                 }
             }
-            crate::TypeInner::Atomic { kind, .. } => {
-                write!(out, "{}::atomic_{}", NAMESPACE, kind.to_msl_name())
+            crate::TypeInner::Atomic { kind, width } => {
+                write!(out, "{}::atomic_{}", NAMESPACE, kind.to_msl_name(width))
             }
-            crate::TypeInner::Vector { size, kind, .. } => {
+            crate::TypeInner::Vector { size, kind, width } => {
                 write!(
                     out,
                     "{}::{}{}",
                     NAMESPACE,
-                    kind.to_msl_name(),
+                    kind.to_msl_name(width),
                     back::vector_size_str(size),
                 )
             }
-            crate::TypeInner::Matrix { columns, rows, .. } => {
+            crate::TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            } => {
                 write!(
                     out,
                     "{}::{}{}x{}",
                     NAMESPACE,
-                    crate::ScalarKind::Float.to_msl_name(),
+                    crate::ScalarKind::Float.to_msl_name(width),
                     back::vector_size_str(columns),
                     back::vector_size_str(rows),
                 )
@@ -86,7 +92,7 @@ impl<'a> Display for TypeContext<'a> {
             crate::TypeInner::ValuePointer {
                 size: None,
                 kind,
-                width: _,
+                width,
                 class,
             } => {
                 let class_name = match class.to_msl_name() {
@@ -97,12 +103,12 @@ impl<'a> Display for TypeContext<'a> {
                 if kind == crate::ScalarKind::Uint {
                     write!(out, "{}::", NAMESPACE)?;
                 }
-                write!(out, "{}&", kind.to_msl_name())
+                write!(out, "{}&", kind.to_msl_name(width))
             }
             crate::TypeInner::ValuePointer {
                 size: Some(size),
                 kind,
-                width: _,
+                width,
                 class,
             } => {
                 let class_name = match class.to_msl_name() {
@@ -114,7 +120,7 @@ impl<'a> Display for TypeContext<'a> {
                     "{} {}::{}{}&",
                     class_name,
                     NAMESPACE,
-                    kind.to_msl_name(),
+                    kind.to_msl_name(width),
                     back::vector_size_str(size),
                 )
             }
@@ -129,6 +135,10 @@ impl<'a> Display for TypeContext<'a> {
                 write!(out, "{}", sub)
             }
             crate::TypeInner::Struct { .. } => unreachable!(),
+            // Binding arrays are only ever written by `TypedGlobalVariable`.
+            crate::TypeInner::BindingArray { .. } => unreachable!(),
+            // Ray query types are rejected by `write_type_defs`.
+            crate::TypeInner::AccelerationStructure | crate::TypeInner::RayQuery => unreachable!(),
             crate::TypeInner::Image {
                 dim,
                 arrayed,
@@ -179,7 +189,7 @@ impl<'a> Display for TypeContext<'a> {
                         ("texture", "", format.into(), access)
                     }
                 };
-                let base_name = kind.to_msl_name();
+                let base_name = kind.to_msl_name(4);
                 let array_str = if arrayed { "_array" } else { "" };
                 write!(
                     out,
@@ -207,6 +217,10 @@ struct TypedGlobalVariable<'a> {
     handle: Handle<crate::GlobalVariable>,
     usage: valid::GlobalUse,
     reference: bool,
+    /// Size of an unbounded binding array, see [`BindTarget::binding_array_size`].
+    ///
+    /// [`BindTarget::binding_array_size`]: super::BindTarget::binding_array_size
+    binding_array_size: Option<u32>,
 }
 
 impl<'a> TypedGlobalVariable<'a> {
@@ -214,9 +228,32 @@ impl<'a> TypedGlobalVariable<'a> {
         let var = &self.module.global_variables[self.handle];
         let name = &self.names[&NameKey::GlobalVariable(self.handle)];
 
+        let (ty, binding_array_size) = match self.module.types[var.ty].inner {
+            crate::TypeInner::BindingArray { base, size } => {
+                let size = match size {
+                    crate::ArraySize::Constant(size) => ConstantContext {
+                        handle: size,
+                        arena: &self.module.constants,
+                        names: self.names,
+                        first_time: false,
+                    }
+                    .to_string(),
+                    crate::ArraySize::Dynamic => match self.binding_array_size {
+                        Some(size) => size.to_string(),
+                        None => {
+                            return Err(Error::FeatureNotImplemented(
+                                "unbounded binding arrays without a binding array size".to_string(),
+                            ))
+                        }
+                    },
+                };
+                (base, Some(size))
+            }
+            _ => (var.ty, None),
+        };
         let storage_access = match var.class {
             crate::StorageClass::Storage { access } => access,
-            _ => match self.module.types[var.ty].inner {
+            _ => match self.module.types[ty].inner {
                 crate::TypeInner::Image {
                     class: crate::ImageClass::Storage { access, .. },
                     ..
@@ -225,14 +262,25 @@ impl<'a> TypedGlobalVariable<'a> {
             },
         };
         let ty_name = TypeContext {
-            handle: var.ty,
+            handle: ty,
             arena: &self.module.types,
             names: self.names,
             access: storage_access,
             first_time: false,
         };
+        let ty_name = match binding_array_size {
+            // Binding arrays are written as `metal::array`s of their elements,
+            // which are pointers for buffers. The array itself is then passed
+            // like an argument buffer.
+            Some(ref size) => match var.class.to_msl_name() {
+                Some(space) => format!("{}::array<{} {}*, {}>", NAMESPACE, space, ty_name, size),
+                None => format!("{}::array<{}, {}>", NAMESPACE, ty_name, size),
+            },
+            None => ty_name.to_string(),
+        };
 
         let (space, access, reference) = match var.class.to_msl_name() {
+            Some(_) if binding_array_size.is_some() => ("constant", "", "&"),
             Some(space) if self.reference => {
                 let access = match var.class {
                     crate::StorageClass::Private | crate::StorageClass::WorkGroup
@@ -277,12 +325,14 @@ impl<'a> Display for ConstantContext<'a> {
         }
 
         match con.inner {
-            crate::ConstantInner::Scalar { value, width: _ } => match value {
+            crate::ConstantInner::Scalar { value, width } => match value {
                 crate::ScalarValue::Sint(value) => {
-                    write!(out, "{}", value)
+                    let long = if width == 8 { "L" } else { "" };
+                    write!(out, "{}{}", value, long)
                 }
                 crate::ScalarValue::Uint(value) => {
-                    write!(out, "{}u", value)
+                    let long = if width == 8 { "L" } else { "" };
+                    write!(out, "{}u{}", value, long)
                 }
                 crate::ScalarValue::Float(value) => {
                     if value.is_infinite() {
@@ -292,8 +342,9 @@ impl<'a> Display for ConstantContext<'a> {
                         write!(out, "NAN")
                     } else {
                         let suffix = if value.fract() == 0.0 { ".0" } else { "" };
+                        let half = if width == 2 { "h" } else { "" };
 
-                        write!(out, "{}{}", value, suffix)
+                        write!(out, "{}{}{}", value, suffix, half)
                     }
                 }
                 crate::ScalarValue::Bool(value) => {
@@ -318,12 +369,15 @@ pub struct Writer<W> {
 }
 
 impl crate::ScalarKind {
-    fn to_msl_name(self) -> &'static str {
-        match self {
-            Self::Float => "float",
-            Self::Sint => "int",
-            Self::Uint => "uint",
-            Self::Bool => "bool",
+    fn to_msl_name(self, width: crate::Bytes) -> &'static str {
+        match (self, width) {
+            (Self::Float, 2) => "half",
+            (Self::Float, _) => "float",
+            (Self::Sint, 8) => "long",
+            (Self::Sint, _) => "int",
+            (Self::Uint, 8) => "ulong",
+            (Self::Uint, _) => "uint",
+            (Self::Bool, _) => "bool",
         }
     }
 }
@@ -429,7 +483,11 @@ impl crate::Type {
             // composite types are better to be aliased, regardless of the name
             Ti::Struct { .. } | Ti::Array { .. } => true,
             // handle types may be different, depending on the global var access, so we always inline them
-            Ti::Image { .. } | Ti::Sampler { .. } => false,
+            Ti::Image { .. }
+            | Ti::Sampler { .. }
+            | Ti::BindingArray { .. }
+            | Ti::AccelerationStructure
+            | Ti::RayQuery => false,
         }
     }
 }
@@ -456,6 +514,7 @@ struct ExpressionContext<'a> {
     module: &'a crate::Module,
     pipeline_options: &'a PipelineOptions,
     policies: index::BoundsCheckPolicies,
+    lang_version: (u8, u8),
 
     /// A bitset containing the `Expression` handle indexes of expressions used
     /// as indices in `ReadZeroSkipWrite`-policy accesses. These may need to be
@@ -513,6 +572,18 @@ struct StatementContext<'a> {
     expression: ExpressionContext<'a>,
     mod_info: &'a valid::ModuleInfo,
     result_struct: Option<&'a str>,
+    /// The `metal::mesh` argument and its types, in mesh entry points.
+    mesh: Option<&'a MeshNames>,
+    /// The `metal::mesh_grid_properties` argument, in task entry points.
+    task_grid: Option<&'a str>,
+}
+
+/// Names of the output types and `metal::mesh` argument of a mesh entry point.
+struct MeshNames {
+    argument: String,
+    vertex_struct: String,
+    /// `None` if the primitives only have indices.
+    primitive_struct: Option<String>,
 }
 
 impl<W: Write> Writer<W> {
@@ -680,27 +751,33 @@ impl<W: Write> Writer<W> {
         context: &ExpressionContext,
     ) -> BackendResult {
         match context.module.types[ty].inner {
-            crate::TypeInner::Scalar { width: 4, kind } if components.len() == 1 => {
-                write!(self.out, "{}", kind.to_msl_name())?;
+            crate::TypeInner::Scalar { width, kind }
+                if components.len() == 1 && (width == 2 || width == 4) =>
+            {
+                write!(self.out, "{}", kind.to_msl_name(width))?;
                 self.put_call_parameters(components.iter().cloned(), context)?;
             }
-            crate::TypeInner::Vector { size, kind, .. } => {
+            crate::TypeInner::Vector { size, kind, width } => {
                 write!(
                     self.out,
                     "{}::{}{}",
                     NAMESPACE,
-                    kind.to_msl_name(),
+                    kind.to_msl_name(width),
                     back::vector_size_str(size)
                 )?;
                 self.put_call_parameters(components.iter().cloned(), context)?;
             }
-            crate::TypeInner::Matrix { columns, rows, .. } => {
+            crate::TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            } => {
                 let kind = crate::ScalarKind::Float;
                 write!(
                     self.out,
                     "{}::{}{}x{}",
                     NAMESPACE,
-                    kind.to_msl_name(),
+                    kind.to_msl_name(width),
                     back::vector_size_str(columns),
                     back::vector_size_str(rows)
                 )?;
@@ -803,6 +880,54 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Emit a compare-exchange, declaring `name` to hold its
+    /// `(old_value, exchanged)` result.
+    #[allow(clippy::too_many_arguments)]
+    fn put_atomic_compare_exchange(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+        cmp: Handle<crate::Expression>,
+        value: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        name: &str,
+        level: back::Level,
+        context: &ExpressionContext,
+    ) -> BackendResult {
+        let ty = match context.info[result].ty {
+            TypeResolution::Handle(ty) => ty,
+            TypeResolution::Value(_) => unreachable!(),
+        };
+        let ty_name = TypeContext {
+            handle: ty,
+            arena: &context.module.types,
+            names: &self.names,
+            access: crate::StorageAccess::empty(),
+            first_time: false,
+        };
+        writeln!(self.out, "{} {};", ty_name, name)?;
+        let old_value = format!("{}.{}", name, self.names[&NameKey::StructMember(ty, 0)]);
+        let exchanged = format!("{}.{}", name, self.names[&NameKey::StructMember(ty, 1)]);
+        // `atomic_compare_exchange_weak_explicit` stores the original value
+        // into its `expected` argument when the exchange fails.
+        write!(self.out, "{}{} = ", level, old_value)?;
+        self.put_expression(cmp, context, true)?;
+        writeln!(self.out, ";")?;
+        write!(
+            self.out,
+            "{}{} = {}::atomic_compare_exchange_weak_explicit({}",
+            level, exchanged, NAMESPACE, ATOMIC_REFERENCE
+        )?;
+        self.put_expression(pointer, context, true)?;
+        write!(self.out, ", &{}, ", old_value)?;
+        self.put_expression(value, context, true)?;
+        writeln!(
+            self.out,
+            ", {}::memory_order_relaxed, {}::memory_order_relaxed);",
+            NAMESPACE, NAMESPACE
+        )?;
+        Ok(())
+    }
+
     /// Emit code for the expression `expr_handle`.
     ///
     /// The `is_scoped` argument is true if the surrounding operators have the
@@ -870,11 +995,10 @@ impl<W: Write> Writer<W> {
                 write!(self.out, "{}", coco)?;
             }
             crate::Expression::Splat { size, value } => {
-                let scalar_kind = match *context.resolve_type(value) {
-                    crate::TypeInner::Scalar { kind, .. } => kind,
+                let scalar = match *context.resolve_type(value) {
+                    crate::TypeInner::Scalar { kind, width } => kind.to_msl_name(width),
                     _ => return Err(Error::Validation),
                 };
-                let scalar = scalar_kind.to_msl_name();
                 let size = back::vector_size_str(size);
 
                 write!(self.out, "{}::{}{}(", NAMESPACE, scalar, size)?;
@@ -1285,19 +1409,26 @@ impl<W: Write> Writer<W> {
                 kind,
                 convert,
             } => {
-                let scalar = kind.to_msl_name();
                 let (src_kind, src_width) = match *context.resolve_type(expr) {
                     crate::TypeInner::Scalar { kind, width }
                     | crate::TypeInner::Vector { kind, width, .. } => (kind, width),
                     _ => return Err(Error::Validation),
                 };
+                let scalar = kind.to_msl_name(convert.unwrap_or(src_width));
                 let is_bool_cast =
                     kind == crate::ScalarKind::Bool || src_kind == crate::ScalarKind::Bool;
                 let op = match convert {
                     Some(w) if w == src_width || is_bool_cast => "static_cast",
+                    Some(2) | Some(4) if kind == crate::ScalarKind::Float => "static_cast",
                     Some(8) if kind == crate::ScalarKind::Float => {
                         return Err(Error::CapabilityNotSupported(valid::Capabilities::FLOAT64))
                     }
+                    // conversions between 32-bit and 64-bit integers
+                    Some(4) | Some(8)
+                        if kind == crate::ScalarKind::Sint || kind == crate::ScalarKind::Uint =>
+                    {
+                        "static_cast"
+                    }
                     Some(_) => return Err(Error::Validation),
                     None => "as_type",
                 };
@@ -1321,9 +1452,16 @@ impl<W: Write> Writer<W> {
                 write!(self.out, ")")?;
             }
             // has to be a named expression
-            crate::Expression::CallResult(_) | crate::Expression::AtomicResult { .. } => {
+            crate::Expression::CallResult(_)
+            | crate::Expression::AtomicResult { .. }
+            | crate::Expression::SubgroupBallotResult
+            | crate::Expression::SubgroupOperationResult { .. }
+            | crate::Expression::RayQueryProceedResult => {
                 unreachable!()
             }
+            crate::Expression::RayQueryGetCommittedIntersection { .. } => {
+                return Err(Error::FeatureNotImplemented("ray queries".to_string()));
+            }
             crate::Expression::ArrayLength(expr) => {
                 // Find the global to which the array belongs.
                 let global = match context.function.expressions[expr] {
@@ -1345,6 +1483,10 @@ impl<W: Write> Writer<W> {
                     write!(self.out, ")")?;
                 }
             }
+            // Metal doesn't need non-uniform indices to be marked
+            crate::Expression::NonUniform(value) => {
+                self.put_expression(value, context, is_scoped)?;
+            }
         }
         Ok(())
     }
@@ -1529,7 +1671,7 @@ impl<W: Write> Writer<W> {
                         let wrap_packed_vec_scalar_kind = context.get_packed_vec_kind(base);
                         //Note: this doesn't work for left-hand side
                         if let Some(scalar_kind) = wrap_packed_vec_scalar_kind {
-                            write!(self.out, "{}::{}3(", NAMESPACE, scalar_kind.to_msl_name())?;
+                            write!(self.out, "{}::{}3(", NAMESPACE, scalar_kind.to_msl_name(4))?;
                             self.put_access_chain(base, policy, context)?;
                             write!(self.out, ")")?;
                         } else {
@@ -1585,7 +1727,18 @@ impl<W: Write> Writer<W> {
             } => true,
             _ => false,
         };
+        // binding arrays of buffers hold pointers, see `TypedGlobalVariable`
+        let accessing_buffer_array = match *base_ty {
+            crate::TypeInner::BindingArray { base, .. } => match context.module.types[base].inner {
+                crate::TypeInner::Image { .. } | crate::TypeInner::Sampler { .. } => false,
+                _ => true,
+            },
+            _ => false,
+        };
 
+        if accessing_buffer_array {
+            write!(self.out, "(*")?;
+        }
         self.put_access_chain(base, policy, context)?;
         if accessing_wrapped_array {
             write!(self.out, ".{}", WRAPPED_ARRAY_FIELD)?;
@@ -1620,6 +1773,9 @@ impl<W: Write> Writer<W> {
         }
 
         write!(self.out, "]")?;
+        if accessing_buffer_array {
+            write!(self.out, ")")?;
+        }
 
         Ok(())
     }
@@ -1671,7 +1827,7 @@ impl<W: Write> Writer<W> {
         };
 
         if let Some(scalar_kind) = wrap_packed_vec_scalar_kind {
-            write!(self.out, "{}::{}3(", NAMESPACE, scalar_kind.to_msl_name())?;
+            write!(self.out, "{}::{}3(", NAMESPACE, scalar_kind.to_msl_name(4))?;
             self.put_access_chain(pointer, policy, context)?;
             write!(self.out, ")")?;
         } else if is_atomic {
@@ -1786,6 +1942,235 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Bake `handle` into a named expression, if it isn't one already,
+    /// and return its name.
+    fn put_baked_operand(
+        &mut self,
+        level: back::Level,
+        handle: Handle<crate::Expression>,
+        context: &ExpressionContext,
+    ) -> Result<String, Error> {
+        if let Some(name) = self.named_expressions.get(&handle) {
+            return Ok(name.clone());
+        }
+        let name = format!("{}{}", back::BAKE_PREFIX, handle.index());
+        write!(self.out, "{}", level)?;
+        self.start_baking_expression(handle, context, &name)?;
+        self.put_expression(handle, context, true)?;
+        writeln!(self.out, ";")?;
+        self.named_expressions.insert(handle, name.clone());
+        Ok(name)
+    }
+
+    fn put_mesh_function(
+        &mut self,
+        level: back::Level,
+        fun: &crate::MeshFunction,
+        context: &StatementContext,
+    ) -> BackendResult {
+        let (mesh, ep_index) = match (context.mesh, &context.expression.origin) {
+            (Some(mesh), &FunctionOrigin::EntryPoint(ep_index)) => (mesh, ep_index),
+            _ => {
+                return Err(Error::FeatureNotImplemented(
+                    "mesh functions outside of entry points".to_string(),
+                ))
+            }
+        };
+        let module = context.expression.module;
+        let mesh_info = module.entry_points[ep_index as usize]
+            .mesh_info
+            .as_ref()
+            .ok_or(Error::Validation)?;
+
+        match *fun {
+            // Metal derives the vertex count from the vertices that are set.
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count: _,
+                primitive_count,
+            } => {
+                write!(self.out, "{}{}.set_primitive_count(", level, mesh.argument)?;
+                self.put_expression(primitive_count, &context.expression, true)?;
+                writeln!(self.out, ");")?;
+            }
+            crate::MeshFunction::SetVertex { index, value } => {
+                let value = self.put_baked_operand(level, value, &context.expression)?;
+                write!(self.out, "{}{}.set_vertex(", level, mesh.argument)?;
+                self.put_expression(index, &context.expression, true)?;
+                write!(self.out, ", {} {{", mesh.vertex_struct)?;
+                let ty = mesh_info.vertex_output_type;
+                if let crate::TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                    for index in 0..members.len() {
+                        let comma = if index == 0 { "" } else { "," };
+                        let name = &self.names[&NameKey::StructMember(ty, index as u32)];
+                        write!(self.out, "{} {}.{}", comma, value, name)?;
+                    }
+                }
+                writeln!(self.out, " }});")?;
+            }
+            crate::MeshFunction::SetPrimitive { index, value } => {
+                let index = self.put_baked_operand(level, index, &context.expression)?;
+                let value = self.put_baked_operand(level, value, &context.expression)?;
+                let ty = mesh_info.primitive_output_type;
+                let members = match module.types[ty].inner {
+                    crate::TypeInner::Struct { ref members, .. } => members,
+                    _ => return Err(Error::Validation),
+                };
+                let indices_binding =
+                    crate::Binding::BuiltIn(mesh_info.topology.indices_built_in());
+                let indices_member = members
+                    .iter()
+                    .position(|member| member.binding.as_ref() == Some(&indices_binding))
+                    .ok_or(Error::Validation)?;
+                let indices = format!(
+                    "{}.{}",
+                    value,
+                    self.names[&NameKey::StructMember(ty, indices_member as u32)]
+                );
+
+                let components: &[&str] = match mesh_info.topology {
+                    crate::MeshOutputTopology::Points => &[""],
+                    crate::MeshOutputTopology::Lines => &[".x", ".y"],
+                    crate::MeshOutputTopology::Triangles => &[".x", ".y", ".z"],
+                };
+                for (i, component) in components.iter().enumerate() {
+                    writeln!(
+                        self.out,
+                        "{}{}.set_index({} * {} + {}, {}{});",
+                        level,
+                        mesh.argument,
+                        index,
+                        components.len(),
+                        i,
+                        indices,
+                        component
+                    )?;
+                }
+
+                if let Some(ref primitive_struct) = mesh.primitive_struct {
+                    write!(
+                        self.out,
+                        "{}{}.set_primitive({}, {} {{",
+                        level, mesh.argument, index, primitive_struct
+                    )?;
+                    let mut is_first = true;
+                    for index in 0..members.len() {
+                        if index == indices_member {
+                            continue;
+                        }
+                        let comma = if is_first { "" } else { "," };
+                        is_first = false;
+                        let name = &self.names[&NameKey::StructMember(ty, index as u32)];
+                        write!(self.out, "{} {}.{}", comma, value, name)?;
+                    }
+                    writeln!(self.out, " }});")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the struct holding the members of the mesh output type `ty`,
+    /// except the one bound to `skip`.
+    fn write_mesh_output_struct(
+        &mut self,
+        module: &crate::Module,
+        ty: Handle<crate::Type>,
+        struct_name: &str,
+        skip: Option<crate::BuiltIn>,
+        options: &Options,
+    ) -> BackendResult {
+        writeln!(self.out, "struct {} {{", struct_name)?;
+        if let crate::TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+            for (index, member) in members.iter().enumerate() {
+                let binding = member.binding.as_ref().ok_or(Error::Validation)?;
+                if skip.is_some() && binding.to_built_in() == skip {
+                    continue;
+                }
+                let ty_name = TypeContext {
+                    handle: member.ty,
+                    arena: &module.types,
+                    names: &self.names,
+                    access: crate::StorageAccess::empty(),
+                    first_time: false,
+                };
+                let name = &self.names[&NameKey::StructMember(ty, index as u32)];
+                let resolved =
+                    options.resolve_local_binding(binding, LocationMode::Intermediate)?;
+                write!(self.out, "{}{} {}", back::INDENT, ty_name, name)?;
+                resolved.try_fmt_decorated(&mut self.out)?;
+                writeln!(self.out, ";")?;
+            }
+        }
+        writeln!(self.out, "}};")?;
+        Ok(())
+    }
+
+    /// Write the output types of a mesh entry point, and the `metal::mesh`
+    /// alias used as the type of its argument.
+    fn write_mesh_types(
+        &mut self,
+        module: &crate::Module,
+        mesh_info: &crate::MeshStageInfo,
+        fun_name: &str,
+        options: &Options,
+    ) -> Result<(MeshNames, String), Error> {
+        let vertex_struct = format!("{}Vertex", fun_name);
+        self.write_mesh_output_struct(
+            module,
+            mesh_info.vertex_output_type,
+            &vertex_struct,
+            None,
+            options,
+        )?;
+
+        let indices_built_in = mesh_info.topology.indices_built_in();
+        let has_primitive_values = match module.types[mesh_info.primitive_output_type].inner {
+            crate::TypeInner::Struct { ref members, .. } => members
+                .iter()
+                .any(|member| member.binding != Some(crate::Binding::BuiltIn(indices_built_in))),
+            _ => false,
+        };
+        let primitive_struct = if has_primitive_values {
+            let primitive_struct = format!("{}Primitive", fun_name);
+            self.write_mesh_output_struct(
+                module,
+                mesh_info.primitive_output_type,
+                &primitive_struct,
+                Some(indices_built_in),
+                options,
+            )?;
+            Some(primitive_struct)
+        } else {
+            None
+        };
+
+        let mesh_type = format!("{}Mesh", fun_name);
+        let topology = match mesh_info.topology {
+            crate::MeshOutputTopology::Points => "point",
+            crate::MeshOutputTopology::Lines => "line",
+            crate::MeshOutputTopology::Triangles => "triangle",
+        };
+        writeln!(
+            self.out,
+            "using {} = {}::mesh<{}, {}, {}, {}, {}::topology::{}>;",
+            mesh_type,
+            NAMESPACE,
+            vertex_struct,
+            primitive_struct.as_deref().unwrap_or("void"),
+            mesh_info.max_vertices,
+            mesh_info.max_primitives,
+            NAMESPACE,
+            topology
+        )?;
+
+        let names = MeshNames {
+            argument: self.namer.call("mesh"),
+            vertex_struct,
+            primitive_struct,
+        };
+        Ok((names, mesh_type))
+    }
+
     fn start_baking_expression(
         &mut self,
         handle: Handle<crate::Expression>,
@@ -1805,29 +2190,38 @@ impl<W: Write> Writer<W> {
             }
             TypeResolution::Value(crate::TypeInner::Scalar {
                 kind: crate::ScalarKind::Uint,
-                ..
+                width,
             }) => {
                 // work around Metal toolchain bug with `uint` typedef
-                write!(self.out, "{}::uint", NAMESPACE)?;
+                write!(
+                    self.out,
+                    "{}::{}",
+                    NAMESPACE,
+                    crate::ScalarKind::Uint.to_msl_name(width)
+                )?;
             }
-            TypeResolution::Value(crate::TypeInner::Scalar { kind, .. }) => {
-                write!(self.out, "{}", kind.to_msl_name())?;
+            TypeResolution::Value(crate::TypeInner::Scalar { kind, width }) => {
+                write!(self.out, "{}", kind.to_msl_name(width))?;
             }
-            TypeResolution::Value(crate::TypeInner::Vector { size, kind, .. }) => {
+            TypeResolution::Value(crate::TypeInner::Vector { size, kind, width }) => {
                 write!(
                     self.out,
                     "{}::{}{}",
                     NAMESPACE,
-                    kind.to_msl_name(),
+                    kind.to_msl_name(width),
                     back::vector_size_str(size)
                 )?;
             }
-            TypeResolution::Value(crate::TypeInner::Matrix { columns, rows, .. }) => {
+            TypeResolution::Value(crate::TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            }) => {
                 write!(
                     self.out,
                     "{}::{}{}x{}",
                     NAMESPACE,
-                    crate::ScalarKind::Float.to_msl_name(),
+                    crate::ScalarKind::Float.to_msl_name(width),
                     back::vector_size_str(columns),
                     back::vector_size_str(rows),
                 )?;
@@ -1994,6 +2388,15 @@ impl<W: Write> Writer<W> {
                 crate::Statement::Continue => {
                     writeln!(self.out, "{}continue;", level)?;
                 }
+                crate::Statement::Return {
+                    value: Some(expr_handle),
+                } if context.task_grid.is_some() => {
+                    let grid = context.task_grid.unwrap();
+                    write!(self.out, "{}{}.set_threadgroups_per_grid(", level, grid)?;
+                    self.put_expression(expr_handle, &context.expression, true)?;
+                    writeln!(self.out, ");")?;
+                    writeln!(self.out, "{}return;", level)?;
+                }
                 crate::Statement::Return {
                     value: Some(expr_handle),
                 } => {
@@ -2034,6 +2437,13 @@ impl<W: Write> Writer<W> {
                             level, NAMESPACE, NAMESPACE,
                         )?;
                     }
+                    if flags.contains(crate::Barrier::SUB_GROUP) {
+                        writeln!(
+                            self.out,
+                            "{}{}::simdgroup_barrier({}::mem_flags::mem_none);",
+                            level, NAMESPACE, NAMESPACE,
+                        )?;
+                    }
                 }
                 crate::Statement::Store { pointer, value } => {
                     self.put_store(pointer, value, level, context)?
@@ -2056,6 +2466,49 @@ impl<W: Write> Writer<W> {
                     }
                     writeln!(self.out, ");")?;
                 }
+                crate::Statement::ImageAtomic {
+                    image,
+                    coordinate,
+                    array_index,
+                    ref fun,
+                    value,
+                    result,
+                } => {
+                    // Texture atomics were introduced in MSL 3.1
+                    if context.expression.lang_version < (3, 1) {
+                        return Err(Error::CapabilityNotSupported(
+                            valid::Capabilities::TEXTURE_ATOMIC,
+                        ));
+                    }
+                    write!(self.out, "{}", level)?;
+                    let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &res_name)?;
+                    self.named_expressions.insert(result, res_name);
+                    let fun_str = match *fun {
+                        crate::AtomicFunction::Add => "fetch_add",
+                        crate::AtomicFunction::Subtract => "fetch_sub",
+                        crate::AtomicFunction::And => "fetch_and",
+                        crate::AtomicFunction::InclusiveOr => "fetch_or",
+                        crate::AtomicFunction::ExclusiveOr => "fetch_xor",
+                        crate::AtomicFunction::Min => "fetch_min",
+                        crate::AtomicFunction::Max => "fetch_max",
+                        crate::AtomicFunction::Exchange { compare: None } => "exchange",
+                        crate::AtomicFunction::Exchange { compare: Some(_) } => {
+                            unreachable!("module is not valid")
+                        }
+                    };
+                    self.put_expression(image, &context.expression, false)?;
+                    write!(self.out, ".atomic_{}(", fun_str)?;
+                    self.put_storage_image_coordinate(coordinate, &context.expression)?;
+                    if let Some(expr) = array_index {
+                        write!(self.out, ", ")?;
+                        self.put_expression(expr, &context.expression, true)?;
+                    }
+                    write!(self.out, ", ")?;
+                    self.put_expression(value, &context.expression, true)?;
+                    // the texture atomics return a 4-component vector
+                    writeln!(self.out, ").x;")?;
+                }
                 crate::Statement::Call {
                     function,
                     ref arguments,
@@ -2106,6 +2559,25 @@ impl<W: Write> Writer<W> {
                     // done
                     writeln!(self.out, ");")?;
                 }
+                crate::Statement::Atomic {
+                    pointer,
+                    fun: crate::AtomicFunction::Exchange { compare: Some(cmp) },
+                    value,
+                    result,
+                } => {
+                    write!(self.out, "{}", level)?;
+                    let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.put_atomic_compare_exchange(
+                        pointer,
+                        cmp,
+                        value,
+                        result,
+                        &res_name,
+                        level,
+                        &context.expression,
+                    )?;
+                    self.named_expressions.insert(result, res_name);
+                }
                 crate::Statement::Atomic {
                     pointer,
                     ref fun,
@@ -2149,15 +2621,99 @@ impl<W: Write> Writer<W> {
                             self.put_expression(value, &context.expression, true)?;
                             write!(self.out, ", {}::memory_order_relaxed)", NAMESPACE)?;
                         }
-                        crate::AtomicFunction::Exchange { .. } => {
-                            return Err(Error::FeatureNotImplemented(
-                                "atomic CompareExchange".to_string(),
-                            ));
-                        }
+                        crate::AtomicFunction::Exchange { compare: Some(_) } => unreachable!(),
                     }
                     // done
                     writeln!(self.out, ";")?;
                 }
+                crate::Statement::SubgroupBallot { result, predicate } => {
+                    write!(self.out, "{}", level)?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions.insert(result, name);
+                    write!(
+                        self.out,
+                        "{}::uint4(as_type<{}::uint2>(static_cast<ulong>({}::simd_ballot(",
+                        NAMESPACE, NAMESPACE, NAMESPACE,
+                    )?;
+                    match predicate {
+                        Some(predicate) => {
+                            self.put_expression(predicate, &context.expression, true)?
+                        }
+                        None => write!(self.out, "true")?,
+                    }
+                    writeln!(self.out, "))), 0u, 0u);")?;
+                }
+                crate::Statement::SubgroupElect { result } => {
+                    write!(self.out, "{}", level)?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions.insert(result, name);
+                    writeln!(self.out, "{}::simd_is_first();", NAMESPACE)?;
+                }
+                crate::Statement::SubgroupGather {
+                    mode,
+                    argument,
+                    result,
+                } => {
+                    write!(self.out, "{}", level)?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions.insert(result, name);
+                    let (fun_str, index) = match mode {
+                        crate::GatherMode::BroadcastFirst => ("broadcast_first", None),
+                        crate::GatherMode::Broadcast(index) => ("broadcast", Some(index)),
+                        crate::GatherMode::Shuffle(index) => ("shuffle", Some(index)),
+                        crate::GatherMode::ShuffleDown(index) => ("shuffle_down", Some(index)),
+                        crate::GatherMode::ShuffleUp(index) => ("shuffle_up", Some(index)),
+                        crate::GatherMode::ShuffleXor(index) => ("shuffle_xor", Some(index)),
+                    };
+                    write!(self.out, "{}::simd_{}(", NAMESPACE, fun_str)?;
+                    self.put_expression(argument, &context.expression, true)?;
+                    if let Some(index) = index {
+                        write!(self.out, ", ")?;
+                        self.put_expression(index, &context.expression, true)?;
+                    }
+                    writeln!(self.out, ");")?;
+                }
+                crate::Statement::SubgroupCollectiveOperation {
+                    op,
+                    collective_op,
+                    argument,
+                    result,
+                } => {
+                    use crate::{CollectiveOperation as Co, SubgroupOperation as So};
+
+                    write!(self.out, "{}", level)?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions.insert(result, name);
+                    let fun_str = match (collective_op, op) {
+                        (Co::Reduce, So::All) => "all",
+                        (Co::Reduce, So::Any) => "any",
+                        (Co::Reduce, So::Add) => "sum",
+                        (Co::Reduce, So::Mul) => "product",
+                        (Co::Reduce, So::Min) => "min",
+                        (Co::Reduce, So::Max) => "max",
+                        (Co::Reduce, So::And) => "and",
+                        (Co::Reduce, So::Or) => "or",
+                        (Co::Reduce, So::Xor) => "xor",
+                        (Co::ExclusiveScan, So::Add) => "prefix_exclusive_sum",
+                        (Co::ExclusiveScan, So::Mul) => "prefix_exclusive_product",
+                        (Co::InclusiveScan, So::Add) => "prefix_inclusive_sum",
+                        (Co::InclusiveScan, So::Mul) => "prefix_inclusive_product",
+                        _ => return Err(Error::Validation),
+                    };
+                    write!(self.out, "{}::simd_{}(", NAMESPACE, fun_str)?;
+                    self.put_expression(argument, &context.expression, true)?;
+                    writeln!(self.out, ");")?;
+                }
+                crate::Statement::RayQuery { .. } => {
+                    return Err(Error::FeatureNotImplemented("ray queries".to_string()));
+                }
+                crate::Statement::MeshFunction(ref fun) => {
+                    self.put_mesh_function(level, fun, context)?;
+                }
             }
         }
 
@@ -2327,6 +2883,9 @@ impl<W: Write> Writer<W> {
 
     fn write_type_defs(&mut self, module: &crate::Module) -> BackendResult {
         for (handle, ty) in module.types.iter() {
+            if let crate::TypeInner::AccelerationStructure | crate::TypeInner::RayQuery = ty.inner {
+                return Err(Error::FeatureNotImplemented("ray queries".to_string()));
+            }
             if !ty.needs_alias() {
                 continue;
             }
@@ -2408,7 +2967,7 @@ impl<W: Write> Writer<W> {
                                     "{}{}::packed_{}3 {};",
                                     back::INDENT,
                                     NAMESPACE,
-                                    kind.to_msl_name(),
+                                    kind.to_msl_name(4),
                                     member_name
                                 )?;
                             }
@@ -2460,21 +3019,24 @@ impl<W: Write> Writer<W> {
     fn write_scalar_constants(&mut self, module: &crate::Module) -> BackendResult {
         for (handle, constant) in module.constants.iter() {
             match constant.inner {
-                crate::ConstantInner::Scalar {
-                    width: _,
-                    ref value,
-                } if constant.name.is_some() => {
+                crate::ConstantInner::Scalar { width, ref value } if constant.name.is_some() => {
                     debug_assert!(constant.needs_alias());
                     write!(self.out, "constexpr constant ")?;
                     match *value {
+                        crate::ScalarValue::Sint(_) if width == 8 => {
+                            write!(self.out, "long")?;
+                        }
                         crate::ScalarValue::Sint(_) => {
                             write!(self.out, "int")?;
                         }
+                        crate::ScalarValue::Uint(_) if width == 8 => {
+                            write!(self.out, "ulong")?;
+                        }
                         crate::ScalarValue::Uint(_) => {
                             write!(self.out, "unsigned")?;
                         }
                         crate::ScalarValue::Float(_) => {
-                            write!(self.out, "float")?;
+                            write!(self.out, "{}", crate::ScalarKind::Float.to_msl_name(width))?;
                         }
                         crate::ScalarValue::Bool(_) => {
                             write!(self.out, "bool")?;
@@ -2682,6 +3244,10 @@ impl<W: Write> Writer<W> {
                     handle,
                     usage: fun_info[handle],
                     reference: true,
+                    binding_array_size: module.global_variables[handle]
+                        .binding
+                        .as_ref()
+                        .and_then(|binding| options.resolve_binding_array_size(binding)),
                 };
                 let separator =
                     separate(index + 1 != pass_through_globals.len() || supports_array_length);
@@ -2731,12 +3297,15 @@ impl<W: Write> Writer<W> {
                     origin: FunctionOrigin::Handle(fun_handle),
                     info: fun_info,
                     policies: options.bounds_check_policies,
+                    lang_version: options.lang_version,
                     guarded_indices,
                     module,
                     pipeline_options,
                 },
                 mod_info,
                 result_struct: None,
+                mesh: None,
+                task_grid: None,
             };
             self.named_expressions.clear();
             self.put_block(back::Level(1), &fun.body, &context)?;
@@ -2766,8 +3335,13 @@ impl<W: Write> Writer<W> {
                         continue;
                     }
                     if let Some(ref br) = var.binding {
+                        // binding arrays need the same kind of target as their elements
+                        let element_ty = match module.types[var.ty].inner {
+                            crate::TypeInner::BindingArray { base, .. } => base,
+                            _ => var.ty,
+                        };
                         let good = match options.per_stage_map[ep.stage].resources.get(br) {
-                            Some(target) => match module.types[var.ty].inner {
+                            Some(target) => match module.types[element_ty].inner {
                                 crate::TypeInner::Struct { .. } => target.buffer.is_some(),
                                 crate::TypeInner::Image { .. } => target.texture.is_some(),
                                 crate::TypeInner::Sampler { .. } => target.sampler.is_some(),
@@ -2799,7 +3373,7 @@ impl<W: Write> Writer<W> {
                 info.entry_point_names.push(Err(err));
                 continue;
             }
-            let fun_name = &self.names[&NameKey::EntryPoint(ep_index as _)];
+            let fun_name = self.names[&NameKey::EntryPoint(ep_index as _)].clone();
             info.entry_point_names.push(Ok(fun_name.clone()));
 
             writeln!(self.out)?;
@@ -2807,6 +3381,15 @@ impl<W: Write> Writer<W> {
             let stage_out_name = format!("{}Output", fun_name);
             let stage_in_name = format!("{}Input", fun_name);
 
+            // Object and mesh functions were introduced in MSL 3.0
+            if let crate::ShaderStage::Task | crate::ShaderStage::Mesh = ep.stage {
+                if options.lang_version < (3, 0) {
+                    return Err(Error::CapabilityNotSupported(
+                        valid::Capabilities::MESH_SHADER,
+                    ));
+                }
+            }
+
             let (em_str, in_mode, out_mode) = match ep.stage {
                 crate::ShaderStage::Vertex => (
                     "vertex",
@@ -2821,6 +3404,12 @@ impl<W: Write> Writer<W> {
                 crate::ShaderStage::Compute { .. } => {
                     ("kernel", LocationMode::Uniform, LocationMode::Uniform)
                 }
+                crate::ShaderStage::Task => {
+                    ("[[object]]", LocationMode::Uniform, LocationMode::Uniform)
+                }
+                crate::ShaderStage::Mesh => {
+                    ("[[mesh]]", LocationMode::Uniform, LocationMode::Uniform)
+                }
             };
 
             let mut argument_members = Vec::new();
@@ -2870,6 +3459,8 @@ impl<W: Write> Writer<W> {
 
             let result_member_name = self.namer.call("member");
             let result_type_name = match fun.result {
+                // Task shaders launch the mesh threadgroups instead of returning their count
+                Some(_) if ep.stage == crate::ShaderStage::Task => "void",
                 Some(ref result) => {
                     let mut result_members = Vec::new();
                     if let crate::TypeInner::Struct { ref members, .. } =
@@ -2936,10 +3527,17 @@ impl<W: Write> Writer<W> {
                         }
                         write!(self.out, " [[")?;
                         resolved.try_fmt(&mut self.out)?;
-                        if options.lang_version >= (2, 1)
-                            && *binding == crate::Binding::BuiltIn(crate::BuiltIn::Position)
+                        if let crate::Binding::BuiltIn(
+                            built_in @ crate::BuiltIn::Position { invariant },
+                        ) = *binding
                         {
-                            write!(self.out, ", invariant")?;
+                            // Positions are invariant whenever the language supports it,
+                            // whether they are flagged or not.
+                            if options.lang_version >= (2, 1) {
+                                write!(self.out, ", invariant")?;
+                            } else if invariant {
+                                return Err(Error::UnsupportedBuiltIn(built_in));
+                            }
                         }
                         writeln!(self.out, "]];")?;
                     }
@@ -2960,9 +3558,32 @@ impl<W: Write> Writer<W> {
                 }
                 None => "void",
             };
+
+            let (mesh_names, mesh_argument) = match ep.mesh_info {
+                Some(ref mesh_info) => {
+                    let (names, mesh_type) =
+                        self.write_mesh_types(module, mesh_info, &fun_name, options)?;
+                    let argument = format!("{} {}", mesh_type, names.argument);
+                    (Some(names), Some(argument))
+                }
+                None => (None, None),
+            };
+            let task_grid = if ep.stage == crate::ShaderStage::Task {
+                Some(self.namer.call("grid"))
+            } else {
+                None
+            };
             writeln!(self.out, "{} {} {}(", em_str, result_type_name, fun_name)?;
 
             let mut is_first_argument = true;
+            let extra_argument = match task_grid {
+                Some(ref grid) => Some(format!("{}::mesh_grid_properties {}", NAMESPACE, grid)),
+                None => mesh_argument,
+            };
+            if let Some(argument) = extra_argument {
+                writeln!(self.out, "  {}", argument)?;
+                is_first_argument = false;
+            }
             if varying_count != 0 {
                 writeln!(
                     self.out,
@@ -3023,6 +3644,10 @@ impl<W: Write> Writer<W> {
                     handle,
                     usage,
                     reference: true,
+                    binding_array_size: var
+                        .binding
+                        .as_ref()
+                        .and_then(|binding| options.resolve_binding_array_size(binding)),
                 };
                 let separator = if is_first_argument {
                     is_first_argument = false;
@@ -3081,6 +3706,7 @@ impl<W: Write> Writer<W> {
                         handle,
                         usage,
                         reference: false,
+                        binding_array_size: None,
                     };
                     write!(self.out, "{}", back::INDENT)?;
                     tyvar.try_fmt(&mut self.out)?;
@@ -3191,12 +3817,15 @@ impl<W: Write> Writer<W> {
                     origin: FunctionOrigin::EntryPoint(ep_index as _),
                     info: fun_info,
                     policies: options.bounds_check_policies,
+                    lang_version: options.lang_version,
                     guarded_indices,
                     module,
                     pipeline_options,
                 },
                 mod_info,
                 result_struct: Some(&stage_out_name),
+                mesh: mesh_names.as_ref(),
+                task_grid: task_grid.as_deref(),
             };
             self.named_expressions.clear();
             self.put_block(back::Level(1), &fun.body, &context)?;
@@ -3290,3 +3919,35 @@ fn test_stack_size() {
         }
     }
 }
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn mesh_shaders_need_msl_3() {
+    use crate::valid::{Capabilities, ValidationFlags};
+
+    let module = crate::front::wgsl::parse_str(
+        "
+        @stage(task) @workgroup_size(1)
+        fn main() -> @builtin(mesh_task_size) vec3<u32> {
+            return vec3<u32>(1u, 1u, 1u);
+        }
+        ",
+    )
+    .unwrap();
+    let info = valid::Validator::new(ValidationFlags::all(), Capabilities::MESH_SHADER)
+        .validate(&module)
+        .unwrap();
+
+    let write = |lang_version| {
+        let options = Options {
+            lang_version,
+            ..Default::default()
+        };
+        Writer::new(String::new()).write(&module, &info, &options, &Default::default())
+    };
+    assert!(matches!(
+        write((2, 4)),
+        Err(Error::CapabilityNotSupported(Capabilities::MESH_SHADER))
+    ));
+    assert!(write((3, 0)).is_ok());
+}
@@ -19,7 +19,7 @@ fn get_dimension(type_inner: &crate::TypeInner) -> Dimension {
 /// The results of emitting code for a left-hand-side expression.
 ///
 /// On success, `write_expression_pointer` returns one of these.
-enum ExpressionPointer {
+pub(super) enum ExpressionPointer {
     /// The pointer to the expression's value is available, as the value of the
     /// expression with the given id.
     Ready { pointer_id: Word },
@@ -142,7 +142,7 @@ impl Writer {
             body.push(Instruction::store(res_member.id, member_value_id, None));
 
             match res_member.built_in {
-                Some(crate::BuiltIn::Position)
+                Some(crate::BuiltIn::Position { .. })
                     if self.flags.contains(WriterFlags::ADJUST_COORDINATE_SPACE) =>
                 {
                     self.write_epilogue_position_y_flip(res_member.id, body)?;
@@ -183,6 +183,127 @@ impl<'w> BlockContext<'w> {
         }
     }
 
+    /// Return true if `expr_handle` is a binding array of images or samplers.
+    ///
+    /// Those live in the `Handle` storage class, so Naga treats them as
+    /// values, but SPIR-V can only select an element through a pointer.
+    fn is_handle_binding_array(&self, expr_handle: Handle<crate::Expression>) -> bool {
+        match *self.fun_info[expr_handle]
+            .ty
+            .inner_with(&self.ir_module.types)
+        {
+            crate::TypeInner::BindingArray { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Return true if `expr_handle` selects an element of a binding array
+    /// with an index marked by [`NonUniform`].
+    ///
+    /// [`NonUniform`]: crate::Expression::NonUniform
+    pub(super) fn is_non_uniform_binding_array_access(
+        &self,
+        expr_handle: Handle<crate::Expression>,
+    ) -> bool {
+        let (base, index) = match self.ir_function.expressions[expr_handle] {
+            crate::Expression::Access { base, index } => (base, index),
+            _ => return false,
+        };
+        let is_binding_array = match *self.fun_info[base].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::BindingArray { .. } => true,
+            crate::TypeInner::Pointer { base, .. } => matches!(
+                self.ir_module.types[base].inner,
+                crate::TypeInner::BindingArray { .. }
+            ),
+            _ => false,
+        };
+        is_binding_array && self.is_non_uniform_index(index)
+    }
+
+    /// Return true if `index` is marked by [`NonUniform`].
+    ///
+    /// [`NonUniform`]: crate::Expression::NonUniform
+    fn is_non_uniform_index(&self, index: Handle<crate::Expression>) -> bool {
+        matches!(
+            self.ir_function.expressions[index],
+            crate::Expression::NonUniform(_)
+        )
+    }
+
+    /// Decorate `id` as `NonUniform`, requesting the capabilities needed to
+    /// index a binding array of `element_ty` in the given storage class.
+    pub(super) fn decorate_non_uniform(
+        &mut self,
+        id: Word,
+        element_ty: Handle<crate::Type>,
+        class: crate::StorageClass,
+    ) -> Result<(), Error> {
+        use spirv::Capability as Cap;
+
+        let capability = match self.ir_module.types[element_ty].inner {
+            crate::TypeInner::Image {
+                class: crate::ImageClass::Storage { .. },
+                ..
+            } => Cap::StorageImageArrayNonUniformIndexing,
+            crate::TypeInner::Image { .. } | crate::TypeInner::Sampler { .. } => {
+                Cap::SampledImageArrayNonUniformIndexing
+            }
+            _ => match class {
+                crate::StorageClass::Storage { .. } => Cap::StorageBufferArrayNonUniformIndexing,
+                _ => Cap::UniformBufferArrayNonUniformIndexing,
+            },
+        };
+        self.writer
+            .require_any("non-uniform indexing", &[Cap::ShaderNonUniform])?;
+        self.writer
+            .require_any("non-uniform binding array indexing", &[capability])?;
+        self.writer.decorate(id, spirv::Decoration::NonUniform, &[]);
+        Ok(())
+    }
+
+    /// Load an element of a binding array of images or samplers.
+    ///
+    /// Return the id of the loaded handle.
+    fn write_binding_array_load(
+        &mut self,
+        binding_array: Handle<crate::Expression>,
+        index_id: Word,
+        non_uniform: bool,
+        block: &mut Block,
+    ) -> Result<Word, Error> {
+        let element_ty = match *self.fun_info[binding_array]
+            .ty
+            .inner_with(&self.ir_module.types)
+        {
+            crate::TypeInner::BindingArray { base, .. } => base,
+            _ => unreachable!(),
+        };
+        let pointer_type_id = self.get_type_id(LookupType::Local(LocalType::Pointer {
+            base: element_ty,
+            class: spirv::StorageClass::UniformConstant,
+        }));
+        let element_type_id = self.get_type_id(LookupType::Handle(element_ty));
+
+        let pointer_id = self.gen_id();
+        block.body.push(Instruction::access_chain(
+            pointer_type_id,
+            pointer_id,
+            self.cached[binding_array],
+            &[index_id],
+        ));
+        let id = self.gen_id();
+        block
+            .body
+            .push(Instruction::load(element_type_id, id, pointer_id, None));
+
+        if non_uniform {
+            let class = crate::StorageClass::Handle;
+            self.decorate_non_uniform(pointer_id, element_ty, class)?;
+            self.decorate_non_uniform(id, element_ty, class)?;
+        }
+        Ok(id)
+    }
+
     /// Cache an expression for a value.
     pub(super) fn cache_expression_value(
         &mut self,
@@ -192,6 +313,17 @@ impl<'w> BlockContext<'w> {
         let result_type_id = self.get_expression_type_id(&self.fun_info[expr_handle].ty);
 
         let id = match self.ir_function.expressions[expr_handle] {
+            crate::Expression::Access { base, index } if self.is_handle_binding_array(base) => {
+                let index_id = self.cached[index];
+                let non_uniform = self.is_non_uniform_index(index);
+                self.write_binding_array_load(base, index_id, non_uniform, block)?
+            }
+            crate::Expression::AccessIndex { base, index }
+                if self.is_handle_binding_array(base) =>
+            {
+                let index_id = self.get_index_constant(index);
+                self.write_binding_array_load(base, index_id, false, block)?
+            }
             crate::Expression::Access { base, index: _ } if self.is_intermediate(base) => {
                 // See `is_intermediate`; we'll handle this later in
                 // `write_expression_pointer`.
@@ -750,8 +882,13 @@ impl<'w> BlockContext<'w> {
                 }
             }
             crate::Expression::FunctionArgument(index) => self.function.parameter_id(index),
-            crate::Expression::CallResult(_) | crate::Expression::AtomicResult { .. } => {
-                self.cached[expr_handle]
+            crate::Expression::CallResult(_)
+            | crate::Expression::AtomicResult { .. }
+            | crate::Expression::SubgroupBallotResult
+            | crate::Expression::SubgroupOperationResult { .. }
+            | crate::Expression::RayQueryProceedResult => self.cached[expr_handle],
+            crate::Expression::RayQueryGetCommittedIntersection { query, ty } => {
+                self.write_ray_query_get_committed_intersection(query, ty, block)?
             }
             crate::Expression::As {
                 expr,
@@ -1003,6 +1140,8 @@ impl<'w> BlockContext<'w> {
                 id
             }
             crate::Expression::ArrayLength(expr) => self.write_runtime_array_length(expr, block)?,
+            // the accesses indexed by the marker are decorated instead
+            crate::Expression::NonUniform(value) => self.cached[value],
         };
 
         self.cached[expr_handle] = id;
@@ -1015,7 +1154,7 @@ impl<'w> BlockContext<'w> {
     ///
     /// On success, the return value is an [`ExpressionPointer`] value; see the
     /// documentation for that type.
-    fn write_expression_pointer(
+    pub(super) fn write_expression_pointer(
         &mut self,
         mut expr_handle: Handle<crate::Expression>,
         block: &mut Block,
@@ -1035,10 +1174,16 @@ impl<'w> BlockContext<'w> {
         // minimum is essential.
         let mut accumulated_checks = None;
 
+        // Whether a binding array is indexed with a non-uniform index
+        // somewhere along the chain.
+        let mut non_uniform = false;
+        let mut non_uniform_element = None;
+
         self.temp_list.clear();
         let root_id = loop {
             expr_handle = match self.ir_function.expressions[expr_handle] {
                 crate::Expression::Access { base, index } => {
+                    non_uniform |= self.is_non_uniform_binding_array_access(expr_handle);
                     let index_id = match self.write_bounds_check(base, index, block)? {
                         BoundsCheckResult::KnownInBounds(known_index) => {
                             // Even if the index is known, `OpAccessIndex`
@@ -1080,6 +1225,14 @@ impl<'w> BlockContext<'w> {
                     base
                 }
                 crate::Expression::GlobalVariable(handle) => {
+                    if non_uniform {
+                        let var = &self.ir_module.global_variables[handle];
+                        if let crate::TypeInner::BindingArray { base, .. } =
+                            self.ir_module.types[var.ty].inner
+                        {
+                            non_uniform_element = Some((base, var.class));
+                        }
+                    }
                     let gv = &self.writer.global_variables[handle.index()];
                     break gv.access_id;
                 }
@@ -1103,6 +1256,9 @@ impl<'w> BlockContext<'w> {
             let pointer_id = self.gen_id();
             let access =
                 Instruction::access_chain(result_type_id, pointer_id, root_id, &self.temp_list);
+            if let Some((element_ty, class)) = non_uniform_element {
+                self.decorate_non_uniform(pointer_id, element_ty, class)?;
+            }
 
             // If we generated some bounds checks, we need to leave it to our
             // caller to generate the branch, the access, the load or store, and
@@ -1316,6 +1472,9 @@ impl<'w> BlockContext<'w> {
                 crate::Statement::Return { value: Some(value) } => {
                     let value_id = self.cached[value];
                     let instruction = match self.function.entry_point_context {
+                        Some(ref context) if context.emits_mesh_tasks => {
+                            self.write_task_return(value, &mut block)
+                        }
                         // If this is an entry point, and we need to return anything,
                         // let's instead store the output variables and return `void`.
                         Some(ref context) => {
@@ -1343,6 +1502,15 @@ impl<'w> BlockContext<'w> {
                 crate::Statement::Barrier(flags) => {
                     let memory_scope = if flags.contains(crate::Barrier::STORAGE) {
                         spirv::Scope::Device
+                    } else if flags.contains(crate::Barrier::WORK_GROUP) {
+                        spirv::Scope::Workgroup
+                    } else if flags.contains(crate::Barrier::SUB_GROUP) {
+                        spirv::Scope::Subgroup
+                    } else {
+                        spirv::Scope::Workgroup
+                    };
+                    let exec_scope = if flags == crate::Barrier::SUB_GROUP {
+                        spirv::Scope::Subgroup
                     } else {
                         spirv::Scope::Workgroup
                     };
@@ -1355,7 +1523,7 @@ impl<'w> BlockContext<'w> {
                         spirv::MemorySemantics::WORKGROUP_MEMORY,
                         flags.contains(crate::Barrier::WORK_GROUP),
                     );
-                    let exec_scope_id = self.get_index_constant(spirv::Scope::Workgroup as u32);
+                    let exec_scope_id = self.get_index_constant(exec_scope as u32);
                     let mem_scope_id = self.get_index_constant(memory_scope as u32);
                     let semantics_id = self.get_index_constant(semantics.bits());
                     block.body.push(Instruction::control_barrier(
@@ -1419,6 +1587,22 @@ impl<'w> BlockContext<'w> {
                     array_index,
                     value,
                 } => self.write_image_store(image, coordinate, array_index, value, &mut block)?,
+                crate::Statement::ImageAtomic {
+                    image,
+                    coordinate,
+                    array_index,
+                    ref fun,
+                    value,
+                    result,
+                } => self.write_image_atomic(
+                    image,
+                    coordinate,
+                    array_index,
+                    fun,
+                    value,
+                    result,
+                    &mut block,
+                )?,
                 crate::Statement::Call {
                     function: local_function,
                     ref arguments,
@@ -1474,6 +1658,10 @@ impl<'w> BlockContext<'w> {
                     let semantics_id = self.get_index_constant(semantics.bits());
                     let value_id = self.cached[value];
                     let value_inner = self.fun_info[value].ty.inner_with(&self.ir_module.types);
+                    if let crate::TypeInner::Scalar { width: 8, .. } = *value_inner {
+                        self.writer
+                            .require_any("64-bit atomics", &[spirv::Capability::Int64Atomics])?;
+                    }
 
                     let instruction = match *fun {
                         crate::AtomicFunction::Add => Instruction::atomic_binary(
@@ -1576,13 +1764,83 @@ impl<'w> BlockContext<'w> {
                                 value_id,
                             )
                         }
-                        crate::AtomicFunction::Exchange { compare: Some(_) } => {
-                            return Err(Error::FeatureNotImplemented("atomic CompareExchange"));
+                        crate::AtomicFunction::Exchange { compare: Some(cmp) } => {
+                            // The result is an `(old_value, exchanged)` structure,
+                            // while `OpAtomicCompareExchange` only produces the former.
+                            let scalar_type_id = match *value_inner {
+                                crate::TypeInner::Scalar { kind, width } => {
+                                    self.get_type_id(LookupType::Local(LocalType::Value {
+                                        vector_size: None,
+                                        kind,
+                                        width,
+                                        pointer_class: None,
+                                    }))
+                                }
+                                _ => {
+                                    return Err(Error::Validation(
+                                        "atomic compare-exchange value type",
+                                    ))
+                                }
+                            };
+                            let bool_type_id = self.writer.get_bool_type_id();
+                            let cmp_id = self.cached[cmp];
+
+                            let old_value_id = self.gen_id();
+                            block.body.push(Instruction::atomic_compare_exchange(
+                                scalar_type_id,
+                                old_value_id,
+                                pointer_id,
+                                scope_constant_id,
+                                semantics_id,
+                                semantics_id,
+                                value_id,
+                                cmp_id,
+                            ));
+                            let exchanged_id = self.gen_id();
+                            block.body.push(Instruction::binary(
+                                spirv::Op::IEqual,
+                                bool_type_id,
+                                exchanged_id,
+                                old_value_id,
+                                cmp_id,
+                            ));
+                            Instruction::composite_construct(
+                                result_type_id,
+                                id,
+                                &[old_value_id, exchanged_id],
+                            )
                         }
                     };
 
                     block.body.push(instruction);
                 }
+                crate::Statement::SubgroupBallot { result, predicate } => {
+                    self.write_subgroup_ballot(predicate, result, &mut block)?;
+                }
+                crate::Statement::SubgroupElect { result } => {
+                    self.write_subgroup_elect(result, &mut block)?;
+                }
+                crate::Statement::SubgroupGather {
+                    ref mode,
+                    argument,
+                    result,
+                } => {
+                    self.write_subgroup_gather(mode, argument, result, &mut block)?;
+                }
+                crate::Statement::SubgroupCollectiveOperation {
+                    op,
+                    collective_op,
+                    argument,
+                    result,
+                } => {
+                    self.write_subgroup_operation(op, collective_op, argument, result, &mut block)?;
+                }
+                crate::Statement::RayQuery { query, ref fun } => {
+                    self.write_ray_query_function(query, fun, &mut block)?;
+                }
+                crate::Statement::MeshFunction(ref fun) => {
+                    self.write_mesh_function(fun, &mut block)?;
+                }
             }
         }
 
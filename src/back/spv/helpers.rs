@@ -49,6 +49,73 @@ pub(super) fn contains_builtin(
     }
 }
 
+/// Return true if `ty` is, or contains, a 16-bit float.
+pub(super) fn contains_16bit_float(
+    ty: Handle<crate::Type>,
+    arena: &UniqueArena<crate::Type>,
+) -> bool {
+    match arena[ty].inner {
+        crate::TypeInner::Scalar {
+            kind: crate::ScalarKind::Float,
+            width: 2,
+        }
+        | crate::TypeInner::Vector {
+            kind: crate::ScalarKind::Float,
+            width: 2,
+            ..
+        }
+        | crate::TypeInner::Matrix { width: 2, .. } => true,
+        crate::TypeInner::Array { base, .. } => contains_16bit_float(base, arena),
+        crate::TypeInner::Struct { ref members, .. } => members
+            .iter()
+            .any(|member| contains_16bit_float(member.ty, arena)),
+        _ => false,
+    }
+}
+
+/// Convert `value` to the bits of the nearest IEEE 754 binary16 number,
+/// rounding ties to even.
+pub(super) fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        // infinity, or a quiet NaN
+        let nan_bit = if mantissa != 0 { 0x0200 } else { 0 };
+        return sign | 0x7c00 | nan_bit;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        // too large, round to infinity
+        return sign | 0x7c00;
+    }
+
+    // number of mantissa bits dropped, and the bits to round
+    let (shift, mantissa) = if half_exponent <= 0 {
+        if half_exponent < -10 {
+            // too small even for a subnormal
+            return sign;
+        }
+        ((14 - half_exponent) as u32, mantissa | 0x0080_0000)
+    } else {
+        (13, mantissa)
+    };
+    let half_bit = 1 << (shift - 1);
+    let dropped = mantissa & ((half_bit << 1) - 1);
+    let mut result = mantissa >> shift;
+    if half_exponent > 0 {
+        result |= (half_exponent as u32) << 10;
+    }
+    // a carry out of the mantissa correctly bumps the exponent
+    if dropped > half_bit || (dropped == half_bit && result & 1 != 0) {
+        result += 1;
+    }
+    sign | result as u16
+}
+
 impl crate::StorageClass {
     pub(super) fn to_spirv_semantics_and_scope(self) -> (spirv::MemorySemantics, spirv::Scope) {
         match self {
@@ -361,6 +361,10 @@ impl<'w> BlockContext<'w> {
             crate::Expression::FunctionArgument(i) => {
                 self.function.parameters[i as usize].handle_id
             }
+            // Elements of binding arrays are loaded when the access is cached.
+            crate::Expression::Access { .. } | crate::Expression::AccessIndex { .. } => {
+                self.cached[expr_handle]
+            }
             ref other => unreachable!("Unexpected image expression {:?}", other),
         };
 
@@ -848,6 +852,12 @@ impl<'w> BlockContext<'w> {
             image_id,
             sampler_id,
         ));
+        if self.is_non_uniform_binding_array_access(image) {
+            self.decorate_non_uniform(sampled_image_id, image_type, crate::StorageClass::Handle)?;
+        } else if self.is_non_uniform_binding_array_access(sampler) {
+            let sampler_type = self.fun_info[sampler].ty.handle().unwrap();
+            self.decorate_non_uniform(sampled_image_id, sampler_type, crate::StorageClass::Handle)?;
+        }
         let id = self.gen_id();
 
         let depth_id = depth_ref.map(|handle| self.cached[handle]);
@@ -1175,4 +1185,100 @@ impl<'w> BlockContext<'w> {
 
         Ok(())
     }
+
+    /// Write an atomic operation on a texel of a storage image.
+    ///
+    /// SPIR-V atomics take a pointer, so we point at the texel with
+    /// `OpImageTexelPointer`, which needs the image variable itself rather
+    /// than the loaded image.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn write_image_atomic(
+        &mut self,
+        image: Handle<crate::Expression>,
+        coordinate: Handle<crate::Expression>,
+        array_index: Option<Handle<crate::Expression>>,
+        fun: &crate::AtomicFunction,
+        value: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        let var_id = match self.ir_function.expressions[image] {
+            crate::Expression::GlobalVariable(handle) => {
+                self.writer.global_variables[handle.index()].var_id
+            }
+            _ => {
+                return Err(Error::FeatureNotImplemented(
+                    "atomics on images that are not global variables",
+                ))
+            }
+        };
+        let coordinates = self.write_image_coordinates(coordinate, array_index, block)?;
+        let coordinates_id = match self.writer.bounds_check_policies.image {
+            crate::proc::BoundsCheckPolicy::Restrict => {
+                let image_id = self.get_image_id(image);
+                let (coords, _, _) =
+                    self.write_restricted_coordinates(image_id, coordinates, None, None, block)?;
+                coords
+            }
+            crate::proc::BoundsCheckPolicy::ReadZeroSkipWrite => {
+                return Err(Error::FeatureNotImplemented(
+                    "image atomics out-of-bounds handling",
+                ));
+            }
+            crate::proc::BoundsCheckPolicy::Unchecked => coordinates.value_id,
+        };
+
+        let kind = match *self.fun_info[value].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::Scalar { kind, .. } => kind,
+            _ => return Err(Error::Validation("image atomic value")),
+        };
+        let op = match *fun {
+            crate::AtomicFunction::Add => spirv::Op::AtomicIAdd,
+            crate::AtomicFunction::Subtract => spirv::Op::AtomicISub,
+            crate::AtomicFunction::And => spirv::Op::AtomicAnd,
+            crate::AtomicFunction::InclusiveOr => spirv::Op::AtomicOr,
+            crate::AtomicFunction::ExclusiveOr => spirv::Op::AtomicXor,
+            crate::AtomicFunction::Min if kind == crate::ScalarKind::Sint => spirv::Op::AtomicSMin,
+            crate::AtomicFunction::Min => spirv::Op::AtomicUMin,
+            crate::AtomicFunction::Max if kind == crate::ScalarKind::Sint => spirv::Op::AtomicSMax,
+            crate::AtomicFunction::Max => spirv::Op::AtomicUMax,
+            crate::AtomicFunction::Exchange { compare: None } => spirv::Op::AtomicExchange,
+            crate::AtomicFunction::Exchange { compare: Some(_) } => {
+                return Err(Error::Validation("image atomic compare-exchange"))
+            }
+        };
+
+        let pointer_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size: None,
+            kind,
+            width: 4,
+            pointer_class: Some(spirv::StorageClass::Image),
+        }));
+        let pointer_id = self.gen_id();
+        let sample_id = self.get_index_constant(0);
+        block.body.push(Instruction::image_texel_pointer(
+            pointer_type_id,
+            pointer_id,
+            var_id,
+            coordinates_id,
+            sample_id,
+        ));
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        self.cached[result] = id;
+        let scope_id = self.get_scope_constant(spirv::Scope::Device as u32);
+        let semantics_id = self.get_index_constant(spirv::MemorySemantics::IMAGE_MEMORY.bits());
+        block.body.push(Instruction::atomic_binary(
+            op,
+            result_type_id,
+            id,
+            pointer_id,
+            scope_id,
+            semantics_id,
+            self.cached[value],
+        ));
+
+        Ok(())
+    }
 }
@@ -324,6 +324,20 @@ impl<'w> BlockContext<'w> {
         index: Handle<crate::Expression>,
         block: &mut Block,
     ) -> Result<BoundsCheckResult, Error> {
+        // Binding arrays are never bounds checked: selecting a missing
+        // descriptor is governed by the API's robustness rules instead.
+        let is_binding_array = match *self.fun_info[base].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::BindingArray { .. } => true,
+            crate::TypeInner::Pointer { base, .. } => matches!(
+                self.ir_module.types[base].inner,
+                crate::TypeInner::BindingArray { .. }
+            ),
+            _ => false,
+        };
+        if is_binding_array {
+            return Ok(BoundsCheckResult::Computed(self.cached[index]));
+        }
+
         let policy = self.writer.bounds_check_policies.choose_policy(
             base,
             &self.ir_module.types,
@@ -127,13 +127,13 @@ impl super::Instruction {
     }
 
     pub(super) fn entry_point(
-        execution_model: spirv::ExecutionModel,
+        execution_model: Word,
         entry_point_id: Word,
         name: &str,
         interface_ids: &[Word],
     ) -> Self {
         let mut instruction = Self::new(Op::EntryPoint);
-        instruction.add_operand(execution_model as u32);
+        instruction.add_operand(execution_model);
         instruction.add_operand(entry_point_id);
         instruction.add_operands(helpers::string_to_words(name));
 
@@ -249,6 +249,18 @@ impl super::Instruction {
         instruction
     }
 
+    pub(super) fn type_acceleration_structure(id: Word) -> Self {
+        let mut instruction = Self::new(Op::TypeAccelerationStructureKHR);
+        instruction.set_result(id);
+        instruction
+    }
+
+    pub(super) fn type_ray_query(id: Word) -> Self {
+        let mut instruction = Self::new(Op::TypeRayQueryKHR);
+        instruction.set_result(id);
+        instruction
+    }
+
     pub(super) fn type_sampled_image(id: Word, image_type_id: Word) -> Self {
         let mut instruction = Self::new(Op::TypeSampledImage);
         instruction.set_result(id);
@@ -774,6 +786,22 @@ impl super::Instruction {
         instruction
     }
 
+    pub(super) fn image_texel_pointer(
+        result_type_id: Word,
+        id: Word,
+        image: Word,
+        coordinates: Word,
+        sample: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::ImageTexelPointer);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(image);
+        instruction.add_operand(coordinates);
+        instruction.add_operand(sample);
+        instruction
+    }
+
     pub(super) fn atomic_binary(
         op: Op,
         result_type_id: Word,
@@ -793,6 +821,29 @@ impl super::Instruction {
         instruction
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn atomic_compare_exchange(
+        result_type_id: Word,
+        id: Word,
+        pointer: Word,
+        scope_id: Word,
+        equal_semantics_id: Word,
+        unequal_semantics_id: Word,
+        value: Word,
+        comparator: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::AtomicCompareExchange);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(pointer);
+        instruction.add_operand(scope_id);
+        instruction.add_operand(equal_semantics_id);
+        instruction.add_operand(unequal_semantics_id);
+        instruction.add_operand(value);
+        instruction.add_operand(comparator);
+        instruction
+    }
+
     //
     // Bit Instructions
     //
@@ -941,6 +992,136 @@ impl super::Instruction {
         instruction.add_operand(semantics_id);
         instruction
     }
+
+    //
+    //  Non-Uniform Instructions
+    //
+
+    pub(super) fn group_non_uniform_ballot(
+        result_type_id: Word,
+        id: Word,
+        exec_scope_id: Word,
+        predicate: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::GroupNonUniformBallot);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(exec_scope_id);
+        instruction.add_operand(predicate);
+        instruction
+    }
+
+    pub(super) fn group_non_uniform_elect(
+        result_type_id: Word,
+        id: Word,
+        exec_scope_id: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::GroupNonUniformElect);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(exec_scope_id);
+        instruction
+    }
+
+    pub(super) fn group_non_uniform_broadcast_first(
+        result_type_id: Word,
+        id: Word,
+        exec_scope_id: Word,
+        value: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::GroupNonUniformBroadcastFirst);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(exec_scope_id);
+        instruction.add_operand(value);
+        instruction
+    }
+
+    pub(super) fn group_non_uniform_gather(
+        op: Op,
+        result_type_id: Word,
+        id: Word,
+        exec_scope_id: Word,
+        value: Word,
+        index: Word,
+    ) -> Self {
+        let mut instruction = Self::new(op);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(exec_scope_id);
+        instruction.add_operand(value);
+        instruction.add_operand(index);
+        instruction
+    }
+
+    pub(super) fn group_non_uniform_arithmetic(
+        op: Op,
+        result_type_id: Word,
+        id: Word,
+        exec_scope_id: Word,
+        group_op: Option<spirv::GroupOperation>,
+        value: Word,
+    ) -> Self {
+        let mut instruction = Self::new(op);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(exec_scope_id);
+        if let Some(group_op) = group_op {
+            instruction.add_operand(group_op as u32);
+        }
+        instruction.add_operand(value);
+        instruction
+    }
+
+    //
+    //  Ray Query Instructions
+    //
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn ray_query_initialize(
+        query: Word,
+        acceleration_structure: Word,
+        ray_flags: Word,
+        cull_mask: Word,
+        ray_origin: Word,
+        ray_tmin: Word,
+        ray_dir: Word,
+        ray_tmax: Word,
+    ) -> Self {
+        let mut instruction = Self::new(Op::RayQueryInitializeKHR);
+        instruction.add_operand(query);
+        instruction.add_operand(acceleration_structure);
+        instruction.add_operand(ray_flags);
+        instruction.add_operand(cull_mask);
+        instruction.add_operand(ray_origin);
+        instruction.add_operand(ray_tmin);
+        instruction.add_operand(ray_dir);
+        instruction.add_operand(ray_tmax);
+        instruction
+    }
+
+    pub(super) fn ray_query_proceed(result_type_id: Word, id: Word, query: Word) -> Self {
+        let mut instruction = Self::new(Op::RayQueryProceedKHR);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(query);
+        instruction
+    }
+
+    pub(super) fn ray_query_get_intersection(
+        op: Op,
+        result_type_id: Word,
+        id: Word,
+        query: Word,
+        intersection: Word,
+    ) -> Self {
+        let mut instruction = Self::new(op);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction.add_operand(query);
+        instruction.add_operand(intersection);
+        instruction
+    }
 }
 
 impl From<crate::StorageFormat> for spirv::ImageFormat {
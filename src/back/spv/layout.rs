@@ -73,6 +73,12 @@ impl super::recyclable::Recyclable for LogicalLayout {
 
 impl Instruction {
     pub(super) fn new(op: Op) -> Self {
+        Self::new_raw(op as Word)
+    }
+
+    /// Create an instruction from a raw opcode, for instructions the
+    /// `spirv` crate doesn't know about.
+    pub(super) fn new_raw(op: Word) -> Self {
         Instruction {
             op,
             wc: 1, // Always start at 1 for the first word (OP + WC),
@@ -108,7 +114,7 @@ impl Instruction {
     }
 
     pub(super) fn to_words(&self, sink: &mut impl Extend<Word>) {
-        sink.extend(Some(self.wc << 16 | self.op as u32));
+        sink.extend(Some(self.wc << 16 | self.op));
         sink.extend(self.type_id);
         sink.extend(self.result_id);
         sink.extend(self.operands.iter().cloned());
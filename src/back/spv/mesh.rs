@@ -0,0 +1,234 @@
+//! Generating SPIR-V for task and mesh shaders.
+//!
+//! These follow `SPV_EXT_mesh_shader`, which is newer than our `spirv`
+//! crate, so the enumerants it doesn't know about are spelled out here.
+//! The execution modes and the `PerPrimitive` decoration share their values
+//! with the older `SPV_NV_mesh_shader` ones, which the crate does have.
+
+use super::{
+    Block, BlockContext, Error, Instruction, LookupType, MeshOutputMember, MeshOutputs, Writer,
+};
+use crate::arena::Handle;
+use spirv::Word;
+
+pub(super) const EXTENSION: &str = "SPV_EXT_mesh_shader";
+const CAPABILITY_MESH_SHADING: Word = 5283;
+pub(super) const EXECUTION_MODEL_TASK: Word = 5364;
+pub(super) const EXECUTION_MODEL_MESH: Word = 5365;
+const OP_EMIT_MESH_TASKS: Word = 5294;
+const OP_SET_MESH_OUTPUTS: Word = 5295;
+pub(super) const BUILT_IN_PRIMITIVE_POINT_INDICES: Word = 5294;
+pub(super) const BUILT_IN_PRIMITIVE_LINE_INDICES: Word = 5295;
+pub(super) const BUILT_IN_PRIMITIVE_TRIANGLE_INDICES: Word = 5296;
+pub(super) const BUILT_IN_CULL_PRIMITIVE: Word = 5299;
+
+impl Instruction {
+    pub(super) fn capability_mesh_shading() -> Self {
+        let mut instruction = Self::new_raw(spirv::Op::Capability as Word);
+        instruction.add_operand(CAPABILITY_MESH_SHADING);
+        instruction
+    }
+
+    pub(super) fn set_mesh_outputs(vertex_count: Word, primitive_count: Word) -> Self {
+        let mut instruction = Self::new_raw(OP_SET_MESH_OUTPUTS);
+        instruction.add_operand(vertex_count);
+        instruction.add_operand(primitive_count);
+        instruction
+    }
+
+    pub(super) fn emit_mesh_tasks(x: Word, y: Word, z: Word) -> Self {
+        let mut instruction = Self::new_raw(OP_EMIT_MESH_TASKS);
+        instruction.add_operand(x);
+        instruction.add_operand(y);
+        instruction.add_operand(z);
+        instruction
+    }
+}
+
+impl crate::MeshOutputTopology {
+    pub(super) fn to_spirv_execution_mode(self) -> spirv::ExecutionMode {
+        match self {
+            Self::Points => spirv::ExecutionMode::OutputPoints,
+            Self::Lines => spirv::ExecutionMode::OutputLinesNV,
+            Self::Triangles => spirv::ExecutionMode::OutputTrianglesNV,
+        }
+    }
+}
+
+impl Writer {
+    /// Write the execution modes of a mesh entry point.
+    pub(super) fn write_mesh_execution_modes(
+        &mut self,
+        function_id: Word,
+        mesh_info: &crate::MeshStageInfo,
+    ) {
+        let execution_modes = &mut self.logical_layout.execution_modes;
+        Instruction::execution_mode(
+            function_id,
+            spirv::ExecutionMode::OutputVertices,
+            &[mesh_info.max_vertices],
+        )
+        .to_words(execution_modes);
+        Instruction::execution_mode(
+            function_id,
+            spirv::ExecutionMode::OutputPrimitivesNV,
+            &[mesh_info.max_primitives],
+        )
+        .to_words(execution_modes);
+        Instruction::execution_mode(
+            function_id,
+            mesh_info.topology.to_spirv_execution_mode(),
+            &[],
+        )
+        .to_words(execution_modes);
+    }
+
+    /// Declare an `Output` array variable for every member of the vertex and
+    /// primitive output structs of a mesh entry point.
+    ///
+    /// The ids of the variables are added to `varying_ids`.
+    pub(super) fn write_mesh_outputs(
+        &mut self,
+        ir_module: &crate::Module,
+        mesh_info: &crate::MeshStageInfo,
+        varying_ids: &mut Vec<Word>,
+    ) -> Result<MeshOutputs, Error> {
+        let indices_binding = crate::Binding::BuiltIn(mesh_info.topology.indices_built_in());
+        let mut outputs = MeshOutputs::default();
+        for &(ty, count, per_primitive) in [
+            (mesh_info.vertex_output_type, mesh_info.max_vertices, false),
+            (
+                mesh_info.primitive_output_type,
+                mesh_info.max_primitives,
+                true,
+            ),
+        ]
+        .iter()
+        {
+            let members = match ir_module.types[ty].inner {
+                crate::TypeInner::Struct { ref members, .. } => members,
+                _ => return Err(Error::Validation("mesh output type")),
+            };
+            let length_id = self.get_index_constant(count);
+            for member in members {
+                let class = spirv::StorageClass::Output;
+                let type_id = self.get_type_id(LookupType::Handle(member.ty));
+                let array_type_id = self.id_gen.next();
+                Instruction::type_array(array_type_id, type_id, length_id)
+                    .to_words(&mut self.logical_layout.declarations);
+                let array_pointer_type_id = self.id_gen.next();
+                Instruction::type_pointer(array_pointer_type_id, class, array_type_id)
+                    .to_words(&mut self.logical_layout.declarations);
+
+                let id = self.id_gen.next();
+                Instruction::variable(array_pointer_type_id, id, class, None)
+                    .to_words(&mut self.logical_layout.declarations);
+                let name = member.name.as_ref().map(AsRef::as_ref);
+                let binding = member.binding.as_ref().unwrap();
+                self.decorate_varying(id, class, name, binding)?;
+                // The vertex indices are per-primitive by definition.
+                if per_primitive && *binding != indices_binding {
+                    self.decorate(id, spirv::Decoration::PerPrimitiveNV, &[]);
+                }
+                varying_ids.push(id);
+
+                let member = MeshOutputMember {
+                    id,
+                    type_id,
+                    pointer_type_id: self.get_pointer_id(&ir_module.types, member.ty, class)?,
+                };
+                if per_primitive {
+                    outputs.primitives.push(member);
+                } else {
+                    outputs.vertices.push(member);
+                }
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+impl<'w> BlockContext<'w> {
+    pub(super) fn write_mesh_function(
+        &mut self,
+        fun: &crate::MeshFunction,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        let (index, value, per_primitive) = match *fun {
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count,
+                primitive_count,
+            } => {
+                block.body.push(Instruction::set_mesh_outputs(
+                    self.cached[vertex_count],
+                    self.cached[primitive_count],
+                ));
+                return Ok(());
+            }
+            crate::MeshFunction::SetVertex { index, value } => (index, value, false),
+            crate::MeshFunction::SetPrimitive { index, value } => (index, value, true),
+        };
+
+        let outputs = match self.function.entry_point_context {
+            Some(super::EntryPointContext {
+                mesh: Some(ref outputs),
+                ..
+            }) => outputs,
+            _ => {
+                return Err(Error::FeatureNotImplemented(
+                    "mesh functions outside of mesh entry points",
+                ))
+            }
+        };
+        let members = if per_primitive {
+            outputs.primitives.clone()
+        } else {
+            outputs.vertices.clone()
+        };
+
+        let index_id = self.cached[index];
+        let value_id = self.cached[value];
+        for (member_index, member) in members.into_iter().enumerate() {
+            let member_value_id = self.gen_id();
+            block.body.push(Instruction::composite_extract(
+                member.type_id,
+                member_value_id,
+                value_id,
+                &[member_index as Word],
+            ));
+            let pointer_id = self.gen_id();
+            block.body.push(Instruction::access_chain(
+                member.pointer_type_id,
+                pointer_id,
+                member.id,
+                &[index_id],
+            ));
+            block
+                .body
+                .push(Instruction::store(pointer_id, member_value_id, None));
+        }
+        Ok(())
+    }
+
+    /// Write the return of a task entry point, which launches a grid of
+    /// `value` mesh workgroups.
+    pub(super) fn write_task_return(
+        &mut self,
+        value: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Instruction {
+        let uint_type_id = self.writer.get_uint_type_id();
+        let value_id = self.cached[value];
+        let mut component_ids = [0; 3];
+        for (index, component_id) in component_ids.iter_mut().enumerate() {
+            *component_id = self.gen_id();
+            block.body.push(Instruction::composite_extract(
+                uint_type_id,
+                *component_id,
+                value_id,
+                &[index as Word],
+            ));
+        }
+        Instruction::emit_mesh_tasks(component_ids[0], component_ids[1], component_ids[2])
+    }
+}
@@ -7,8 +7,11 @@ mod image;
 mod index;
 mod instructions;
 mod layout;
+mod mesh;
+mod ray;
 mod recyclable;
 mod selection;
+mod subgroup;
 mod writer;
 
 pub use spirv::Capability;
@@ -45,7 +48,7 @@ struct LogicalLayout {
 }
 
 struct Instruction {
-    op: spirv::Op,
+    op: Word,
     wc: u32,
     type_id: Option<Word>,
     result_id: Option<Word>,
@@ -60,6 +63,8 @@ pub enum Error {
     EntryPointNotFound,
     #[error("target SPIRV-{0}.{1} is not supported")]
     UnsupportedVersion(u8, u8),
+    #[error("using {0} requires at least SPIRV-{1}.{2}")]
+    MissingVersion(&'static str, u8, u8),
     #[error("using {0} requires at least one of the capabilities {1:?}, but none are available")]
     MissingCapabilities(&'static str, Vec<Capability>),
     #[error("unimplemented {0}")]
@@ -120,9 +125,30 @@ struct ResultMember {
     built_in: Option<crate::BuiltIn>,
 }
 
+/// An output array of a mesh entry point, holding one struct member for
+/// every vertex or primitive.
+#[derive(Clone, Copy)]
+struct MeshOutputMember {
+    id: Word,
+    type_id: Word,
+    /// Type of a pointer to a single element of the array.
+    pointer_type_id: Word,
+}
+
+#[derive(Default)]
+struct MeshOutputs {
+    vertices: Vec<MeshOutputMember>,
+    primitives: Vec<MeshOutputMember>,
+}
+
 struct EntryPointContext {
     argument_ids: Vec<Word>,
     results: Vec<ResultMember>,
+    /// Output arrays, if this is a mesh entry point.
+    mesh: Option<MeshOutputs>,
+    /// If true, this is a task entry point, and returning means launching
+    /// the mesh workgroups.
+    emits_mesh_tasks: bool,
 }
 
 #[derive(Default)]
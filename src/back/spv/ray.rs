@@ -0,0 +1,159 @@
+//! Generating SPIR-V for ray queries.
+
+use super::{block::ExpressionPointer, Block, BlockContext, Error, Instruction, LookupType};
+use crate::arena::Handle;
+
+impl<'w> BlockContext<'w> {
+    /// Return the id of a pointer to the ray query `query` refers to.
+    fn get_ray_query_pointer(
+        &mut self,
+        query: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<spirv::Word, Error> {
+        match self.write_expression_pointer(query, block)? {
+            ExpressionPointer::Ready { pointer_id } => Ok(pointer_id),
+            ExpressionPointer::Conditional { .. } => Err(Error::FeatureNotImplemented(
+                "ray query out-of-bounds handling",
+            )),
+        }
+    }
+
+    /// Return the id of the loaded acceleration structure `expr_handle` refers to.
+    fn get_acceleration_structure_id(&self, expr_handle: Handle<crate::Expression>) -> spirv::Word {
+        match self.ir_function.expressions[expr_handle] {
+            crate::Expression::GlobalVariable(handle) => {
+                self.writer.global_variables[handle.index()].handle_id
+            }
+            crate::Expression::FunctionArgument(i) => {
+                self.function.parameters[i as usize].handle_id
+            }
+            _ => self.cached[expr_handle],
+        }
+    }
+
+    /// Return the type ids of the members of the structure `ty`.
+    fn get_member_type_ids(&mut self, ty: Handle<crate::Type>) -> Result<Vec<spirv::Word>, Error> {
+        let members = match self.ir_module.types[ty].inner {
+            crate::TypeInner::Struct { ref members, .. } => members,
+            _ => return Err(Error::Validation("ray query structure type")),
+        };
+        let member_types = members.iter().map(|member| member.ty).collect::<Vec<_>>();
+        Ok(member_types
+            .into_iter()
+            .map(|ty| self.get_type_id(LookupType::Handle(ty)))
+            .collect())
+    }
+
+    pub(super) fn write_ray_query_function(
+        &mut self,
+        query: Handle<crate::Expression>,
+        fun: &crate::RayQueryFunction,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        let query_id = self.get_ray_query_pointer(query, block)?;
+
+        match *fun {
+            crate::RayQueryFunction::Initialize {
+                acceleration_structure,
+                descriptor,
+            } => {
+                let acceleration_structure_id =
+                    self.get_acceleration_structure_id(acceleration_structure);
+                let desc_ty = match self.fun_info[descriptor].ty {
+                    crate::proc::TypeResolution::Handle(handle) => handle,
+                    crate::proc::TypeResolution::Value(_) => {
+                        return Err(Error::Validation("ray descriptor type"))
+                    }
+                };
+                let member_type_ids = self.get_member_type_ids(desc_ty)?;
+                let desc_id = self.cached[descriptor];
+
+                // flags, cull_mask, tmin, tmax, origin, dir
+                let mut field_ids = [0; 6];
+                for (index, (field_id, &type_id)) in
+                    field_ids.iter_mut().zip(member_type_ids.iter()).enumerate()
+                {
+                    *field_id = self.gen_id();
+                    block.body.push(Instruction::composite_extract(
+                        type_id,
+                        *field_id,
+                        desc_id,
+                        &[index as u32],
+                    ));
+                }
+
+                block.body.push(Instruction::ray_query_initialize(
+                    query_id,
+                    acceleration_structure_id,
+                    field_ids[0],
+                    field_ids[1],
+                    field_ids[4],
+                    field_ids[2],
+                    field_ids[5],
+                    field_ids[3],
+                ));
+            }
+            crate::RayQueryFunction::Proceed { result } => {
+                let id = self.gen_id();
+                let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+                self.cached[result] = id;
+                block
+                    .body
+                    .push(Instruction::ray_query_proceed(result_type_id, id, query_id));
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_ray_query_get_committed_intersection(
+        &mut self,
+        query: Handle<crate::Expression>,
+        ty: Handle<crate::Type>,
+        block: &mut Block,
+    ) -> Result<spirv::Word, Error> {
+        use spirv::Op;
+
+        // The members of `RayIntersection`, in order.
+        const OPS: [Op; 11] = [
+            Op::RayQueryGetIntersectionTypeKHR,
+            Op::RayQueryGetIntersectionTKHR,
+            Op::RayQueryGetIntersectionInstanceCustomIndexKHR,
+            Op::RayQueryGetIntersectionInstanceIdKHR,
+            Op::RayQueryGetIntersectionInstanceShaderBindingTableRecordOffsetKHR,
+            Op::RayQueryGetIntersectionGeometryIndexKHR,
+            Op::RayQueryGetIntersectionPrimitiveIndexKHR,
+            Op::RayQueryGetIntersectionBarycentricsKHR,
+            Op::RayQueryGetIntersectionFrontFaceKHR,
+            Op::RayQueryGetIntersectionObjectToWorldKHR,
+            Op::RayQueryGetIntersectionWorldToObjectKHR,
+        ];
+
+        let query_id = self.get_ray_query_pointer(query, block)?;
+        let member_type_ids = self.get_member_type_ids(ty)?;
+        let intersection_id = self.get_index_constant(
+            spirv::RayQueryIntersection::RayQueryCommittedIntersectionKHR as u32,
+        );
+
+        let mut member_ids = Vec::with_capacity(OPS.len());
+        for (&op, &type_id) in OPS.iter().zip(member_type_ids.iter()) {
+            let id = self.gen_id();
+            block.body.push(Instruction::ray_query_get_intersection(
+                op,
+                type_id,
+                id,
+                query_id,
+                intersection_id,
+            ));
+            member_ids.push(id);
+        }
+
+        let id = self.gen_id();
+        let result_type_id = self.get_type_id(LookupType::Handle(ty));
+        block.body.push(Instruction::composite_construct(
+            result_type_id,
+            id,
+            &member_ids,
+        ));
+        Ok(id)
+    }
+}
@@ -0,0 +1,198 @@
+//! Generating SPIR-V for subgroup operations.
+
+use super::{Block, BlockContext, Error, Instruction};
+use crate::arena::Handle;
+
+impl<'w> BlockContext<'w> {
+    /// Check that the target supports the `OpGroupNonUniform*` instructions
+    /// and require the given capability.
+    fn require_subgroup(
+        &mut self,
+        what: &'static str,
+        capability: spirv::Capability,
+    ) -> Result<(), Error> {
+        self.writer.require_version(what, (1, 3))?;
+        self.writer.require_any(what, &[capability])
+    }
+
+    pub(super) fn write_subgroup_ballot(
+        &mut self,
+        predicate: Option<Handle<crate::Expression>>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        self.require_subgroup("subgroup ballot", spirv::Capability::GroupNonUniformBallot)?;
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        self.cached[result] = id;
+
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        let predicate_id = match predicate {
+            Some(predicate) => self.cached[predicate],
+            None => self
+                .writer
+                .get_constant_scalar(crate::ScalarValue::Bool(true), crate::BOOL_WIDTH),
+        };
+        block.body.push(Instruction::group_non_uniform_ballot(
+            result_type_id,
+            id,
+            exec_scope_id,
+            predicate_id,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_elect(
+        &mut self,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        self.require_subgroup("subgroup elect", spirv::Capability::GroupNonUniform)?;
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        self.cached[result] = id;
+
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        block.body.push(Instruction::group_non_uniform_elect(
+            result_type_id,
+            id,
+            exec_scope_id,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_gather(
+        &mut self,
+        mode: &crate::GatherMode,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        use crate::GatherMode as Gm;
+
+        match *mode {
+            Gm::BroadcastFirst | Gm::Broadcast(_) => self.require_subgroup(
+                "subgroup broadcast",
+                spirv::Capability::GroupNonUniformBallot,
+            )?,
+            Gm::Shuffle(_) | Gm::ShuffleXor(_) => self.require_subgroup(
+                "subgroup shuffle",
+                spirv::Capability::GroupNonUniformShuffle,
+            )?,
+            Gm::ShuffleDown(_) | Gm::ShuffleUp(_) => self.require_subgroup(
+                "subgroup relative shuffle",
+                spirv::Capability::GroupNonUniformShuffleRelative,
+            )?,
+        }
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        self.cached[result] = id;
+
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        let arg_id = self.cached[argument];
+        let instruction = match *mode {
+            Gm::BroadcastFirst => Instruction::group_non_uniform_broadcast_first(
+                result_type_id,
+                id,
+                exec_scope_id,
+                arg_id,
+            ),
+            Gm::Broadcast(index)
+            | Gm::Shuffle(index)
+            | Gm::ShuffleDown(index)
+            | Gm::ShuffleUp(index)
+            | Gm::ShuffleXor(index) => {
+                let op = match *mode {
+                    Gm::BroadcastFirst => unreachable!(),
+                    Gm::Broadcast(_) => spirv::Op::GroupNonUniformBroadcast,
+                    Gm::Shuffle(_) => spirv::Op::GroupNonUniformShuffle,
+                    Gm::ShuffleDown(_) => spirv::Op::GroupNonUniformShuffleDown,
+                    Gm::ShuffleUp(_) => spirv::Op::GroupNonUniformShuffleUp,
+                    Gm::ShuffleXor(_) => spirv::Op::GroupNonUniformShuffleXor,
+                };
+                Instruction::group_non_uniform_gather(
+                    op,
+                    result_type_id,
+                    id,
+                    exec_scope_id,
+                    arg_id,
+                    self.cached[index],
+                )
+            }
+        };
+        block.body.push(instruction);
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_operation(
+        &mut self,
+        op: crate::SubgroupOperation,
+        collective_op: crate::CollectiveOperation,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        use crate::{ScalarKind as Sk, SubgroupOperation as So};
+
+        match op {
+            So::All | So::Any => self.require_subgroup(
+                "subgroup vote operation",
+                spirv::Capability::GroupNonUniformVote,
+            )?,
+            _ => self.require_subgroup(
+                "subgroup arithmetic operation",
+                spirv::Capability::GroupNonUniformArithmetic,
+            )?,
+        }
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        self.cached[result] = id;
+
+        let kind = match *self.fun_info[argument].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::Scalar { kind, .. } | crate::TypeInner::Vector { kind, .. } => kind,
+            _ => return Err(Error::Validation("subgroup operand type")),
+        };
+
+        let spirv_op = match (op, kind) {
+            (So::All, _) => spirv::Op::GroupNonUniformAll,
+            (So::Any, _) => spirv::Op::GroupNonUniformAny,
+            (So::Add, Sk::Float) => spirv::Op::GroupNonUniformFAdd,
+            (So::Add, _) => spirv::Op::GroupNonUniformIAdd,
+            (So::Mul, Sk::Float) => spirv::Op::GroupNonUniformFMul,
+            (So::Mul, _) => spirv::Op::GroupNonUniformIMul,
+            (So::Min, Sk::Float) => spirv::Op::GroupNonUniformFMin,
+            (So::Min, Sk::Sint) => spirv::Op::GroupNonUniformSMin,
+            (So::Min, _) => spirv::Op::GroupNonUniformUMin,
+            (So::Max, Sk::Float) => spirv::Op::GroupNonUniformFMax,
+            (So::Max, Sk::Sint) => spirv::Op::GroupNonUniformSMax,
+            (So::Max, _) => spirv::Op::GroupNonUniformUMax,
+            (So::And, Sk::Bool) => spirv::Op::GroupNonUniformLogicalAnd,
+            (So::And, _) => spirv::Op::GroupNonUniformBitwiseAnd,
+            (So::Or, Sk::Bool) => spirv::Op::GroupNonUniformLogicalOr,
+            (So::Or, _) => spirv::Op::GroupNonUniformBitwiseOr,
+            (So::Xor, Sk::Bool) => spirv::Op::GroupNonUniformLogicalXor,
+            (So::Xor, _) => spirv::Op::GroupNonUniformBitwiseXor,
+        };
+        let group_op = match op {
+            So::All | So::Any => None,
+            _ => Some(match collective_op {
+                crate::CollectiveOperation::Reduce => spirv::GroupOperation::Reduce,
+                crate::CollectiveOperation::InclusiveScan => spirv::GroupOperation::InclusiveScan,
+                crate::CollectiveOperation::ExclusiveScan => spirv::GroupOperation::ExclusiveScan,
+            }),
+        };
+
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        block.body.push(Instruction::group_non_uniform_arithmetic(
+            spirv_op,
+            result_type_id,
+            id,
+            exec_scope_id,
+            group_op,
+            self.cached[argument],
+        ));
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 use super::{
-    helpers::{contains_builtin, global_needs_wrapper, map_storage_class},
+    helpers::{
+        contains_16bit_float, contains_builtin, f32_to_f16_bits, global_needs_wrapper,
+        map_storage_class,
+    },
     make_local, Block, BlockContext, CachedExpressions, EntryPointContext, Error, Function,
     FunctionArgument, GlobalVariable, IdGenerator, Instruction, LocalType, LocalVariable,
     LogicalLayout, LookupFunctionType, LookupType, LoopContext, Options, PhysicalLayout,
@@ -16,6 +19,7 @@ use std::collections::hash_map::Entry;
 struct FunctionInterface<'a> {
     varying_ids: &'a mut Vec<Word>,
     stage: crate::ShaderStage,
+    mesh_info: Option<&'a crate::MeshStageInfo>,
 }
 
 impl Function {
@@ -127,6 +131,23 @@ impl Writer {
         self.capabilities_used.insert(spirv::Capability::Shader);
     }
 
+    /// Indicate that the code requires at least the given SPIR-V version.
+    ///
+    /// If the target version given in the [`Options`] from which this `Writer`
+    /// was created is older, return an error. The `what` string is used in the
+    /// error message to explain what provoked the requirement.
+    pub(super) fn require_version(
+        &self,
+        what: &'static str,
+        (major, minor): (u8, u8),
+    ) -> Result<(), Error> {
+        let raw_version = ((major as u32) << 16) | ((minor as u32) << 8);
+        if self.physical_layout.version < raw_version {
+            return Err(Error::MissingVersion(what, major, minor));
+        }
+        Ok(())
+    }
+
     /// Indicate that the code requires any one of the listed capabilities.
     ///
     /// If nothing in `capabilities` appears in the available capabilities
@@ -267,7 +288,7 @@ impl Writer {
         self.get_type_id(local_type.into())
     }
 
-    fn decorate(&mut self, id: Word, decoration: spirv::Decoration, operands: &[Word]) {
+    pub(super) fn decorate(&mut self, id: Word, decoration: spirv::Decoration, operands: &[Word]) {
         self.annotations
             .push(Instruction::decorate(id, decoration, operands));
     }
@@ -311,6 +332,8 @@ impl Writer {
         let mut ep_context = EntryPointContext {
             argument_ids: Vec::new(),
             results: Vec::new(),
+            mesh: None,
+            emits_mesh_tasks: false,
         };
 
         let mut parameter_type_ids = Vec::with_capacity(ir_function.arguments.len());
@@ -392,7 +415,23 @@ impl Writer {
             };
         }
 
+        if let Some(ref mut iface) = interface {
+            if let Some(mesh_info) = iface.mesh_info {
+                ep_context.mesh =
+                    Some(self.write_mesh_outputs(ir_module, mesh_info, iface.varying_ids)?);
+            }
+        }
+
         let return_type_id = match ir_function.result {
+            Some(_)
+                if interface.as_ref().map(|iface| iface.stage)
+                    == Some(crate::ShaderStage::Task) =>
+            {
+                // The mesh workgroup count is passed to `OpEmitMeshTasksEXT`
+                // instead of being stored in an output variable.
+                ep_context.emits_mesh_tasks = true;
+                self.void_type
+            }
             Some(ref result) => {
                 if let Some(ref mut iface) = interface {
                     let mut has_point_size = false;
@@ -497,7 +536,11 @@ impl Writer {
             let mut gv = self.global_variables[handle.index()].clone();
 
             // Handle globals are pre-emitted and should be loaded automatically.
-            if var.class == crate::StorageClass::Handle {
+            if let crate::TypeInner::BindingArray { .. } = ir_module.types[var.ty].inner {
+                // Binding arrays are indexed through access chains, and only
+                // the selected element gets loaded.
+                gv.access_id = gv.var_id;
+            } else if var.class == crate::StorageClass::Handle {
                 let var_type_id = self.get_type_id(LookupType::Handle(var.ty));
                 let id = self.id_gen.next();
                 prelude
@@ -596,11 +639,12 @@ impl Writer {
             Some(FunctionInterface {
                 varying_ids: &mut interface_ids,
                 stage: entry_point.stage,
+                mesh_info: entry_point.mesh_info.as_ref(),
             }),
         )?;
 
         let exec_model = match entry_point.stage {
-            crate::ShaderStage::Vertex => spirv::ExecutionModel::Vertex,
+            crate::ShaderStage::Vertex => spirv::ExecutionModel::Vertex as Word,
             crate::ShaderStage::Fragment => {
                 self.write_execution_mode(function_id, spirv::ExecutionMode::OriginUpperLeft)?;
                 if let Some(ref result) = entry_point.function.result {
@@ -616,9 +660,9 @@ impl Writer {
                         )?;
                     }
                 }
-                spirv::ExecutionModel::Fragment
+                spirv::ExecutionModel::Fragment as Word
             }
-            crate::ShaderStage::Compute => {
+            crate::ShaderStage::Compute | crate::ShaderStage::Task | crate::ShaderStage::Mesh => {
                 let execution_mode = spirv::ExecutionMode::LocalSize;
                 //self.check(execution_mode.required_capabilities())?;
                 Instruction::execution_mode(
@@ -627,7 +671,16 @@ impl Writer {
                     &entry_point.workgroup_size,
                 )
                 .to_words(&mut self.logical_layout.execution_modes);
-                spirv::ExecutionModel::GLCompute
+                match entry_point.stage {
+                    crate::ShaderStage::Task => super::mesh::EXECUTION_MODEL_TASK,
+                    crate::ShaderStage::Mesh => {
+                        if let Some(ref mesh_info) = entry_point.mesh_info {
+                            self.write_mesh_execution_modes(function_id, mesh_info);
+                        }
+                        super::mesh::EXECUTION_MODEL_MESH
+                    }
+                    _ => spirv::ExecutionModel::GLCompute as Word,
+                }
             }
         };
         //self.check(exec_model.required_capabilities())?;
@@ -668,8 +721,14 @@ impl Writer {
                 Instruction::type_int(id, bits, signedness)
             }
             Sk::Float => {
-                if bits == 64 {
-                    self.capabilities_used.insert(spirv::Capability::Float64);
+                match bits {
+                    16 => {
+                        self.capabilities_used.insert(spirv::Capability::Float16);
+                    }
+                    64 => {
+                        self.capabilities_used.insert(spirv::Capability::Float64);
+                    }
+                    _ => {}
                 }
                 Instruction::type_float(id, bits)
             }
@@ -842,6 +901,30 @@ impl Writer {
                         crate::ArraySize::Dynamic => Instruction::type_runtime_array(id, type_id),
                     }
                 }
+                crate::TypeInner::BindingArray { base, size } => {
+                    let type_id = self.get_type_id(LookupType::Handle(base));
+                    match size {
+                        crate::ArraySize::Constant(const_handle) => {
+                            let length_id = self.constant_ids[const_handle.index()];
+                            Instruction::type_array(id, type_id, length_id)
+                        }
+                        crate::ArraySize::Dynamic => {
+                            self.require_any(
+                                "unbounded binding arrays",
+                                &[spirv::Capability::RuntimeDescriptorArray],
+                            )?;
+                            Instruction::type_runtime_array(id, type_id)
+                        }
+                    }
+                }
+                crate::TypeInner::AccelerationStructure => {
+                    self.require_any("ray queries", &[spirv::Capability::RayQueryKHR])?;
+                    Instruction::type_acceleration_structure(id)
+                }
+                crate::TypeInner::RayQuery => {
+                    self.require_any("ray queries", &[spirv::Capability::RayQueryKHR])?;
+                    Instruction::type_ray_query(id)
+                }
                 crate::TypeInner::Struct {
                     ref members,
                     span: _,
@@ -1052,6 +1135,10 @@ impl Writer {
             }
             crate::ScalarValue::Float(val) => {
                 let words = match width {
+                    2 => {
+                        solo = [f32_to_f16_bits(val as f32) as u32];
+                        &solo[..]
+                    }
                     4 => {
                         solo = [(val as f32).to_bits()];
                         &solo[..]
@@ -1128,7 +1215,20 @@ impl Writer {
         let pointer_type_id = self.get_pointer_id(&ir_module.types, ty, class)?;
         Instruction::variable(pointer_type_id, id, class, None)
             .to_words(&mut self.logical_layout.declarations);
+        self.decorate_varying(id, class, debug_name, binding)?;
+        Ok(id)
+    }
 
+    /// Name and decorate the varying variable `id`, as [`write_varying`] does.
+    ///
+    /// [`write_varying`]: Writer::write_varying
+    pub(super) fn decorate_varying(
+        &mut self,
+        id: Word,
+        class: spirv::StorageClass,
+        debug_name: Option<&str>,
+        binding: &crate::Binding,
+    ) -> Result<(), Error> {
         if self
             .flags
             .contains(WriterFlags::DEBUG | WriterFlags::LABEL_VARYINGS)
@@ -1145,8 +1245,12 @@ impl Writer {
                 location,
                 interpolation,
                 sampling,
+                blend_src,
             } => {
                 self.decorate(id, Decoration::Location, &[location]);
+                if let Some(blend_src) = blend_src {
+                    self.decorate(id, Decoration::Index, &[blend_src]);
+                }
 
                 match interpolation {
                     // Perspective-correct interpolation is the default in SPIR-V.
@@ -1177,8 +1281,11 @@ impl Writer {
             crate::Binding::BuiltIn(built_in) => {
                 use crate::BuiltIn as Bi;
                 let built_in = match built_in {
-                    Bi::Position => {
+                    Bi::Position { invariant } => {
                         if class == spirv::StorageClass::Output {
+                            if invariant {
+                                self.decorate(id, Decoration::Invariant, &[]);
+                            }
                             BuiltIn::Position
                         } else {
                             BuiltIn::FragCoord
@@ -1222,13 +1329,47 @@ impl Writer {
                     Bi::WorkGroupId => BuiltIn::WorkgroupId,
                     Bi::WorkGroupSize => BuiltIn::WorkgroupSize,
                     Bi::NumWorkGroups => BuiltIn::NumWorkgroups,
+                    // subgroup
+                    Bi::SubgroupSize => {
+                        self.require_version("`subgroup_size` built-in", (1, 3))?;
+                        self.require_any(
+                            "`subgroup_size` built-in",
+                            &[spirv::Capability::GroupNonUniform],
+                        )?;
+                        BuiltIn::SubgroupSize
+                    }
+                    Bi::SubgroupInvocationId => {
+                        self.require_version("`subgroup_invocation_id` built-in", (1, 3))?;
+                        self.require_any(
+                            "`subgroup_invocation_id` built-in",
+                            &[spirv::Capability::GroupNonUniform],
+                        )?;
+                        BuiltIn::SubgroupLocalInvocationId
+                    }
+                    // task
+                    Bi::MeshTaskSize => {
+                        unreachable!("task results are not stored in variables")
+                    }
+                    // mesh
+                    Bi::PointIndex | Bi::LineIndices | Bi::TriangleIndices | Bi::CullPrimitive => {
+                        use super::mesh;
+                        let raw = match built_in {
+                            Bi::PointIndex => mesh::BUILT_IN_PRIMITIVE_POINT_INDICES,
+                            Bi::LineIndices => mesh::BUILT_IN_PRIMITIVE_LINE_INDICES,
+                            Bi::TriangleIndices => mesh::BUILT_IN_PRIMITIVE_TRIANGLE_INDICES,
+                            _ => mesh::BUILT_IN_CULL_PRIMITIVE,
+                        };
+                        // not known to the `spirv` crate
+                        self.decorate(id, Decoration::BuiltIn, &[raw]);
+                        return Ok(());
+                    }
                 };
 
                 self.decorate(id, Decoration::BuiltIn, &[built_in as u32]);
             }
         }
 
-        Ok(id)
+        Ok(())
     }
 
     fn write_global_variable(
@@ -1252,6 +1393,13 @@ impl Writer {
         let storage_access = match global_variable.class {
             crate::StorageClass::Storage { access } => Some(access),
             _ => match ir_module.types[global_variable.ty].inner {
+                crate::TypeInner::BindingArray { base, .. } => match ir_module.types[base].inner {
+                    crate::TypeInner::Image {
+                        class: crate::ImageClass::Storage { access, .. },
+                        ..
+                    } => Some(access),
+                    _ => None,
+                },
                 crate::TypeInner::Image {
                     class: crate::ImageClass::Storage { access, .. },
                     ..
@@ -1301,8 +1449,21 @@ impl Writer {
             // This is a global variable in a Storage class. The only way it could
             // have `global_needs_wrapper() == false` is if it has a runtime-sized array.
             // In this case, we need to decorate it with Block.
-            if let crate::StorageClass::Storage { .. } = global_variable.class {
-                self.decorate(inner_type_id, Decoration::Block, &[]);
+            // Binding arrays of buffers can't be wrapped either, so it's their
+            // element type that needs the decoration.
+            match ir_module.types[global_variable.ty].inner {
+                crate::TypeInner::BindingArray { base, .. } => match global_variable.class {
+                    crate::StorageClass::Uniform | crate::StorageClass::Storage { .. } => {
+                        let base_type_id = self.get_type_id(LookupType::Handle(base));
+                        self.decorate(base_type_id, Decoration::Block, &[]);
+                    }
+                    _ => {}
+                },
+                _ => {
+                    if let crate::StorageClass::Storage { .. } = global_variable.class {
+                        self.decorate(inner_type_id, Decoration::Block, &[]);
+                    }
+                }
             }
             self.get_pointer_id(&ir_module.types, global_variable.ty, class)?
         };
@@ -1378,6 +1539,74 @@ impl Writer {
             Instruction::extension("SPV_KHR_multiview")
                 .to_words(&mut self.logical_layout.extensions)
         }
+        let has_binding_arrays = ir_module
+            .global_variables
+            .iter()
+            .any(|(_, var)| match ir_module.types[var.ty].inner {
+                crate::TypeInner::BindingArray { .. } => true,
+                _ => false,
+            });
+        if self.physical_layout.version < 0x10500 && has_binding_arrays {
+            // descriptor indexing is part of the core since SPV-1.5
+            Instruction::extension("SPV_EXT_descriptor_indexing")
+                .to_words(&mut self.logical_layout.extensions);
+        }
+        let has_ray_queries = ir_module.types.iter().any(|(_, ty)| match ty.inner {
+            crate::TypeInner::AccelerationStructure | crate::TypeInner::RayQuery => true,
+            _ => false,
+        });
+        if has_ray_queries {
+            Instruction::extension("SPV_KHR_ray_query")
+                .to_words(&mut self.logical_layout.extensions);
+        }
+        let has_mesh_stages = ir_module.entry_points.iter().any(|ep| match ep.stage {
+            crate::ShaderStage::Task | crate::ShaderStage::Mesh => true,
+            _ => false,
+        });
+        if has_mesh_stages {
+            self.require_version("task and mesh shaders", (1, 4))?;
+            Instruction::extension(super::mesh::EXTENSION)
+                .to_words(&mut self.logical_layout.extensions);
+        }
+
+        // 16-bit floats in interfaces need explicit storage capabilities
+        let mut has_16bit_storage = false;
+        for (_, var) in ir_module.global_variables.iter() {
+            if !contains_16bit_float(var.ty, &ir_module.types) {
+                continue;
+            }
+            let capability = match var.class {
+                crate::StorageClass::Storage { .. } => spirv::Capability::StorageBuffer16BitAccess,
+                crate::StorageClass::Uniform => {
+                    spirv::Capability::UniformAndStorageBuffer16BitAccess
+                }
+                crate::StorageClass::PushConstant => spirv::Capability::StoragePushConstant16,
+                _ => continue,
+            };
+            self.require_any("16-bit floats in buffers", &[capability])?;
+            has_16bit_storage = true;
+        }
+        let has_16bit_varyings = ir_module.entry_points.iter().any(|entry| {
+            entry
+                .function
+                .arguments
+                .iter()
+                .map(|arg| arg.ty)
+                .chain(entry.function.result.as_ref().map(|result| result.ty))
+                .any(|ty| contains_16bit_float(ty, &ir_module.types))
+        });
+        if has_16bit_varyings {
+            self.require_any(
+                "16-bit floats in shader stage interfaces",
+                &[spirv::Capability::StorageInputOutput16],
+            )?;
+            has_16bit_storage = true;
+        }
+        if self.physical_layout.version < 0x10300 && has_16bit_storage {
+            // 16-bit storage is part of the core since SPV-1.3
+            Instruction::extension("SPV_KHR_16bit_storage")
+                .to_words(&mut self.logical_layout.extensions);
+        }
         Instruction::type_void(self.void_type).to_words(&mut self.logical_layout.declarations);
         Instruction::ext_inst_import(self.gl450_ext_inst_id, "GLSL.std.450")
             .to_words(&mut self.logical_layout.ext_inst_imports);
@@ -1475,6 +1704,11 @@ impl Writer {
         for capability in self.capabilities_used.iter() {
             Instruction::capability(*capability).to_words(&mut self.logical_layout.capabilities);
         }
+        if has_mesh_stages {
+            // Not in `capabilities_used`, because the `spirv` crate doesn't
+            // know about it, so it can't be checked against the available ones.
+            Instruction::capability_mesh_shading().to_words(&mut self.logical_layout.capabilities);
+        }
         if ir_module.entry_points.is_empty() {
             // SPIR-V doesn't like modules without entry points
             Instruction::capability(spirv::Capability::Linkage)
@@ -44,3 +44,29 @@ impl crate::AtomicFunction {
         }
     }
 }
+
+impl crate::SubgroupOperation {
+    fn to_wgsl(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Any => "Any",
+            Self::Add => "Add",
+            Self::Mul => "Mul",
+            Self::Min => "Min",
+            Self::Max => "Max",
+            Self::And => "And",
+            Self::Or => "Or",
+            Self::Xor => "Xor",
+        }
+    }
+}
+
+impl crate::CollectiveOperation {
+    fn to_wgsl(self) -> &'static str {
+        match self {
+            Self::Reduce => "",
+            Self::InclusiveScan => "Inclusive",
+            Self::ExclusiveScan => "Exclusive",
+        }
+    }
+}
@@ -14,10 +14,15 @@ enum Attribute {
     Binding(u32),
     BuiltIn(crate::BuiltIn),
     Group(u32),
+    Invariant,
     Interpolate(Option<crate::Interpolation>, Option<crate::Sampling>),
     Location(u32),
+    BlendSrc(u32),
     Stage(ShaderStage),
-    WorkGroupSize([u32; 3]),
+    WorkGroupSize([String; 3]),
+    OutputTopology(crate::MeshOutputTopology),
+    VertexOutput(String, u32),
+    PrimitiveOutput(String, u32),
 }
 
 /// The WGSL form that `write_expr_with_indirection` should use to render a Naga
@@ -65,6 +70,9 @@ pub struct Writer<W> {
     namer: proc::Namer,
     named_expressions: crate::NamedExpressions,
     ep_results: Vec<(ShaderStage, Handle<crate::Type>)>,
+    /// Result types of `atomicCompareExchangeWeak`, which are predeclared
+    /// in WGSL and can't be spelled out.
+    atomic_result_types: crate::FastHashSet<Handle<crate::Type>>,
 }
 
 impl<W: Write> Writer<W> {
@@ -76,6 +84,7 @@ impl<W: Write> Writer<W> {
             namer: proc::Namer::default(),
             named_expressions: crate::NamedExpressions::default(),
             ep_results: vec![],
+            atomic_result_types: crate::FastHashSet::default(),
         }
     }
 
@@ -90,11 +99,68 @@ impl<W: Write> Writer<W> {
         );
         self.named_expressions.clear();
         self.ep_results.clear();
+        self.atomic_result_types.clear();
+        let functions = module
+            .functions
+            .iter()
+            .map(|(_, fun)| fun)
+            .chain(module.entry_points.iter().map(|ep| &ep.function));
+        for fun in functions {
+            for (_, expr) in fun.expressions.iter() {
+                if let crate::Expression::AtomicResult {
+                    ty,
+                    comparison: true,
+                } = *expr
+                {
+                    self.atomic_result_types.insert(ty);
+                }
+            }
+        }
     }
 
     pub fn write(&mut self, module: &Module, info: &valid::ModuleInfo) -> BackendResult {
         self.reset(module);
 
+        // Enable the extensions required by the module's types
+        let uses_f16 = module.types.iter().any(|(_, ty)| match ty.inner {
+            TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 2,
+            }
+            | TypeInner::Vector {
+                kind: crate::ScalarKind::Float,
+                width: 2,
+                ..
+            }
+            | TypeInner::Matrix { width: 2, .. } => true,
+            _ => false,
+        });
+        let has_blend_src = |binding: Option<&crate::Binding>| match binding {
+            Some(&crate::Binding::Location {
+                blend_src: Some(_), ..
+            }) => true,
+            _ => false,
+        };
+        let uses_blend_src = module
+            .entry_points
+            .iter()
+            .filter_map(|ep| ep.function.result.as_ref())
+            .any(|result| match module.types[result.ty].inner {
+                TypeInner::Struct { ref members, .. } => members
+                    .iter()
+                    .any(|member| has_blend_src(member.binding.as_ref())),
+                _ => has_blend_src(result.binding.as_ref()),
+            });
+        if uses_f16 {
+            writeln!(self.out, "enable f16;")?;
+        }
+        if uses_blend_src {
+            writeln!(self.out, "enable dual_source_blending;")?;
+        }
+        if uses_f16 || uses_blend_src {
+            writeln!(self.out)?;
+        }
+
         // Save all ep result types
         for (_, ep) in module.entry_points.iter().enumerate() {
             if let Some(ref result) = ep.function.result {
@@ -109,6 +175,11 @@ impl<W: Write> Writer<W> {
                 span: _,
             } = ty.inner
             {
+                if self.atomic_result_types.contains(&handle)
+                    || is_predeclared_ray_type(ty, &module.types)
+                {
+                    continue;
+                }
                 self.write_struct(module, handle, members)?;
                 writeln!(self.out)?;
             }
@@ -117,6 +188,9 @@ impl<W: Write> Writer<W> {
         // Write all constants
         for (handle, constant) in module.constants.iter() {
             if constant.name.is_some() {
+                if let Some(id) = constant.specialization {
+                    write!(self.out, "@id({}) ", id)?;
+                }
                 self.write_global_constant(module, &constant.inner, handle)?;
             }
         }
@@ -152,10 +226,31 @@ impl<W: Write> Writer<W> {
         for (index, ep) in module.entry_points.iter().enumerate() {
             let attributes = match ep.stage {
                 ShaderStage::Vertex | ShaderStage::Fragment => vec![Attribute::Stage(ep.stage)],
-                ShaderStage::Compute => vec![
-                    Attribute::Stage(ShaderStage::Compute),
-                    Attribute::WorkGroupSize(ep.workgroup_size),
-                ],
+                ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh => {
+                    // Overridden components are written as the name of the override
+                    let size = |i: usize| match ep.workgroup_size_overrides.and_then(|o| o[i]) {
+                        Some(handle) => self.names[&NameKey::Constant(handle)].clone(),
+                        None => ep.workgroup_size[i].to_string(),
+                    };
+                    let mut attributes = vec![
+                        Attribute::Stage(ep.stage),
+                        Attribute::WorkGroupSize([size(0), size(1), size(2)]),
+                    ];
+                    if let Some(ref mesh_info) = ep.mesh_info {
+                        let vertex_name =
+                            self.names[&NameKey::Type(mesh_info.vertex_output_type)].clone();
+                        let primitive_name =
+                            self.names[&NameKey::Type(mesh_info.primitive_output_type)].clone();
+                        attributes.push(Attribute::OutputTopology(mesh_info.topology));
+                        attributes
+                            .push(Attribute::VertexOutput(vertex_name, mesh_info.max_vertices));
+                        attributes.push(Attribute::PrimitiveOutput(
+                            primitive_name,
+                            mesh_info.max_primitives,
+                        ));
+                    }
+                    attributes
+                }
             };
 
             self.write_attributes(&attributes)?;
@@ -182,14 +277,21 @@ impl<W: Write> Writer<W> {
     ///
     /// # Notes
     /// Adds no trailing or leading whitespace
-    fn write_scalar_value(&mut self, value: crate::ScalarValue) -> BackendResult {
+    fn write_scalar_value(
+        &mut self,
+        value: crate::ScalarValue,
+        width: crate::Bytes,
+    ) -> BackendResult {
         use crate::ScalarValue as Sv;
 
         match value {
+            Sv::Sint(value) if width == 8 => write!(self.out, "{}li", value)?,
             Sv::Sint(value) => write!(self.out, "{}", value)?,
+            Sv::Uint(value) if width == 8 => write!(self.out, "{}lu", value)?,
             Sv::Uint(value) => write!(self.out, "{}u", value)?,
             // Floats are written using `Debug` instead of `Display` because it always appends the
             // decimal part even it's zero
+            Sv::Float(value) if width == 2 => write!(self.out, "{:?}h", value)?,
             Sv::Float(value) => write!(self.out, "{:?}", value)?,
             Sv::Bool(value) => write!(self.out, "{}", value)?,
         }
@@ -206,6 +308,8 @@ impl<W: Write> Writer<W> {
             if let Some(&(stage, _)) = self.ep_results.iter().find(|&&(_, ty)| ty == handle) {
                 let name = match stage {
                     ShaderStage::Compute => "ComputeOutput",
+                    ShaderStage::Task => "TaskOutput",
+                    ShaderStage::Mesh => "MeshOutput",
                     ShaderStage::Fragment => "FragmentOutput",
                     ShaderStage::Vertex => "VertexOutput",
                 };
@@ -333,6 +437,7 @@ impl<W: Write> Writer<W> {
         for attribute in attributes {
             match *attribute {
                 Attribute::Location(id) => write!(self.out, "@location({}) ", id)?,
+                Attribute::BlendSrc(index) => write!(self.out, "@blend_src({}) ", index)?,
                 Attribute::BuiltIn(builtin_attrib) => {
                     if let Some(builtin) = builtin_str(builtin_attrib) {
                         write!(self.out, "@builtin({}) ", builtin)?;
@@ -340,15 +445,32 @@ impl<W: Write> Writer<W> {
                         log::warn!("Unsupported builtin attribute: {:?}", builtin_attrib);
                     }
                 }
+                Attribute::Invariant => write!(self.out, "@invariant ")?,
                 Attribute::Stage(shader_stage) => {
                     let stage_str = match shader_stage {
                         ShaderStage::Vertex => "vertex",
                         ShaderStage::Fragment => "fragment",
                         ShaderStage::Compute => "compute",
+                        ShaderStage::Task => "task",
+                        ShaderStage::Mesh => "mesh",
                     };
                     write!(self.out, "@stage({}) ", stage_str)?;
                 }
-                Attribute::WorkGroupSize(size) => {
+                Attribute::OutputTopology(topology) => {
+                    let topology_str = match topology {
+                        crate::MeshOutputTopology::Points => "points",
+                        crate::MeshOutputTopology::Lines => "lines",
+                        crate::MeshOutputTopology::Triangles => "triangles",
+                    };
+                    write!(self.out, "@output_topology({}) ", topology_str)?;
+                }
+                Attribute::VertexOutput(ref name, max) => {
+                    write!(self.out, "@vertex_output({}, {}) ", name, max)?;
+                }
+                Attribute::PrimitiveOutput(ref name, max) => {
+                    write!(self.out, "@primitive_output({}, {}) ", name, max)?;
+                }
+                Attribute::WorkGroupSize(ref size) => {
                     write!(
                         self.out,
                         "@workgroup_size({}, {}, {}) ",
@@ -450,11 +572,11 @@ impl<W: Write> Writer<W> {
     /// Adds no trailing or leading whitespace
     fn write_value_type(&mut self, module: &Module, inner: &TypeInner) -> BackendResult {
         match *inner {
-            TypeInner::Vector { size, kind, .. } => write!(
+            TypeInner::Vector { size, kind, width } => write!(
                 self.out,
                 "vec{}<{}>",
                 back::vector_size_str(size),
-                scalar_kind_str(kind),
+                scalar_kind_str(kind, width),
             )?,
             TypeInner::Sampler { comparison: false } => {
                 write!(self.out, "sampler")?;
@@ -462,6 +584,12 @@ impl<W: Write> Writer<W> {
             TypeInner::Sampler { comparison: true } => {
                 write!(self.out, "sampler_comparison")?;
             }
+            TypeInner::AccelerationStructure => {
+                write!(self.out, "acceleration_structure")?;
+            }
+            TypeInner::RayQuery => {
+                write!(self.out, "ray_query")?;
+            }
             TypeInner::Image {
                 dim,
                 arrayed,
@@ -476,7 +604,7 @@ impl<W: Write> Writer<W> {
                     Ic::Sampled { kind, multi } => (
                         "",
                         if multi { "multisampled_" } else { "" },
-                        scalar_kind_str(kind),
+                        scalar_kind_str(kind, 4),
                         "",
                     ),
                     Ic::Depth { multi } => {
@@ -506,11 +634,11 @@ impl<W: Write> Writer<W> {
                     write!(self.out, "<{}{}>", format_str, storage_str)?;
                 }
             }
-            TypeInner::Scalar { kind, .. } => {
-                write!(self.out, "{}", scalar_kind_str(kind))?;
+            TypeInner::Scalar { kind, width } => {
+                write!(self.out, "{}", scalar_kind_str(kind, width))?;
             }
-            TypeInner::Atomic { kind, .. } => {
-                write!(self.out, "atomic<{}>", scalar_kind_str(kind))?;
+            TypeInner::Atomic { kind, width } => {
+                write!(self.out, "atomic<{}>", scalar_kind_str(kind, width))?;
             }
             TypeInner::Array {
                 base,
@@ -533,17 +661,28 @@ impl<W: Write> Writer<W> {
                 }
                 write!(self.out, ">")?;
             }
+            TypeInner::BindingArray { base, size } => {
+                // binding_array<A, 3> -- Constant array
+                // binding_array<A> -- Unbounded array
+                write!(self.out, "binding_array<")?;
+                self.write_type(module, base)?;
+                if let crate::ArraySize::Constant(handle) = size {
+                    write!(self.out, ",")?;
+                    self.write_constant(module, handle)?;
+                }
+                write!(self.out, ">")?;
+            }
             TypeInner::Matrix {
                 columns,
                 rows,
-                width: _,
+                width,
             } => {
                 write!(
                     self.out,
-                    //TODO: Can matrix be other than f32?
-                    "mat{}x{}<f32>",
+                    "mat{}x{}<{}>",
                     back::vector_size_str(columns),
                     back::vector_size_str(rows),
+                    scalar_kind_str(crate::ScalarKind::Float, width),
                 )?;
             }
             TypeInner::Pointer { base, class } => {
@@ -565,12 +704,12 @@ impl<W: Write> Writer<W> {
             TypeInner::ValuePointer {
                 size: None,
                 kind,
-                width: _,
+                width,
                 class,
             } => {
                 let (storage, maybe_access) = storage_class_str(class);
                 if let Some(class) = storage {
-                    write!(self.out, "ptr<{}, {}", class, scalar_kind_str(kind))?;
+                    write!(self.out, "ptr<{}, {}", class, scalar_kind_str(kind, width))?;
                     if let Some(access) = maybe_access {
                         write!(self.out, ", {}", access)?;
                     }
@@ -585,7 +724,7 @@ impl<W: Write> Writer<W> {
             TypeInner::ValuePointer {
                 size: Some(size),
                 kind,
-                width: _,
+                width,
                 class,
             } => {
                 let (storage, maybe_access) = storage_class_str(class);
@@ -595,7 +734,7 @@ impl<W: Write> Writer<W> {
                         "ptr<{}, vec{}<{}>",
                         class,
                         back::vector_size_str(size),
-                        scalar_kind_str(kind)
+                        scalar_kind_str(kind, width)
                     )?;
                     if let Some(access) = maybe_access {
                         write!(self.out, ", {}", access)?;
@@ -810,6 +949,116 @@ impl<W: Write> Writer<W> {
                 self.write_expr(module, value, func_ctx)?;
                 writeln!(self.out, ");")?
             }
+            Statement::RayQuery { query, ref fun } => {
+                write!(self.out, "{}", level)?;
+                match *fun {
+                    crate::RayQueryFunction::Initialize {
+                        acceleration_structure,
+                        descriptor,
+                    } => {
+                        write!(self.out, "rayQueryInitialize(")?;
+                        self.write_expr(module, query, func_ctx)?;
+                        write!(self.out, ", ")?;
+                        self.write_expr(module, acceleration_structure, func_ctx)?;
+                        write!(self.out, ", ")?;
+                        self.write_expr(module, descriptor, func_ctx)?;
+                        writeln!(self.out, ");")?
+                    }
+                    crate::RayQueryFunction::Proceed { result } => {
+                        let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                        self.start_named_expr(module, result, func_ctx, &res_name)?;
+                        self.named_expressions.insert(result, res_name);
+
+                        write!(self.out, "rayQueryProceed(")?;
+                        self.write_expr(module, query, func_ctx)?;
+                        writeln!(self.out, ");")?
+                    }
+                }
+            }
+            Statement::MeshFunction(ref fun) => {
+                write!(self.out, "{}", level)?;
+                let (fun_str, first, second) = match *fun {
+                    crate::MeshFunction::SetMeshOutputs {
+                        vertex_count,
+                        primitive_count,
+                    } => ("setMeshOutputs", vertex_count, primitive_count),
+                    crate::MeshFunction::SetVertex { index, value } => ("setVertex", index, value),
+                    crate::MeshFunction::SetPrimitive { index, value } => {
+                        ("setPrimitive", index, value)
+                    }
+                };
+                write!(self.out, "{}(", fun_str)?;
+                self.write_expr(module, first, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, second, func_ctx)?;
+                writeln!(self.out, ");")?
+            }
+            Statement::SubgroupBallot { result, predicate } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                write!(self.out, "subgroupBallot(")?;
+                if let Some(predicate) = predicate {
+                    self.write_expr(module, predicate, func_ctx)?;
+                }
+                writeln!(self.out, ");")?
+            }
+            Statement::SubgroupElect { result } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                writeln!(self.out, "subgroupElect();")?
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                let (fun_str, index) = match mode {
+                    crate::GatherMode::BroadcastFirst => ("BroadcastFirst", None),
+                    crate::GatherMode::Broadcast(index) => ("Broadcast", Some(index)),
+                    crate::GatherMode::Shuffle(index) => ("Shuffle", Some(index)),
+                    crate::GatherMode::ShuffleDown(index) => ("ShuffleDown", Some(index)),
+                    crate::GatherMode::ShuffleUp(index) => ("ShuffleUp", Some(index)),
+                    crate::GatherMode::ShuffleXor(index) => ("ShuffleXor", Some(index)),
+                };
+                write!(self.out, "subgroup{}(", fun_str)?;
+                self.write_expr(module, argument, func_ctx)?;
+                if let Some(index) = index {
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, index, func_ctx)?;
+                }
+                writeln!(self.out, ");")?
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                write!(
+                    self.out,
+                    "subgroup{}{}(",
+                    collective_op.to_wgsl(),
+                    op.to_wgsl()
+                )?;
+                self.write_expr(module, argument, func_ctx)?;
+                writeln!(self.out, ");")?
+            }
             Statement::ImageStore {
                 image,
                 coordinate,
@@ -829,6 +1078,31 @@ impl<W: Write> Writer<W> {
                 self.write_expr(module, value, func_ctx)?;
                 writeln!(self.out, ");")?;
             }
+            Statement::ImageAtomic {
+                image,
+                coordinate,
+                array_index,
+                ref fun,
+                value,
+                result,
+            } => {
+                write!(self.out, "{}", level)?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions.insert(result, res_name);
+
+                write!(self.out, "textureAtomic{}(", fun.to_wgsl())?;
+                self.write_expr(module, image, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, coordinate, func_ctx)?;
+                if let Some(array_index_expr) = array_index {
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, array_index_expr, func_ctx)?;
+                }
+                write!(self.out, ", ")?;
+                self.write_expr(module, value, func_ctx)?;
+                writeln!(self.out, ");")?;
+            }
             // TODO: copy-paste from glsl-out
             Statement::Block(ref block) => {
                 write!(self.out, "{}", level)?;
@@ -919,6 +1193,10 @@ impl<W: Write> Writer<W> {
                 if barrier.contains(crate::Barrier::WORK_GROUP) {
                     writeln!(self.out, "{}workgroupBarrier();", level)?;
                 }
+
+                if barrier.contains(crate::Barrier::SUB_GROUP) {
+                    writeln!(self.out, "{}subgroupBarrier();", level)?;
+                }
             }
         }
 
@@ -993,9 +1271,13 @@ impl<W: Write> Writer<W> {
     ) -> BackendResult {
         // Write variable name
         write!(self.out, "let {}", name)?;
-        if self.flags.contains(WriterFlags::EXPLICIT_TYPES) {
+        let ty = &func_ctx.info[handle].ty;
+        let is_predeclared = match *ty {
+            proc::TypeResolution::Handle(handle) => self.atomic_result_types.contains(&handle),
+            proc::TypeResolution::Value(_) => false,
+        };
+        if self.flags.contains(WriterFlags::EXPLICIT_TYPES) && !is_predeclared {
             write!(self.out, ": ")?;
-            let ty = &func_ctx.info[handle].ty;
             // Write variable type
             match *ty {
                 proc::TypeResolution::Handle(handle) => {
@@ -1095,6 +1377,11 @@ impl<W: Write> Writer<W> {
         // subscripting.
         match *expression {
             Expression::Constant(constant) => self.write_constant(module, constant)?,
+            Expression::RayQueryGetCommittedIntersection { query, ty: _ } => {
+                write!(self.out, "rayQueryGetCommittedIntersection(")?;
+                self.write_expr(module, query, func_ctx)?;
+                write!(self.out, ")")?
+            }
             Expression::Compose { ty, ref components } => {
                 self.write_type(module, ty)?;
                 write!(self.out, "(")?;
@@ -1179,6 +1466,7 @@ impl<W: Write> Writer<W> {
                     }
                     TypeInner::Matrix { .. }
                     | TypeInner::Array { .. }
+                    | TypeInner::BindingArray { .. }
                     | TypeInner::ValuePointer { .. } => write!(self.out, "[{}]", index)?,
                     TypeInner::Struct { .. } => {
                         // This will never panic in case the type is a `Struct`, this is not true
@@ -1362,17 +1650,22 @@ impl<W: Write> Writer<W> {
             } => {
                 let inner = func_ctx.info[expr].ty.inner_with(&module.types);
                 match *inner {
-                    TypeInner::Matrix { columns, rows, .. } => {
+                    TypeInner::Matrix {
+                        columns,
+                        rows,
+                        width,
+                    } => {
                         write!(
                             self.out,
-                            "mat{}x{}<f32>",
+                            "mat{}x{}<{}>",
                             back::vector_size_str(columns),
-                            back::vector_size_str(rows)
+                            back::vector_size_str(rows),
+                            scalar_kind_str(kind, convert.unwrap_or(width))
                         )?;
                     }
-                    TypeInner::Vector { size, .. } => {
+                    TypeInner::Vector { size, width, .. } => {
                         let vector_size_str = back::vector_size_str(size);
-                        let scalar_kind_str = scalar_kind_str(kind);
+                        let scalar_kind_str = scalar_kind_str(kind, convert.unwrap_or(width));
                         if convert.is_some() {
                             write!(self.out, "vec{}<{}>", vector_size_str, scalar_kind_str)?;
                         } else {
@@ -1383,11 +1676,12 @@ impl<W: Write> Writer<W> {
                             )?;
                         }
                     }
-                    TypeInner::Scalar { .. } => {
+                    TypeInner::Scalar { width, .. } => {
+                        let scalar_kind_str = scalar_kind_str(kind, convert.unwrap_or(width));
                         if convert.is_some() {
-                            write!(self.out, "{}", scalar_kind_str(kind))?
+                            write!(self.out, "{}", scalar_kind_str)?
                         } else {
-                            write!(self.out, "bitcast<{}>", scalar_kind_str(kind))?
+                            write!(self.out, "bitcast<{}>", scalar_kind_str)?
                         }
                     }
                     _ => {
@@ -1403,8 +1697,8 @@ impl<W: Write> Writer<W> {
             }
             Expression::Splat { size, value } => {
                 let inner = func_ctx.info[value].ty.inner_with(&module.types);
-                let scalar_kind = match *inner {
-                    crate::TypeInner::Scalar { kind, .. } => kind,
+                let (scalar_kind, scalar_width) = match *inner {
+                    TypeInner::Scalar { kind, width } => (kind, width),
                     _ => {
                         return Err(Error::Unimplemented(format!(
                             "write_expr expression::splat {:?}",
@@ -1412,7 +1706,7 @@ impl<W: Write> Writer<W> {
                         )));
                     }
                 };
-                let scalar = scalar_kind_str(scalar_kind);
+                let scalar = scalar_kind_str(scalar_kind, scalar_width);
                 let size = back::vector_size_str(size);
 
                 write!(self.out, "vec{}<{}>(", size, scalar)?;
@@ -1449,6 +1743,11 @@ impl<W: Write> Writer<W> {
                 self.write_expr(module, expr, func_ctx)?;
                 write!(self.out, ")")?;
             }
+            Expression::NonUniform(value) => {
+                write!(self.out, "nonuniform(")?;
+                self.write_expr(module, value, func_ctx)?;
+                write!(self.out, ")")?;
+            }
             Expression::Math {
                 fun,
                 arg,
@@ -1657,7 +1956,11 @@ impl<W: Write> Writer<W> {
                 write!(self.out, ")")?
             }
             // Nothing to do here, since call expression already cached
-            Expression::CallResult(_) | Expression::AtomicResult { .. } => {}
+            Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::RayQueryProceedResult
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => {}
         }
 
         Ok(())
@@ -1723,14 +2026,11 @@ impl<W: Write> Writer<W> {
     ) -> BackendResult {
         let constant = &module.constants[handle];
         match constant.inner {
-            crate::ConstantInner::Scalar {
-                width: _,
-                ref value,
-            } => {
+            crate::ConstantInner::Scalar { width, ref value } => {
                 if constant.name.is_some() {
                     write!(self.out, "{}", self.names[&NameKey::Constant(handle)])?;
                 } else {
-                    self.write_scalar_value(*value)?;
+                    self.write_scalar_value(*value, width)?;
                 }
             }
             crate::ConstantInner::Composite { ty, ref components } => {
@@ -1781,30 +2081,19 @@ impl<W: Write> Writer<W> {
         handle: Handle<crate::Constant>,
     ) -> BackendResult {
         match *inner {
-            crate::ConstantInner::Scalar {
-                width: _,
-                ref value,
-            } => {
+            crate::ConstantInner::Scalar { width, ref value } => {
                 let name = &self.names[&NameKey::Constant(handle)];
+                let keyword = if module.constants[handle].specialization.is_some() {
+                    "override"
+                } else {
+                    "let"
+                };
                 // First write only constant name
-                write!(self.out, "let {}: ", name)?;
+                write!(self.out, "{} {}: ", keyword, name)?;
                 // Next write constant type and value
-                match *value {
-                    crate::ScalarValue::Sint(value) => {
-                        write!(self.out, "i32 = {}", value)?;
-                    }
-                    crate::ScalarValue::Uint(value) => {
-                        write!(self.out, "u32 = {}u", value)?;
-                    }
-                    crate::ScalarValue::Float(value) => {
-                        // Floats are written using `Debug` instead of `Display` because it always appends the
-                        // decimal part even it's zero
-                        write!(self.out, "f32 = {:?}", value)?;
-                    }
-                    crate::ScalarValue::Bool(value) => {
-                        write!(self.out, "bool = {}", value)?;
-                    }
-                };
+                let kind_str = scalar_kind_str(value.scalar_kind(), width);
+                write!(self.out, "{} = ", kind_str)?;
+                self.write_scalar_value(*value, width)?;
                 // End with semicolon
                 writeln!(self.out, ";")?;
             }
@@ -1846,7 +2135,7 @@ fn builtin_str(built_in: crate::BuiltIn) -> Option<&'static str> {
     match built_in {
         Bi::VertexIndex => Some("vertex_index"),
         Bi::InstanceIndex => Some("instance_index"),
-        Bi::Position => Some("position"),
+        Bi::Position { .. } => Some("position"),
         Bi::FrontFacing => Some("front_facing"),
         Bi::FragDepth => Some("frag_depth"),
         Bi::LocalInvocationId => Some("local_invocation_id"),
@@ -1859,6 +2148,13 @@ fn builtin_str(built_in: crate::BuiltIn) -> Option<&'static str> {
         Bi::SampleMask => Some("sample_mask"),
         Bi::PrimitiveIndex => Some("primitive_index"),
         Bi::ViewIndex => Some("view_index"),
+        Bi::SubgroupSize => Some("subgroup_size"),
+        Bi::SubgroupInvocationId => Some("subgroup_invocation_id"),
+        Bi::MeshTaskSize => Some("mesh_task_size"),
+        Bi::PointIndex => Some("point_index"),
+        Bi::LineIndices => Some("line_indices"),
+        Bi::TriangleIndices => Some("triangle_indices"),
+        Bi::CullPrimitive => Some("cull_primitive"),
         _ => None,
     }
 }
@@ -1874,14 +2170,17 @@ fn image_dimension_str(dim: crate::ImageDimension) -> &'static str {
     }
 }
 
-fn scalar_kind_str(kind: crate::ScalarKind) -> &'static str {
+fn scalar_kind_str(kind: crate::ScalarKind, width: crate::Bytes) -> &'static str {
     use crate::ScalarKind as Sk;
 
-    match kind {
-        Sk::Float => "f32",
-        Sk::Sint => "i32",
-        Sk::Uint => "u32",
-        Sk::Bool => "bool",
+    match (kind, width) {
+        (Sk::Float, 2) => "f16",
+        (Sk::Float, _) => "f32",
+        (Sk::Sint, 8) => "i64",
+        (Sk::Sint, _) => "i32",
+        (Sk::Uint, 8) => "u64",
+        (Sk::Uint, _) => "u32",
+        (Sk::Bool, _) => "bool",
     }
 }
 
@@ -1971,23 +2270,40 @@ fn storage_class_str(
     )
 }
 
+/// Return true if `ty` is one of the structures WGSL predeclares for ray queries.
+fn is_predeclared_ray_type(ty: &crate::Type, types: &crate::UniqueArena<crate::Type>) -> bool {
+    match ty.name.as_deref() {
+        Some(proc::RAY_DESC_NAME) => ty.inner.is_ray_desc(types),
+        Some(proc::RAY_INTERSECTION_NAME) => ty.inner.is_ray_intersection(types),
+        _ => false,
+    }
+}
+
 fn map_binding_to_attribute(
     binding: &crate::Binding,
     scalar_kind: Option<crate::ScalarKind>,
 ) -> Vec<Attribute> {
     match *binding {
+        crate::Binding::BuiltIn(crate::BuiltIn::Position { invariant: true }) => vec![
+            Attribute::BuiltIn(crate::BuiltIn::Position { invariant: true }),
+            Attribute::Invariant,
+        ],
         crate::Binding::BuiltIn(built_in) => vec![Attribute::BuiltIn(built_in)],
         crate::Binding::Location {
             location,
             interpolation,
             sampling,
-        } => match scalar_kind {
-            Some(crate::ScalarKind::Float) => vec![
-                Attribute::Location(location),
-                Attribute::Interpolate(interpolation, sampling),
-            ],
-            _ => vec![Attribute::Location(location)],
-        },
+            blend_src,
+        } => {
+            let mut attributes = vec![Attribute::Location(location)];
+            if let Some(blend_src) = blend_src {
+                attributes.push(Attribute::BlendSrc(blend_src));
+            }
+            if scalar_kind == Some(crate::ScalarKind::Float) {
+                attributes.push(Attribute::Interpolate(interpolation, sampling));
+            }
+            attributes
+        }
     }
 }
 
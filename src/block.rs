@@ -97,6 +97,20 @@ impl Block {
         self.body.iter_mut().zip(span_iter)
     }
 
+    pub fn span_into_iter(self) -> impl Iterator<Item = (Statement, Span)> {
+        let Block {
+            body,
+            #[cfg(feature = "span")]
+            span_info,
+        } = self;
+        #[cfg(feature = "span")]
+        let span_iter = span_info.into_iter();
+        #[cfg(not(feature = "span"))]
+        let span_iter = std::iter::repeat_with(|| Span::UNDEFINED);
+
+        body.into_iter().zip(span_iter)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.body.is_empty()
     }
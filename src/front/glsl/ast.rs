@@ -141,8 +141,10 @@ pub enum TypeQualifier {
     Set(u32),
     Binding(u32),
     Location(u32),
+    Index(u32),
     WorkGroupSize(usize, u32),
     Sampling(Sampling),
+    Invariant,
     Layout(StructLayout),
     Precision(Precision),
     EarlyFragmentTests,
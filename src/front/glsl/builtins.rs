@@ -4,9 +4,9 @@ use super::{
     Error, ErrorKind, Parser, Result,
 };
 use crate::{
-    BinaryOperator, Block, Constant, DerivativeAxis, Expression, Handle, ImageClass,
-    ImageDimension, ImageQuery, MathFunction, Module, RelationalFunction, SampleLevel,
-    ScalarKind as Sk, Span, Type, TypeInner, VectorSize,
+    BinaryOperator, Block, CollectiveOperation, Constant, DerivativeAxis, Expression, Handle,
+    ImageClass, ImageDimension, ImageQuery, MathFunction, Module, RelationalFunction, SampleLevel,
+    ScalarKind as Sk, Span, Statement, SubgroupOperation, Type, TypeInner, VectorSize,
 };
 
 impl Module {
@@ -963,6 +963,147 @@ pub fn inject_builtin(declaration: &mut FunctionDeclaration, module: &mut Module
                     .push(module.add_builtin(args, MacroCall::Clamp(size)))
             }
         }
+        "subgroupElect" => declaration
+            .overloads
+            .push(module.add_builtin(Vec::new(), MacroCall::SubgroupElect)),
+        "subgroupBallot" => declaration.overloads.push(module.add_builtin(
+            vec![TypeInner::Scalar {
+                kind: Sk::Bool,
+                width: crate::BOOL_WIDTH,
+            }],
+            MacroCall::SubgroupBallot,
+        )),
+        "subgroupAll" | "subgroupAny" => {
+            let op = match name {
+                "subgroupAll" => SubgroupOperation::All,
+                _ => SubgroupOperation::Any,
+            };
+            declaration.overloads.push(module.add_builtin(
+                vec![TypeInner::Scalar {
+                    kind: Sk::Bool,
+                    width: crate::BOOL_WIDTH,
+                }],
+                MacroCall::SubgroupCollective(op, CollectiveOperation::Reduce),
+            ))
+        }
+        "subgroupAdd"
+        | "subgroupMul"
+        | "subgroupMin"
+        | "subgroupMax"
+        | "subgroupAnd"
+        | "subgroupOr"
+        | "subgroupXor"
+        | "subgroupInclusiveAdd"
+        | "subgroupInclusiveMul"
+        | "subgroupExclusiveAdd"
+        | "subgroupExclusiveMul" => {
+            let (op, collective_op) = match name {
+                "subgroupAdd" => (SubgroupOperation::Add, CollectiveOperation::Reduce),
+                "subgroupMul" => (SubgroupOperation::Mul, CollectiveOperation::Reduce),
+                "subgroupMin" => (SubgroupOperation::Min, CollectiveOperation::Reduce),
+                "subgroupMax" => (SubgroupOperation::Max, CollectiveOperation::Reduce),
+                "subgroupAnd" => (SubgroupOperation::And, CollectiveOperation::Reduce),
+                "subgroupOr" => (SubgroupOperation::Or, CollectiveOperation::Reduce),
+                "subgroupXor" => (SubgroupOperation::Xor, CollectiveOperation::Reduce),
+                "subgroupInclusiveAdd" => {
+                    (SubgroupOperation::Add, CollectiveOperation::InclusiveScan)
+                }
+                "subgroupInclusiveMul" => {
+                    (SubgroupOperation::Mul, CollectiveOperation::InclusiveScan)
+                }
+                "subgroupExclusiveAdd" => {
+                    (SubgroupOperation::Add, CollectiveOperation::ExclusiveScan)
+                }
+                "subgroupExclusiveMul" => {
+                    (SubgroupOperation::Mul, CollectiveOperation::ExclusiveScan)
+                }
+                _ => unreachable!(),
+            };
+            let bitwise = match op {
+                SubgroupOperation::And | SubgroupOperation::Or | SubgroupOperation::Xor => true,
+                _ => false,
+            };
+
+            // bits layout
+            // bit 0 trough 1 - float/int/uint/bool
+            // bit 2 trough 3 - dims
+            for bits in 0..0b10000 {
+                let kind = match bits & 0b11 {
+                    0b00 if !bitwise => Sk::Float,
+                    0b01 => Sk::Sint,
+                    0b10 => Sk::Uint,
+                    0b11 if bitwise => Sk::Bool,
+                    _ => continue,
+                };
+                let width = match kind {
+                    Sk::Bool => crate::BOOL_WIDTH,
+                    _ => width,
+                };
+                let size = match bits >> 2 {
+                    0b00 => None,
+                    0b01 => Some(VectorSize::Bi),
+                    0b10 => Some(VectorSize::Tri),
+                    _ => Some(VectorSize::Quad),
+                };
+
+                declaration.overloads.push(module.add_builtin(
+                    vec![match size {
+                        Some(size) => TypeInner::Vector { size, kind, width },
+                        None => TypeInner::Scalar { kind, width },
+                    }],
+                    MacroCall::SubgroupCollective(op, collective_op),
+                ))
+            }
+        }
+        "subgroupBroadcastFirst"
+        | "subgroupBroadcast"
+        | "subgroupShuffle"
+        | "subgroupShuffleXor"
+        | "subgroupShuffleUp"
+        | "subgroupShuffleDown" => {
+            let mode = match name {
+                "subgroupBroadcastFirst" => SubgroupGather::BroadcastFirst,
+                "subgroupBroadcast" => SubgroupGather::Broadcast,
+                "subgroupShuffle" => SubgroupGather::Shuffle,
+                "subgroupShuffleXor" => SubgroupGather::ShuffleXor,
+                "subgroupShuffleUp" => SubgroupGather::ShuffleUp,
+                "subgroupShuffleDown" => SubgroupGather::ShuffleDown,
+                _ => unreachable!(),
+            };
+
+            // bits layout
+            // bit 0 trough 1 - float/int/uint/bool
+            // bit 2 trough 3 - dims
+            for bits in 0..0b10000 {
+                let (kind, width) = match bits & 0b11 {
+                    0b00 => (Sk::Float, width),
+                    0b01 => (Sk::Sint, width),
+                    0b10 => (Sk::Uint, width),
+                    _ => (Sk::Bool, crate::BOOL_WIDTH),
+                };
+                let size = match bits >> 2 {
+                    0b00 => None,
+                    0b01 => Some(VectorSize::Bi),
+                    0b10 => Some(VectorSize::Tri),
+                    _ => Some(VectorSize::Quad),
+                };
+
+                let mut args = vec![match size {
+                    Some(size) => TypeInner::Vector { size, kind, width },
+                    None => TypeInner::Scalar { kind, width },
+                }];
+                if mode != SubgroupGather::BroadcastFirst {
+                    args.push(TypeInner::Scalar {
+                        kind: Sk::Uint,
+                        width: 4,
+                    });
+                }
+
+                declaration
+                    .overloads
+                    .push(module.add_builtin(args, MacroCall::SubgroupGather(mode)))
+            }
+        }
         // Add common builtins with floats
         _ => inject_common_builtin(declaration, module, name, 4),
     }
@@ -1529,6 +1670,18 @@ pub enum TextureLevelType {
     Grad,
 }
 
+/// The kind of a subgroup gather builtin, the index argument (if any) is only
+/// known once the builtin is called
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SubgroupGather {
+    BroadcastFirst,
+    Broadcast,
+    Shuffle,
+    ShuffleDown,
+    ShuffleUp,
+    ShuffleXor,
+}
+
 /// A compiler defined builtin function
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum MacroCall {
@@ -1553,6 +1706,10 @@ pub enum MacroCall {
     Clamp(Option<VectorSize>),
     BitCast(Sk),
     Derivate(DerivativeAxis),
+    SubgroupBallot,
+    SubgroupElect,
+    SubgroupGather(SubgroupGather),
+    SubgroupCollective(SubgroupOperation, CollectiveOperation),
 }
 
 impl MacroCall {
@@ -1884,10 +2041,108 @@ impl MacroCall {
                 Span::default(),
                 body,
             )),
+            MacroCall::SubgroupBallot => {
+                ctx.emit_flush(body);
+                let result = ctx.add_expression(Expression::SubgroupBallotResult, meta, body);
+                body.push(
+                    Statement::SubgroupBallot {
+                        result,
+                        predicate: Some(args[0]),
+                    },
+                    meta,
+                );
+                ctx.emit_start();
+                Ok(result)
+            }
+            MacroCall::SubgroupElect => {
+                let ty = parser.module.types.insert(
+                    Type {
+                        name: None,
+                        inner: TypeInner::Scalar {
+                            kind: Sk::Bool,
+                            width: crate::BOOL_WIDTH,
+                        },
+                    },
+                    Span::default(),
+                );
+                ctx.emit_flush(body);
+                let result =
+                    ctx.add_expression(Expression::SubgroupOperationResult { ty }, meta, body);
+                body.push(Statement::SubgroupElect { result }, meta);
+                ctx.emit_start();
+                Ok(result)
+            }
+            MacroCall::SubgroupGather(mode) => {
+                let ty = subgroup_result_type(parser, ctx, args[0], meta)?;
+                let mode = match mode {
+                    SubgroupGather::BroadcastFirst => crate::GatherMode::BroadcastFirst,
+                    SubgroupGather::Broadcast => crate::GatherMode::Broadcast(args[1]),
+                    SubgroupGather::Shuffle => crate::GatherMode::Shuffle(args[1]),
+                    SubgroupGather::ShuffleDown => crate::GatherMode::ShuffleDown(args[1]),
+                    SubgroupGather::ShuffleUp => crate::GatherMode::ShuffleUp(args[1]),
+                    SubgroupGather::ShuffleXor => crate::GatherMode::ShuffleXor(args[1]),
+                };
+                ctx.emit_flush(body);
+                let result =
+                    ctx.add_expression(Expression::SubgroupOperationResult { ty }, meta, body);
+                body.push(
+                    Statement::SubgroupGather {
+                        mode,
+                        argument: args[0],
+                        result,
+                    },
+                    meta,
+                );
+                ctx.emit_start();
+                Ok(result)
+            }
+            MacroCall::SubgroupCollective(op, collective_op) => {
+                let ty = subgroup_result_type(parser, ctx, args[0], meta)?;
+                ctx.emit_flush(body);
+                let result =
+                    ctx.add_expression(Expression::SubgroupOperationResult { ty }, meta, body);
+                body.push(
+                    Statement::SubgroupCollectiveOperation {
+                        op,
+                        collective_op,
+                        argument: args[0],
+                        result,
+                    },
+                    meta,
+                );
+                ctx.emit_start();
+                Ok(result)
+            }
         }
     }
 }
 
+/// Helper function that returns a type handle for the result of a subgroup
+/// operation, which always has the same type as its argument
+fn subgroup_result_type(
+    parser: &mut Parser,
+    ctx: &mut Context,
+    argument: Handle<Expression>,
+    meta: Span,
+) -> Result<Handle<Type>> {
+    let inner = match *parser.resolve_type(ctx, argument, meta)? {
+        TypeInner::Scalar { kind, width } => TypeInner::Scalar { kind, width },
+        TypeInner::Vector { size, kind, width } => TypeInner::Vector { size, kind, width },
+        _ => {
+            return Err(Error {
+                kind: ErrorKind::SemanticError(
+                    "Subgroup operations require a scalar or vector argument".into(),
+                ),
+                meta,
+            })
+        }
+    };
+    Ok(parser
+        .module
+        .types
+        .insert(Type { name: None, inner }, Span::default()))
+}
+
 fn texture_call(
     ctx: &mut Context,
     image: Handle<Expression>,
@@ -1190,6 +1190,7 @@ impl Parser {
                                 location,
                                 interpolation,
                                 sampling: None,
+                                blend_src: None,
                             };
                             location += 1;
                             binding
@@ -1336,6 +1337,8 @@ impl Parser {
             early_depth_test: Some(crate::EarlyDepthTest { conservative: None })
                 .filter(|_| self.meta.early_fragment_tests),
             workgroup_size: self.meta.workgroup_size,
+            workgroup_size_overrides: None,
+            mesh_info: None,
             function: Function {
                 arguments,
                 expressions,
@@ -77,6 +77,7 @@ impl<'a> Iterator for Lexer<'a> {
                     "smooth" => TokenValue::Interpolation(crate::Interpolation::Perspective),
                     "centroid" => TokenValue::Sampling(crate::Sampling::Centroid),
                     "sample" => TokenValue::Sampling(crate::Sampling::Sample),
+                    "invariant" => TokenValue::Invariant,
                     "const" => TokenValue::Const,
                     "inout" => TokenValue::InOut,
                     "precision" => TokenValue::Precision,
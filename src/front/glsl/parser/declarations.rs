@@ -366,9 +366,18 @@ impl<'source> ParsingContext<'source> {
                                 token.meta,
                             )
                             .map(Some)
+                        } else if self.bump_if(parser, TokenValue::Semicolon).is_some() {
+                            // type_qualifier IDENTIFIER SEMICOLON
+                            parser.add_variable_qualifiers(
+                                ctx,
+                                body,
+                                &ty_name,
+                                &qualifiers,
+                                token.meta,
+                            )?;
+                            Ok(Some(token.meta))
                         } else {
                             //TODO: declaration
-                            // type_qualifier IDENTIFIER SEMICOLON
                             // type_qualifier IDENTIFIER identifier_list SEMICOLON
                             Err(Error {
                                 kind: ErrorKind::NotImplemented("variable qualifier"),
@@ -114,6 +114,7 @@ impl<'source> ParsingContext<'source> {
         self.peek(parser).map_or(false, |t| match t.value {
             TokenValue::Interpolation(_)
             | TokenValue::Sampling(_)
+            | TokenValue::Invariant
             | TokenValue::PrecisionQualifier(_)
             | TokenValue::Const
             | TokenValue::In
@@ -161,6 +162,7 @@ impl<'source> ParsingContext<'source> {
                         }),
                     ),
                     TokenValue::Sampling(s) => TypeQualifier::Sampling(s),
+                    TokenValue::Invariant => TypeQualifier::Invariant,
                     TokenValue::PrecisionQualifier(p) => TypeQualifier::Precision(p),
                     TokenValue::StorageAccess(access) => TypeQualifier::StorageAccess(access),
                     TokenValue::Restrict => continue,
@@ -212,6 +214,7 @@ impl<'source> ParsingContext<'source> {
                     qualifiers.push((
                         match name.as_str() {
                             "location" => TypeQualifier::Location(value),
+                            "index" => TypeQualifier::Index(value),
                             "set" => TypeQualifier::Set(value),
                             "binding" => TypeQualifier::Binding(value),
                             "local_size_x" => TypeQualifier::WorkGroupSize(0, value),
@@ -42,6 +42,7 @@ pub enum TokenValue {
 
     Interpolation(Interpolation),
     Sampling(Sampling),
+    Invariant,
     Precision,
     PrecisionQualifier(Precision),
 
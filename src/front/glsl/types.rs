@@ -1,7 +1,8 @@
-use super::{constants::ConstantSolver, context::Context, Error, ErrorKind, Parser, Result, Span};
+use super::{context::Context, Error, ErrorKind, Parser, Result, Span};
 use crate::{
-    proc::ResolveContext, ArraySize, Bytes, Constant, Expression, Handle, ImageClass,
-    ImageDimension, ScalarKind, Type, TypeInner, VectorSize,
+    proc::{ConstantEvaluator, ResolveContext},
+    ArraySize, Bytes, Constant, Expression, Handle, ImageClass, ImageDimension, ScalarKind, Type,
+    TypeInner, VectorSize,
 };
 
 pub fn parse_type(type_name: &str) -> Option<Type> {
@@ -20,6 +21,13 @@ pub fn parse_type(type_name: &str) -> Option<Type> {
                 width: 4,
             },
         }),
+        "float16_t" => Some(Type {
+            name: None,
+            inner: TypeInner::Scalar {
+                kind: ScalarKind::Float,
+                width: 2,
+            },
+        }),
         "double" => Some(Type {
             name: None,
             inner: TypeInner::Scalar {
@@ -41,6 +49,20 @@ pub fn parse_type(type_name: &str) -> Option<Type> {
                 width: 4,
             },
         }),
+        "int64_t" => Some(Type {
+            name: None,
+            inner: TypeInner::Scalar {
+                kind: ScalarKind::Sint,
+                width: 8,
+            },
+        }),
+        "uint64_t" => Some(Type {
+            name: None,
+            inner: TypeInner::Scalar {
+                kind: ScalarKind::Uint,
+                width: 8,
+            },
+        }),
         "sampler" | "samplerShadow" => Some(Type {
             name: None,
             inner: TypeInner::Sampler {
@@ -55,6 +77,9 @@ pub fn parse_type(type_name: &str) -> Option<Type> {
                     "i" => (ScalarKind::Sint, 4),
                     "u" => (ScalarKind::Uint, 4),
                     "d" => (ScalarKind::Float, 8),
+                    "f16" => (ScalarKind::Float, 2),
+                    "i64" => (ScalarKind::Sint, 8),
+                    "u64" => (ScalarKind::Uint, 8),
                     _ => return None,
                 })
             }
@@ -87,7 +112,10 @@ pub fn parse_type(type_name: &str) -> Option<Type> {
 
                 let kind = iter.next()?;
                 let size = iter.next()?;
-                let (_, width) = kind_width_parse(kind)?;
+                let width = match kind_width_parse(kind)? {
+                    (ScalarKind::Float, width) => width,
+                    _ => return None,
+                };
 
                 let (columns, rows) = if let Some(size) = size_parse(size) {
                     (size, size)
@@ -171,8 +199,9 @@ pub fn type_power(kind: ScalarKind, width: Bytes) -> Option<u32> {
     Some(match kind {
         ScalarKind::Sint => 0,
         ScalarKind::Uint => 1,
-        ScalarKind::Float if width == 4 => 2,
-        ScalarKind::Float => 3,
+        ScalarKind::Float if width == 2 => 2,
+        ScalarKind::Float if width == 4 => 3,
+        ScalarKind::Float => 4,
         ScalarKind::Bool => return None,
     })
 }
@@ -241,13 +270,13 @@ impl Parser {
         root: Handle<Expression>,
         meta: Span,
     ) -> Result<Handle<Constant>> {
-        let mut solver = ConstantSolver {
+        let mut evaluator = ConstantEvaluator {
             types: &mut self.module.types,
-            expressions: &ctx.expressions,
             constants: &mut self.module.constants,
+            expressions: &ctx.expressions,
         };
 
-        solver.solve(root).map_err(|e| Error {
+        evaluator.evaluate(root).map_err(|e| Error {
             kind: e.into(),
             meta,
         })
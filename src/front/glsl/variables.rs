@@ -130,7 +130,7 @@ impl Parser {
                     kind: ScalarKind::Float,
                     width: 4,
                 },
-                builtin: BuiltIn::Position,
+                builtin: BuiltIn::Position { invariant: false },
                 mutable: true,
                 storage: StorageQualifier::Output,
             },
@@ -140,7 +140,7 @@ impl Parser {
                     kind: ScalarKind::Float,
                     width: 4,
                 },
-                builtin: BuiltIn::Position,
+                builtin: BuiltIn::Position { invariant: false },
                 mutable: false,
                 storage: StorageQualifier::Input,
             },
@@ -223,6 +223,8 @@ impl Parser {
                     "gl_VertexIndex" => BuiltIn::VertexIndex,
                     "gl_SampleID" => BuiltIn::SampleIndex,
                     "gl_LocalInvocationIndex" => BuiltIn::LocalInvocationIndex,
+                    "gl_SubgroupSize" => BuiltIn::SubgroupSize,
+                    "gl_SubgroupInvocationID" => BuiltIn::SubgroupInvocationId,
                     _ => return None,
                 };
 
@@ -400,6 +402,52 @@ impl Parser {
         }
     }
 
+    /// Applies the qualifiers of a redeclaration like `invariant gl_Position;`
+    /// to an existing variable
+    pub(crate) fn add_variable_qualifiers(
+        &mut self,
+        ctx: &mut Context,
+        body: &mut Block,
+        name: &str,
+        qualifiers: &[(TypeQualifier, Span)],
+        meta: Span,
+    ) -> Result<()> {
+        let var = self
+            .lookup_variable(ctx, body, name, meta)
+            .ok_or_else(|| Error {
+                kind: ErrorKind::UnknownVariable(name.into()),
+                meta,
+            })?;
+
+        for &(ref qualifier, meta) in qualifiers {
+            match *qualifier {
+                TypeQualifier::Invariant => {
+                    match var.entry_arg.map(|idx| &mut self.entry_args[idx]) {
+                        Some(&mut EntryArg {
+                            binding: Binding::BuiltIn(BuiltIn::Position { ref mut invariant }),
+                            storage: StorageQualifier::Output,
+                            ..
+                        }) => *invariant = true,
+                        _ => self.errors.push(Error {
+                            kind: ErrorKind::SemanticError(
+                                "invariant can only be applied to gl_Position".into(),
+                            ),
+                            meta,
+                        }),
+                    }
+                }
+                _ => self.errors.push(Error {
+                    kind: ErrorKind::SemanticError(
+                        "Qualifier not supported in redeclarations".into(),
+                    ),
+                    meta,
+                }),
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn add_global_var(
         &mut self,
         ctx: &mut Context,
@@ -417,6 +465,7 @@ impl Parser {
         let mut set = None;
         let mut binding = None;
         let mut location = None;
+        let mut index = None;
         let mut sampling = None;
         let mut layout = None;
         let mut precision = None;
@@ -469,6 +518,13 @@ impl Parser {
                     "Cannot use more than one binding per declaration",
                     self.errors
                 ),
+                TypeQualifier::Index(i) => qualifier_arm!(
+                    i,
+                    index,
+                    meta,
+                    "Cannot use more than one index per declaration",
+                    self.errors
+                ),
                 TypeQualifier::Sampling(s) => qualifier_arm!(
                     s,
                     sampling,
@@ -534,6 +590,15 @@ impl Parser {
             }
         }
 
+        if index.is_some() && location.is_none() {
+            return Err(Error {
+                kind: ErrorKind::SemanticError(
+                    "index can only be used together with a location".into(),
+                ),
+                meta,
+            });
+        }
+
         if (sampling.is_some() || interpolation.is_some()) && location.is_none() {
             return Err(Error {
                 kind: ErrorKind::SemanticError(
@@ -572,6 +637,7 @@ impl Parser {
                     location,
                     interpolation,
                     sampling,
+                    blend_src: index,
                 },
                 handle,
                 storage,
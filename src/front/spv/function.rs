@@ -414,7 +414,7 @@ impl<I: Iterator<Item = u32>> super::Parser<I> {
 
             for (member_index, member) in members.iter().enumerate() {
                 match member.binding {
-                    Some(crate::Binding::BuiltIn(crate::BuiltIn::Position))
+                    Some(crate::Binding::BuiltIn(crate::BuiltIn::Position { .. }))
                         if self.options.adjust_coordinate_space =>
                     {
                         let mut emitter = Emitter::default();
@@ -513,6 +513,8 @@ impl<I: Iterator<Item = u32>> super::Parser<I> {
                 stage: ep.stage,
                 early_depth_test: ep.early_depth_test,
                 workgroup_size: ep.workgroup_size,
+                workgroup_size_overrides: None,
+                mesh_info: None,
                 function,
             });
         }
@@ -29,6 +29,7 @@ mod error;
 mod function;
 mod image;
 mod null;
+mod subgroup;
 
 use convert::*;
 pub use error::Error;
@@ -65,9 +66,20 @@ pub const SUPPORTED_CAPABILITIES: &[spirv::Capability] = &[
     spirv::Capability::Int8,
     spirv::Capability::Int16,
     spirv::Capability::Int64,
+    spirv::Capability::Int64Atomics,
     spirv::Capability::Float16,
     spirv::Capability::Float64,
     spirv::Capability::Geometry,
+    spirv::Capability::StorageBuffer16BitAccess,
+    spirv::Capability::UniformAndStorageBuffer16BitAccess,
+    spirv::Capability::StoragePushConstant16,
+    spirv::Capability::StorageInputOutput16,
+    spirv::Capability::GroupNonUniform,
+    spirv::Capability::GroupNonUniformVote,
+    spirv::Capability::GroupNonUniformArithmetic,
+    spirv::Capability::GroupNonUniformBallot,
+    spirv::Capability::GroupNonUniformShuffle,
+    spirv::Capability::GroupNonUniformShuffleRelative,
     // tricky ones
     spirv::Capability::UniformBufferArrayDynamicIndexing,
     spirv::Capability::StorageBufferArrayDynamicIndexing,
@@ -76,6 +88,7 @@ pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "SPV_KHR_storage_buffer_storage_class",
     "SPV_KHR_vulkan_memory_model",
     "SPV_KHR_multiview",
+    "SPV_KHR_16bit_storage",
 ];
 pub const SUPPORTED_EXT_SETS: &[&str] = &["GLSL.std.450"];
 
@@ -191,7 +204,9 @@ enum Majority {
 struct Decoration {
     name: Option<String>,
     built_in: Option<spirv::Word>,
+    invariant: bool,
     location: Option<spirv::Word>,
+    index: Option<spirv::Word>,
     desc_set: Option<spirv::Word>,
     desc_index: Option<spirv::Word>,
     specialization: Option<spirv::Word>,
@@ -229,11 +244,13 @@ impl Decoration {
             Decoration {
                 built_in: Some(built_in),
                 location: None,
+                invariant,
                 ..
-            } => map_builtin(built_in).map(crate::Binding::BuiltIn),
+            } => map_builtin(built_in, invariant).map(crate::Binding::BuiltIn),
             Decoration {
                 built_in: None,
                 location: Some(location),
+                index,
                 interpolation,
                 sampling,
                 ..
@@ -241,6 +258,7 @@ impl Decoration {
                 location,
                 interpolation,
                 sampling,
+                blend_src: index,
             }),
             _ => Err(Error::MissingDecoration(spirv::Decoration::Location)),
         }
@@ -655,6 +673,13 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                 inst.expect(base_words + 2)?;
                 dec.location = Some(self.next()?);
             }
+            spirv::Decoration::Index => {
+                inst.expect(base_words + 2)?;
+                dec.index = Some(self.next()?);
+            }
+            spirv::Decoration::Invariant => {
+                dec.invariant = true;
+            }
             spirv::Decoration::DescriptorSet => {
                 inst.expect(base_words + 2)?;
                 dec.desc_set = Some(self.next()?);
@@ -3199,7 +3224,9 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                         } => raw as u32,
                         _ => return Err(Error::InvalidBarrierMemorySemantics(semantics_id)),
                     };
-                    if exec_scope == spirv::Scope::Workgroup as u32 {
+                    if exec_scope == spirv::Scope::Subgroup as u32 {
+                        block.push(crate::Statement::Barrier(crate::Barrier::SUB_GROUP), span);
+                    } else if exec_scope == spirv::Scope::Workgroup as u32 {
                         let mut flags = crate::Barrier::empty();
                         flags.set(
                             crate::Barrier::STORAGE,
@@ -3218,6 +3245,61 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                         log::warn!("Unsupported barrier execution scope: {}", exec_scope);
                     }
                 }
+                Op::GroupNonUniformElect => {
+                    self.parse_subgroup_elect(inst, ctx, &mut emitter, &mut block, block_id)?;
+                }
+                Op::GroupNonUniformBallot => {
+                    self.parse_subgroup_ballot(
+                        inst,
+                        ctx,
+                        &mut emitter,
+                        &mut block,
+                        block_id,
+                        body_idx,
+                    )?;
+                }
+                Op::GroupNonUniformBroadcastFirst
+                | Op::GroupNonUniformBroadcast
+                | Op::GroupNonUniformShuffle
+                | Op::GroupNonUniformShuffleDown
+                | Op::GroupNonUniformShuffleUp
+                | Op::GroupNonUniformShuffleXor => {
+                    self.parse_subgroup_gather(
+                        inst,
+                        ctx,
+                        &mut emitter,
+                        &mut block,
+                        block_id,
+                        body_idx,
+                    )?;
+                }
+                Op::GroupNonUniformAll
+                | Op::GroupNonUniformAny
+                | Op::GroupNonUniformIAdd
+                | Op::GroupNonUniformFAdd
+                | Op::GroupNonUniformIMul
+                | Op::GroupNonUniformFMul
+                | Op::GroupNonUniformSMin
+                | Op::GroupNonUniformUMin
+                | Op::GroupNonUniformFMin
+                | Op::GroupNonUniformSMax
+                | Op::GroupNonUniformUMax
+                | Op::GroupNonUniformFMax
+                | Op::GroupNonUniformBitwiseAnd
+                | Op::GroupNonUniformBitwiseOr
+                | Op::GroupNonUniformBitwiseXor
+                | Op::GroupNonUniformLogicalAnd
+                | Op::GroupNonUniformLogicalOr
+                | Op::GroupNonUniformLogicalXor => {
+                    self.parse_subgroup_collective_operation(
+                        inst,
+                        ctx,
+                        &mut emitter,
+                        &mut block,
+                        block_id,
+                        body_idx,
+                    )?;
+                }
                 Op::CopyObject => {
                     inst.expect(4)?;
                     let result_type_id = self.next()?;
@@ -3361,7 +3443,14 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                 | S::Barrier(_)
                 | S::Store { .. }
                 | S::ImageStore { .. }
-                | S::Atomic { .. } => {}
+                | S::ImageAtomic { .. }
+                | S::Atomic { .. }
+                | S::SubgroupBallot { .. }
+                | S::SubgroupElect { .. }
+                | S::SubgroupGather { .. }
+                | S::SubgroupCollectiveOperation { .. }
+                | S::RayQuery { .. }
+                | S::MeshFunction(_) => {}
                 S::Call {
                     function: ref mut callee,
                     ref arguments,
@@ -4422,6 +4511,7 @@ impl<I: Iterator<Item = u32>> Parser<I> {
             } => {
                 let low = self.next()?;
                 let extended = match width {
+                    2 => map_f16_bits(low as u16),
                     4 => f64::from(f32::from_bits(low)),
                     8 => {
                         inst.expect(5)?;
@@ -0,0 +1,237 @@
+use super::{Error, Instruction, LookupExpression, LookupHelper as _};
+
+use num_traits::cast::FromPrimitive;
+
+impl<I: Iterator<Item = u32>> super::Parser<I> {
+    /// Appends the result expression of a subgroup statement, registers it
+    /// under `result_id` and pushes the statement itself.
+    #[allow(clippy::too_many_arguments)]
+    fn push_subgroup_statement(
+        &mut self,
+        ctx: &mut super::BlockContext,
+        emitter: &mut crate::front::Emitter,
+        block: &mut crate::Block,
+        block_id: spirv::Word,
+        result_type_id: spirv::Word,
+        result_id: spirv::Word,
+        result_expr: crate::Expression,
+        make_statement: impl FnOnce(crate::Handle<crate::Expression>) -> crate::Statement,
+        span: crate::Span,
+    ) {
+        block.extend(emitter.finish(ctx.expressions));
+        let result = ctx.expressions.append(result_expr, span);
+        self.lookup_expression.insert(
+            result_id,
+            LookupExpression {
+                handle: result,
+                type_id: result_type_id,
+                block_id,
+            },
+        );
+        block.push(make_statement(result), span);
+        emitter.start(ctx.expressions);
+    }
+
+    pub(super) fn parse_subgroup_ballot(
+        &mut self,
+        inst: Instruction,
+        ctx: &mut super::BlockContext,
+        emitter: &mut crate::front::Emitter,
+        block: &mut crate::Block,
+        block_id: spirv::Word,
+        body_idx: usize,
+    ) -> Result<(), Error> {
+        inst.expect(5)?;
+        let start = self.data_offset;
+        let result_type_id = self.next()?;
+        let result_id = self.next()?;
+        let _exec_scope_id = self.next()?;
+        let predicate_id = self.next()?;
+
+        let predicate_lexp = self.lookup_expression.lookup(predicate_id)?;
+        let predicate =
+            self.get_expr_handle(predicate_id, predicate_lexp, ctx, emitter, block, body_idx);
+
+        let span = self.span_from_with_op(start);
+        self.push_subgroup_statement(
+            ctx,
+            emitter,
+            block,
+            block_id,
+            result_type_id,
+            result_id,
+            crate::Expression::SubgroupBallotResult,
+            |result| crate::Statement::SubgroupBallot {
+                result,
+                predicate: Some(predicate),
+            },
+            span,
+        );
+        Ok(())
+    }
+
+    pub(super) fn parse_subgroup_elect(
+        &mut self,
+        inst: Instruction,
+        ctx: &mut super::BlockContext,
+        emitter: &mut crate::front::Emitter,
+        block: &mut crate::Block,
+        block_id: spirv::Word,
+    ) -> Result<(), Error> {
+        inst.expect(4)?;
+        let start = self.data_offset;
+        let result_type_id = self.next()?;
+        let result_id = self.next()?;
+        let _exec_scope_id = self.next()?;
+
+        let ty = self.lookup_type.lookup(result_type_id)?.handle;
+        let span = self.span_from_with_op(start);
+        self.push_subgroup_statement(
+            ctx,
+            emitter,
+            block,
+            block_id,
+            result_type_id,
+            result_id,
+            crate::Expression::SubgroupOperationResult { ty },
+            |result| crate::Statement::SubgroupElect { result },
+            span,
+        );
+        Ok(())
+    }
+
+    pub(super) fn parse_subgroup_gather(
+        &mut self,
+        inst: Instruction,
+        ctx: &mut super::BlockContext,
+        emitter: &mut crate::front::Emitter,
+        block: &mut crate::Block,
+        block_id: spirv::Word,
+        body_idx: usize,
+    ) -> Result<(), Error> {
+        let has_index = match inst.op {
+            spirv::Op::GroupNonUniformBroadcastFirst => false,
+            _ => true,
+        };
+        inst.expect(if has_index { 6 } else { 5 })?;
+        let start = self.data_offset;
+        let result_type_id = self.next()?;
+        let result_id = self.next()?;
+        let _exec_scope_id = self.next()?;
+        let argument_id = self.next()?;
+
+        let argument_lexp = self.lookup_expression.lookup(argument_id)?;
+        let argument =
+            self.get_expr_handle(argument_id, argument_lexp, ctx, emitter, block, body_idx);
+
+        let mode = if has_index {
+            let index_id = self.next()?;
+            let index_lexp = self.lookup_expression.lookup(index_id)?;
+            let index = self.get_expr_handle(index_id, index_lexp, ctx, emitter, block, body_idx);
+            match inst.op {
+                spirv::Op::GroupNonUniformBroadcast => crate::GatherMode::Broadcast(index),
+                spirv::Op::GroupNonUniformShuffle => crate::GatherMode::Shuffle(index),
+                spirv::Op::GroupNonUniformShuffleDown => crate::GatherMode::ShuffleDown(index),
+                spirv::Op::GroupNonUniformShuffleUp => crate::GatherMode::ShuffleUp(index),
+                spirv::Op::GroupNonUniformShuffleXor => crate::GatherMode::ShuffleXor(index),
+                _ => unreachable!(),
+            }
+        } else {
+            crate::GatherMode::BroadcastFirst
+        };
+
+        let ty = self.lookup_type.lookup(result_type_id)?.handle;
+        let span = self.span_from_with_op(start);
+        self.push_subgroup_statement(
+            ctx,
+            emitter,
+            block,
+            block_id,
+            result_type_id,
+            result_id,
+            crate::Expression::SubgroupOperationResult { ty },
+            |result| crate::Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(())
+    }
+
+    pub(super) fn parse_subgroup_collective_operation(
+        &mut self,
+        inst: Instruction,
+        ctx: &mut super::BlockContext,
+        emitter: &mut crate::front::Emitter,
+        block: &mut crate::Block,
+        block_id: spirv::Word,
+        body_idx: usize,
+    ) -> Result<(), Error> {
+        use crate::SubgroupOperation as So;
+        use spirv::Op;
+
+        let (op, is_vote) = match inst.op {
+            Op::GroupNonUniformAll => (So::All, true),
+            Op::GroupNonUniformAny => (So::Any, true),
+            Op::GroupNonUniformIAdd | Op::GroupNonUniformFAdd => (So::Add, false),
+            Op::GroupNonUniformIMul | Op::GroupNonUniformFMul => (So::Mul, false),
+            Op::GroupNonUniformSMin | Op::GroupNonUniformUMin | Op::GroupNonUniformFMin => {
+                (So::Min, false)
+            }
+            Op::GroupNonUniformSMax | Op::GroupNonUniformUMax | Op::GroupNonUniformFMax => {
+                (So::Max, false)
+            }
+            Op::GroupNonUniformBitwiseAnd | Op::GroupNonUniformLogicalAnd => (So::And, false),
+            Op::GroupNonUniformBitwiseOr | Op::GroupNonUniformLogicalOr => (So::Or, false),
+            Op::GroupNonUniformBitwiseXor | Op::GroupNonUniformLogicalXor => (So::Xor, false),
+            _ => return Err(Error::UnsupportedInstruction(self.state, inst.op)),
+        };
+        // Clustered reductions take an extra operand, which we don't support.
+        inst.expect(if is_vote { 5 } else { 6 })?;
+        let start = self.data_offset;
+        let result_type_id = self.next()?;
+        let result_id = self.next()?;
+        let _exec_scope_id = self.next()?;
+        let collective_op = if is_vote {
+            crate::CollectiveOperation::Reduce
+        } else {
+            match spirv::GroupOperation::from_u32(self.next()?) {
+                Some(spirv::GroupOperation::Reduce) => crate::CollectiveOperation::Reduce,
+                Some(spirv::GroupOperation::InclusiveScan) => {
+                    crate::CollectiveOperation::InclusiveScan
+                }
+                Some(spirv::GroupOperation::ExclusiveScan) => {
+                    crate::CollectiveOperation::ExclusiveScan
+                }
+                _ => return Err(Error::InvalidParameter(inst.op)),
+            }
+        };
+        let argument_id = self.next()?;
+
+        let argument_lexp = self.lookup_expression.lookup(argument_id)?;
+        let argument =
+            self.get_expr_handle(argument_id, argument_lexp, ctx, emitter, block, body_idx);
+
+        let ty = self.lookup_type.lookup(result_type_id)?.handle;
+        let span = self.span_from_with_op(start);
+        self.push_subgroup_statement(
+            ctx,
+            emitter,
+            block,
+            block_id,
+            result_type_id,
+            result_id,
+            crate::Expression::SubgroupOperationResult { ty },
+            |result| crate::Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Error, Span};
+use super::{EnableExtension, Error, Span};
 
 pub fn map_storage_class(word: &str, span: Span) -> Result<crate::StorageClass, Error<'_>> {
     match word {
@@ -14,9 +14,17 @@ pub fn map_storage_class(word: &str, span: Span) -> Result<crate::StorageClass,
     }
 }
 
+pub fn map_enable_extension(word: &str, span: Span) -> Result<EnableExtension, Error<'_>> {
+    match word {
+        "f16" => Ok(EnableExtension::F16),
+        "dual_source_blending" => Ok(EnableExtension::DualSourceBlending),
+        _ => Err(Error::UnknownEnableExtension(span)),
+    }
+}
+
 pub fn map_built_in(word: &str, span: Span) -> Result<crate::BuiltIn, Error<'_>> {
     Ok(match word {
-        "position" => crate::BuiltIn::Position,
+        "position" => crate::BuiltIn::Position { invariant: false },
         // vertex
         "vertex_index" => crate::BuiltIn::VertexIndex,
         "instance_index" => crate::BuiltIn::InstanceIndex,
@@ -34,6 +42,16 @@ pub fn map_built_in(word: &str, span: Span) -> Result<crate::BuiltIn, Error<'_>>
         "workgroup_id" => crate::BuiltIn::WorkGroupId,
         "workgroup_size" => crate::BuiltIn::WorkGroupSize,
         "num_workgroups" => crate::BuiltIn::NumWorkGroups,
+        // subgroup
+        "subgroup_size" => crate::BuiltIn::SubgroupSize,
+        "subgroup_invocation_id" => crate::BuiltIn::SubgroupInvocationId,
+        // task
+        "mesh_task_size" => crate::BuiltIn::MeshTaskSize,
+        // mesh
+        "point_index" => crate::BuiltIn::PointIndex,
+        "line_indices" => crate::BuiltIn::LineIndices,
+        "triangle_indices" => crate::BuiltIn::TriangleIndices,
+        "cull_primitive" => crate::BuiltIn::CullPrimitive,
         _ => return Err(Error::UnknownBuiltin(span)),
     })
 }
@@ -43,10 +61,24 @@ pub fn map_shader_stage(word: &str, span: Span) -> Result<crate::ShaderStage, Er
         "vertex" => Ok(crate::ShaderStage::Vertex),
         "fragment" => Ok(crate::ShaderStage::Fragment),
         "compute" => Ok(crate::ShaderStage::Compute),
+        "task" => Ok(crate::ShaderStage::Task),
+        "mesh" => Ok(crate::ShaderStage::Mesh),
         _ => Err(Error::UnknownShaderStage(span)),
     }
 }
 
+pub fn map_mesh_output_topology(
+    word: &str,
+    span: Span,
+) -> Result<crate::MeshOutputTopology, Error<'_>> {
+    match word {
+        "points" => Ok(crate::MeshOutputTopology::Points),
+        "lines" => Ok(crate::MeshOutputTopology::Lines),
+        "triangles" => Ok(crate::MeshOutputTopology::Triangles),
+        _ => Err(Error::UnknownAttribute(span)),
+    }
+}
+
 pub fn map_interpolation(word: &str, span: Span) -> Result<crate::Interpolation, Error<'_>> {
     match word {
         "linear" => Ok(crate::Interpolation::Linear),
@@ -141,6 +173,43 @@ pub fn map_relational_fun(word: &str) -> Option<crate::RelationalFunction> {
     }
 }
 
+pub fn map_subgroup_operation(
+    word: &str,
+) -> Option<(crate::SubgroupOperation, crate::CollectiveOperation)> {
+    use crate::{CollectiveOperation as co, SubgroupOperation as sg};
+    Some(match word {
+        "subgroupAll" => (sg::All, co::Reduce),
+        "subgroupAny" => (sg::Any, co::Reduce),
+        "subgroupAdd" => (sg::Add, co::Reduce),
+        "subgroupMul" => (sg::Mul, co::Reduce),
+        "subgroupMin" => (sg::Min, co::Reduce),
+        "subgroupMax" => (sg::Max, co::Reduce),
+        "subgroupAnd" => (sg::And, co::Reduce),
+        "subgroupOr" => (sg::Or, co::Reduce),
+        "subgroupXor" => (sg::Xor, co::Reduce),
+        "subgroupExclusiveAdd" => (sg::Add, co::ExclusiveScan),
+        "subgroupExclusiveMul" => (sg::Mul, co::ExclusiveScan),
+        "subgroupInclusiveAdd" => (sg::Add, co::InclusiveScan),
+        "subgroupInclusiveMul" => (sg::Mul, co::InclusiveScan),
+        _ => return None,
+    })
+}
+
+pub fn map_texture_atomic_function(word: &str) -> Option<crate::AtomicFunction> {
+    use crate::AtomicFunction as Af;
+    Some(match word {
+        "textureAtomicAdd" => Af::Add,
+        "textureAtomicSub" => Af::Subtract,
+        "textureAtomicAnd" => Af::And,
+        "textureAtomicOr" => Af::InclusiveOr,
+        "textureAtomicXor" => Af::ExclusiveOr,
+        "textureAtomicMin" => Af::Min,
+        "textureAtomicMax" => Af::Max,
+        "textureAtomicExchange" => Af::Exchange { compare: None },
+        _ => return None,
+    })
+}
+
 pub fn map_standard_fun(word: &str) -> Option<crate::MathFunction> {
     use crate::MathFunction as Mf;
     Some(match word {
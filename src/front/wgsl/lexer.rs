@@ -262,7 +262,33 @@ fn consume_number(input: &str) -> (Token, &str) {
     let pos = working_substr
         .find(|c| !what(c))
         .unwrap_or_else(|| working_substr.len());
+    let is_integer = !state.uint_suffix
+        && (state.digit_state == NLDigitState::LeadingZero
+            || state.digit_state == NLDigitState::DigitBeforeDot);
+    // A trailing `h` turns an integer or float literal into a 16-bit float.
+    let half_suffix = !state.uint_suffix
+        && working_substr[pos..].starts_with('h')
+        && (state.is_float() || (!hex && is_integer));
+    // Trailing `li` and `lu` make 64-bit integer literals.
+    let long_suffix = if is_integer {
+        if working_substr[pos..].starts_with("li") {
+            Some(NumberType::Sint)
+        } else if working_substr[pos..].starts_with("lu") {
+            Some(NumberType::Uint)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
     let (value, rest) = input.split_at(pos + minus_offset + hex_offset);
+    let rest = if half_suffix {
+        &rest[1..]
+    } else if long_suffix.is_some() {
+        &rest[2..]
+    } else {
+        rest
+    };
 
     // NOTE: This code can use string slicing,
     //       because number literals are exclusively ASCII.
@@ -279,14 +305,22 @@ fn consume_number(input: &str) -> (Token, &str) {
             } else {
                 value
             },
-            ty: if state.uint_suffix {
+            ty: if let Some(ty) = long_suffix {
+                ty
+            } else if state.uint_suffix {
                 NumberType::Uint
-            } else if state.is_float() {
+            } else if state.is_float() || half_suffix {
                 NumberType::Float
             } else {
                 NumberType::Sint
             },
-            width: None,
+            width: if half_suffix {
+                Some(2)
+            } else if long_suffix.is_some() {
+                Some(8)
+            } else {
+                None
+            },
         },
         rest,
     )
@@ -696,6 +730,36 @@ fn test_tokens() {
             Token::Word("f44po"),
         ],
     );
+    sub_test(
+        "-7li 0x10lu",
+        &[
+            Token::Number {
+                value: "-7",
+                ty: NumberType::Sint,
+                width: Some(8),
+            },
+            Token::Number {
+                value: "0x10",
+                ty: NumberType::Uint,
+                width: Some(8),
+            },
+        ],
+    );
+    sub_test(
+        "1.5h 2h",
+        &[
+            Token::Number {
+                value: "1.5",
+                ty: NumberType::Float,
+                width: Some(2),
+            },
+            Token::Number {
+                value: "2",
+                ty: NumberType::Float,
+                width: Some(2),
+            },
+        ],
+    );
     sub_test(
         "æNoø",
         &[Token::Unknown('æ'), Token::Word("No"), Token::Unknown('ø')],
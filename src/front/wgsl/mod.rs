@@ -14,14 +14,14 @@ use crate::{
         ensure_block_returns, Alignment, Layouter, ResolveContext, ResolveError, TypeResolution,
     },
     span::Span as NagaSpan,
-    Bytes, ConstantInner, FastHashMap, ScalarValue,
+    Bytes, ConstantInner, FastHashMap, FastHashSet, ScalarValue,
 };
 
 use self::{
     lexer::Lexer,
     number_literals::{
-        get_f32_literal, get_i32_literal, get_u32_literal, parse_generic_non_negative_int_literal,
-        parse_non_negative_sint_literal,
+        get_f32_literal, get_i32_literal, get_i64_literal, get_u32_literal, get_u64_literal,
+        parse_generic_non_negative_int_literal, parse_non_negative_sint_literal,
     },
 };
 use codespan_reporting::{
@@ -99,7 +99,7 @@ pub enum ExpectedToken<'a> {
     SwitchItem,
     /// Expected: ',', ')'
     WorkgroupSizeSeparator,
-    /// Expected: 'struct', 'let', 'var', 'type', ';', 'fn', eof
+    /// Expected: 'enable', 'struct', 'let', 'var', 'type', ';', 'fn', eof
     GlobalItem,
 }
 
@@ -158,14 +158,21 @@ pub enum Error<'a> {
     UnknownType(Span),
     UnknownStorageFormat(Span),
     UnknownConservativeDepth(Span),
+    UnknownEnableExtension(Span),
+    EnableExtensionNotEnabled {
+        span: Span,
+        extension: EnableExtension,
+    },
     ZeroSizeOrAlign(Span),
     InconsistentBinding(Span),
     UnknownLocalFunction(Span),
     InitializationTypeMismatch(Span, String),
     MissingType(Span),
     MissingAttribute(&'static str, Span),
+    MissingMeshAttribute(&'static str, Span),
     InvalidAtomicPointer(Span),
     InvalidAtomicOperandType(Span),
+    InvalidRayQueryPointer(Span),
     Pointer(&'static str, Span),
     NotPointer(Span),
     NotReference(&'static str, Span),
@@ -174,6 +181,12 @@ pub enum Error<'a> {
         previous: Span,
         current: Span,
     },
+    OverrideIdRedefinition {
+        previous: Span,
+        current: Span,
+    },
+    MissingOverrideInitializer(Span),
+    NonScalarOverride(Span),
     Other,
 }
 
@@ -235,7 +248,7 @@ impl<'a> Error<'a> {
                         ExpectedToken::Statement => "statement".to_string(),
                         ExpectedToken::SwitchItem => "switch item ('case' or 'default') or a closing curly bracket to signify the end of the switch statement ('}')".to_string(),
                         ExpectedToken::WorkgroupSizeSeparator => "workgroup size separator (',') or a closing parenthesis".to_string(),
-                        ExpectedToken::GlobalItem => "global item ('struct', 'let', 'var', 'type', ';', 'fn') or the end of the file".to_string(),
+                        ExpectedToken::GlobalItem => "global item ('enable', 'struct', 'let', 'var', 'type', ';', 'fn') or the end of the file".to_string(),
                     };
                     ParseError {
                     message: format!(
@@ -294,7 +307,26 @@ impl<'a> Error<'a> {
             Error::BadScalarWidth(ref bad_span, width) => ParseError {
                 message: format!("invalid width of `{}` bits for literal", width as u32 * 8,),
                 labels: vec![(bad_span.clone(), "invalid width".into())],
-                notes: vec!["the only valid width is 32 for now".to_string()],
+                notes: vec![
+                    "the only valid widths are 32, 16 for floats and 64 for integers".to_string(),
+                ],
+            },
+            Error::UnknownEnableExtension(ref bad_span) => ParseError {
+                message: format!("unknown enable-extension `{}`", &source[bad_span.clone()]),
+                labels: vec![(bad_span.clone(), "unknown extension".into())],
+                notes: vec![],
+            },
+            Error::EnableExtensionNotEnabled { ref span, extension } => ParseError {
+                message: format!(
+                    "`{}` requires the `{}` extension to be enabled",
+                    &source[span.clone()],
+                    extension.to_wgsl(),
+                ),
+                labels: vec![(span.clone(), "extension not enabled".into())],
+                notes: vec![format!(
+                    "add `enable {};` at the top of the shader",
+                    extension.to_wgsl()
+                )],
             },
             Error::BadAccessor(ref accessor_span) => ParseError {
                 message: format!(
@@ -422,11 +454,21 @@ impl<'a> Error<'a> {
                 labels: vec![(name_span.clone(), format!("definition of `{}`", &source[name_span.clone()]).into())],
                 notes: vec![],
             },
+            Error::MissingMeshAttribute(name, ref span) => ParseError {
+                message: format!("mesh entry point needs a '{}' attribute", name),
+                labels: vec![(span.clone(), "mesh entry point".into())],
+                notes: vec![],
+            },
             Error::InvalidAtomicPointer(ref span) => ParseError {
                 message: "atomic operation is done on a pointer to a non-atomic".to_string(),
                 labels: vec![(span.clone(), "atomic pointer is invalid".into())],
                 notes: vec![],
             },
+            Error::InvalidRayQueryPointer(ref span) => ParseError {
+                message: "ray query operation is done on a pointer to a non-ray-query".to_string(),
+                labels: vec![(span.clone(), "ray query pointer is invalid".into())],
+                notes: vec![],
+            },
             Error::InvalidAtomicOperandType(ref span) => ParseError {
                 message: "atomic operand type is inconsistent with the operation".to_string(),
                 labels: vec![(span.clone(), "atomic operand type is invalid".into())],
@@ -459,6 +501,23 @@ impl<'a> Error<'a> {
                 ],
                 notes: vec![],
             },
+            Error::OverrideIdRedefinition { ref previous, ref current } => ParseError {
+                message: format!("override id `{}` is used more than once", &source[current.clone()]),
+                labels: vec![(current.clone(), "redefinition of the override id".into()),
+                             (previous.clone(), "previous use of the override id".into())
+                ],
+                notes: vec![],
+            },
+            Error::MissingOverrideInitializer(ref name_span) => ParseError {
+                message: format!("override `{}` needs an initializer", &source[name_span.clone()]),
+                labels: vec![(name_span.clone(), format!("definition of `{}`", &source[name_span.clone()]).into())],
+                notes: vec!["overrides without a default value are not supported yet".to_string()],
+            },
+            Error::NonScalarOverride(ref name_span) => ParseError {
+                message: format!("override `{}` must have a scalar type", &source[name_span.clone()]),
+                labels: vec![(name_span.clone(), format!("definition of `{}`", &source[name_span.clone()]).into())],
+                notes: vec![],
+            },
             Error::Other => ParseError {
                 message: "other error".to_string(),
                 labels: vec![],
@@ -609,6 +668,27 @@ impl crate::TypeInner {
                 )
             }
             Ti::Sampler { .. } => "sampler".to_string(),
+            Ti::BindingArray { base, size } => {
+                let member_type = &types[base];
+                let base = match member_type.name {
+                    Some(ref name) => name.clone(),
+                    None => member_type.inner.to_wgsl(types, constants),
+                };
+                match size {
+                    crate::ArraySize::Constant(size) => {
+                        let constant = &constants[size];
+                        let size = match (constant.name.as_ref(), constant.to_array_length()) {
+                            (Some(name), _) => name.clone(),
+                            (None, Some(length)) => length.to_string(),
+                            (None, None) => "unknown".to_string(),
+                        };
+                        format!("binding_array<{}, {}>", base, size)
+                    }
+                    crate::ArraySize::Dynamic => format!("binding_array<{}>", base),
+                }
+            }
+            Ti::AccelerationStructure => "acceleration_structure".to_string(),
+            Ti::RayQuery => "ray_query".to_string(),
         }
     }
 }
@@ -720,17 +800,6 @@ impl crate::ScalarKind {
     }
 }
 
-trait StringValueLookup<'a> {
-    type Value;
-    fn lookup(&self, key: &'a str, span: Span) -> Result<Self::Value, Error<'a>>;
-}
-impl<'a> StringValueLookup<'a> for FastHashMap<&'a str, TypedExpression> {
-    type Value = TypedExpression;
-    fn lookup(&self, key: &'a str, span: Span) -> Result<Self::Value, Error<'a>> {
-        self.get(key).cloned().ok_or(Error::UnknownIdent(span, key))
-    }
-}
-
 struct StatementContext<'input, 'temp, 'out> {
     lookup_ident: &'temp mut FastHashMap<&'input str, TypedExpression>,
     typifier: &'temp mut super::Typifier,
@@ -838,12 +907,83 @@ impl<'a> ExpressionContext<'a, '_, '_> {
         }
     }
 
+    /// Return a handle to the type of the given expression, registering it
+    /// in the type arena if needed.
+    fn register_type(
+        &mut self,
+        handle: Handle<crate::Expression>,
+    ) -> Result<Handle<crate::Type>, Error<'a>> {
+        self.resolve_type(handle)?;
+        Ok(match self.typifier[handle].clone() {
+            TypeResolution::Handle(ty) => ty,
+            TypeResolution::Value(inner) => self
+                .types
+                .insert(crate::Type { name: None, inner }, Default::default()),
+        })
+    }
+
+    /// Get the type of the result of `atomicCompareExchangeWeak` on an atomic
+    /// scalar of the given `kind` and `width`.
+    fn atomic_compare_exchange_result_type(
+        &mut self,
+        kind: crate::ScalarKind,
+        width: Bytes,
+    ) -> Handle<crate::Type> {
+        let value_ty = self.types.insert(
+            crate::Type {
+                name: None,
+                inner: crate::TypeInner::Scalar { kind, width },
+            },
+            Default::default(),
+        );
+        let bool_ty = self.types.insert(
+            crate::Type {
+                name: None,
+                inner: crate::TypeInner::Scalar {
+                    kind: crate::ScalarKind::Bool,
+                    width: crate::BOOL_WIDTH,
+                },
+            },
+            Default::default(),
+        );
+        let prefix = match kind {
+            crate::ScalarKind::Sint => "i",
+            _ => "u",
+        };
+        self.types.insert(
+            crate::Type {
+                name: Some(format!(
+                    "atomic_compare_exchange_result_{}{}",
+                    prefix,
+                    width as u32 * 8
+                )),
+                inner: crate::TypeInner::Struct {
+                    members: vec![
+                        crate::StructMember {
+                            name: Some("old_value".to_string()),
+                            ty: value_ty,
+                            binding: None,
+                            offset: 0,
+                        },
+                        crate::StructMember {
+                            name: Some("exchanged".to_string()),
+                            ty: bool_ty,
+                            binding: None,
+                            offset: width as u32,
+                        },
+                    ],
+                    span: width as u32 * 2,
+                },
+            },
+            Default::default(),
+        )
+    }
+
     fn prepare_sampling(
         &mut self,
-        image_name: &'a str,
+        image: Handle<crate::Expression>,
         span: Span,
     ) -> Result<SamplingContext, Error<'a>> {
-        let image = self.lookup_ident.lookup(image_name, span.clone())?.handle;
         Ok(SamplingContext {
             image,
             arrayed: match *self.resolve_type(image)? {
@@ -1043,8 +1183,8 @@ impl Composition {
 #[derive(Default)]
 struct TypeAttributes {
     // Although WGSL nas no type attributes at the moment, it had them in the past
-// (`[[stride]]`) and may as well acquire some again in the future.
-// Therefore, we are leaving the plumbing in for now.
+    // (`[[stride]]`) and may as well acquire some again in the future.
+    // Therefore, we are leaving the plumbing in for now.
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1071,6 +1211,8 @@ struct BindingParser {
     built_in: Option<crate::BuiltIn>,
     interpolation: Option<crate::Interpolation>,
     sampling: Option<crate::Sampling>,
+    blend_src: Option<u32>,
+    invariant: bool,
 }
 
 impl BindingParser {
@@ -1079,6 +1221,7 @@ impl BindingParser {
         lexer: &mut Lexer<'a>,
         name: &'a str,
         name_span: Span,
+        enable_extensions: &FastHashSet<EnableExtension>,
     ) -> Result<(), Error<'a>> {
         match name {
             "location" => {
@@ -1102,6 +1245,18 @@ impl BindingParser {
                 }
                 lexer.expect(Token::Paren(')'))?;
             }
+            "blend_src" => {
+                if !enable_extensions.contains(&EnableExtension::DualSourceBlending) {
+                    return Err(Error::EnableExtensionNotEnabled {
+                        span: name_span,
+                        extension: EnableExtension::DualSourceBlending,
+                    });
+                }
+                lexer.expect(Token::Paren('('))?;
+                self.blend_src = Some(parse_non_negative_sint_literal(lexer, 4)?);
+                lexer.expect(Token::Paren(')'))?;
+            }
+            "invariant" => self.invariant = true,
             _ => return Err(Error::UnknownAttribute(name_span)),
         }
         Ok(())
@@ -1113,9 +1268,10 @@ impl BindingParser {
             self.built_in,
             self.interpolation,
             self.sampling,
+            self.blend_src,
         ) {
-            (None, None, None, None) => Ok(None),
-            (Some(location), None, interpolation, sampling) => {
+            (None, None, None, None, None) if !self.invariant => Ok(None),
+            (Some(location), None, interpolation, sampling, blend_src) if !self.invariant => {
                 // Before handing over the completed `Module`, we call
                 // `apply_default_interpolation` to ensure that the interpolation and
                 // sampling have been explicitly specified on all vertex shader output and fragment
@@ -1124,10 +1280,18 @@ impl BindingParser {
                     location,
                     interpolation,
                     sampling,
+                    blend_src,
                 }))
             }
-            (None, Some(bi), None, None) => Ok(Some(crate::Binding::BuiltIn(bi))),
-            (_, _, _, _) => Err(Error::InconsistentBinding(span)),
+            (None, Some(crate::BuiltIn::Position { .. }), None, None, None) => {
+                Ok(Some(crate::Binding::BuiltIn(crate::BuiltIn::Position {
+                    invariant: self.invariant,
+                })))
+            }
+            (None, Some(bi), None, None, None) if !self.invariant => {
+                Ok(Some(crate::Binding::BuiltIn(bi)))
+            }
+            (_, _, _, _, _) => Err(Error::InconsistentBinding(span)),
         }
     }
 }
@@ -1223,11 +1387,35 @@ impl std::error::Error for ParseError {
     }
 }
 
+/// A language extension that a shader can turn on with an `enable` directive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnableExtension {
+    /// Allows the `f16` type and `h`-suffixed literals.
+    F16,
+    /// Allows the `blend_src` attribute on fragment outputs.
+    DualSourceBlending,
+}
+
+impl EnableExtension {
+    fn to_wgsl(self) -> &'static str {
+        match self {
+            Self::F16 => "f16",
+            Self::DualSourceBlending => "dual_source_blending",
+        }
+    }
+}
+
 pub struct Parser {
     scopes: Vec<(Scope, usize)>,
     module_scope_identifiers: FastHashMap<String, Span>,
     lookup_type: FastHashMap<String, Handle<crate::Type>>,
     layouter: Layouter,
+    enable_extensions: FastHashSet<EnableExtension>,
+    /// Explicit `@id` attributes of the overrides seen so far.
+    override_ids: FastHashMap<u32, Span>,
+    /// Overrides declared without an `@id` attribute, which get their
+    /// specialization id assigned once the whole module is parsed.
+    implicit_overrides: Vec<Handle<crate::Constant>>,
 }
 
 impl Parser {
@@ -1237,9 +1425,41 @@ impl Parser {
             module_scope_identifiers: FastHashMap::default(),
             lookup_type: FastHashMap::default(),
             layouter: Default::default(),
+            enable_extensions: FastHashSet::default(),
+            override_ids: FastHashMap::default(),
+            implicit_overrides: Vec::new(),
         }
     }
 
+    /// Make sure the type `inner`, declared at `span`, only uses
+    /// extensions that were enabled.
+    fn check_type_extensions<'a>(
+        &self,
+        inner: &crate::TypeInner,
+        span: Span,
+    ) -> Result<(), Error<'a>> {
+        let uses_f16 = match *inner {
+            crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 2,
+            }
+            | crate::TypeInner::Vector {
+                kind: crate::ScalarKind::Float,
+                width: 2,
+                ..
+            }
+            | crate::TypeInner::Matrix { width: 2, .. } => true,
+            _ => false,
+        };
+        if uses_f16 && !self.enable_extensions.contains(&EnableExtension::F16) {
+            return Err(Error::EnableExtensionNotEnabled {
+                span,
+                extension: EnableExtension::F16,
+            });
+        }
+        Ok(())
+    }
+
     fn push_scope(&mut self, scope: Scope, lexer: &Lexer<'_>) {
         self.scopes.push((scope, lexer.current_byte_offset()));
     }
@@ -1262,14 +1482,26 @@ impl Parser {
     ) -> Result<ConstantInner, Error<'a>> {
         let span = token_span.1;
 
-        if let Some(width) = width {
-            if width != 4 {
-                // Only 32-bit literals supported by the spec and naga for now!
+        match (ty, width) {
+            (_, None)
+            | (_, Some(4))
+            | (NumberType::Float, Some(2))
+            | (NumberType::Sint, Some(8))
+            | (NumberType::Uint, Some(8)) => {}
+            (_, Some(width)) => {
+                // Only 32-bit literals, 16-bit float literals and 64-bit
+                // integer literals are supported by naga for now!
                 return Err(Error::BadScalarWidth(span, width));
             }
         }
 
         let value = match ty {
+            NumberType::Sint if width == Some(8) => {
+                get_i64_literal(word, span).map(crate::ScalarValue::Sint)?
+            }
+            NumberType::Uint if width == Some(8) => {
+                get_u64_literal(word, span).map(crate::ScalarValue::Uint)?
+            }
             NumberType::Sint => {
                 get_i32_literal(word, span).map(|val| crate::ScalarValue::Sint(val as i64))?
             }
@@ -1334,6 +1566,28 @@ impl Parser {
         }
     }
 
+    fn parse_ray_query_pointer<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        mut ctx: ExpressionContext<'a, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'a>> {
+        let (pointer, pointer_span) =
+            lexer.capture_span(|lexer| self.parse_general_expression(lexer, ctx.reborrow()))?;
+        match *ctx.resolve_type(pointer)? {
+            crate::TypeInner::Pointer { base, .. } => match ctx.types[base].inner {
+                crate::TypeInner::RayQuery => Ok(pointer),
+                ref other => {
+                    log::error!("Pointer type to {:?} passed to ray query op", other);
+                    Err(Error::InvalidRayQueryPointer(pointer_span))
+                }
+            },
+            ref other => {
+                log::error!("Type {:?} passed to ray query op", other);
+                Err(Error::InvalidRayQueryPointer(pointer_span))
+            }
+        }
+    }
+
     /// Expects name to be peeked from lexer, does not consume if returns None.
     fn parse_local_function_call<'a>(
         &mut self,
@@ -1379,9 +1633,8 @@ impl Parser {
         lexer.close_arguments()?;
 
         let expression = match *ctx.resolve_type(value)? {
-            crate::TypeInner::Scalar { kind, width } => crate::Expression::AtomicResult {
-                kind,
-                width,
+            crate::TypeInner::Scalar { .. } => crate::Expression::AtomicResult {
+                ty: ctx.register_type(value)?,
                 comparison: false,
             },
             _ => return Err(Error::InvalidAtomicOperandType(value_span)),
@@ -1401,6 +1654,113 @@ impl Parser {
         Ok(result)
     }
 
+    fn parse_texture_atomic_helper<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        fun: crate::AtomicFunction,
+        mut ctx: ExpressionContext<'a, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'a>> {
+        lexer.open_arguments()?;
+        let (image, image_span) = self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
+        lexer.expect(Token::Separator(','))?;
+        let arrayed = match *ctx.resolve_type(image)? {
+            crate::TypeInner::Image { arrayed, .. } => arrayed,
+            _ => return Err(Error::BadTexture(image_span)),
+        };
+        let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
+        let array_index = if arrayed {
+            lexer.expect(Token::Separator(','))?;
+            Some(self.parse_general_expression(lexer, ctx.reborrow())?)
+        } else {
+            None
+        };
+        lexer.expect(Token::Separator(','))?;
+        let ctx_span = ctx.reborrow();
+        let (value, value_span) =
+            lexer.capture_span(|lexer| self.parse_general_expression(lexer, ctx_span))?;
+        lexer.close_arguments()?;
+
+        let expression = match *ctx.resolve_type(value)? {
+            crate::TypeInner::Scalar { .. } => crate::Expression::AtomicResult {
+                ty: ctx.register_type(value)?,
+                comparison: false,
+            },
+            _ => return Err(Error::InvalidAtomicOperandType(value_span)),
+        };
+
+        let span = NagaSpan::from(value_span);
+        let result = ctx.interrupt_emitter(expression, span);
+        ctx.block.push(
+            crate::Statement::ImageAtomic {
+                image,
+                coordinate,
+                array_index,
+                fun,
+                value,
+                result,
+            },
+            span,
+        );
+        Ok(result)
+    }
+
+    fn parse_subgroup_operation_helper<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        op: crate::SubgroupOperation,
+        collective_op: crate::CollectiveOperation,
+        mut ctx: ExpressionContext<'a, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'a>> {
+        lexer.open_arguments()?;
+        let argument = self.parse_general_expression(lexer, ctx.reborrow())?;
+        lexer.close_arguments()?;
+
+        let ty = ctx.register_type(argument)?;
+        let span = NagaSpan::from(self.peek_scope(lexer));
+        let result = ctx.interrupt_emitter(crate::Expression::SubgroupOperationResult { ty }, span);
+        ctx.block.push(
+            crate::Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(result)
+    }
+
+    fn parse_subgroup_gather_helper<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        mode: Option<fn(Handle<crate::Expression>) -> crate::GatherMode>,
+        mut ctx: ExpressionContext<'a, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'a>> {
+        lexer.open_arguments()?;
+        let argument = self.parse_general_expression(lexer, ctx.reborrow())?;
+        let mode = match mode {
+            Some(mode) => {
+                lexer.expect(Token::Separator(','))?;
+                mode(self.parse_general_expression(lexer, ctx.reborrow())?)
+            }
+            None => crate::GatherMode::BroadcastFirst,
+        };
+        lexer.close_arguments()?;
+
+        let ty = ctx.register_type(argument)?;
+        let span = NagaSpan::from(self.peek_scope(lexer));
+        let result = ctx.interrupt_emitter(crate::Expression::SubgroupOperationResult { ty }, span);
+        ctx.block.push(
+            crate::Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(result)
+    }
+
     /// Expects [`Scope::PrimaryExpr`] or [`Scope::SingularExpr`] on top; does not pop it.
     /// Expects `word` to be peeked (still in lexer), doesn't consume if returning None.
     fn parse_function_call_inner<'a>(
@@ -1422,6 +1782,18 @@ impl Parser {
             let expr = self.parse_general_expression(lexer, ctx.reborrow())?;
             lexer.close_arguments()?;
             crate::Expression::Derivative { axis, expr }
+        } else if let Some((op, collective_op)) = conv::map_subgroup_operation(name) {
+            let _ = lexer.next();
+            let handle = self.parse_subgroup_operation_helper(lexer, op, collective_op, ctx)?;
+            return Ok(Some(CalledFunction {
+                result: Some(handle),
+            }));
+        } else if let Some(fun) = conv::map_texture_atomic_function(name) {
+            let _ = lexer.next();
+            let handle = self.parse_texture_atomic_helper(lexer, fun, ctx)?;
+            return Ok(Some(CalledFunction {
+                result: Some(handle),
+            }));
         } else if let Some(fun) = conv::map_standard_fun(name) {
             let _ = lexer.next();
             lexer.open_arguments()?;
@@ -1507,6 +1879,13 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ArrayLength(array)
                 }
+                "nonuniform" => {
+                    let _ = lexer.next();
+                    lexer.open_arguments()?;
+                    let value = self.parse_general_expression(lexer, ctx.reborrow())?;
+                    lexer.close_arguments()?;
+                    crate::Expression::NonUniform(value)
+                }
                 // atomics
                 "atomicLoad" => {
                     let _ = lexer.next();
@@ -1612,8 +1991,7 @@ impl Parser {
                     let expression = match *ctx.resolve_type(value)? {
                         crate::TypeInner::Scalar { kind, width } => {
                             crate::Expression::AtomicResult {
-                                kind,
-                                width,
+                                ty: ctx.atomic_compare_exchange_result_type(kind, width),
                                 comparison: true,
                             }
                         }
@@ -1635,16 +2013,152 @@ impl Parser {
                         result: Some(result),
                     }));
                 }
+                // subgroup operations
+                "rayQueryProceed" => {
+                    let _ = lexer.next();
+                    lexer.open_arguments()?;
+                    let query = self.parse_ray_query_pointer(lexer, ctx.reborrow())?;
+                    lexer.close_arguments()?;
+
+                    let span = NagaSpan::from(self.peek_scope(lexer));
+                    let result =
+                        ctx.interrupt_emitter(crate::Expression::RayQueryProceedResult, span);
+                    ctx.block.push(
+                        crate::Statement::RayQuery {
+                            query,
+                            fun: crate::RayQueryFunction::Proceed { result },
+                        },
+                        span,
+                    );
+                    return Ok(Some(CalledFunction {
+                        result: Some(result),
+                    }));
+                }
+                "rayQueryGetCommittedIntersection" => {
+                    let _ = lexer.next();
+                    lexer.open_arguments()?;
+                    let query = self.parse_ray_query_pointer(lexer, ctx.reborrow())?;
+                    lexer.close_arguments()?;
+                    let ty = crate::proc::generate_ray_intersection_type(ctx.types);
+                    crate::Expression::RayQueryGetCommittedIntersection { query, ty }
+                }
+                "subgroupBallot" => {
+                    let _ = lexer.next();
+                    lexer.open_arguments()?;
+                    let predicate = if lexer.skip(Token::Paren(')')) {
+                        None
+                    } else {
+                        let predicate = self.parse_general_expression(lexer, ctx.reborrow())?;
+                        lexer.close_arguments()?;
+                        Some(predicate)
+                    };
+
+                    let span = NagaSpan::from(self.peek_scope(lexer));
+                    let result =
+                        ctx.interrupt_emitter(crate::Expression::SubgroupBallotResult, span);
+                    ctx.block
+                        .push(crate::Statement::SubgroupBallot { result, predicate }, span);
+                    return Ok(Some(CalledFunction {
+                        result: Some(result),
+                    }));
+                }
+                "subgroupElect" => {
+                    let _ = lexer.next();
+                    lexer.open_arguments()?;
+                    lexer.close_arguments()?;
+
+                    let ty = ctx.types.insert(
+                        crate::Type {
+                            name: None,
+                            inner: crate::TypeInner::Scalar {
+                                kind: crate::ScalarKind::Bool,
+                                width: crate::BOOL_WIDTH,
+                            },
+                        },
+                        Default::default(),
+                    );
+                    let span = NagaSpan::from(self.peek_scope(lexer));
+                    let result = ctx
+                        .interrupt_emitter(crate::Expression::SubgroupOperationResult { ty }, span);
+                    ctx.block
+                        .push(crate::Statement::SubgroupElect { result }, span);
+                    return Ok(Some(CalledFunction {
+                        result: Some(result),
+                    }));
+                }
+                "subgroupBroadcastFirst" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(lexer, None, ctx)?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
+                "subgroupBroadcast" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(
+                        lexer,
+                        Some(crate::GatherMode::Broadcast),
+                        ctx,
+                    )?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
+                "subgroupShuffle" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(
+                        lexer,
+                        Some(crate::GatherMode::Shuffle),
+                        ctx,
+                    )?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
+                "subgroupShuffleDown" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(
+                        lexer,
+                        Some(crate::GatherMode::ShuffleDown),
+                        ctx,
+                    )?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
+                "subgroupShuffleUp" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(
+                        lexer,
+                        Some(crate::GatherMode::ShuffleUp),
+                        ctx,
+                    )?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
+                "subgroupShuffleXor" => {
+                    let _ = lexer.next();
+                    let handle = self.parse_subgroup_gather_helper(
+                        lexer,
+                        Some(crate::GatherMode::ShuffleXor),
+                        ctx,
+                    )?;
+                    return Ok(Some(CalledFunction {
+                        result: Some(handle),
+                    }));
+                }
                 // texture sampling
                 "textureSample" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1659,7 +2173,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1671,12 +2185,13 @@ impl Parser {
                 "textureSampleLevel" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1693,7 +2208,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1705,12 +2220,13 @@ impl Parser {
                 "textureSampleBias" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1727,7 +2243,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1739,12 +2255,13 @@ impl Parser {
                 "textureSampleGrad" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1763,7 +2280,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1775,12 +2292,13 @@ impl Parser {
                 "textureSampleCompare" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1797,7 +2315,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1809,12 +2327,13 @@ impl Parser {
                 "textureSampleCompareLevel" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1831,7 +2350,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: None,
                         coordinate,
                         array_index,
@@ -1861,12 +2380,13 @@ impl Parser {
                     } else {
                         crate::SwizzleComponent::X
                     };
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1881,7 +2401,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: Some(component),
                         coordinate,
                         array_index,
@@ -1893,12 +2413,13 @@ impl Parser {
                 "textureGatherCompare" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
-                    let (sampler_name, sampler_span) = lexer.next_ident_with_span()?;
+                    let sampler = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
-                    let sc = ctx.prepare_sampling(image_name, image_span)?;
+                    let sc = ctx.prepare_sampling(image, image_span)?;
                     let array_index = if sc.arrayed {
                         lexer.expect(Token::Separator(','))?;
                         Some(self.parse_general_expression(lexer, ctx.reborrow())?)
@@ -1915,7 +2436,7 @@ impl Parser {
                     lexer.close_arguments()?;
                     crate::Expression::ImageSample {
                         image: sc.image,
-                        sampler: ctx.lookup_ident.lookup(sampler_name, sampler_span)?.handle,
+                        sampler,
                         gather: Some(crate::SwizzleComponent::X),
                         coordinate,
                         array_index,
@@ -1927,11 +2448,8 @@ impl Parser {
                 "textureLoad" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
-                    let image = ctx
-                        .lookup_ident
-                        .lookup(image_name, image_span.clone())?
-                        .handle;
+                    let (image, image_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
                     lexer.expect(Token::Separator(','))?;
                     let coordinate = self.parse_general_expression(lexer, ctx.reborrow())?;
                     let (class, arrayed) = match *ctx.resolve_type(image)? {
@@ -1963,8 +2481,7 @@ impl Parser {
                 "textureDimensions" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
-                    let image = ctx.lookup_ident.lookup(image_name, image_span)?.handle;
+                    let image = self.parse_general_expression(lexer, ctx.reborrow())?;
                     let level = if lexer.skip(Token::Separator(',')) {
                         let expr = self.parse_general_expression(lexer, ctx.reborrow())?;
                         Some(expr)
@@ -1980,8 +2497,7 @@ impl Parser {
                 "textureNumLevels" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
-                    let image = ctx.lookup_ident.lookup(image_name, image_span)?.handle;
+                    let image = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.close_arguments()?;
                     crate::Expression::ImageQuery {
                         image,
@@ -1991,8 +2507,7 @@ impl Parser {
                 "textureNumLayers" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
-                    let image = ctx.lookup_ident.lookup(image_name, image_span)?.handle;
+                    let image = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.close_arguments()?;
                     crate::Expression::ImageQuery {
                         image,
@@ -2002,8 +2517,7 @@ impl Parser {
                 "textureNumSamples" => {
                     let _ = lexer.next();
                     lexer.open_arguments()?;
-                    let (image_name, image_span) = lexer.next_ident_with_span()?;
-                    let image = ctx.lookup_ident.lookup(image_name, image_span)?.handle;
+                    let image = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.close_arguments()?;
                     crate::Expression::ImageQuery {
                         image,
@@ -2058,6 +2572,9 @@ impl Parser {
         );
         let ty_resolution = match self.lookup_type.get(type_name) {
             Some(&handle) => TypeResolution::Handle(handle),
+            None if Self::is_predeclared_type(type_name) => {
+                TypeResolution::Handle(Self::get_predeclared_type(type_name, ctx.types))
+            }
             None => match self.parse_type_decl_impl(
                 lexer,
                 TypeAttributes::default(),
@@ -2065,7 +2582,11 @@ impl Parser {
                 ctx.types,
                 ctx.constants,
             )? {
-                Some(inner) => TypeResolution::Value(inner),
+                Some(inner) => {
+                    let span = self.peek_scope(lexer);
+                    self.check_type_extensions(&inner, span)?;
+                    TypeResolution::Value(inner)
+                }
                 None => return Ok(None),
             },
         };
@@ -2209,7 +2730,13 @@ impl Parser {
         let inner = match first_token_span {
             (Token::Word("true"), _) => crate::ConstantInner::boolean(true),
             (Token::Word("false"), _) => crate::ConstantInner::boolean(false),
-            (Token::Number { value, ty, width }, _) => {
+            (Token::Number { value, ty, width }, ref span) => {
+                if width == Some(2) && !self.enable_extensions.contains(&EnableExtension::F16) {
+                    return Err(Error::EnableExtensionNotEnabled {
+                        span: span.clone(),
+                        extension: EnableExtension::F16,
+                    });
+                }
                 Self::get_constant_inner(value, ty, width, first_token_span)?
             }
             (Token::Word(name), name_span) => {
@@ -2688,6 +3215,16 @@ impl Parser {
         Ok(ctx.apply_load_rule(expr))
     }
 
+    fn parse_general_expression_with_span<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: ExpressionContext<'a, '_, '_>,
+    ) -> Result<(Handle<crate::Expression>, Span), Error<'a>> {
+        let start = lexer.peek().1.start;
+        let expr = self.parse_general_expression(lexer, ctx)?;
+        Ok((expr, start..lexer.current_byte_offset()))
+    }
+
     fn parse_general_expression_for_reference<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
@@ -2842,7 +3379,9 @@ impl Parser {
                         lexer.expect(Token::Paren(')'))?;
                         align = Some(NonZeroU32::new(value).ok_or(Error::ZeroSizeOrAlign(span))?);
                     }
-                    (word, word_span) => bind_parser.parse(lexer, word, word_span)?,
+                    (word, word_span) => {
+                        bind_parser.parse(lexer, word, word_span, &self.enable_extensions)?
+                    }
                 }
             }
 
@@ -3027,8 +3566,24 @@ impl Parser {
                 };
                 crate::TypeInner::Array { base, size, stride }
             }
+            "binding_array" => {
+                lexer.expect_generic_paren('<')?;
+                let (base, _access) = self.parse_type_decl(lexer, None, type_arena, const_arena)?;
+                let size = if lexer.skip(Token::Separator(',')) {
+                    let const_handle =
+                        self.parse_const_expression(lexer, type_arena, const_arena)?;
+                    crate::ArraySize::Constant(const_handle)
+                } else {
+                    crate::ArraySize::Dynamic
+                };
+                lexer.expect_generic_paren('>')?;
+
+                crate::TypeInner::BindingArray { base, size }
+            }
             "sampler" => crate::TypeInner::Sampler { comparison: false },
             "sampler_comparison" => crate::TypeInner::Sampler { comparison: true },
+            "acceleration_structure" => crate::TypeInner::AccelerationStructure,
+            "ray_query" => crate::TypeInner::RayQuery,
             "texture_1d" => {
                 let (kind, width, span) = lexer.next_scalar_generic_with_span()?;
                 Self::check_texture_sample_type(kind, width, span)?;
@@ -3192,6 +3747,23 @@ impl Parser {
         }
     }
 
+    /// Return true if `name` is a predeclared structure type.
+    fn is_predeclared_type(name: &str) -> bool {
+        name == crate::proc::RAY_DESC_NAME || name == crate::proc::RAY_INTERSECTION_NAME
+    }
+
+    /// Add the predeclared structure type `name` to `type_arena`.
+    fn get_predeclared_type(
+        name: &str,
+        type_arena: &mut UniqueArena<crate::Type>,
+    ) -> Handle<crate::Type> {
+        if name == crate::proc::RAY_DESC_NAME {
+            crate::proc::generate_ray_desc_type(type_arena)
+        } else {
+            crate::proc::generate_ray_intersection_type(type_arena)
+        }
+    }
+
     /// Parse type declaration of a given name and attribute.
     #[allow(clippy::too_many_arguments)]
     fn parse_type_decl_name<'a>(
@@ -3206,10 +3778,12 @@ impl Parser {
     ) -> Result<Handle<crate::Type>, Error<'a>> {
         Ok(match self.lookup_type.get(name) {
             Some(&handle) => handle,
+            None if Self::is_predeclared_type(name) => Self::get_predeclared_type(name, type_arena),
             None => {
                 match self.parse_type_decl_impl(lexer, attribute, name, type_arena, const_arena)? {
                     Some(inner) => {
                         let span = name_span.start..lexer.current_byte_offset();
+                        self.check_type_extensions(&inner, span.clone())?;
                         type_arena.insert(
                             crate::Type {
                                 name: debug_name.map(|s| s.to_string()),
@@ -3850,6 +4424,12 @@ impl Parser {
                         lexer.expect(Token::Paren(')'))?;
                         Some(crate::Statement::Barrier(crate::Barrier::WORK_GROUP))
                     }
+                    "subgroupBarrier" => {
+                        let _ = lexer.next();
+                        lexer.expect(Token::Paren('('))?;
+                        lexer.expect(Token::Paren(')'))?;
+                        Some(crate::Statement::Barrier(crate::Barrier::SUB_GROUP))
+                    }
                     "atomicStore" => {
                         let _ = lexer.next();
                         emitter.start(context.expressions);
@@ -3863,17 +4443,73 @@ impl Parser {
                         block.extend(emitter.finish(context.expressions));
                         Some(crate::Statement::Store { pointer, value })
                     }
-                    "textureStore" => {
+                    "rayQueryInitialize" => {
+                        let _ = lexer.next();
+                        emitter.start(context.expressions);
+                        lexer.open_arguments()?;
+                        let mut expression_ctx = context.as_expression(block, &mut emitter);
+                        let query =
+                            self.parse_ray_query_pointer(lexer, expression_ctx.reborrow())?;
+                        lexer.expect(Token::Separator(','))?;
+                        let acceleration_structure =
+                            self.parse_general_expression(lexer, expression_ctx.reborrow())?;
+                        lexer.expect(Token::Separator(','))?;
+                        let descriptor = self.parse_general_expression(lexer, expression_ctx)?;
+                        lexer.close_arguments()?;
+                        block.extend(emitter.finish(context.expressions));
+                        Some(crate::Statement::RayQuery {
+                            query,
+                            fun: crate::RayQueryFunction::Initialize {
+                                acceleration_structure,
+                                descriptor,
+                            },
+                        })
+                    }
+                    "setMeshOutputs" => {
+                        let _ = lexer.next();
+                        emitter.start(context.expressions);
+                        lexer.open_arguments()?;
+                        let mut expression_ctx = context.as_expression(block, &mut emitter);
+                        let vertex_count =
+                            self.parse_general_expression(lexer, expression_ctx.reborrow())?;
+                        lexer.expect(Token::Separator(','))?;
+                        let primitive_count =
+                            self.parse_general_expression(lexer, expression_ctx)?;
+                        lexer.close_arguments()?;
+                        block.extend(emitter.finish(context.expressions));
+                        Some(crate::Statement::MeshFunction(
+                            crate::MeshFunction::SetMeshOutputs {
+                                vertex_count,
+                                primitive_count,
+                            },
+                        ))
+                    }
+                    "setVertex" | "setPrimitive" => {
+                        let is_vertex = word == "setVertex";
                         let _ = lexer.next();
                         emitter.start(context.expressions);
                         lexer.open_arguments()?;
-                        let (image_name, image_span) = lexer.next_ident_with_span()?;
-                        let image = context
-                            .lookup_ident
-                            .lookup(image_name, image_span.clone())?
-                            .handle;
+                        let mut expression_ctx = context.as_expression(block, &mut emitter);
+                        let index =
+                            self.parse_general_expression(lexer, expression_ctx.reborrow())?;
                         lexer.expect(Token::Separator(','))?;
+                        let value = self.parse_general_expression(lexer, expression_ctx)?;
+                        lexer.close_arguments()?;
+                        block.extend(emitter.finish(context.expressions));
+                        Some(crate::Statement::MeshFunction(if is_vertex {
+                            crate::MeshFunction::SetVertex { index, value }
+                        } else {
+                            crate::MeshFunction::SetPrimitive { index, value }
+                        }))
+                    }
+                    "textureStore" => {
+                        let _ = lexer.next();
+                        emitter.start(context.expressions);
+                        lexer.open_arguments()?;
                         let mut expr_context = context.as_expression(block, &mut emitter);
+                        let (image, image_span) = self
+                            .parse_general_expression_with_span(lexer, expr_context.reborrow())?;
+                        lexer.expect(Token::Separator(','))?;
                         let arrayed = match *expr_context.resolve_type(image)? {
                             crate::TypeInner::Image { arrayed, .. } => arrayed,
                             _ => return Err(Error::BadTexture(image_span)),
@@ -3963,7 +4599,7 @@ impl Parser {
 
         while lexer.skip(Token::Attribute) {
             let (word, span) = lexer.next_ident_with_span()?;
-            bind_parser.parse(lexer, word, span)?;
+            bind_parser.parse(lexer, word, span, &self.enable_extensions)?;
         }
 
         let span = self.pop_scope(lexer);
@@ -4112,9 +4748,12 @@ impl Parser {
     ) -> Result<bool, Error<'a>> {
         // read attributes
         let mut binding = None;
-        let mut stage = None;
+        let (mut stage, mut stage_span) = (None, None);
         let mut workgroup_size = [0u32; 3];
+        let mut workgroup_size_overrides = [None; 3];
         let mut early_depth_test = None;
+        let (mut output_topology, mut vertex_output, mut primitive_output) = (None, None, None);
+        let mut override_id = None;
         let (mut bind_index, mut bind_group) = (None, None);
 
         self.push_scope(Scope::Attribute, lexer);
@@ -4130,16 +4769,41 @@ impl Parser {
                     bind_group = Some(parse_non_negative_sint_literal(lexer, 4)?);
                     lexer.expect(Token::Paren(')'))?;
                 }
+                ("id", _) => {
+                    lexer.expect(Token::Paren('('))?;
+                    let start = lexer.current_byte_offset();
+                    let id = parse_non_negative_sint_literal(lexer, 4)?;
+                    override_id = Some((id, lexer.span_from(start)));
+                    lexer.expect(Token::Paren(')'))?;
+                }
                 ("stage", _) => {
                     lexer.expect(Token::Paren('('))?;
                     let (ident, ident_span) = lexer.next_ident_with_span()?;
-                    stage = Some(conv::map_shader_stage(ident, ident_span)?);
+                    stage = Some(conv::map_shader_stage(ident, ident_span.clone())?);
+                    stage_span = Some(ident_span);
                     lexer.expect(Token::Paren(')'))?;
                 }
                 ("workgroup_size", _) => {
                     lexer.expect(Token::Paren('('))?;
                     for (i, size) in workgroup_size.iter_mut().enumerate() {
-                        *size = parse_generic_non_negative_int_literal(lexer, 4)?;
+                        *size = match lexer.peek() {
+                            (Token::Word(name), name_span) => {
+                                let _ = lexer.next();
+                                let (handle, constant) = module
+                                    .constants
+                                    .iter()
+                                    .find(|&(_, c)| c.name.as_deref() == Some(name))
+                                    .ok_or(Error::UnknownIdent(name_span.clone(), name))?;
+                                if constant.specialization.is_some() {
+                                    workgroup_size_overrides[i] = Some(handle);
+                                }
+                                constant.to_array_length().ok_or(Error::Unexpected(
+                                    (Token::Word(name), name_span),
+                                    ExpectedToken::Integer,
+                                ))?
+                            }
+                            _ => parse_generic_non_negative_int_literal(lexer, 4)?,
+                        };
                         match lexer.next() {
                             (Token::Paren(')'), _) => break,
                             (Token::Separator(','), _) if i != 2 => (),
@@ -4168,6 +4832,30 @@ impl Parser {
                     };
                     early_depth_test = Some(crate::EarlyDepthTest { conservative });
                 }
+                ("output_topology", _) => {
+                    lexer.expect(Token::Paren('('))?;
+                    let (ident, ident_span) = lexer.next_ident_with_span()?;
+                    output_topology = Some(conv::map_mesh_output_topology(ident, ident_span)?);
+                    lexer.expect(Token::Paren(')'))?;
+                }
+                (attribute, _)
+                    if attribute == "vertex_output" || attribute == "primitive_output" =>
+                {
+                    lexer.expect(Token::Paren('('))?;
+                    let (name, name_span) = lexer.next_ident_with_span()?;
+                    let ty = *self
+                        .lookup_type
+                        .get(name)
+                        .ok_or(Error::UnknownType(name_span))?;
+                    lexer.expect(Token::Separator(','))?;
+                    let max = parse_non_negative_sint_literal(lexer, 4)?;
+                    lexer.expect(Token::Paren(')'))?;
+                    if attribute == "vertex_output" {
+                        vertex_output = Some((ty, max));
+                    } else {
+                        primitive_output = Some((ty, max));
+                    }
+                }
                 (_, word_span) => return Err(Error::UnknownAttribute(word_span)),
             }
         }
@@ -4184,11 +4872,38 @@ impl Parser {
             (None, Some(_)) => return Err(Error::MissingAttribute("group", attrib_scope)),
             (None, None) => {}
         }
+        let mesh_info = match (output_topology, vertex_output, primitive_output) {
+            (Some(topology), Some(vertex), Some(primitive)) => Some(crate::MeshStageInfo {
+                topology,
+                max_vertices: vertex.1,
+                max_primitives: primitive.1,
+                vertex_output_type: vertex.0,
+                primitive_output_type: primitive.0,
+            }),
+            (None, None, None) if stage != Some(crate::ShaderStage::Mesh) => None,
+            (topology, vertex, _) => {
+                let name = if topology.is_none() {
+                    "output_topology"
+                } else if vertex.is_none() {
+                    "vertex_output"
+                } else {
+                    "primitive_output"
+                };
+                let span = stage_span.unwrap_or_else(|| attrib_scope.clone());
+                return Err(Error::MissingMeshAttribute(name, span));
+            }
+        };
 
         // read items
         let start = lexer.current_byte_offset();
         match lexer.next() {
             (Token::Separator(';'), _) => {}
+            (Token::Word("enable"), _) => {
+                let (name, name_span) = lexer.next_ident_with_span()?;
+                let extension = conv::map_enable_extension(name, name_span)?;
+                self.enable_extensions.insert(extension);
+                lexer.expect(Token::Separator(';'))?;
+            }
             (Token::Word("struct"), _) => {
                 let (name, span) = lexer.next_ident_with_span()?;
                 if crate::keywords::wgsl::RESERVED.contains(&name) {
@@ -4219,7 +4934,8 @@ impl Parser {
                 self.lookup_type.insert(name.to_owned(), ty);
                 lexer.expect(Token::Separator(';'))?;
             }
-            (Token::Word("let"), _) => {
+            (Token::Word(keyword), _) if keyword == "let" || keyword == "override" => {
+                let is_override = keyword == "override";
                 let (name, name_span) = lexer.next_ident_with_span()?;
                 if crate::keywords::wgsl::RESERVED.contains(&name) {
                     return Err(Error::ReservedKeyword(name_span));
@@ -4245,9 +4961,13 @@ impl Parser {
                     None
                 };
 
-                lexer.expect(Token::Operation('='))?;
+                if is_override && !lexer.skip(Token::Operation('=')) {
+                    return Err(Error::MissingOverrideInitializer(name_span));
+                } else if !is_override {
+                    lexer.expect(Token::Operation('='))?;
+                }
                 let first_token_span = lexer.next();
-                let const_handle = self.parse_const_expression_impl(
+                let mut const_handle = self.parse_const_expression_impl(
                     first_token_span,
                     lexer,
                     Some(name),
@@ -4287,6 +5007,16 @@ impl Parser {
                     }
                 }
 
+                if is_override {
+                    const_handle = self.register_override(
+                        name,
+                        name_span,
+                        const_handle,
+                        override_id.take(),
+                        &mut module.constants,
+                    )?;
+                }
+
                 lexer.expect(Token::Separator(';'))?;
                 lookup_global_expression.insert(name, crate::Expression::Constant(const_handle));
             }
@@ -4327,6 +5057,15 @@ impl Parser {
                         stage,
                         early_depth_test,
                         workgroup_size,
+                        workgroup_size_overrides: if workgroup_size_overrides
+                            .iter()
+                            .any(Option::is_some)
+                        {
+                            Some(workgroup_size_overrides)
+                        } else {
+                            None
+                        },
+                        mesh_info,
                         function,
                     }),
                     None => {
@@ -4340,17 +5079,72 @@ impl Parser {
             other => return Err(Error::Unexpected(other, ExpectedToken::GlobalItem)),
         }
 
-        match binding {
-            None => Ok(true),
-            // we had the attribute but no var?
-            Some(_) => Err(Error::Other),
+        match (binding, override_id) {
+            (None, None) => Ok(true),
+            // we had the attribute but no var or override?
+            _ => Err(Error::Other),
+        }
+    }
+
+    /// Turn the constant `handle`, parsed as the initializer of the override
+    /// `name`, into a specializable constant.
+    fn register_override<'a>(
+        &mut self,
+        name: &'a str,
+        name_span: Span,
+        handle: Handle<crate::Constant>,
+        override_id: Option<(u32, Span)>,
+        const_arena: &mut Arena<crate::Constant>,
+    ) -> Result<Handle<crate::Constant>, Error<'a>> {
+        let inner = match const_arena[handle].inner {
+            ConstantInner::Scalar { width, value } => ConstantInner::Scalar { width, value },
+            ConstantInner::Composite { .. } => return Err(Error::NonScalarOverride(name_span)),
+        };
+
+        let is_implicit = override_id.is_none();
+        let specialization = match override_id {
+            Some((id, id_span)) => {
+                if let Some(previous) = self.override_ids.insert(id, id_span.clone()) {
+                    return Err(Error::OverrideIdRedefinition {
+                        previous,
+                        current: id_span,
+                    });
+                }
+                id
+            }
+            // assigned at the end of `parse`
+            None => 0,
+        };
+
+        // The initializer may refer to another constant, in which case we
+        // need a new one, so that specializing this override doesn't affect it.
+        let handle = if const_arena[handle].name.as_deref() == Some(name) {
+            const_arena.get_mut(handle).specialization = Some(specialization);
+            handle
+        } else {
+            const_arena.append(
+                crate::Constant {
+                    name: Some(name.to_string()),
+                    specialization: Some(specialization),
+                    inner,
+                },
+                NagaSpan::from(name_span),
+            )
+        };
+
+        if is_implicit {
+            self.implicit_overrides.push(handle);
         }
+        Ok(handle)
     }
 
     pub fn parse(&mut self, source: &str) -> Result<crate::Module, ParseError> {
         self.scopes.clear();
         self.lookup_type.clear();
         self.layouter.clear();
+        self.enable_extensions.clear();
+        self.override_ids.clear();
+        self.implicit_overrides.clear();
 
         let mut module = crate::Module::default();
         let mut lexer = Lexer::new(source);
@@ -4364,6 +5158,15 @@ impl Parser {
                         log::error!("Reached the end of file, but scopes are not closed");
                         return Err(Error::Other.as_parse_error(lexer.source));
                     };
+                    // Overrides without an explicit id get the lowest unused ones
+                    let mut next_id = 0;
+                    for &handle in self.implicit_overrides.iter() {
+                        while self.override_ids.contains_key(&next_id) {
+                            next_id += 1;
+                        }
+                        module.constants.get_mut(handle).specialization = Some(next_id);
+                        next_id += 1;
+                    }
                     return Ok(module);
                 }
             }
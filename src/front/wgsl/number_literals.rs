@@ -57,6 +57,39 @@ pub fn get_u32_literal(word: &str, span: Span) -> Result<u32, Error<'_>> {
     parsed_val.map_err(|e| Error::BadU32(span, e.into()))
 }
 
+pub fn get_i64_literal(word: &str, span: Span) -> Result<i64, Error<'_>> {
+    let (minus, word_without_minus, _) = try_skip_prefix(word, "-");
+    let (hex, word_without_minus_and_0x, _) = try_skip_prefix(word_without_minus, "0x");
+
+    check_int_literal(word_without_minus, minus, hex)
+        .map_err(|e| Error::BadI32(span.clone(), e))?;
+
+    let parsed_val = match (hex, minus) {
+        (true, true) => i64::from_str_radix(&format!("-{}", word_without_minus_and_0x), 16),
+        (true, false) => i64::from_str_radix(word_without_minus_and_0x, 16),
+        (false, _) => word.parse(),
+    };
+
+    parsed_val.map_err(|e| Error::BadI32(span, e.into()))
+}
+
+pub fn get_u64_literal(word: &str, span: Span) -> Result<u64, Error<'_>> {
+    let (minus, word_without_minus, _) = try_skip_prefix(word, "-");
+    let (hex, word_without_minus_and_0x, _) = try_skip_prefix(word_without_minus, "0x");
+
+    check_int_literal(word_without_minus, minus, hex)
+        .map_err(|e| Error::BadU32(span.clone(), e))?;
+
+    // We need to add a minus here as well, since the lexer also accepts syntactically incorrect negative uints
+    let parsed_val = match (hex, minus) {
+        (true, true) => u64::from_str_radix(&format!("-{}", word_without_minus_and_0x), 16),
+        (true, false) => u64::from_str_radix(word_without_minus_and_0x, 16),
+        (false, _) => word.parse(),
+    };
+
+    parsed_val.map_err(|e| Error::BadU32(span, e.into()))
+}
+
 pub fn get_f32_literal(word: &str, span: Span) -> Result<f32, Error<'_>> {
     let hex = word.starts_with("0x") || word.starts_with("-0x");
 
@@ -104,6 +104,14 @@ Naga's rules for when `Expression`s are evaluated are as follows:
     [`Atomic`] statement, representing the result of the atomic operation, is
     evaluated when the `Atomic` statement is executed.
 
+-   The [`SubgroupBallotResult`] and [`SubgroupOperationResult`] expressions
+    that are the `result` of a subgroup statement are evaluated when that
+    statement is executed, since their value depends on the set of active
+    invocations at that point.
+
+-   A [`RayQueryProceedResult`] expression is evaluated when the
+    [`RayQuery`] statement that advances the query is executed.
+
 -   All other expressions are evaluated when the (unique) [`Statement::Emit`]
     statement that covers them is executed. The [`Expression::needs_pre_emit`]
     method returns `true` if the given expression is one of those variants that
@@ -155,9 +163,9 @@ An expression's scope is defined as follows:
     subsequent expressions in that `Emit`, the subsequent statements in the `Block`
     to which that `Emit` belongs (if any) and their sub-statements (if any).
 
--   The `result` expression of a [`Call`] or [`Atomic`] statement has a scope
-    covering the subsequent statements in the `Block` in which the statement
-    occurs (if any) and their sub-statements (if any).
+-   The `result` expression of a [`Call`], [`Atomic`], subgroup or [`RayQuery`]
+    statement has a scope covering the subsequent statements in the `Block` in
+    which the statement occurs (if any) and their sub-statements (if any).
 
 For example, this implies that an expression evaluated by some statement in a
 nested `Block` is not available in the `Block`'s parents. Such a value would
@@ -174,10 +182,14 @@ tree.
 [`ImageSample`]: Expression::ImageSample
 [`Load`]: Expression::Load
 [`LocalVariable`]: Expression::LocalVariable
+[`RayQueryProceedResult`]: Expression::RayQueryProceedResult
+[`SubgroupBallotResult`]: Expression::SubgroupBallotResult
+[`SubgroupOperationResult`]: Expression::SubgroupOperationResult
 
 [`Atomic`]: Statement::Atomic
 [`Call`]: Statement::Call
 [`Emit`]: Statement::Emit
+[`RayQuery`]: Statement::RayQuery
 [`Store`]: Statement::Store
 
 [`Validator::validate`]: valid::Validator::validate
@@ -211,10 +223,11 @@ mod block;
 pub mod front;
 pub mod keywords;
 pub mod proc;
+pub mod reflect;
 mod span;
 pub mod valid;
 
-pub use crate::arena::{Arena, Handle, Range, UniqueArena};
+pub use crate::arena::{Arena, Handle, HandleMap, Range, UniqueArena};
 
 pub use crate::span::{Span, SpanContext, WithSpan};
 #[cfg(feature = "arbitrary")]
@@ -289,6 +302,8 @@ pub enum ShaderStage {
     Vertex,
     Fragment,
     Compute,
+    Task,
+    Mesh,
 }
 
 /// Class of storage for variables.
@@ -319,7 +334,13 @@ pub enum StorageClass {
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 pub enum BuiltIn {
-    Position,
+    /// Position of the vertex, or of the fragment being shaded.
+    ///
+    /// If `invariant` is true, the position is computed the same way in every
+    /// shader that computes it from the same inputs.
+    Position {
+        invariant: bool,
+    },
     ViewIndex,
     // vertex
     BaseInstance,
@@ -342,6 +363,16 @@ pub enum BuiltIn {
     WorkGroupId,
     WorkGroupSize,
     NumWorkGroups,
+    // subgroup
+    SubgroupSize,
+    SubgroupInvocationId,
+    // task
+    MeshTaskSize,
+    // mesh
+    PointIndex,
+    LineIndices,
+    TriangleIndices,
+    CullPrimitive,
 }
 
 /// Number of bytes per scalar.
@@ -554,7 +585,7 @@ pub enum ImageClass {
 }
 
 /// A data type declared in the module.
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -566,7 +597,7 @@ pub struct Type {
 }
 
 /// Enum with additional information, depending on the kind of type.
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -693,10 +724,45 @@ pub enum TypeInner {
     },
     /// Can be used to sample values from images.
     Sampler { comparison: bool },
+
+    /// Array of bindings.
+    ///
+    /// A `BindingArray` represents an array where each element draws its value
+    /// from a separate bound resource. The array's element type `base` may be
+    /// an [`Image`], a [`Sampler`], or a [`Struct`] used as the type of a
+    /// buffer in the [`Uniform`] or [`Storage`] storage classes. Only global
+    /// variables may be binding arrays; on the host side, their size is the
+    /// number of bound resources.
+    ///
+    /// If `size` is [`Dynamic`], the number of bound resources is only known at
+    /// pipeline creation time.
+    ///
+    /// Indexing a binding array with an index that isn't uniform across the
+    /// invocations requires the non-uniform indexing [`Capabilities`] matching
+    /// the element type, and the index must be marked with
+    /// [`Expression::NonUniform`].
+    ///
+    /// [`Image`]: TypeInner::Image
+    /// [`Sampler`]: TypeInner::Sampler
+    /// [`Struct`]: TypeInner::Struct
+    /// [`Uniform`]: StorageClass::Uniform
+    /// [`Storage`]: StorageClass::Storage
+    /// [`Dynamic`]: ArraySize::Dynamic
+    /// [`Capabilities`]: valid::Capabilities
+    BindingArray { base: Handle<Type>, size: ArraySize },
+
+    /// Opaque object representing an acceleration structure of geometry.
+    AccelerationStructure,
+
+    /// Opaque object holding the state of a ray traversal.
+    ///
+    /// Ray queries can only be stored in local variables, and are operated on
+    /// through pointers to them, with [`Statement::RayQuery`].
+    RayQuery,
 }
 
 /// Constant value.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -757,12 +823,17 @@ pub enum Binding {
     /// For anything other than floating-point scalars and vectors, the
     /// interpolation must be `Flat`.
     ///
+    /// With dual-source blending, the two [`Fragment`] outputs share
+    /// location 0, and are told apart by their `blend_src` index, 0 or 1.
+    ///
     /// [`Vertex`]: crate::ShaderStage::Vertex
     /// [`Fragment`]: crate::ShaderStage::Fragment
     Location {
         location: u32,
         interpolation: Option<Interpolation>,
         sampling: Option<Sampling>,
+        /// Blend source index, for dual-source blending.
+        blend_src: Option<u32>,
     },
 }
 
@@ -867,6 +938,159 @@ pub enum AtomicFunction {
     Exchange { compare: Option<Handle<Expression>> },
 }
 
+/// Operation applied across the active invocations of a subgroup.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum SubgroupOperation {
+    All,
+    Any,
+    Add,
+    Mul,
+    Min,
+    Max,
+    And,
+    Or,
+    Xor,
+}
+
+/// How a [`SubgroupOperation`] combines the values of the invocations.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum CollectiveOperation {
+    /// Combine the values of all active invocations.
+    Reduce,
+    /// Combine the values of the active invocations with an id lower or equal
+    /// to the current one.
+    InclusiveScan,
+    /// Combine the values of the active invocations with an id lower than the
+    /// current one.
+    ExclusiveScan,
+}
+
+/// Operation on a ray query, performed by a [`Statement::RayQuery`].
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum RayQueryFunction {
+    /// Start traversing `acceleration_structure` along a ray.
+    ///
+    /// The `descriptor` is a value of the ray descriptor structure, see
+    /// [`Module::generate_ray_desc_type`].
+    Initialize {
+        acceleration_structure: Handle<Expression>,
+        descriptor: Handle<Expression>,
+    },
+    /// Advance the traversal.
+    Proceed {
+        /// [`RayQueryProceedResult`] expression telling whether the
+        /// traversal is still in progress.
+        ///
+        /// [`RayQueryProceedResult`]: Expression::RayQueryProceedResult
+        result: Handle<Expression>,
+    },
+}
+
+/// Operation performed by a mesh shader, see [`Statement::MeshFunction`].
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum MeshFunction {
+    /// Declare how many vertices and primitives the workgroup outputs.
+    ///
+    /// Both counts are `u32` values, no greater than the entry point's
+    /// [`max_vertices`] and [`max_primitives`].
+    ///
+    /// [`max_vertices`]: MeshStageInfo::max_vertices
+    /// [`max_primitives`]: MeshStageInfo::max_primitives
+    SetMeshOutputs {
+        vertex_count: Handle<Expression>,
+        primitive_count: Handle<Expression>,
+    },
+    /// Write the output vertex at `index`, a `u32`.
+    ///
+    /// The `value` has the entry point's [`vertex_output_type`].
+    ///
+    /// [`vertex_output_type`]: MeshStageInfo::vertex_output_type
+    SetVertex {
+        index: Handle<Expression>,
+        value: Handle<Expression>,
+    },
+    /// Write the output primitive at `index`, a `u32`.
+    ///
+    /// The `value` has the entry point's [`primitive_output_type`].
+    ///
+    /// [`primitive_output_type`]: MeshStageInfo::primitive_output_type
+    SetPrimitive {
+        index: Handle<Expression>,
+        value: Handle<Expression>,
+    },
+}
+
+/// Kind of primitives a mesh shader outputs.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum MeshOutputTopology {
+    /// Primitives are indexed by a [`BuiltIn::PointIndex`].
+    Points,
+    /// Primitives are indexed by [`BuiltIn::LineIndices`].
+    Lines,
+    /// Primitives are indexed by [`BuiltIn::TriangleIndices`].
+    Triangles,
+}
+
+/// Output declaration of a mesh shader entry point.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub struct MeshStageInfo {
+    /// Kind of the output primitives.
+    pub topology: MeshOutputTopology,
+    /// Maximum number of vertices the workgroup outputs.
+    pub max_vertices: u32,
+    /// Maximum number of primitives the workgroup outputs.
+    pub max_primitives: u32,
+    /// Structure written by [`MeshFunction::SetVertex`].
+    ///
+    /// All of its members have a [`Binding`], like a vertex shader's result.
+    pub vertex_output_type: Handle<Type>,
+    /// Structure written by [`MeshFunction::SetPrimitive`].
+    ///
+    /// All of its members have a [`Binding`]. Exactly one of them is the
+    /// built-in holding the vertex indices for the [`topology`].
+    ///
+    /// [`topology`]: MeshStageInfo::topology
+    pub primitive_output_type: Handle<Type>,
+}
+
+/// Which invocation of the subgroup a [`Statement::SubgroupGather`] reads from.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum GatherMode {
+    /// The active invocation with the lowest id.
+    BroadcastFirst,
+    /// The invocation with the given id, which must be uniform.
+    Broadcast(Handle<Expression>),
+    /// The invocation with the given id.
+    Shuffle(Handle<Expression>),
+    /// The invocation whose id is the current one plus the given delta.
+    ShuffleDown(Handle<Expression>),
+    /// The invocation whose id is the current one minus the given delta.
+    ShuffleUp(Handle<Expression>),
+    /// The invocation whose id is the current one xor the given mask.
+    ShuffleXor(Handle<Expression>),
+}
+
 /// Axis on which to compute a derivative.
 #[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
@@ -977,7 +1201,7 @@ pub enum MathFunction {
 }
 
 /// Sampling modifier to control the level of detail.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -993,7 +1217,7 @@ pub enum SampleLevel {
 }
 
 /// Type of an image query.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1013,7 +1237,7 @@ pub enum ImageQuery {
 
 /// Component selection for a vector swizzle.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1039,14 +1263,15 @@ bitflags::bitflags! {
         const STORAGE = 0x1;
         /// Barrier affects all `StorageClass::WorkGroup` accesses.
         const WORK_GROUP = 0x2;
+        /// Barrier synchronizes execution across all invocations within a subgroup.
+        const SUB_GROUP = 0x4;
     }
 }
 
 /// An expression that can be evaluated to obtain a value.
 ///
 /// This is a Single Static Assignment (SSA) scheme similar to SPIR-V.
-#[derive(Clone, Debug)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1318,10 +1543,41 @@ pub enum Expression {
     /// Result of calling another function.
     CallResult(Handle<Function>),
     /// Result of an atomic operation.
-    AtomicResult {
-        kind: ScalarKind,
-        width: Bytes,
-        comparison: bool,
+    ///
+    /// The `ty` is the scalar type of the atomic value, unless `comparison`
+    /// is set: the result of an [`Exchange`] with a `compare` value is a
+    /// structure whose first member is the old value, and whose second
+    /// member is a `bool` telling whether the exchange happened.
+    ///
+    /// [`Exchange`]: AtomicFunction::Exchange
+    AtomicResult { ty: Handle<Type>, comparison: bool },
+    /// Result of a [`SubgroupBallot`] statement, a `vec4<u32>` bitmask of the
+    /// invocations in the subgroup.
+    ///
+    /// [`SubgroupBallot`]: Statement::SubgroupBallot
+    SubgroupBallotResult,
+    /// Result of a [`SubgroupElect`], [`SubgroupGather`] or
+    /// [`SubgroupCollectiveOperation`] statement.
+    ///
+    /// [`SubgroupElect`]: Statement::SubgroupElect
+    /// [`SubgroupGather`]: Statement::SubgroupGather
+    /// [`SubgroupCollectiveOperation`]: Statement::SubgroupCollectiveOperation
+    SubgroupOperationResult { ty: Handle<Type> },
+    /// Result of a [`Proceed`] ray query statement: a `bool` that is true
+    /// while the traversal hasn't finished yet.
+    ///
+    /// [`Proceed`]: RayQueryFunction::Proceed
+    RayQueryProceedResult,
+    /// Committed intersection of a ray query.
+    ///
+    /// The `query` is a pointer to a [`RayQuery`] variable. The result is a
+    /// value of the ray intersection structure `ty`, see
+    /// [`Module::generate_ray_intersection_type`].
+    ///
+    /// [`RayQuery`]: TypeInner::RayQuery
+    RayQueryGetCommittedIntersection {
+        query: Handle<Expression>,
+        ty: Handle<Type>,
     },
     /// Get the length of an array.
     /// The expression must resolve to a pointer to an array with a dynamic size.
@@ -1329,6 +1585,14 @@ pub enum Expression {
     /// This doesn't match the semantics of spirv's `OpArrayLength`, which must be passed
     /// a pointer to a structure containing a runtime array in its' last field.
     ArrayLength(Handle<Expression>),
+    /// Mark an integer value as not being uniform across the invocations.
+    ///
+    /// Evaluates to the value itself. Indexing a [`BindingArray`] with a
+    /// non-uniform index must go through this marker, so that the backends
+    /// can emit the matching decorations or intrinsics.
+    ///
+    /// [`BindingArray`]: TypeInner::BindingArray
+    NonUniform(Handle<Expression>),
 }
 
 pub use block::Block;
@@ -1481,6 +1745,33 @@ pub enum Statement {
         array_index: Option<Handle<Expression>>,
         value: Handle<Expression>,
     },
+    /// Atomically applies a function to a texel of a storage image.
+    ///
+    /// The `image`, `coordinate`, and `array_index` fields have the same
+    /// meanings as the corresponding operands of an [`ImageStore`] statement.
+    /// The image must have the [`R32Uint`] or [`R32Sint`] storage format, and
+    /// `value` is a scalar of the corresponding kind. Compare-exchange is not
+    /// supported on images.
+    ///
+    /// This statement is a barrier for any operations on the corresponding
+    /// [`Expression::GlobalVariable`] for this image.
+    ///
+    /// [`ImageStore`]: Statement::ImageStore
+    /// [`R32Uint`]: StorageFormat::R32Uint
+    /// [`R32Sint`]: StorageFormat::R32Sint
+    ImageAtomic {
+        image: Handle<Expression>,
+        coordinate: Handle<Expression>,
+        array_index: Option<Handle<Expression>>,
+        /// Function to run on the texel.
+        fun: AtomicFunction,
+        /// Value to use in the function.
+        value: Handle<Expression>,
+        /// [`AtomicResult`] expression holding the previous value of the texel.
+        ///
+        /// [`AtomicResult`]: crate::Expression::AtomicResult
+        result: Handle<Expression>,
+    },
     /// Atomic function.
     Atomic {
         /// Pointer to an atomic value.
@@ -1504,6 +1795,60 @@ pub enum Statement {
         arguments: Vec<Handle<Expression>>,
         result: Option<Handle<Expression>>,
     },
+    /// Computes a bitmask of the active invocations of the subgroup for
+    /// which `predicate` is true, or of all active invocations if it is `None`.
+    SubgroupBallot {
+        /// [`SubgroupBallotResult`] expression representing the bitmask.
+        ///
+        /// [`SubgroupBallotResult`]: crate::Expression::SubgroupBallotResult
+        result: Handle<Expression>,
+        /// Boolean condition of the invocation, if any.
+        predicate: Option<Handle<Expression>>,
+    },
+    /// Returns true for exactly one active invocation of the subgroup, the
+    /// one with the lowest id.
+    SubgroupElect {
+        /// [`SubgroupOperationResult`] expression of type `bool`.
+        ///
+        /// [`SubgroupOperationResult`]: crate::Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
+    /// Reads `argument` from another invocation of the subgroup.
+    SubgroupGather {
+        /// Which invocation to read from.
+        mode: GatherMode,
+        /// Scalar or vector value to read.
+        argument: Handle<Expression>,
+        /// [`SubgroupOperationResult`] expression representing the value.
+        ///
+        /// [`SubgroupOperationResult`]: crate::Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
+    /// Combines `argument` across the active invocations of the subgroup.
+    SubgroupCollectiveOperation {
+        op: SubgroupOperation,
+        collective_op: CollectiveOperation,
+        /// Scalar or vector value to combine.
+        argument: Handle<Expression>,
+        /// [`SubgroupOperationResult`] expression representing the value.
+        ///
+        /// [`SubgroupOperationResult`]: crate::Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
+    /// Operates on a ray query.
+    RayQuery {
+        /// Pointer to a [`RayQuery`] local variable.
+        ///
+        /// [`RayQuery`]: TypeInner::RayQuery
+        query: Handle<Expression>,
+        fun: RayQueryFunction,
+    },
+    /// Writes the output of a mesh shader.
+    ///
+    /// This may only appear in [`Mesh`] entry points, which have no result.
+    ///
+    /// [`Mesh`]: ShaderStage::Mesh
+    MeshFunction(MeshFunction),
 }
 
 /// A function argument.
@@ -1534,7 +1879,7 @@ pub struct FunctionResult {
 }
 
 /// A function defined in the module.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1598,7 +1943,7 @@ pub struct Function {
 /// [`Location`]: Binding::Location
 /// [`function`]: EntryPoint::function
 /// [`stage`]: EntryPoint::stage
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1611,9 +1956,26 @@ pub struct EntryPoint {
     pub stage: ShaderStage,
     /// Early depth test for fragment stages.
     pub early_depth_test: Option<EarlyDepthTest>,
-    /// Workgroup size for compute stages
+    /// Workgroup size for compute, task and mesh stages
     pub workgroup_size: [u32; 3],
+    /// Specializable constants overriding the corresponding components of
+    /// [`workgroup_size`], if any.
+    ///
+    /// The components of [`workgroup_size`] hold the unspecialized values of
+    /// these constants. [`proc::process_overrides`] replaces them with the
+    /// values provided for the pipeline.
+    ///
+    /// [`workgroup_size`]: EntryPoint::workgroup_size
+    /// [`proc::process_overrides`]: proc::process_overrides
+    pub workgroup_size_overrides: Option<[Option<Handle<Constant>>; 3]>,
+    /// Output declaration, for mesh stages.
+    pub mesh_info: Option<MeshStageInfo>,
     /// The entrance function.
+    ///
+    /// A [`Task`] entry point returns the number of mesh workgroups to
+    /// launch, as a `vec3<u32>` bound to [`BuiltIn::MeshTaskSize`].
+    ///
+    /// [`Task`]: ShaderStage::Task
     pub function: Function,
 }
 
@@ -1628,7 +1990,7 @@ pub struct EntryPoint {
 /// Alternatively, you can load an existing shader using one of the [available front ends][front].
 ///
 /// When finished, you can export modules using one of the [available backends][back].
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -0,0 +1,213 @@
+//! Remapping of the resource bindings of a module.
+//!
+//! The GLSL, HLSL and MSL back ends can each remap bindings as they write
+//! their output, through their own options. [`remap_bindings`] changes the
+//! [`binding`] of the global variables in the module instead, so the result
+//! can be written by any back end, or kept as it is.
+//!
+//! [`binding`]: crate::GlobalVariable::binding
+
+use crate::{Module, ResourceBinding};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How [`remap_bindings`] assigns new bindings.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum BindingRemapping {
+    /// Move the bindings that are keys of the map to the corresponding
+    /// values, and keep the others.
+    Map(BTreeMap<ResourceBinding, ResourceBinding>),
+    /// Number the bindings of each group from zero, removing the gaps between
+    /// them. The order of the bindings and their groups are kept.
+    Compact,
+    /// Move all the bindings to group zero, numbered from zero in the order
+    /// of their groups and bindings.
+    Flatten,
+    /// Move all the bindings to group zero, adding to each binding number the
+    /// offset in this list at the index of its group.
+    Offset(Vec<u32>),
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum RemapError {
+    #[error("No offset is given for group {0}")]
+    MissingGroupOffset(u32),
+    #[error("The offset binding number of {0:?} is too large")]
+    Overflow(ResourceBinding),
+    #[error("Both {first:?} and {second:?} are moved to {target:?}")]
+    Collision {
+        first: ResourceBinding,
+        second: ResourceBinding,
+        target: ResourceBinding,
+    },
+}
+
+/// Change the [`binding`] of the global variables of `module` as `remapping`
+/// says.
+///
+/// Variables bound at the same [`ResourceBinding`], like the ones different
+/// entry points use, are moved together. The variables keep their storage
+/// class and type, and two different bindings are never moved to the same
+/// one: if the remapping asks for that, an error is returned and the module
+/// is left unchanged.
+///
+/// Return the map of each binding in use to the one it was moved to.
+///
+/// [`binding`]: crate::GlobalVariable::binding
+pub fn remap_bindings(
+    module: &mut Module,
+    remapping: &BindingRemapping,
+) -> Result<BTreeMap<ResourceBinding, ResourceBinding>, RemapError> {
+    let bindings = module
+        .global_variables
+        .iter()
+        .filter_map(|(_, var)| var.binding.clone())
+        .collect::<BTreeSet<_>>();
+
+    let mut map = BTreeMap::new();
+    let mut current_group = None;
+    let mut next_binding = 0;
+    for binding in bindings.iter() {
+        let target = match *remapping {
+            BindingRemapping::Map(ref targets) => match targets.get(binding) {
+                Some(target) => target.clone(),
+                None => binding.clone(),
+            },
+            BindingRemapping::Compact => {
+                if current_group != Some(binding.group) {
+                    current_group = Some(binding.group);
+                    next_binding = 0;
+                }
+                next_binding += 1;
+                ResourceBinding {
+                    group: binding.group,
+                    binding: next_binding - 1,
+                }
+            }
+            BindingRemapping::Flatten => {
+                next_binding += 1;
+                ResourceBinding {
+                    group: 0,
+                    binding: next_binding - 1,
+                }
+            }
+            BindingRemapping::Offset(ref offsets) => {
+                let offset = offsets
+                    .get(binding.group as usize)
+                    .ok_or(RemapError::MissingGroupOffset(binding.group))?;
+                ResourceBinding {
+                    group: 0,
+                    binding: offset
+                        .checked_add(binding.binding)
+                        .ok_or_else(|| RemapError::Overflow(binding.clone()))?,
+                }
+            }
+        };
+        map.insert(binding.clone(), target);
+    }
+
+    let mut sources = BTreeMap::new();
+    for (binding, target) in map.iter() {
+        if let Some(first) = sources.insert(target, binding) {
+            return Err(RemapError::Collision {
+                first: first.clone(),
+                second: binding.clone(),
+                target: target.clone(),
+            });
+        }
+    }
+
+    for (_, var) in module.global_variables.iter_mut() {
+        if let Some(ref mut binding) = var.binding {
+            *binding = map[binding].clone();
+        }
+    }
+    Ok(map)
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{remap_bindings, BindingRemapping, RemapError};
+    use crate::ResourceBinding;
+
+    const SOURCE: &str = "
+        @group(0) @binding(3) var t: texture_2d<f32>;
+        @group(0) @binding(7) var s: sampler;
+        @group(2) @binding(1) var u: texture_2d<f32>;
+
+        @stage(fragment)
+        fn first() -> @location(0) vec4<f32> {
+            return textureSample(t, s, vec2<f32>(0.5)) + textureSample(u, s, vec2<f32>(0.5));
+        }
+
+        @stage(fragment)
+        fn second() -> @location(0) vec4<f32> {
+            return textureSample(t, s, vec2<f32>(0.5));
+        }
+    ";
+
+    fn rb(group: u32, binding: u32) -> ResourceBinding {
+        ResourceBinding { group, binding }
+    }
+
+    fn remap(remapping: BindingRemapping) -> Result<Vec<ResourceBinding>, RemapError> {
+        let mut module = crate::front::wgsl::parse_str(SOURCE).unwrap();
+        remap_bindings(&mut module, &remapping)?;
+        Ok(module
+            .global_variables
+            .iter()
+            .map(|(_, var)| var.binding.clone().unwrap())
+            .collect())
+    }
+
+    #[test]
+    fn strategies() {
+        assert_eq!(
+            remap(BindingRemapping::Compact),
+            Ok(vec![rb(0, 0), rb(0, 1), rb(2, 0)])
+        );
+        assert_eq!(
+            remap(BindingRemapping::Flatten),
+            Ok(vec![rb(0, 0), rb(0, 1), rb(0, 2)])
+        );
+        assert_eq!(
+            remap(BindingRemapping::Offset(vec![0, 8, 16])),
+            Ok(vec![rb(0, 3), rb(0, 7), rb(0, 17)])
+        );
+        let map = vec![(rb(0, 7), rb(1, 0))].into_iter().collect();
+        assert_eq!(
+            remap(BindingRemapping::Map(map)),
+            Ok(vec![rb(0, 3), rb(1, 0), rb(2, 1)])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            remap(BindingRemapping::Offset(vec![0, 8])),
+            Err(RemapError::MissingGroupOffset(2))
+        );
+        assert_eq!(
+            remap(BindingRemapping::Offset(vec![0, 0, u32::MAX])),
+            Err(RemapError::Overflow(rb(2, 1)))
+        );
+        assert_eq!(
+            remap(BindingRemapping::Offset(vec![0, 0, 6])),
+            Err(RemapError::Collision {
+                first: rb(0, 7),
+                second: rb(2, 1),
+                target: rb(0, 7),
+            })
+        );
+        let map = vec![(rb(0, 3), rb(2, 1))].into_iter().collect();
+        assert_eq!(
+            remap(BindingRemapping::Map(map)),
+            Err(RemapError::Collision {
+                first: rb(0, 3),
+                second: rb(2, 1),
+                target: rb(2, 1),
+            })
+        );
+    }
+}
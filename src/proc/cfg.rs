@@ -0,0 +1,811 @@
+/*!
+Control flow graphs of functions.
+
+The body of a [`Function`] is a tree of nested [`Block`]s, in which control
+leaves a block at its end, or through [`Break`], [`Continue`], [`Return`] and
+[`Kill`] statements. A [`ControlFlowGraph`] flattens this tree into
+[`BasicBlock`]s: runs of statements that are executed together, linked by
+[`Edge`]s for each way control can pass from one to the other.
+
+The graph also holds the dominator and post-dominator trees of its blocks,
+and the [`Loop`]s they are nested in.
+
+[`Function`]: crate::Function
+[`Break`]: Statement::Break
+[`Continue`]: Statement::Continue
+[`Return`]: Statement::Return
+[`Kill`]: Statement::Kill
+*/
+
+use crate::arena::{Arena, Handle};
+use crate::{Block, Span, Statement};
+
+/// A run of statements that are executed together.
+#[derive(Debug)]
+pub struct BasicBlock<'a> {
+    /// The statements of the block, in order.
+    ///
+    /// These are never control flow statements: nested [`Block`]s are
+    /// flattened into the basic blocks, and the other control flow
+    /// statements end the basic block they appear in.
+    ///
+    /// [`Block`]: Statement::Block
+    pub statements: Vec<&'a Statement>,
+
+    /// The statement passing control to the successors of the block.
+    ///
+    /// This is an [`If`], [`Switch`], [`Loop`], [`Break`], [`Continue`],
+    /// [`Return`] or [`Kill`] statement, or `None` if control reaches the end
+    /// of the [`Block`] the statements are in.
+    ///
+    /// [`If`]: Statement::If
+    /// [`Switch`]: Statement::Switch
+    /// [`Loop`]: Statement::Loop
+    /// [`Break`]: Statement::Break
+    /// [`Continue`]: Statement::Continue
+    /// [`Return`]: Statement::Return
+    /// [`Kill`]: Statement::Kill
+    pub terminator: Option<&'a Statement>,
+
+    /// The edges to the blocks control may pass to after this one.
+    pub successors: Vec<Edge<'a>>,
+
+    /// The blocks control may come from.
+    ///
+    /// A block has a predecessor for each edge to it, so the same block may
+    /// appear several times.
+    pub predecessors: Vec<Handle<BasicBlock<'a>>>,
+
+    /// The innermost loop the block is part of, if any.
+    pub innermost_loop: Option<Handle<Loop<'a>>>,
+}
+
+impl<'a> BasicBlock<'a> {
+    fn new(innermost_loop: Option<Handle<Loop<'a>>>) -> Self {
+        BasicBlock {
+            statements: Vec::new(),
+            terminator: None,
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+            innermost_loop,
+        }
+    }
+}
+
+/// An edge of a [`ControlFlowGraph`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Edge<'a> {
+    /// The block control passes to.
+    pub target: Handle<BasicBlock<'a>>,
+    /// What passes control along the edge.
+    pub kind: EdgeKind,
+}
+
+/// What passes control along an [`Edge`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EdgeKind {
+    /// Reaching the end of a [`Block`], or entering a [`Loop`] statement.
+    ///
+    /// At the end of the body of a function, this leads to the exit block: the
+    /// function returns without a [`Return`] statement.
+    ///
+    /// [`Loop`]: Statement::Loop
+    /// [`Return`]: Statement::Return
+    Next,
+    /// The condition of an [`If`] statement is true.
+    ///
+    /// [`If`]: Statement::If
+    Accept,
+    /// The condition of an [`If`] statement is false.
+    ///
+    /// [`If`]: Statement::If
+    Reject,
+    /// The selector of a [`Switch`] statement selects the case at the given
+    /// index.
+    ///
+    /// [`Switch`]: Statement::Switch
+    Case(usize),
+    /// Reaching the end of a [`SwitchCase`] that falls through to the next one.
+    ///
+    /// [`SwitchCase`]: crate::SwitchCase
+    FallThrough,
+    /// A [`Break`] statement.
+    ///
+    /// [`Break`]: Statement::Break
+    Break,
+    /// A [`Continue`] statement.
+    ///
+    /// [`Continue`]: Statement::Continue
+    Continue,
+    /// Reaching the end of the `continuing` block of a loop, back to the start
+    /// of its body.
+    Back,
+    /// A [`Return`] statement.
+    ///
+    /// [`Return`]: Statement::Return
+    Return,
+    /// A [`Kill`] statement.
+    ///
+    /// [`Kill`]: Statement::Kill
+    Kill,
+}
+
+/// A [`Loop`] statement of a function.
+///
+/// [`Loop`]: Statement::Loop
+#[derive(Debug)]
+pub struct Loop<'a> {
+    /// The [`Loop`] statement.
+    ///
+    /// [`Loop`]: Statement::Loop
+    pub statement: &'a Statement,
+    /// The first block of the body of the loop, which [`Back`] edges lead to.
+    ///
+    /// [`Back`]: EdgeKind::Back
+    pub header: Handle<BasicBlock<'a>>,
+    /// The first block of the `continuing` block of the loop, which
+    /// [`Continue`] edges lead to.
+    ///
+    /// [`Continue`]: EdgeKind::Continue
+    pub continuing: Handle<BasicBlock<'a>>,
+    /// The block following the loop, which [`Break`] edges lead to.
+    ///
+    /// [`Break`]: EdgeKind::Break
+    pub merge: Handle<BasicBlock<'a>>,
+    /// The innermost loop containing this one, if any.
+    pub parent: Option<Handle<Loop<'a>>>,
+    /// The number of loops this one is nested in, including itself.
+    pub depth: usize,
+}
+
+/// The control flow graph of a function.
+///
+/// The graph has a block for each run of statements, and two blocks with no
+/// statements of their own: the entry block, with no predecessors, at the
+/// start of the body of the function, and the exit block, with no successors,
+/// which every way of leaving the function leads to.
+///
+/// Statements following a [`Break`], [`Continue`], [`Return`] or [`Kill`]
+/// statement in the same [`Block`] are put in blocks that aren't
+/// [reachable](ControlFlowGraph::is_reachable).
+///
+/// [`Break`]: Statement::Break
+/// [`Continue`]: Statement::Continue
+/// [`Return`]: Statement::Return
+/// [`Kill`]: Statement::Kill
+#[derive(Debug)]
+pub struct ControlFlowGraph<'a> {
+    blocks: Arena<BasicBlock<'a>>,
+    loops: Arena<Loop<'a>>,
+    entry: Handle<BasicBlock<'a>>,
+    exit: Handle<BasicBlock<'a>>,
+    /// The immediate dominator of each block, if any.
+    dominators: Vec<Option<Handle<BasicBlock<'a>>>>,
+    /// The immediate post-dominator of each block, if any.
+    post_dominators: Vec<Option<Handle<BasicBlock<'a>>>>,
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    /// Build the control flow graph of `function`.
+    ///
+    /// The function is expected to be valid: [`Break`] and [`Continue`]
+    /// statements outside of the constructs they apply to are given no
+    /// successors.
+    ///
+    /// [`Break`]: Statement::Break
+    /// [`Continue`]: Statement::Continue
+    pub fn new(function: &'a crate::Function) -> Self {
+        let mut blocks = Arena::new();
+        let entry = blocks.append(BasicBlock::new(None), Span::default());
+        let exit = blocks.append(BasicBlock::new(None), Span::default());
+        let mut builder = Builder {
+            blocks,
+            loops: Arena::new(),
+            exit,
+            break_targets: Vec::new(),
+            continue_targets: Vec::new(),
+            current_loop: None,
+        };
+        if let Some(end) = builder.add_block(&function.body, Some(entry)) {
+            builder.add_edge(end, exit, EdgeKind::Next);
+        }
+
+        let blocks = builder.blocks;
+        let successors = blocks
+            .iter()
+            .map(|(_, block)| {
+                block
+                    .successors
+                    .iter()
+                    .map(|edge| edge.target.index())
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let predecessors = blocks
+            .iter()
+            .map(|(_, block)| block.predecessors.iter().map(|pred| pred.index()).collect())
+            .collect::<Vec<Vec<_>>>();
+        let to_handles = |tree: Vec<Option<usize>>| {
+            tree.into_iter()
+                .map(|index| index.map(Handle::from_usize))
+                .collect()
+        };
+        let dominators = to_handles(immediate_dominators(
+            entry.index(),
+            &successors,
+            &predecessors,
+        ));
+        let post_dominators = to_handles(immediate_dominators(
+            exit.index(),
+            &predecessors,
+            &successors,
+        ));
+
+        ControlFlowGraph {
+            blocks,
+            loops: builder.loops,
+            entry,
+            exit,
+            dominators,
+            post_dominators,
+        }
+    }
+
+    /// Return the blocks of the graph.
+    pub fn blocks(&self) -> &Arena<BasicBlock<'a>> {
+        &self.blocks
+    }
+
+    /// Return the loops of the function, outer loops first.
+    pub fn loops(&self) -> &Arena<Loop<'a>> {
+        &self.loops
+    }
+
+    /// Return the block control enters the function at.
+    pub fn entry(&self) -> Handle<BasicBlock<'a>> {
+        self.entry
+    }
+
+    /// Return the block control leaves the function from.
+    pub fn exit(&self) -> Handle<BasicBlock<'a>> {
+        self.exit
+    }
+
+    /// Return `true` if control can reach `block` from the entry block.
+    pub fn is_reachable(&self, block: Handle<BasicBlock<'a>>) -> bool {
+        block == self.entry || self.dominators[block.index()].is_some()
+    }
+
+    /// Return the immediate dominator of `block`.
+    ///
+    /// This is `None` for the entry block, and for blocks that aren't
+    /// [reachable](ControlFlowGraph::is_reachable).
+    pub fn immediate_dominator(
+        &self,
+        block: Handle<BasicBlock<'a>>,
+    ) -> Option<Handle<BasicBlock<'a>>> {
+        self.dominators[block.index()]
+    }
+
+    /// Return `true` if every path from the entry block to `block` goes
+    /// through `dominator`.
+    ///
+    /// Every reachable block dominates itself.
+    pub fn dominates(
+        &self,
+        dominator: Handle<BasicBlock<'a>>,
+        block: Handle<BasicBlock<'a>>,
+    ) -> bool {
+        self.is_reachable(block) && is_ancestor(&self.dominators, dominator, block)
+    }
+
+    /// Return the immediate post-dominator of `block`.
+    ///
+    /// This is `None` for the exit block, and for blocks from which control
+    /// can't reach the exit block, like the blocks of an infinite loop.
+    pub fn immediate_post_dominator(
+        &self,
+        block: Handle<BasicBlock<'a>>,
+    ) -> Option<Handle<BasicBlock<'a>>> {
+        self.post_dominators[block.index()]
+    }
+
+    /// Return `true` if every path from `block` to the exit block goes through
+    /// `post_dominator`.
+    ///
+    /// Every block that can reach the exit block post-dominates itself.
+    pub fn post_dominates(
+        &self,
+        post_dominator: Handle<BasicBlock<'a>>,
+        block: Handle<BasicBlock<'a>>,
+    ) -> bool {
+        (block == self.exit || self.post_dominators[block.index()].is_some())
+            && is_ancestor(&self.post_dominators, post_dominator, block)
+    }
+
+    /// Return the number of loops `block` is part of.
+    pub fn loop_depth(&self, block: Handle<BasicBlock<'a>>) -> usize {
+        match self.blocks[block].innermost_loop {
+            Some(handle) => self.loops[handle].depth,
+            None => 0,
+        }
+    }
+}
+
+/// Return `true` if `ancestor` is `node` or one of its ancestors in `tree`.
+fn is_ancestor<T>(tree: &[Option<Handle<T>>], ancestor: Handle<T>, mut node: Handle<T>) -> bool {
+    loop {
+        if node == ancestor {
+            return true;
+        }
+        match tree[node.index()] {
+            Some(parent) => node = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Compute the immediate dominator of each node of a graph, for the given
+/// `root`.
+///
+/// The graph is given by the `successors` and `predecessors` of each node.
+/// The root, and the nodes it doesn't reach, have no immediate dominator.
+///
+/// This is the algorithm from "A Simple, Fast Dominance Algorithm", by Keith
+/// D. Cooper, Timothy J. Harvey and Ken Kennedy.
+fn immediate_dominators(
+    root: usize,
+    successors: &[Vec<usize>],
+    predecessors: &[Vec<usize>],
+) -> Vec<Option<usize>> {
+    // Number the nodes reachable from the root in postorder.
+    let mut postorder = Vec::with_capacity(successors.len());
+    let mut visited = vec![false; successors.len()];
+    let mut stack = vec![(root, 0)];
+    visited[root] = true;
+    while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+        match successors[node].get(*next) {
+            Some(&successor) => {
+                *next += 1;
+                if !visited[successor] {
+                    visited[successor] = true;
+                    stack.push((successor, 0));
+                }
+            }
+            None => {
+                postorder.push(node);
+                stack.pop();
+            }
+        }
+    }
+    let mut postorder_numbers = vec![None; successors.len()];
+    for (number, &node) in postorder.iter().enumerate() {
+        postorder_numbers[node] = Some(number);
+    }
+
+    let mut dominators = vec![None; successors.len()];
+    dominators[root] = Some(root);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &node in postorder.iter().rev() {
+            if node == root {
+                continue;
+            }
+            let mut new_dominator = None;
+            for &predecessor in predecessors[node].iter() {
+                if dominators[predecessor].is_none() {
+                    continue;
+                }
+                new_dominator = Some(match new_dominator {
+                    None => predecessor,
+                    Some(other) => intersect(&dominators, &postorder_numbers, predecessor, other),
+                });
+            }
+            if new_dominator != dominators[node] {
+                dominators[node] = new_dominator;
+                changed = true;
+            }
+        }
+    }
+
+    dominators[root] = None;
+    dominators
+}
+
+/// Return the nearest common dominator of `a` and `b`.
+fn intersect(
+    dominators: &[Option<usize>],
+    postorder_numbers: &[Option<usize>],
+    mut a: usize,
+    mut b: usize,
+) -> usize {
+    while a != b {
+        while postorder_numbers[a] < postorder_numbers[b] {
+            a = dominators[a].expect("processed node without a dominator");
+        }
+        while postorder_numbers[b] < postorder_numbers[a] {
+            b = dominators[b].expect("processed node without a dominator");
+        }
+    }
+    a
+}
+
+struct Builder<'a> {
+    blocks: Arena<BasicBlock<'a>>,
+    loops: Arena<Loop<'a>>,
+    exit: Handle<BasicBlock<'a>>,
+    /// The blocks [`Break`] statements lead to, innermost last.
+    ///
+    /// [`Break`]: Statement::Break
+    break_targets: Vec<Handle<BasicBlock<'a>>>,
+    /// The blocks [`Continue`] statements lead to, innermost last.
+    ///
+    /// [`Continue`]: Statement::Continue
+    continue_targets: Vec<Handle<BasicBlock<'a>>>,
+    current_loop: Option<Handle<Loop<'a>>>,
+}
+
+impl<'a> Builder<'a> {
+    fn new_block(&mut self, span: Span) -> Handle<BasicBlock<'a>> {
+        self.blocks.append(BasicBlock::new(self.current_loop), span)
+    }
+
+    fn add_edge(
+        &mut self,
+        from: Handle<BasicBlock<'a>>,
+        to: Handle<BasicBlock<'a>>,
+        kind: EdgeKind,
+    ) {
+        self.blocks
+            .get_mut(from)
+            .successors
+            .push(Edge { target: to, kind });
+        self.blocks.get_mut(to).predecessors.push(from);
+    }
+
+    /// Add the statements of `block` to the graph, starting in `current`.
+    ///
+    /// If `current` is `None`, the statements can't be reached, and are added
+    /// to a new block with no predecessors.
+    ///
+    /// Return the basic block control reaches the end of `block` in, if it
+    /// does.
+    fn add_block(
+        &mut self,
+        block: &'a Block,
+        mut current: Option<Handle<BasicBlock<'a>>>,
+    ) -> Option<Handle<BasicBlock<'a>>> {
+        for (statement, &span) in block.span_iter() {
+            let basic_block = match current {
+                Some(basic_block) => basic_block,
+                None => self.new_block(span),
+            };
+            current = match *statement {
+                Statement::Block(ref block) => self.add_block(block, Some(basic_block)),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    let accept_start = self.new_block(span);
+                    let reject_start = self.new_block(span);
+                    self.add_edge(basic_block, accept_start, EdgeKind::Accept);
+                    self.add_edge(basic_block, reject_start, EdgeKind::Reject);
+                    let accept_end = self.add_block(accept, Some(accept_start));
+                    let reject_end = self.add_block(reject, Some(reject_start));
+                    let merge = self.new_block(span);
+                    for &end in accept_end.iter().chain(reject_end.iter()) {
+                        self.add_edge(end, merge, EdgeKind::Next);
+                    }
+                    Some(merge)
+                }
+                Statement::Switch { ref cases, .. } => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    let case_starts = cases
+                        .iter()
+                        .enumerate()
+                        .map(|(index, _)| {
+                            let start = self.new_block(span);
+                            self.add_edge(basic_block, start, EdgeKind::Case(index));
+                            start
+                        })
+                        .collect::<Vec<_>>();
+                    let merge = self.new_block(span);
+                    self.break_targets.push(merge);
+                    for (index, case) in cases.iter().enumerate() {
+                        if let Some(end) = self.add_block(&case.body, Some(case_starts[index])) {
+                            match case_starts.get(index + 1) {
+                                Some(&next) if case.fall_through => {
+                                    self.add_edge(end, next, EdgeKind::FallThrough)
+                                }
+                                _ => self.add_edge(end, merge, EdgeKind::Next),
+                            }
+                        }
+                    }
+                    self.break_targets.pop();
+                    Some(merge)
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    let parent = self.current_loop;
+                    let depth = match parent {
+                        Some(handle) => self.loops[handle].depth + 1,
+                        None => 1,
+                    };
+                    let merge = self.new_block(span);
+                    let header = self.new_block(span);
+                    let continuing_start = self.new_block(span);
+                    let handle = self.loops.append(
+                        Loop {
+                            statement,
+                            header,
+                            continuing: continuing_start,
+                            merge,
+                            parent,
+                            depth,
+                        },
+                        span,
+                    );
+                    self.blocks.get_mut(header).innermost_loop = Some(handle);
+                    self.blocks.get_mut(continuing_start).innermost_loop = Some(handle);
+                    self.add_edge(basic_block, header, EdgeKind::Next);
+
+                    self.current_loop = Some(handle);
+                    self.break_targets.push(merge);
+                    self.continue_targets.push(continuing_start);
+                    if let Some(end) = self.add_block(body, Some(header)) {
+                        self.add_edge(end, continuing_start, EdgeKind::Next);
+                    }
+                    self.break_targets.pop();
+                    self.continue_targets.pop();
+                    if let Some(end) = self.add_block(continuing, Some(continuing_start)) {
+                        self.add_edge(end, header, EdgeKind::Back);
+                    }
+                    self.current_loop = parent;
+                    Some(merge)
+                }
+                Statement::Break => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    if let Some(&target) = self.break_targets.last() {
+                        self.add_edge(basic_block, target, EdgeKind::Break);
+                    }
+                    None
+                }
+                Statement::Continue => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    if let Some(&target) = self.continue_targets.last() {
+                        self.add_edge(basic_block, target, EdgeKind::Continue);
+                    }
+                    None
+                }
+                Statement::Return { .. } | Statement::Kill => {
+                    self.blocks.get_mut(basic_block).terminator = Some(statement);
+                    let kind = match *statement {
+                        Statement::Kill => EdgeKind::Kill,
+                        _ => EdgeKind::Return,
+                    };
+                    self.add_edge(basic_block, self.exit, kind);
+                    None
+                }
+                Statement::Emit(_)
+                | Statement::Barrier(_)
+                | Statement::Store { .. }
+                | Statement::ImageStore { .. }
+                | Statement::ImageAtomic { .. }
+                | Statement::Atomic { .. }
+                | Statement::Call { .. }
+                | Statement::SubgroupBallot { .. }
+                | Statement::SubgroupElect { .. }
+                | Statement::SubgroupGather { .. }
+                | Statement::SubgroupCollectiveOperation { .. }
+                | Statement::RayQuery { .. }
+                | Statement::MeshFunction(_) => {
+                    self.blocks.get_mut(basic_block).statements.push(statement);
+                    Some(basic_block)
+                }
+            };
+        }
+        current
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{ControlFlowGraph, EdgeKind};
+    use crate::Statement;
+
+    fn parse(source: &str) -> crate::Module {
+        crate::front::wgsl::parse_str(source).unwrap()
+    }
+
+    #[test]
+    fn branches() {
+        let module = parse(
+            "
+            fn f(x: i32) -> i32 {
+                var y = 0;
+                if (x > 0) {
+                    y = 1;
+                } else {
+                    return 2;
+                }
+                switch (x) {
+                    case 1: { y = 2; fallthrough; }
+                    case 2: { y = 3; }
+                    default: { y = 4; }
+                }
+                return y;
+            }
+            ",
+        );
+        let (_, function) = module.functions.iter().next().unwrap();
+        let cfg = ControlFlowGraph::new(function);
+        let blocks = cfg.blocks();
+
+        let entry = &blocks[cfg.entry()];
+        assert_eq!(entry.predecessors.len(), 0);
+        match entry.terminator {
+            Some(&Statement::If { .. }) => {}
+            ref other => unreachable!("{:?}", other),
+        }
+        let accept = entry.successors[0].target;
+        let reject = entry.successors[1].target;
+        assert_eq!(entry.successors[0].kind, EdgeKind::Accept);
+        assert_eq!(blocks[reject].successors[0].target, cfg.exit());
+        assert_eq!(blocks[reject].successors[0].kind, EdgeKind::Return);
+
+        // The `switch` is only reached through the `accept` branch.
+        let switch = blocks[accept].successors[0].target;
+        assert_eq!(cfg.immediate_dominator(switch), Some(accept));
+        assert!(cfg.dominates(cfg.entry(), switch));
+        assert!(!cfg.dominates(reject, switch));
+        let cases = &blocks[switch].successors;
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[2].kind, EdgeKind::Case(2));
+        let fall_through = blocks[cases[0].target].successors[0];
+        assert_eq!(fall_through.kind, EdgeKind::FallThrough);
+        assert_eq!(fall_through.target, cases[1].target);
+
+        // Every case leads to the last `return`.
+        let end = blocks[cases[1].target].successors[0].target;
+        assert!(cfg.post_dominates(end, cases[0].target));
+        assert!(cfg.post_dominates(end, switch));
+        assert!(!cfg.post_dominates(end, cfg.entry()));
+        assert!(cfg.post_dominates(cfg.exit(), cfg.entry()));
+        assert_eq!(cfg.immediate_dominator(end), Some(switch));
+        assert_eq!(cfg.immediate_post_dominator(cfg.entry()), Some(cfg.exit()));
+    }
+
+    #[test]
+    fn loops() {
+        let module = parse(
+            "
+            fn f(n: i32) -> i32 {
+                var sum = 0;
+                for (var i = 0; i < n; i = i + 1) {
+                    if (i == 2) {
+                        continue;
+                    }
+                    loop {
+                        sum = sum + i;
+                        if (sum > 10) {
+                            break;
+                        }
+                    }
+                }
+                return sum;
+                sum = 1;
+            }
+            ",
+        );
+        let (_, function) = module.functions.iter().next().unwrap();
+        let cfg = ControlFlowGraph::new(function);
+        let blocks = cfg.blocks();
+        let loops = cfg.loops();
+        assert_eq!(loops.len(), 2);
+
+        let (outer_handle, outer) = loops.iter().next().unwrap();
+        let (inner_handle, inner) = loops.iter().nth(1).unwrap();
+        assert_eq!(inner.parent, Some(outer_handle));
+        assert_eq!(inner.depth, 2);
+        assert_eq!(cfg.loop_depth(inner.header), 2);
+        assert_eq!(cfg.loop_depth(outer.continuing), 1);
+        assert_eq!(cfg.loop_depth(outer.merge), 0);
+        assert_eq!(blocks[inner.header].innermost_loop, Some(inner_handle));
+
+        assert!(cfg.dominates(outer.header, inner.header));
+        assert!(cfg.dominates(outer.header, outer.continuing));
+        assert!(cfg.dominates(outer.header, outer.merge));
+        assert!(cfg.post_dominates(outer.header, inner.header));
+        assert!(!cfg.post_dominates(inner.header, outer.header));
+        assert!(cfg.post_dominates(inner.merge, inner.header));
+
+        let edges = blocks
+            .iter()
+            .flat_map(|(_, block)| block.successors.iter().map(|edge| edge.kind))
+            .collect::<Vec<_>>();
+        let count = |kind| edges.iter().filter(|&&other| other == kind).count();
+        // The condition of the `for` loop breaks out of it.
+        assert_eq!(count(EdgeKind::Break), 2);
+        assert_eq!(count(EdgeKind::Back), 2);
+        assert_eq!(count(EdgeKind::Continue), 1);
+        let continues = blocks[outer.continuing]
+            .predecessors
+            .iter()
+            .filter(|&&pred| {
+                blocks[pred]
+                    .successors
+                    .iter()
+                    .any(|edge| edge.kind == EdgeKind::Continue)
+            })
+            .count();
+        assert_eq!(continues, 1);
+
+        // The store after the `return` is unreachable.
+        let (unreachable, _) = blocks.iter().last().unwrap();
+        assert!(!cfg.is_reachable(unreachable));
+        assert_eq!(cfg.immediate_dominator(unreachable), None);
+        assert!(!cfg.dominates(cfg.entry(), unreachable));
+        match blocks[unreachable].statements[..] {
+            [&Statement::Store { .. }] => {}
+            ref other => unreachable!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn missing_return() {
+        let mut module = parse(
+            "
+            fn f(x: i32) {
+                if (x > 0) {
+                    return;
+                }
+            }
+
+            fn g() {
+                loop {}
+            }
+            ",
+        );
+        let mut functions = module.functions.iter_mut();
+
+        // Remove the `return` the front end adds to the `else` branch.
+        let (_, f) = functions.next().unwrap();
+        for statement in f.body.iter_mut() {
+            if let Statement::If { ref mut reject, .. } = *statement {
+                *reject = crate::Block::new();
+            }
+        }
+        let cfg = ControlFlowGraph::new(f);
+        let exit = &cfg.blocks()[cfg.exit()];
+        let kinds = exit
+            .predecessors
+            .iter()
+            .map(|&pred| {
+                cfg.blocks()[pred]
+                    .successors
+                    .iter()
+                    .find(|edge| edge.target == cfg.exit())
+                    .unwrap()
+                    .kind
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, [EdgeKind::Return, EdgeKind::Next]);
+
+        let (_, g) = functions.next().unwrap();
+        let cfg = ControlFlowGraph::new(g);
+        let infinite = cfg.loops().iter().next().unwrap().1;
+        assert!(cfg.is_reachable(infinite.header));
+        assert_eq!(cfg.immediate_post_dominator(infinite.header), None);
+        // The `return` after the loop can't be reached.
+        assert!(!cfg.is_reachable(infinite.merge));
+        for &pred in cfg.blocks()[cfg.exit()].predecessors.iter() {
+            assert!(!cfg.is_reachable(pred));
+        }
+    }
+}
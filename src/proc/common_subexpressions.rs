@@ -0,0 +1,479 @@
+//! Merging of identical expressions within functions.
+//!
+//! Front ends translate each use of a value separately: SPIR-V and GLSL
+//! shaders in particular load the same uniform over and over, and build the
+//! same [`AccessIndex`] chains within a block. Back ends bake each of those
+//! into its own temporary. [`eliminate_common_subexpressions`] replaces the
+//! uses of such an expression with the identical one evaluated before it.
+//!
+//! [`AccessIndex`]: crate::Expression::AccessIndex
+
+use super::Mutator;
+use crate::arena::{Arena, Handle, HandleMap};
+use crate::{Block, Expression, FastHashMap, Function, GlobalVariable, Statement, StorageClass};
+
+/// Merge the identical expressions of every function and entry point of
+/// `module`.
+///
+/// An expression is replaced with an earlier identical one if it is in
+/// scope, that is if that one is emitted earlier in the same block or in a
+/// block enclosing it. Expressions like [`Constant`] or [`GlobalVariable`],
+/// which are in scope everywhere, are merged throughout the function.
+///
+/// The value of a [`Load`] or [`ImageLoad`] depends on the stores before it,
+/// so these are only merged if no statement between them may write to
+/// memory, and not at all across loop iterations. Loads from uniform buffers,
+/// push constants and read-only storage buffers are merged like other
+/// expressions. The results of statements, like [`CallResult`] and
+/// [`AtomicResult`], are never merged.
+///
+/// The merged expressions are removed from the function, along with the names
+/// given to them if the expressions replacing them aren't named already.
+///
+/// [`Constant`]: Expression::Constant
+/// [`GlobalVariable`]: Expression::GlobalVariable
+/// [`Load`]: Expression::Load
+/// [`ImageLoad`]: Expression::ImageLoad
+/// [`CallResult`]: Expression::CallResult
+/// [`AtomicResult`]: Expression::AtomicResult
+pub fn eliminate_common_subexpressions(module: &mut crate::Module) {
+    for (_, function) in module.functions.iter_mut() {
+        eliminate_in_function(function, &module.global_variables);
+    }
+    for ep in module.entry_points.iter_mut() {
+        eliminate_in_function(&mut ep.function, &module.global_variables);
+    }
+}
+
+fn eliminate_in_function(function: &mut Function, global_variables: &Arena<GlobalVariable>) {
+    let mut finder = DuplicateFinder {
+        expressions: &function.expressions,
+        global_variables,
+        replacements: HandleMap::new(function.expressions.len()),
+        available: FastHashMap::default(),
+        scope_keys: Vec::new(),
+        memory_version: 0,
+        memory_versions: 0,
+    };
+    finder.find_in_function(&function.body);
+    let replacements = finder.replacements;
+
+    let kept = HandleMap::from_kept(function.expressions.len(), |handle| {
+        !replacements.contains(handle)
+    });
+    retain_emitted(&mut function.body, &kept);
+
+    let mut maps = ExpressionMaps {
+        replacements: &replacements,
+        kept: &kept,
+    };
+    let mut named_expressions = FastHashMap::default();
+    for (mut handle, name) in std::mem::take(&mut function.named_expressions) {
+        let replaced = replacements.contains(handle);
+        maps.visit_expression(&mut handle);
+        if replaced {
+            named_expressions.entry(handle).or_insert(name);
+        } else {
+            named_expressions.insert(handle, name);
+        }
+    }
+    function.named_expressions = named_expressions;
+
+    function.expressions.retain_mut(|handle, expression| {
+        if !kept.contains(handle) {
+            return false;
+        }
+        expression.visit_handles_mut(&mut maps);
+        true
+    });
+    function.body.visit_handles_mut(&mut maps);
+}
+
+/// What an expression's value depends on, besides its operands.
+enum Dependency {
+    /// Nothing: identical expressions have the same value.
+    None,
+    /// The contents of memory, which statements writing to memory change.
+    Memory,
+    /// The statement producing it: the expression is never merged.
+    Statement,
+}
+
+fn dependency(
+    expression: &Expression,
+    expressions: &Arena<Expression>,
+    global_variables: &Arena<GlobalVariable>,
+) -> Dependency {
+    match *expression {
+        Expression::Load { pointer } => {
+            if is_read_only(pointer, expressions, global_variables) {
+                Dependency::None
+            } else {
+                Dependency::Memory
+            }
+        }
+        Expression::ImageLoad { .. } => Dependency::Memory,
+        Expression::CallResult(_)
+        | Expression::AtomicResult { .. }
+        | Expression::SubgroupBallotResult
+        | Expression::SubgroupOperationResult { .. }
+        | Expression::RayQueryProceedResult
+        | Expression::RayQueryGetCommittedIntersection { .. } => Dependency::Statement,
+        _ => Dependency::None,
+    }
+}
+
+/// Return `true` if `pointer` points into a global variable no statement can
+/// write to.
+fn is_read_only(
+    mut pointer: Handle<Expression>,
+    expressions: &Arena<Expression>,
+    global_variables: &Arena<GlobalVariable>,
+) -> bool {
+    loop {
+        match expressions[pointer] {
+            Expression::Access { base, .. } | Expression::AccessIndex { base, .. } => {
+                pointer = base;
+            }
+            Expression::GlobalVariable(var) => {
+                return match global_variables[var].class {
+                    StorageClass::Uniform | StorageClass::Handle | StorageClass::PushConstant => {
+                        true
+                    }
+                    StorageClass::Storage { access } => {
+                        !access.contains(crate::StorageAccess::STORE)
+                    }
+                    StorageClass::Function | StorageClass::Private | StorageClass::WorkGroup => {
+                        false
+                    }
+                };
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// The key of an expression in [`DuplicateFinder::available`]: the
+/// expression with its operands replaced, and the memory version it was
+/// evaluated at if it depends on memory.
+type Key = (Expression, Option<usize>);
+
+/// Finds the expressions of a function that are identical to earlier ones
+/// in scope.
+struct DuplicateFinder<'a> {
+    expressions: &'a Arena<Expression>,
+    global_variables: &'a Arena<GlobalVariable>,
+    /// The earlier expression replacing each merged one.
+    replacements: HandleMap<Expression>,
+    /// The expressions in scope, by key.
+    available: FastHashMap<Key, Handle<Expression>>,
+    /// The keys added to `available` within each enclosing block.
+    scope_keys: Vec<Vec<Key>>,
+    /// The version of memory at the current statement, changed by every
+    /// statement that may write to memory.
+    memory_version: usize,
+    /// The number of memory versions used so far.
+    memory_versions: usize,
+}
+
+impl Mutator for DuplicateFinder<'_> {
+    fn visit_expression(&mut self, handle: &mut Handle<Expression>) {
+        if let Some(replacement) = self.replacements.get(*handle) {
+            *handle = replacement;
+        }
+    }
+}
+
+impl DuplicateFinder<'_> {
+    fn find_in_function(&mut self, body: &Block) {
+        // Expressions that don't need to be emitted are in scope throughout
+        // the function.
+        self.scope_keys.push(Vec::new());
+        let expressions = self.expressions;
+        for (handle, expression) in expressions.iter() {
+            if expression.needs_pre_emit() {
+                self.merge(handle);
+            }
+        }
+        self.find_in_block(body);
+    }
+
+    /// Merge the expression `handle` with an identical one in scope, or make
+    /// it available to the following ones.
+    fn merge(&mut self, handle: Handle<Expression>) {
+        let expressions = self.expressions;
+        let version = match dependency(&expressions[handle], expressions, self.global_variables) {
+            Dependency::None => None,
+            Dependency::Memory => Some(self.memory_version),
+            Dependency::Statement => return,
+        };
+        let mut expression = expressions[handle].clone();
+        expression.visit_handles_mut(self);
+        let key = (expression, version);
+        match self.available.get(&key) {
+            Some(&replacement) => self.replacements.insert(handle, replacement),
+            None => {
+                self.available.insert(key.clone(), handle);
+                if let Some(keys) = self.scope_keys.last_mut() {
+                    keys.push(key);
+                }
+            }
+        }
+    }
+
+    fn find_in_nested_block(&mut self, block: &Block) {
+        self.scope_keys.push(Vec::new());
+        self.find_in_block(block);
+        self.end_scope();
+    }
+
+    /// Start a new version of memory, after a write to it.
+    fn write_memory(&mut self) {
+        self.memory_versions += 1;
+        self.memory_version = self.memory_versions;
+    }
+
+    fn end_scope(&mut self) {
+        if let Some(keys) = self.scope_keys.pop() {
+            for key in keys {
+                self.available.remove(&key);
+            }
+        }
+    }
+
+    fn find_in_block(&mut self, block: &Block) {
+        for statement in block.iter() {
+            match *statement {
+                Statement::Emit(ref range) => {
+                    for handle in range.clone() {
+                        self.merge(handle);
+                    }
+                }
+                Statement::Block(ref block) => self.find_in_nested_block(block),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    // Each branch starts with the memory before the `If`.
+                    let before = self.memory_version;
+                    self.find_in_nested_block(accept);
+                    let accept_writes = self.memory_version != before;
+                    self.memory_version = before;
+                    self.find_in_nested_block(reject);
+                    if accept_writes || self.memory_version != before {
+                        self.write_memory();
+                    }
+                }
+                Statement::Switch { ref cases, .. } => {
+                    let before = self.memory_version;
+                    let mut writes = false;
+                    let mut fall_through = false;
+                    for case in cases.iter() {
+                        if !fall_through {
+                            self.memory_version = before;
+                        }
+                        self.find_in_nested_block(&case.body);
+                        writes |= self.memory_version != before;
+                        fall_through = case.fall_through;
+                    }
+                    self.memory_version = before;
+                    if writes {
+                        self.write_memory();
+                    }
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    // Memory may have changed in a previous iteration.
+                    self.write_memory();
+                    // The continuing block is within the scope of the body,
+                    // but `Continue` statements may reach it from anywhere
+                    // in the body.
+                    self.scope_keys.push(Vec::new());
+                    self.find_in_block(body);
+                    self.write_memory();
+                    self.find_in_nested_block(continuing);
+                    self.end_scope();
+                }
+                Statement::Store { .. }
+                | Statement::ImageStore { .. }
+                | Statement::ImageAtomic { .. }
+                | Statement::Atomic { .. }
+                | Statement::Call { .. }
+                | Statement::Barrier(_)
+                | Statement::RayQuery { .. }
+                | Statement::MeshFunction(_) => self.write_memory(),
+                Statement::Break
+                | Statement::Continue
+                | Statement::Return { .. }
+                | Statement::Kill
+                | Statement::SubgroupBallot { .. }
+                | Statement::SubgroupElect { .. }
+                | Statement::SubgroupGather { .. }
+                | Statement::SubgroupCollectiveOperation { .. } => {}
+            }
+        }
+    }
+}
+
+/// Shorten the [`Emit`] statements of `block` and its nested blocks to the
+/// expressions `kept` maps, and drop the ones left empty.
+///
+/// [`Emit`]: Statement::Emit
+fn retain_emitted(block: &mut Block, kept: &HandleMap<Expression>) {
+    let mut index = 0;
+    while index < block.len() {
+        let keep = match block[index] {
+            Statement::Emit(ref mut range) => {
+                let mut emitted = range.clone().filter(|&handle| kept.contains(handle));
+                match emitted.next() {
+                    Some(first) => {
+                        let last = emitted.last().unwrap_or(first);
+                        *range = crate::arena::Range::new_from_bounds(first, last);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Statement::Block(ref mut block) => {
+                retain_emitted(block, kept);
+                true
+            }
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                retain_emitted(accept, kept);
+                retain_emitted(reject, kept);
+                true
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    retain_emitted(&mut case.body, kept);
+                }
+                true
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                retain_emitted(body, kept);
+                retain_emitted(continuing, kept);
+                true
+            }
+            _ => true,
+        };
+        if keep {
+            index += 1;
+        } else {
+            block.cull(index..index + 1);
+        }
+    }
+}
+
+/// Replaces the merged expressions, and adjusts the handles of the kept ones.
+struct ExpressionMaps<'a> {
+    replacements: &'a HandleMap<Expression>,
+    kept: &'a HandleMap<Expression>,
+}
+
+impl Mutator for ExpressionMaps<'_> {
+    fn visit_expression(&mut self, handle: &mut Handle<Expression>) {
+        if let Some(replacement) = self.replacements.get(*handle) {
+            *handle = replacement;
+        }
+        self.kept.adjust(handle);
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::eliminate_common_subexpressions;
+    use crate::Expression;
+
+    fn eliminate(source: &str) -> crate::Function {
+        let mut module = crate::proc::run_pass(source, eliminate_common_subexpressions);
+        module.entry_points.pop().unwrap().function
+    }
+
+    fn count(function: &crate::Function, predicate: fn(&Expression) -> bool) -> usize {
+        function
+            .expressions
+            .iter()
+            .filter(|&(_, expression)| predicate(expression))
+            .count()
+    }
+
+    fn is_load(expression: &Expression) -> bool {
+        matches!(*expression, Expression::Load { .. })
+    }
+
+    fn is_multiplication(expression: &Expression) -> bool {
+        matches!(
+            *expression,
+            Expression::Binary {
+                op: crate::BinaryOperator::Multiply,
+                ..
+            }
+        )
+    }
+
+    #[test]
+    fn uniform_loads() {
+        let function = eliminate(
+            "
+            struct Params {
+                scale: vec4<f32>;
+            };
+
+            @group(0) @binding(0)
+            var<uniform> params: Params;
+
+            var<private> counter: f32;
+
+            @stage(fragment)
+            fn main() -> @location(0) vec4<f32> {
+                let a = params.scale.x * params.scale.y;
+                counter = a;
+                let b = params.scale.x * params.scale.y;
+                return vec4<f32>(a, b, counter, counter);
+            }
+            ",
+        );
+        // One load of each uniform component, and one of `counter`.
+        assert_eq!(count(&function, is_load), 3);
+        assert_eq!(count(&function, is_multiplication), 1);
+    }
+
+    #[test]
+    fn stores_and_scopes() {
+        let function = eliminate(
+            "
+            var<private> value: f32;
+
+            @stage(fragment)
+            fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+                let a = value;
+                value = x;
+                let b = value;
+                var c = 0.0;
+                if (x > 0.0) {
+                    value = 2.0;
+                    c = value * x * 3.0;
+                } else {
+                    c = value * x * 3.0;
+                }
+                return vec4<f32>(a, b, c, value);
+            }
+            ",
+        );
+        // The loads of `value` after a store aren't merged with the ones
+        // before it. The load in the `else` branch is merged with `b`, but
+        // the multiplications in each branch aren't merged with each other.
+        assert_eq!(count(&function, is_load), 5);
+        assert_eq!(count(&function, is_multiplication), 4);
+    }
+}
@@ -0,0 +1,527 @@
+//! Removal of unreferenced module-scope items.
+//!
+//! Front ends translate every declaration of the source, and a module that
+//! is only used for some of its entry points still carries the types,
+//! constants, global variables and functions of the others. [`compact`]
+//! drops all of those that the entry points can't reach, so back ends don't
+//! emit dead declarations.
+
+use super::{Mutator, Visitor};
+use crate::{
+    arena::{Handle, HandleMap},
+    span::WithSpan,
+    valid::{ModuleInfo, ValidationError, Validator},
+};
+use bit_set::BitSet;
+
+/// Remove the types, constants, global variables and functions of `module`
+/// that aren't reachable from any of its entry points.
+///
+/// Everything referenced by the entry point functions, their workgroup size
+/// overrides and mesh output declarations is kept, along with everything
+/// those items reference in turn. Within the kept functions, expressions
+/// that are neither used by a statement, named, nor the operand of another
+/// kept expression are removed as well, since front ends may add them for
+/// every global in scope.
+///
+/// The kept items stay in the same order and keep their spans, and every
+/// handle to them is adjusted throughout the module.
+///
+/// Entry points are the roots of this pass, so removing the ones that aren't
+/// needed beforehand reduces the module to what the remaining ones use.
+/// Unreferenced overrides are removed as well, which means they no longer
+/// accept pipeline constants.
+pub fn compact(module: &mut crate::Module) {
+    let mut tracer = ModuleTracer::new(module);
+    tracer.trace_entry_points();
+    let maps = tracer.into_maps();
+    maps.adjust_module(module);
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum ExtractError {
+    #[error("Entry point '{name}' of the {stage:?} stage doesn't exist")]
+    EntryPointNotFound {
+        stage: crate::ShaderStage,
+        name: String,
+    },
+    #[error(transparent)]
+    Validation(#[from] WithSpan<ValidationError>),
+}
+
+/// Return a new module holding only the entry point `name` of `stage` in
+/// `module`, and the types, constants, global variables and functions it
+/// uses directly or transitively.
+///
+/// `info` is the result of validating `module`: the global variables the
+/// entry point uses, according to its [`FunctionInfo`], are kept along with
+/// everything its functions refer to. Only the kept items are copied from
+/// `module`, in the same order and with the same spans.
+///
+/// The new module is self-contained, so it can be cached, hashed and
+/// translated independently of the other entry points. Use
+/// [`extract_entry_point_with_info`] to validate it as well.
+///
+/// [`FunctionInfo`]: crate::valid::FunctionInfo
+pub fn extract_entry_point(
+    module: &crate::Module,
+    info: &ModuleInfo,
+    stage: crate::ShaderStage,
+    name: &str,
+) -> Result<crate::Module, ExtractError> {
+    let (index, ep) = module
+        .entry_points
+        .iter()
+        .enumerate()
+        .find(|&(_, ep)| ep.stage == stage && ep.name == name)
+        .ok_or_else(|| ExtractError::EntryPointNotFound {
+            stage,
+            name: name.to_string(),
+        })?;
+
+    let mut tracer = ModuleTracer::new(module);
+    let ep_info = info.get_entry_point(index);
+    for (handle, _) in module.global_variables.iter() {
+        if !ep_info[handle].is_empty() {
+            tracer.visit_global_variable(handle);
+        }
+    }
+    tracer.trace_entry_point(ep);
+    tracer.trace_functions();
+    let maps = tracer.into_maps();
+    Ok(maps.extract_module(module, ep))
+}
+
+/// Like [`extract_entry_point`], but also validate the new module with
+/// `validator`, and return its [`ModuleInfo`] with it.
+pub fn extract_entry_point_with_info(
+    module: &crate::Module,
+    info: &ModuleInfo,
+    stage: crate::ShaderStage,
+    name: &str,
+    validator: &mut Validator,
+) -> Result<(crate::Module, ModuleInfo), ExtractError> {
+    let extracted = extract_entry_point(module, info, stage, name)?;
+    let extracted_info = validator.validate(&extracted)?;
+    Ok((extracted, extracted_info))
+}
+
+/// Sets of the module-scope items reachable from the entry points, indexed
+/// by handle index.
+struct ModuleTracer<'a> {
+    module: &'a crate::Module,
+    types: BitSet,
+    constants: BitSet,
+    global_variables: BitSet,
+    functions: BitSet,
+}
+
+impl<'a> ModuleTracer<'a> {
+    fn new(module: &'a crate::Module) -> Self {
+        ModuleTracer {
+            module,
+            types: BitSet::with_capacity(module.types.len()),
+            constants: BitSet::with_capacity(module.constants.len()),
+            global_variables: BitSet::with_capacity(module.global_variables.len()),
+            functions: BitSet::with_capacity(module.functions.len()),
+        }
+    }
+
+    fn trace_entry_points(&mut self) {
+        let module = self.module;
+        for ep in module.entry_points.iter() {
+            self.trace_entry_point(ep);
+        }
+        self.trace_functions();
+    }
+
+    fn trace_entry_point(&mut self, ep: &crate::EntryPoint) {
+        if let Some(ref overrides) = ep.workgroup_size_overrides {
+            for &constant in overrides.iter().flatten() {
+                self.visit_constant(constant);
+            }
+        }
+        if let Some(ref mesh_info) = ep.mesh_info {
+            self.visit_type(mesh_info.vertex_output_type);
+            self.visit_type(mesh_info.primitive_output_type);
+        }
+        self.trace_function(&ep.function);
+    }
+
+    /// Trace the functions called by the ones traced so far.
+    fn trace_functions(&mut self) {
+        let module = self.module;
+        // Functions are only called by the ones appearing after them in the
+        // arena, so a single pass in reverse order reaches all the callees.
+        for (handle, function) in module.functions.iter().rev() {
+            if self.functions.contains(handle.index()) {
+                self.trace_function(function);
+            }
+        }
+    }
+
+    fn trace_function(&mut self, function: &crate::Function) {
+        for argument in function.arguments.iter() {
+            self.visit_type(argument.ty);
+        }
+        if let Some(ref result) = function.result {
+            self.visit_type(result.ty);
+        }
+        for (_, local) in function.local_variables.iter() {
+            self.visit_type(local.ty);
+            if let Some(init) = local.init {
+                self.visit_constant(init);
+            }
+        }
+        let used_expressions = trace_expressions(function);
+        for (handle, expression) in function.expressions.iter() {
+            if used_expressions.contains(handle.index()) {
+                expression.visit_handles(self);
+            }
+        }
+        function.body.visit_handles(self);
+    }
+
+    fn into_maps(self) -> ModuleMaps {
+        let types = &self.types;
+        let constants = &self.constants;
+        let global_variables = &self.global_variables;
+        let functions = &self.functions;
+        ModuleMaps {
+            types: HandleMap::from_kept(self.module.types.len(), |h| types.contains(h.index())),
+            constants: HandleMap::from_kept(self.module.constants.len(), |h| {
+                constants.contains(h.index())
+            }),
+            global_variables: HandleMap::from_kept(self.module.global_variables.len(), |h| {
+                global_variables.contains(h.index())
+            }),
+            functions: HandleMap::from_kept(self.module.functions.len(), |h| {
+                functions.contains(h.index())
+            }),
+        }
+    }
+}
+
+impl Visitor for ModuleTracer<'_> {
+    fn visit_type(&mut self, handle: Handle<crate::Type>) {
+        if self.types.insert(handle.index()) {
+            let module = self.module;
+            module.types[handle].inner.visit_handles(self);
+        }
+    }
+
+    fn visit_constant(&mut self, handle: Handle<crate::Constant>) {
+        if self.constants.insert(handle.index()) {
+            let module = self.module;
+            module.constants[handle].inner.visit_handles(self);
+        }
+    }
+
+    fn visit_global_variable(&mut self, handle: Handle<crate::GlobalVariable>) {
+        if self.global_variables.insert(handle.index()) {
+            let var = &self.module.global_variables[handle];
+            self.visit_type(var.ty);
+            if let Some(init) = var.init {
+                self.visit_constant(init);
+            }
+        }
+    }
+
+    fn visit_function(&mut self, handle: Handle<crate::Function>) {
+        self.functions.insert(handle.index());
+    }
+}
+
+/// The expressions waiting to be traced by [`trace_expressions`].
+struct PendingExpressions(Vec<Handle<crate::Expression>>);
+
+impl Visitor for PendingExpressions {
+    fn visit_expression(&mut self, handle: Handle<crate::Expression>) {
+        self.0.push(handle);
+    }
+}
+
+/// Return the set of expressions of `function` that are used by its
+/// statements, are named, or are operands of other such expressions.
+fn trace_expressions(function: &crate::Function) -> BitSet {
+    let mut used = BitSet::with_capacity(function.expressions.len());
+    let mut pending = PendingExpressions(function.named_expressions.keys().cloned().collect());
+    function.body.visit_handles(&mut pending);
+    while let Some(handle) = pending.0.pop() {
+        if used.insert(handle.index()) {
+            function.expressions[handle].visit_handles(&mut pending);
+        }
+    }
+    used
+}
+
+struct ModuleMaps {
+    types: HandleMap<crate::Type>,
+    constants: HandleMap<crate::Constant>,
+    global_variables: HandleMap<crate::GlobalVariable>,
+    functions: HandleMap<crate::Function>,
+}
+
+/// The maps adjusting the handles within one function.
+struct FunctionMaps<'a> {
+    module: &'a ModuleMaps,
+    expressions: &'a HandleMap<crate::Expression>,
+}
+
+impl Mutator for FunctionMaps<'_> {
+    fn visit_type(&mut self, handle: &mut Handle<crate::Type>) {
+        self.module.types.adjust(handle);
+    }
+
+    fn visit_constant(&mut self, handle: &mut Handle<crate::Constant>) {
+        self.module.constants.adjust(handle);
+    }
+
+    fn visit_global_variable(&mut self, handle: &mut Handle<crate::GlobalVariable>) {
+        self.module.global_variables.adjust(handle);
+    }
+
+    fn visit_function(&mut self, handle: &mut Handle<crate::Function>) {
+        self.module.functions.adjust(handle);
+    }
+
+    fn visit_expression(&mut self, handle: &mut Handle<crate::Expression>) {
+        self.expressions.adjust(handle);
+    }
+}
+
+impl ModuleMaps {
+    fn adjust_module(&self, module: &mut crate::Module) {
+        // Items at module scope don't refer to expressions.
+        let mut maps = FunctionMaps {
+            module: self,
+            expressions: &HandleMap::new(0),
+        };
+
+        // The kept types are distinct, and remain so once adjusted.
+        module.types.retain_mut(|handle, ty| {
+            if !self.types.contains(handle) {
+                return false;
+            }
+            ty.inner.visit_handles_mut(&mut maps);
+            true
+        });
+
+        module.constants.retain_mut(|handle, constant| {
+            if !self.constants.contains(handle) {
+                return false;
+            }
+            constant.inner.visit_handles_mut(&mut maps);
+            true
+        });
+
+        module.global_variables.retain_mut(|handle, var| {
+            if !self.global_variables.contains(handle) {
+                return false;
+            }
+            self.types.adjust(&mut var.ty);
+            self.constants.adjust_option(&mut var.init);
+            true
+        });
+
+        module.functions.retain_mut(|handle, function| {
+            if !self.functions.contains(handle) {
+                return false;
+            }
+            self.adjust_function(function);
+            true
+        });
+
+        for ep in module.entry_points.iter_mut() {
+            self.adjust_entry_point(ep);
+        }
+    }
+
+    /// Return a module holding the kept items of `module`, and `ep`.
+    fn extract_module(&self, module: &crate::Module, ep: &crate::EntryPoint) -> crate::Module {
+        let mut maps = FunctionMaps {
+            module: self,
+            expressions: &HandleMap::new(0),
+        };
+        let mut extracted = crate::Module::default();
+
+        for (handle, ty) in module.types.iter() {
+            if self.types.contains(handle) {
+                let mut ty = ty.clone();
+                ty.inner.visit_handles_mut(&mut maps);
+                extracted.types.insert(ty, module.types.get_span(handle));
+            }
+        }
+
+        for (handle, constant) in module.constants.iter() {
+            if self.constants.contains(handle) {
+                let mut constant = constant.clone();
+                constant.inner.visit_handles_mut(&mut maps);
+                extracted
+                    .constants
+                    .append(constant, module.constants.get_span(handle));
+            }
+        }
+
+        for (handle, var) in module.global_variables.iter() {
+            if self.global_variables.contains(handle) {
+                let mut var = var.clone();
+                self.types.adjust(&mut var.ty);
+                self.constants.adjust_option(&mut var.init);
+                extracted
+                    .global_variables
+                    .append(var, module.global_variables.get_span(handle));
+            }
+        }
+
+        for (handle, function) in module.functions.iter() {
+            if self.functions.contains(handle) {
+                let mut function = function.clone();
+                self.adjust_function(&mut function);
+                extracted
+                    .functions
+                    .append(function, module.functions.get_span(handle));
+            }
+        }
+
+        let mut ep = ep.clone();
+        self.adjust_entry_point(&mut ep);
+        extracted.entry_points.push(ep);
+        extracted
+    }
+
+    fn adjust_entry_point(&self, ep: &mut crate::EntryPoint) {
+        if let Some(ref mut overrides) = ep.workgroup_size_overrides {
+            for constant in overrides.iter_mut() {
+                self.constants.adjust_option(constant);
+            }
+        }
+        if let Some(ref mut mesh_info) = ep.mesh_info {
+            self.types.adjust(&mut mesh_info.vertex_output_type);
+            self.types.adjust(&mut mesh_info.primitive_output_type);
+        }
+        self.adjust_function(&mut ep.function);
+    }
+
+    fn adjust_function(&self, function: &mut crate::Function) {
+        for argument in function.arguments.iter_mut() {
+            self.types.adjust(&mut argument.ty);
+        }
+        if let Some(ref mut result) = function.result {
+            self.types.adjust(&mut result.ty);
+        }
+        for (_, local) in function.local_variables.iter_mut() {
+            self.types.adjust(&mut local.ty);
+            self.constants.adjust_option(&mut local.init);
+        }
+
+        let used_expressions = trace_expressions(function);
+        let expressions = HandleMap::from_kept(function.expressions.len(), |handle| {
+            used_expressions.contains(handle.index())
+        });
+        let mut maps = FunctionMaps {
+            module: self,
+            expressions: &expressions,
+        };
+        function.expressions.retain_mut(|handle, expression| {
+            if !expressions.contains(handle) {
+                return false;
+            }
+            expression.visit_handles_mut(&mut maps);
+            true
+        });
+        function.named_expressions = std::mem::take(&mut function.named_expressions)
+            .into_iter()
+            .map(|(mut handle, name)| {
+                expressions.adjust(&mut handle);
+                (handle, name)
+            })
+            .collect();
+        function.body.visit_handles_mut(&mut maps);
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{extract_entry_point, extract_entry_point_with_info, ExtractError};
+    use crate::ShaderStage;
+
+    const SOURCE: &str = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>;
+            @location(0) color: vec4<f32>;
+        };
+
+        struct Transform {
+            matrix: mat4x4<f32>;
+        };
+
+        struct Tint {
+            color: vec4<f32>;
+        };
+
+        @group(0) @binding(0)
+        var<uniform> transform: Transform;
+        @group(0) @binding(1)
+        var<uniform> tint: Tint;
+
+        fn apply_tint(color: vec4<f32>) -> vec4<f32> {
+            return color * tint.color;
+        }
+
+        @stage(vertex)
+        fn vs_main(@location(0) position: vec4<f32>) -> VertexOutput {
+            return VertexOutput(transform.matrix * position, vec4<f32>(1.0));
+        }
+
+        @stage(fragment)
+        fn fs_main(@location(0) color: vec4<f32>) -> @location(0) vec4<f32> {
+            return apply_tint(color);
+        }
+    ";
+
+    fn global_names(module: &crate::Module) -> Vec<&str> {
+        module
+            .global_variables
+            .iter()
+            .map(|(_, var)| var.name.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn extract() {
+        let module = crate::front::wgsl::parse_str(SOURCE).unwrap();
+        let mut validator = crate::valid::Validator::new(
+            crate::valid::ValidationFlags::all(),
+            crate::valid::Capabilities::empty(),
+        );
+        let info = validator.validate(&module).unwrap();
+
+        let vertex = extract_entry_point(&module, &info, ShaderStage::Vertex, "vs_main").unwrap();
+        validator.validate(&vertex).unwrap();
+        assert_eq!(vertex.entry_points.len(), 1);
+        assert_eq!(global_names(&vertex), ["transform"]);
+        assert!(vertex.functions.is_empty());
+
+        let (fragment, fragment_info) = extract_entry_point_with_info(
+            &module,
+            &info,
+            ShaderStage::Fragment,
+            "fs_main",
+            &mut validator,
+        )
+        .unwrap();
+        assert_eq!(global_names(&fragment), ["tint"]);
+        assert_eq!(fragment.functions.len(), 1);
+        let tint = fragment.global_variables.iter().next().unwrap().0;
+        assert!(!fragment_info.get_entry_point(0)[tint].is_empty());
+        assert!(fragment
+            .types
+            .iter()
+            .all(|(_, ty)| ty.name.as_deref() != Some("VertexOutput")));
+
+        let error =
+            extract_entry_point(&module, &info, ShaderStage::Fragment, "vs_main").unwrap_err();
+        assert!(matches!(error, ExtractError::EntryPointNotFound { .. }));
+    }
+}
@@ -0,0 +1,1664 @@
+//! Evaluation of expressions whose operands are all constants.
+//!
+//! Front ends emit arithmetic on constants straight into function expression
+//! arenas, and back ends print it verbatim. The [`ConstantEvaluator`] turns
+//! such an expression into a new [`Constant`], and [`fold_constants`] applies
+//! it to every function of a module.
+//!
+//! Evaluation follows the runtime semantics of WGSL: integer arithmetic wraps
+//! at the width of its type, integer division by zero returns the dividend
+//! and the remainder of a division by zero is zero. Floating-point results
+//! are rounded to the width of their type, and expressions whose result isn't
+//! finite are left alone.
+
+use crate::{
+    arena::{Arena, Handle, UniqueArena},
+    BinaryOperator, Bytes, Constant, ConstantInner, Expression, MathFunction, ScalarKind,
+    ScalarValue, Span, Type, TypeInner, UnaryOperator,
+};
+use bit_set::BitSet;
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum ConstantEvaluatorError {
+    #[error("Constants cannot access function arguments")]
+    FunctionArg,
+    #[error("Constants cannot access global variables")]
+    GlobalVariable,
+    #[error("Constants cannot access local variables")]
+    LocalVariable,
+    #[error("The value of an override isn't known until pipeline creation")]
+    Override,
+    #[error("Cannot get the array length of a non array type")]
+    InvalidArrayLengthArg,
+    #[error("Constants cannot get the array length of a dynamically sized array")]
+    ArrayLengthDynamic,
+    #[error("Constants cannot call functions")]
+    Call,
+    #[error("Constants don't support atomic functions")]
+    Atomic,
+    #[error("Constants don't support subgroup operations")]
+    Subgroup,
+    #[error("Constants don't support ray queries")]
+    RayQuery,
+    #[error("Constants don't support relational functions")]
+    Relational,
+    #[error("Constants don't support derivative functions")]
+    Derivative,
+    #[error("Constants don't support select expressions")]
+    Select,
+    #[error("Constants don't support load expressions")]
+    Load,
+    #[error("Constants don't support image expressions")]
+    ImageExpression,
+    #[error("Cannot access the type")]
+    InvalidAccessBase,
+    #[error("Cannot access at the index")]
+    InvalidAccessIndex,
+    #[error("Cannot access with index of type")]
+    InvalidAccessIndexTy,
+    #[error("Cannot cast type")]
+    InvalidCastArg,
+    #[error("Cannot apply the unary op to the argument")]
+    InvalidUnaryOpArg,
+    #[error("Cannot apply the binary op to the arguments")]
+    InvalidBinaryOpArgs,
+    #[error("Cannot apply math function to type")]
+    InvalidMathArg,
+    #[error("Splat is defined only on scalar values")]
+    SplatScalarOnly,
+    #[error("Can only swizzle vector constants")]
+    SwizzleVectorOnly,
+    #[error("Swizzle component {0:?} is outside of the vector")]
+    SwizzleOutOfBounds(crate::SwizzleComponent),
+    #[error("The result isn't a finite number")]
+    NotFinite,
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
+}
+
+/// Evaluates expressions of `expressions` into constants of `constants`.
+///
+/// New types and constants are added to `types` and `constants` as needed.
+#[derive(Debug)]
+pub struct ConstantEvaluator<'a> {
+    pub types: &'a mut UniqueArena<Type>,
+    pub constants: &'a mut Arena<Constant>,
+    pub expressions: &'a Arena<Expression>,
+}
+
+type Scalar = (ScalarValue, Bytes);
+
+impl<'a> ConstantEvaluator<'a> {
+    /// Return the constant `expr` evaluates to.
+    ///
+    /// The operands of `expr` are evaluated as well, so this fails if any of
+    /// them depends on something other than constants, or on an override.
+    pub fn evaluate(
+        &mut self,
+        expr: Handle<Expression>,
+    ) -> Result<Handle<Constant>, ConstantEvaluatorError> {
+        let span = self.expressions.get_span(expr);
+        match self.expressions[expr] {
+            Expression::Constant(constant) => {
+                self.check_not_override(constant)?;
+                Ok(constant)
+            }
+            Expression::AccessIndex { base, index } => self.access(base, index as usize),
+            Expression::Access { base, index } => {
+                let index = self.evaluate(index)?;
+                let index = self.constant_index(index)?;
+                self.access(base, index)
+            }
+            Expression::Splat { size, value } => {
+                let value = self.evaluate(value)?;
+                let ty = match self.constants[value].inner {
+                    ConstantInner::Scalar { ref value, width } => {
+                        let kind = value.scalar_kind();
+                        self.types.insert(
+                            Type {
+                                name: None,
+                                inner: TypeInner::Vector { size, kind, width },
+                            },
+                            span,
+                        )
+                    }
+                    ConstantInner::Composite { .. } => {
+                        return Err(ConstantEvaluatorError::SplatScalarOnly);
+                    }
+                };
+                let inner = ConstantInner::Composite {
+                    ty,
+                    components: vec![value; size as usize],
+                };
+                Ok(self.register_constant(inner, span))
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.evaluate(vector)?;
+                let (ty, components) = match self.constants[vector].inner {
+                    ConstantInner::Composite { ty, ref components } => match self.types[ty].inner {
+                        TypeInner::Vector { kind, width, .. } => {
+                            let components = pattern[..size as usize]
+                                .iter()
+                                .map(|&sc| {
+                                    components
+                                        .get(sc as usize)
+                                        .cloned()
+                                        .ok_or(ConstantEvaluatorError::SwizzleOutOfBounds(sc))
+                                })
+                                .collect::<Result<_, _>>()?;
+                            let ty = self.types.insert(
+                                Type {
+                                    name: None,
+                                    inner: TypeInner::Vector { size, kind, width },
+                                },
+                                span,
+                            );
+                            (ty, components)
+                        }
+                        _ => return Err(ConstantEvaluatorError::SwizzleVectorOnly),
+                    },
+                    ConstantInner::Scalar { .. } => {
+                        return Err(ConstantEvaluatorError::SwizzleVectorOnly)
+                    }
+                };
+                let inner = ConstantInner::Composite { ty, components };
+                Ok(self.register_constant(inner, span))
+            }
+            Expression::Compose { ty, ref components } => {
+                let mut flattened = Vec::with_capacity(components.len());
+                for &component in components.iter() {
+                    let component = self.evaluate(component)?;
+                    // Vectors may be composed from smaller vectors.
+                    let is_vector = match self.types[ty].inner {
+                        TypeInner::Vector { .. } => true,
+                        _ => false,
+                    };
+                    match self.constants[component].inner {
+                        ConstantInner::Composite { ref components, .. } if is_vector => {
+                            flattened.extend_from_slice(components)
+                        }
+                        _ => flattened.push(component),
+                    }
+                }
+                let inner = ConstantInner::Composite {
+                    ty,
+                    components: flattened,
+                };
+                Ok(self.register_constant(inner, span))
+            }
+            Expression::Unary { op, expr } => {
+                let constant = self.evaluate(expr)?;
+                self.componentwise(
+                    &[constant],
+                    span,
+                    ConstantEvaluatorError::InvalidUnaryOpArg,
+                    &mut |args| unary_op(op, args[0]),
+                )
+            }
+            Expression::Binary { op, left, right } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                if op == BinaryOperator::Multiply
+                    && (self.is_matrix(left) && !self.is_scalar(right)
+                        || self.is_matrix(right) && !self.is_scalar(left))
+                {
+                    return Err(ConstantEvaluatorError::NotImplemented(
+                        "matrix multiplication".to_string(),
+                    ));
+                }
+                self.componentwise(
+                    &[left, right],
+                    span,
+                    ConstantEvaluatorError::InvalidBinaryOpArgs,
+                    &mut |args| binary_op(op, args[0], args[1]),
+                )
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let mut args = vec![self.evaluate(arg)?];
+                for &arg in [arg1, arg2, arg3].iter().flatten() {
+                    args.push(self.evaluate(arg)?);
+                }
+                match fun {
+                    MathFunction::Dot => self.dot(&args, span),
+                    _ => self.componentwise(
+                        &args,
+                        span,
+                        ConstantEvaluatorError::InvalidMathArg,
+                        &mut |args| math_function(fun, args),
+                    ),
+                }
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => {
+                let constant = self.evaluate(expr)?;
+                self.componentwise(
+                    &[constant],
+                    span,
+                    ConstantEvaluatorError::InvalidCastArg,
+                    &mut |args| match convert {
+                        Some(width) => convert_scalar(args[0], kind, width),
+                        None => bitcast_scalar(args[0], kind),
+                    },
+                )
+            }
+            Expression::ArrayLength(expr) => {
+                let array = self.evaluate(expr)?;
+                match self.constants[array].inner {
+                    ConstantInner::Scalar { .. } => {
+                        Err(ConstantEvaluatorError::InvalidArrayLengthArg)
+                    }
+                    ConstantInner::Composite { ty, .. } => match self.types[ty].inner {
+                        TypeInner::Array { size, .. } => match size {
+                            crate::ArraySize::Constant(constant) => Ok(constant),
+                            crate::ArraySize::Dynamic => {
+                                Err(ConstantEvaluatorError::ArrayLengthDynamic)
+                            }
+                        },
+                        _ => Err(ConstantEvaluatorError::InvalidArrayLengthArg),
+                    },
+                }
+            }
+            // a constant is uniform, so the marker has no effect
+            Expression::NonUniform(value) => self.evaluate(value),
+
+            Expression::Load { .. } => Err(ConstantEvaluatorError::Load),
+            Expression::Select { .. } => Err(ConstantEvaluatorError::Select),
+            Expression::LocalVariable(_) => Err(ConstantEvaluatorError::LocalVariable),
+            Expression::Derivative { .. } => Err(ConstantEvaluatorError::Derivative),
+            Expression::Relational { .. } => Err(ConstantEvaluatorError::Relational),
+            Expression::CallResult { .. } => Err(ConstantEvaluatorError::Call),
+            Expression::AtomicResult { .. } => Err(ConstantEvaluatorError::Atomic),
+            Expression::SubgroupBallotResult | Expression::SubgroupOperationResult { .. } => {
+                Err(ConstantEvaluatorError::Subgroup)
+            }
+            Expression::RayQueryProceedResult
+            | Expression::RayQueryGetCommittedIntersection { .. } => {
+                Err(ConstantEvaluatorError::RayQuery)
+            }
+            Expression::FunctionArgument(_) => Err(ConstantEvaluatorError::FunctionArg),
+            Expression::GlobalVariable(_) => Err(ConstantEvaluatorError::GlobalVariable),
+            Expression::ImageSample { .. }
+            | Expression::ImageLoad { .. }
+            | Expression::ImageQuery { .. } => Err(ConstantEvaluatorError::ImageExpression),
+        }
+    }
+
+    fn check_not_override(&self, constant: Handle<Constant>) -> Result<(), ConstantEvaluatorError> {
+        let constant = &self.constants[constant];
+        if constant.specialization.is_some() {
+            return Err(ConstantEvaluatorError::Override);
+        }
+        if let ConstantInner::Composite { ref components, .. } = constant.inner {
+            for &component in components.iter() {
+                self.check_not_override(component)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn access(
+        &mut self,
+        base: Handle<Expression>,
+        index: usize,
+    ) -> Result<Handle<Constant>, ConstantEvaluatorError> {
+        let base = self.evaluate(base)?;
+        match self.constants[base].inner {
+            ConstantInner::Scalar { .. } => Err(ConstantEvaluatorError::InvalidAccessBase),
+            ConstantInner::Composite { ty, ref components } => {
+                match self.types[ty].inner {
+                    TypeInner::Vector { .. }
+                    | TypeInner::Matrix { .. }
+                    | TypeInner::Array { .. }
+                    | TypeInner::Struct { .. } => (),
+                    _ => return Err(ConstantEvaluatorError::InvalidAccessBase),
+                }
+                components
+                    .get(index)
+                    .copied()
+                    .ok_or(ConstantEvaluatorError::InvalidAccessIndex)
+            }
+        }
+    }
+
+    fn constant_index(&self, constant: Handle<Constant>) -> Result<usize, ConstantEvaluatorError> {
+        match self.constants[constant].inner {
+            ConstantInner::Scalar {
+                value: ScalarValue::Uint(index),
+                ..
+            } => Ok(index as usize),
+            ConstantInner::Scalar {
+                value: ScalarValue::Sint(index),
+                ..
+            } if index >= 0 => Ok(index as usize),
+            _ => Err(ConstantEvaluatorError::InvalidAccessIndexTy),
+        }
+    }
+
+    fn is_scalar(&self, constant: Handle<Constant>) -> bool {
+        match self.constants[constant].inner {
+            ConstantInner::Scalar { .. } => true,
+            ConstantInner::Composite { .. } => false,
+        }
+    }
+
+    fn is_matrix(&self, constant: Handle<Constant>) -> bool {
+        match self.constants[constant].inner {
+            ConstantInner::Composite { ty, .. } => match self.types[ty].inner {
+                TypeInner::Matrix { .. } => true,
+                _ => false,
+            },
+            ConstantInner::Scalar { .. } => false,
+        }
+    }
+
+    /// Apply `fun` to the scalars of `args`, component by component if they
+    /// are vectors or matrices. Scalar arguments are used for every
+    /// component of the others. Return `error` if the arguments don't have
+    /// matching shapes.
+    fn componentwise<F>(
+        &mut self,
+        args: &[Handle<Constant>],
+        span: Span,
+        error: ConstantEvaluatorError,
+        fun: &mut F,
+    ) -> Result<Handle<Constant>, ConstantEvaluatorError>
+    where
+        F: FnMut(&[Scalar]) -> Result<Scalar, ConstantEvaluatorError>,
+    {
+        let mut shape = None;
+        for &arg in args.iter() {
+            if let ConstantInner::Composite { ty, ref components } = self.constants[arg].inner {
+                match self.types[ty].inner {
+                    TypeInner::Vector { .. } | TypeInner::Matrix { .. } => {}
+                    _ => return Err(error),
+                }
+                match shape {
+                    Some((_, len)) if len != components.len() => return Err(error),
+                    _ => shape = Some((ty, components.len())),
+                }
+            }
+        }
+
+        let (ty, len) = match shape {
+            Some(shape) => shape,
+            None => {
+                let scalars = args
+                    .iter()
+                    .map(|&arg| match self.constants[arg].inner {
+                        ConstantInner::Scalar { value, width } => (value, width),
+                        ConstantInner::Composite { .. } => unreachable!(),
+                    })
+                    .collect::<Vec<_>>();
+                let (value, width) = fun(&scalars)?;
+                let inner = ConstantInner::Scalar { value, width };
+                return Ok(self.register_constant(inner, span));
+            }
+        };
+
+        let mut components = Vec::with_capacity(len);
+        for index in 0..len {
+            let component_args = args
+                .iter()
+                .map(|&arg| match self.constants[arg].inner {
+                    ConstantInner::Composite { ref components, .. } => components[index],
+                    ConstantInner::Scalar { .. } => arg,
+                })
+                .collect::<Vec<_>>();
+            components.push(self.componentwise(&component_args, span, error.clone(), fun)?);
+        }
+
+        let ty = self.result_type(ty, components[0], span, error)?;
+        let inner = ConstantInner::Composite { ty, components };
+        Ok(self.register_constant(inner, span))
+    }
+
+    /// Return the type of a vector or matrix of the same size as `ty`, whose
+    /// components have the type of `first_component`.
+    fn result_type(
+        &mut self,
+        ty: Handle<Type>,
+        first_component: Handle<Constant>,
+        span: Span,
+        error: ConstantEvaluatorError,
+    ) -> Result<Handle<Type>, ConstantEvaluatorError> {
+        let inner = match (
+            &self.types[ty].inner,
+            &self.constants[first_component].inner,
+        ) {
+            (&TypeInner::Vector { size, .. }, &ConstantInner::Scalar { ref value, width }) => {
+                TypeInner::Vector {
+                    size,
+                    kind: value.scalar_kind(),
+                    width,
+                }
+            }
+            (&TypeInner::Matrix { columns, .. }, &ConstantInner::Composite { ty, .. }) => {
+                match self.types[ty].inner {
+                    TypeInner::Vector {
+                        size,
+                        kind: ScalarKind::Float,
+                        width,
+                    } => TypeInner::Matrix {
+                        columns,
+                        rows: size,
+                        width,
+                    },
+                    _ => return Err(error),
+                }
+            }
+            _ => return Err(error),
+        };
+        Ok(self.types.insert(Type { name: None, inner }, span))
+    }
+
+    fn dot(
+        &mut self,
+        args: &[Handle<Constant>],
+        span: Span,
+    ) -> Result<Handle<Constant>, ConstantEvaluatorError> {
+        let products = self.componentwise(
+            args,
+            span,
+            ConstantEvaluatorError::InvalidMathArg,
+            &mut |args| binary_op(BinaryOperator::Multiply, args[0], args[1]),
+        )?;
+        let components = match self.constants[products].inner {
+            ConstantInner::Composite { ref components, .. } => components.clone(),
+            ConstantInner::Scalar { .. } => return Err(ConstantEvaluatorError::InvalidMathArg),
+        };
+        let mut sum = None;
+        for component in components {
+            let value = match self.constants[component].inner {
+                ConstantInner::Scalar { value, width } => (value, width),
+                ConstantInner::Composite { .. } => {
+                    return Err(ConstantEvaluatorError::InvalidMathArg)
+                }
+            };
+            sum = Some(match sum {
+                Some(sum) => binary_op(BinaryOperator::Add, sum, value)?,
+                None => value,
+            });
+        }
+        let (value, width) = sum.ok_or(ConstantEvaluatorError::InvalidMathArg)?;
+        let inner = ConstantInner::Scalar { value, width };
+        Ok(self.register_constant(inner, span))
+    }
+
+    fn register_constant(&mut self, inner: ConstantInner, span: Span) -> Handle<Constant> {
+        self.constants.fetch_or_append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner,
+            },
+            span,
+        )
+    }
+}
+
+/// Round `value` to a float of `width` bytes.
+fn float(value: f64, width: Bytes) -> Result<Scalar, ConstantEvaluatorError> {
+    let value = match width {
+        2 => half::f16::from_f64(value).to_f64(),
+        4 => value as f32 as f64,
+        8 => value,
+        _ => {
+            return Err(ConstantEvaluatorError::NotImplemented(format!(
+                "{}-byte float arithmetic",
+                width
+            )))
+        }
+    };
+    if value.is_finite() {
+        Ok((ScalarValue::Float(value), width))
+    } else {
+        Err(ConstantEvaluatorError::NotFinite)
+    }
+}
+
+/// Evaluate an integer operation with wrapping semantics on the values
+/// `$a` and `$b`. Shifts are by `$b` as a `u32`, given as `$shift`.
+macro_rules! integer_binary_op {
+    ($op:expr, $a:expr, $b:expr, $shift:expr) => {{
+        let (a, b) = ($a, $b);
+        match $op {
+            BinaryOperator::Add => a.wrapping_add(b),
+            BinaryOperator::Subtract => a.wrapping_sub(b),
+            BinaryOperator::Multiply => a.wrapping_mul(b),
+            BinaryOperator::Divide => {
+                if b == 0 {
+                    a
+                } else {
+                    a.wrapping_div(b)
+                }
+            }
+            BinaryOperator::Modulo => {
+                if b == 0 {
+                    0
+                } else {
+                    a.wrapping_rem(b)
+                }
+            }
+            BinaryOperator::And => a & b,
+            BinaryOperator::ExclusiveOr => a ^ b,
+            BinaryOperator::InclusiveOr => a | b,
+            BinaryOperator::ShiftLeft => a.wrapping_shl($shift),
+            BinaryOperator::ShiftRight => a.wrapping_shr($shift),
+            _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+        }
+    }};
+}
+
+fn compare<T: PartialOrd>(op: BinaryOperator, a: T, b: T) -> Option<bool> {
+    Some(match op {
+        BinaryOperator::Equal => a == b,
+        BinaryOperator::NotEqual => a != b,
+        BinaryOperator::Less => a < b,
+        BinaryOperator::LessEqual => a <= b,
+        BinaryOperator::Greater => a > b,
+        BinaryOperator::GreaterEqual => a >= b,
+        _ => return None,
+    })
+}
+
+fn binary_op(
+    op: BinaryOperator,
+    left: Scalar,
+    right: Scalar,
+) -> Result<Scalar, ConstantEvaluatorError> {
+    use ScalarValue as Sv;
+    let (left, width) = left;
+    let (right, _) = right;
+
+    let comparison = match (left, right) {
+        (Sv::Sint(a), Sv::Sint(b)) => compare(op, a, b),
+        (Sv::Uint(a), Sv::Uint(b)) => compare(op, a, b),
+        (Sv::Float(a), Sv::Float(b)) => compare(op, a, b),
+        (Sv::Bool(a), Sv::Bool(b)) => match op {
+            BinaryOperator::Equal | BinaryOperator::NotEqual => compare(op, a, b),
+            _ => None,
+        },
+        _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+    };
+    if let Some(result) = comparison {
+        return Ok((Sv::Bool(result), crate::BOOL_WIDTH));
+    }
+
+    let value = match (left, right) {
+        (Sv::Sint(a), Sv::Sint(b)) if width == 4 => {
+            Sv::Sint(integer_binary_op!(op, a as i32, b as i32, b as u32) as i64)
+        }
+        (Sv::Sint(a), Sv::Sint(b)) => Sv::Sint(integer_binary_op!(op, a, b, b as u32)),
+        (Sv::Uint(a), Sv::Uint(b)) if width == 4 => {
+            Sv::Uint(integer_binary_op!(op, a as u32, b as u32, b as u32) as u64)
+        }
+        (Sv::Uint(a), Sv::Uint(b)) => Sv::Uint(integer_binary_op!(op, a, b, b as u32)),
+        (Sv::Sint(a), Sv::Uint(b)) => {
+            // Only shifts take operands of different kinds.
+            let shift = b as u32;
+            let value = match op {
+                BinaryOperator::ShiftLeft if width == 4 => (a as i32).wrapping_shl(shift) as i64,
+                BinaryOperator::ShiftRight if width == 4 => (a as i32).wrapping_shr(shift) as i64,
+                BinaryOperator::ShiftLeft => a.wrapping_shl(shift),
+                BinaryOperator::ShiftRight => a.wrapping_shr(shift),
+                _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+            };
+            Sv::Sint(value)
+        }
+        (Sv::Float(a), Sv::Float(b)) => {
+            let value = match op {
+                BinaryOperator::Add => a + b,
+                BinaryOperator::Subtract => a - b,
+                BinaryOperator::Multiply => a * b,
+                BinaryOperator::Divide => a / b,
+                BinaryOperator::Modulo => a % b,
+                _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+            };
+            return float(value, width);
+        }
+        (Sv::Bool(a), Sv::Bool(b)) => Sv::Bool(match op {
+            BinaryOperator::LogicalAnd | BinaryOperator::And => a && b,
+            BinaryOperator::LogicalOr | BinaryOperator::InclusiveOr => a || b,
+            _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+        }),
+        _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+    };
+    Ok((value, width))
+}
+
+fn unary_op(op: UnaryOperator, arg: Scalar) -> Result<Scalar, ConstantEvaluatorError> {
+    use ScalarValue as Sv;
+    let (value, width) = arg;
+    let value = match (op, value) {
+        (UnaryOperator::Negate, Sv::Sint(v)) if width == 4 => {
+            Sv::Sint((v as i32).wrapping_neg() as i64)
+        }
+        (UnaryOperator::Negate, Sv::Sint(v)) => Sv::Sint(v.wrapping_neg()),
+        (UnaryOperator::Negate, Sv::Float(v)) => return float(-v, width),
+        (UnaryOperator::Not, Sv::Sint(v)) if width == 4 => Sv::Sint(!(v as i32) as i64),
+        (UnaryOperator::Not, Sv::Sint(v)) => Sv::Sint(!v),
+        (UnaryOperator::Not, Sv::Uint(v)) if width == 4 => Sv::Uint(!(v as u32) as u64),
+        (UnaryOperator::Not, Sv::Uint(v)) => Sv::Uint(!v),
+        (UnaryOperator::Not, Sv::Bool(v)) => Sv::Bool(!v),
+        _ => return Err(ConstantEvaluatorError::InvalidUnaryOpArg),
+    };
+    Ok((value, width))
+}
+
+/// Round `value` to the nearest integer, and to the even one if it is halfway
+/// between two of them.
+fn round_ties_even(value: f64) -> f64 {
+    let rounded = value.round();
+    if (value - value.trunc()).abs() == 0.5 {
+        2.0 * (value / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+// `f64::clamp` requires Rust 1.50.
+fn clamp(value: f64, low: f64, high: f64) -> f64 {
+    value.max(low).min(high)
+}
+
+fn math_function(fun: MathFunction, args: &[Scalar]) -> Result<Scalar, ConstantEvaluatorError> {
+    use MathFunction as Mf;
+    use ScalarValue as Sv;
+    let width = args[0].1;
+
+    // Functions that also apply to integers.
+    match (fun, args) {
+        (Mf::Abs, &[(Sv::Sint(v), _)]) if width == 4 => {
+            return Ok((Sv::Sint((v as i32).wrapping_abs() as i64), width))
+        }
+        (Mf::Abs, &[(Sv::Sint(v), _)]) => return Ok((Sv::Sint(v.wrapping_abs()), width)),
+        (Mf::Abs, &[(Sv::Uint(v), _)]) => return Ok((Sv::Uint(v), width)),
+        (Mf::Sign, &[(Sv::Sint(v), _)]) => return Ok((Sv::Sint(v.signum()), width)),
+        (Mf::Min, &[(Sv::Sint(a), _), (Sv::Sint(b), _)]) => return Ok((Sv::Sint(a.min(b)), width)),
+        (Mf::Min, &[(Sv::Uint(a), _), (Sv::Uint(b), _)]) => return Ok((Sv::Uint(a.min(b)), width)),
+        (Mf::Max, &[(Sv::Sint(a), _), (Sv::Sint(b), _)]) => return Ok((Sv::Sint(a.max(b)), width)),
+        (Mf::Max, &[(Sv::Uint(a), _), (Sv::Uint(b), _)]) => return Ok((Sv::Uint(a.max(b)), width)),
+        (Mf::Clamp, &[(Sv::Sint(e), _), (Sv::Sint(low), _), (Sv::Sint(high), _)]) => {
+            return Ok((Sv::Sint(e.max(low).min(high)), width))
+        }
+        (Mf::Clamp, &[(Sv::Uint(e), _), (Sv::Uint(low), _), (Sv::Uint(high), _)]) => {
+            return Ok((Sv::Uint(e.max(low).min(high)), width))
+        }
+        (Mf::CountOneBits, &[(Sv::Sint(v), _)]) if width == 4 => {
+            return Ok((Sv::Sint((v as i32).count_ones() as i64), width))
+        }
+        (Mf::CountOneBits, &[(Sv::Sint(v), _)]) => {
+            return Ok((Sv::Sint(v.count_ones() as i64), width))
+        }
+        (Mf::CountOneBits, &[(Sv::Uint(v), _)]) if width == 4 => {
+            return Ok((Sv::Uint((v as u32).count_ones() as u64), width))
+        }
+        (Mf::CountOneBits, &[(Sv::Uint(v), _)]) => {
+            return Ok((Sv::Uint(v.count_ones() as u64), width))
+        }
+        (Mf::ReverseBits, &[(Sv::Sint(v), _)]) if width == 4 => {
+            return Ok((Sv::Sint((v as i32).reverse_bits() as i64), width))
+        }
+        (Mf::ReverseBits, &[(Sv::Sint(v), _)]) => return Ok((Sv::Sint(v.reverse_bits()), width)),
+        (Mf::ReverseBits, &[(Sv::Uint(v), _)]) if width == 4 => {
+            return Ok((Sv::Uint((v as u32).reverse_bits() as u64), width))
+        }
+        (Mf::ReverseBits, &[(Sv::Uint(v), _)]) => return Ok((Sv::Uint(v.reverse_bits()), width)),
+        _ => {}
+    }
+
+    let mut floats = Vec::with_capacity(args.len());
+    for &(value, _) in args.iter() {
+        match value {
+            Sv::Float(v) => floats.push(v),
+            _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+        }
+    }
+    let arg = |index: usize| {
+        floats
+            .get(index)
+            .cloned()
+            .ok_or(ConstantEvaluatorError::InvalidMathArg)
+    };
+    let x = arg(0)?;
+    let value = match fun {
+        Mf::Abs => x.abs(),
+        Mf::Min => x.min(arg(1)?),
+        Mf::Max => x.max(arg(1)?),
+        Mf::Clamp => clamp(x, arg(1)?, arg(2)?),
+        Mf::Cos => x.cos(),
+        Mf::Cosh => x.cosh(),
+        Mf::Sin => x.sin(),
+        Mf::Sinh => x.sinh(),
+        Mf::Tan => x.tan(),
+        Mf::Tanh => x.tanh(),
+        Mf::Acos => x.acos(),
+        Mf::Asin => x.asin(),
+        Mf::Atan => x.atan(),
+        Mf::Atan2 => x.atan2(arg(1)?),
+        Mf::Asinh => x.asinh(),
+        Mf::Acosh => x.acosh(),
+        Mf::Atanh => x.atanh(),
+        Mf::Radians => x.to_radians(),
+        Mf::Degrees => x.to_degrees(),
+        Mf::Ceil => x.ceil(),
+        Mf::Floor => x.floor(),
+        Mf::Round => round_ties_even(x),
+        Mf::Fract => x - x.floor(),
+        Mf::Trunc => x.trunc(),
+        Mf::Exp => x.exp(),
+        Mf::Exp2 => x.exp2(),
+        Mf::Log => x.ln(),
+        Mf::Log2 => x.log2(),
+        Mf::Pow => x.powf(arg(1)?),
+        Mf::Sqrt => x.sqrt(),
+        Mf::InverseSqrt => 1.0 / x.sqrt(),
+        Mf::Sign => {
+            if x > 0.0 {
+                1.0
+            } else if x < 0.0 {
+                -1.0
+            } else {
+                0.0
+            }
+        }
+        Mf::Fma => x.mul_add(arg(1)?, arg(2)?),
+        Mf::Mix => {
+            let t = arg(2)?;
+            x * (1.0 - t) + arg(1)? * t
+        }
+        Mf::Step => {
+            if arg(1)? >= x {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Mf::SmoothStep => {
+            let (low, high) = (x, arg(1)?);
+            let t = clamp((arg(2)? - low) / (high - low), 0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        }
+        _ => return Err(ConstantEvaluatorError::NotImplemented(format!("{:?}", fun))),
+    };
+    float(value, width)
+}
+
+fn convert_scalar(
+    arg: Scalar,
+    kind: ScalarKind,
+    width: Bytes,
+) -> Result<Scalar, ConstantEvaluatorError> {
+    use ScalarValue as Sv;
+    let (value, _) = arg;
+    // integers wrap around when converted to a narrower type
+    let value = match kind {
+        ScalarKind::Sint => Sv::Sint(match value {
+            Sv::Sint(v) if width == 4 => v as i32 as i64,
+            Sv::Sint(v) => v,
+            Sv::Uint(v) if width == 4 => v as u32 as i32 as i64,
+            Sv::Uint(v) => v as i64,
+            Sv::Float(v) if width == 4 => {
+                v.trunc().max(i32::MIN as f64).min(i32::MAX as f64) as i64
+            }
+            Sv::Float(v) => v.trunc().max(i64::MIN as f64).min(i64::MAX as f64) as i64,
+            Sv::Bool(v) => v as i64,
+        }),
+        ScalarKind::Uint => Sv::Uint(match value {
+            Sv::Sint(v) if width == 4 => v as u32 as u64,
+            Sv::Sint(v) => v as u64,
+            Sv::Uint(v) if width == 4 => v as u32 as u64,
+            Sv::Uint(v) => v,
+            Sv::Float(v) if width == 4 => v.trunc().max(0.0).min(u32::MAX as f64) as u64,
+            Sv::Float(v) => v.trunc().max(0.0).min(u64::MAX as f64) as u64,
+            Sv::Bool(v) => v as u64,
+        }),
+        ScalarKind::Float => {
+            let value = match value {
+                Sv::Sint(v) => v as f64,
+                Sv::Uint(v) => v as f64,
+                Sv::Float(v) => v,
+                Sv::Bool(v) => v as u8 as f64,
+            };
+            return float(value, width);
+        }
+        ScalarKind::Bool => Sv::Bool(match value {
+            Sv::Sint(v) => v != 0,
+            Sv::Uint(v) => v != 0,
+            Sv::Float(v) => v != 0.0,
+            Sv::Bool(v) => v,
+        }),
+    };
+    Ok((value, width))
+}
+
+fn bitcast_scalar(arg: Scalar, kind: ScalarKind) -> Result<Scalar, ConstantEvaluatorError> {
+    use ScalarValue as Sv;
+    let (value, width) = arg;
+    let bits = match (value, width) {
+        (Sv::Sint(v), 4) => v as i32 as u32 as u64,
+        (Sv::Uint(v), 4) => v as u32 as u64,
+        (Sv::Float(v), 4) => (v as f32).to_bits() as u64,
+        (Sv::Sint(v), 8) => v as u64,
+        (Sv::Uint(v), 8) => v,
+        (Sv::Float(v), 8) => v.to_bits(),
+        _ => return Err(ConstantEvaluatorError::InvalidCastArg),
+    };
+    let value = match (kind, width) {
+        (ScalarKind::Sint, 4) => Sv::Sint(bits as u32 as i32 as i64),
+        (ScalarKind::Uint, _) => Sv::Uint(bits),
+        (ScalarKind::Float, 4) => return float(f32::from_bits(bits as u32) as f64, width),
+        (ScalarKind::Sint, _) => Sv::Sint(bits as i64),
+        (ScalarKind::Float, _) => return float(f64::from_bits(bits), width),
+        (ScalarKind::Bool, _) => return Err(ConstantEvaluatorError::InvalidCastArg),
+    };
+    Ok((value, width))
+}
+
+/// Replace the expressions of the functions in `module` that only operate on
+/// constants with [`Expression::Constant`]s of the values they evaluate to.
+///
+/// This folds [`Binary`], [`Unary`], [`Math`], [`As`], [`Compose`], [`Splat`]
+/// and [`Swizzle`] expressions whose operands are constants, including the
+/// ones folded before them, and removes the folded expressions from the
+/// [`Emit`] statements covering them. Expressions depending on overrides are
+/// left alone, since their values are only known at pipeline creation.
+///
+/// [`Binary`]: Expression::Binary
+/// [`Unary`]: Expression::Unary
+/// [`Math`]: Expression::Math
+/// [`As`]: Expression::As
+/// [`Compose`]: Expression::Compose
+/// [`Splat`]: Expression::Splat
+/// [`Swizzle`]: Expression::Swizzle
+/// [`Emit`]: crate::Statement::Emit
+pub fn fold_constants(module: &mut crate::Module) {
+    for (_, function) in module.functions.iter_mut() {
+        fold_function(&mut module.types, &mut module.constants, function);
+    }
+    for ep in module.entry_points.iter_mut() {
+        fold_function(&mut module.types, &mut module.constants, &mut ep.function);
+    }
+}
+
+/// Checks whether all the operands of an expression are constants.
+struct ConstantOperands<'a> {
+    expressions: &'a Arena<Expression>,
+    all_constant: bool,
+}
+
+impl super::Visitor for ConstantOperands<'_> {
+    fn visit_expression(&mut self, handle: Handle<Expression>) {
+        if let Expression::Constant(_) = self.expressions[handle] {
+        } else {
+            self.all_constant = false;
+        }
+    }
+}
+
+fn fold_function(
+    types: &mut UniqueArena<Type>,
+    constants: &mut Arena<Constant>,
+    function: &mut crate::Function,
+) {
+    let mut folded = BitSet::new();
+    for index in 0..function.expressions.len() {
+        let handle = Handle::from_usize(index);
+        let expression = &function.expressions[handle];
+        match *expression {
+            Expression::Binary { .. }
+            | Expression::Unary { .. }
+            | Expression::Math { .. }
+            | Expression::As { .. }
+            | Expression::Compose { .. }
+            | Expression::Splat { .. }
+            | Expression::Swizzle { .. } => {}
+            _ => continue,
+        }
+        let mut operands = ConstantOperands {
+            expressions: &function.expressions,
+            all_constant: true,
+        };
+        expression.visit_handles(&mut operands);
+        if !operands.all_constant {
+            continue;
+        }
+
+        let mut evaluator = ConstantEvaluator {
+            types,
+            constants,
+            expressions: &function.expressions,
+        };
+        if let Ok(constant) = evaluator.evaluate(handle) {
+            function.expressions[handle] = Expression::Constant(constant);
+            folded.insert(index);
+        }
+    }
+
+    if !folded.is_empty() {
+        remove_from_emits(&mut function.body, &folded);
+    }
+}
+
+/// Split the [`Emit`] statements of `block` so that they don't cover the
+/// expressions in `removed`.
+///
+/// [`Emit`]: crate::Statement::Emit
+fn remove_from_emits(block: &mut crate::Block, removed: &BitSet) {
+    use crate::Statement as S;
+    let old = std::mem::take(block);
+    let mut new = crate::Block::with_capacity(old.len());
+    for (mut statement, span) in old.span_into_iter() {
+        match statement {
+            S::Emit(ref range) if range.clone().any(|handle| removed.contains(handle.index())) => {
+                let mut first_and_last = None;
+                for handle in range.clone() {
+                    if removed.contains(handle.index()) {
+                        if let Some((first, last)) = first_and_last.take() {
+                            let range = crate::Range::new_from_bounds(first, last);
+                            new.push(S::Emit(range), span);
+                        }
+                    } else {
+                        first_and_last = match first_and_last {
+                            Some((first, _)) => Some((first, handle)),
+                            None => Some((handle, handle)),
+                        };
+                    }
+                }
+                if let Some((first, last)) = first_and_last {
+                    new.push(S::Emit(crate::Range::new_from_bounds(first, last)), span);
+                }
+                continue;
+            }
+            S::Block(ref mut block) => remove_from_emits(block, removed),
+            S::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                remove_from_emits(accept, removed);
+                remove_from_emits(reject, removed);
+            }
+            S::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    remove_from_emits(&mut case.body, removed);
+                }
+            }
+            S::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remove_from_emits(body, removed);
+                remove_from_emits(continuing, removed);
+            }
+            _ => {}
+        }
+        new.push(statement, span);
+    }
+    *block = new;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use crate::{
+        Arena, BinaryOperator, Constant, ConstantInner, Expression, ScalarKind, ScalarValue, Type,
+        TypeInner, UnaryOperator, UniqueArena, VectorSize,
+    };
+
+    use super::{ConstantEvaluator, ConstantEvaluatorError};
+
+    #[test]
+    fn unary_op() {
+        let mut types = UniqueArena::new();
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+
+        let vec_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Bi,
+                    kind: ScalarKind::Sint,
+                    width: 4,
+                },
+            },
+            Default::default(),
+        );
+
+        let h = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar {
+                    width: 4,
+                    value: ScalarValue::Sint(4),
+                },
+            },
+            Default::default(),
+        );
+
+        let h1 = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar {
+                    width: 4,
+                    value: ScalarValue::Sint(8),
+                },
+            },
+            Default::default(),
+        );
+
+        let vec_h = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Composite {
+                    ty: vec_ty,
+                    components: vec![h, h1],
+                },
+            },
+            Default::default(),
+        );
+
+        let expr = expressions.append(Expression::Constant(h), Default::default());
+        let expr1 = expressions.append(Expression::Constant(vec_h), Default::default());
+
+        let root1 = expressions.append(
+            Expression::Unary {
+                op: UnaryOperator::Negate,
+                expr,
+            },
+            Default::default(),
+        );
+
+        let root2 = expressions.append(
+            Expression::Unary {
+                op: UnaryOperator::Not,
+                expr,
+            },
+            Default::default(),
+        );
+
+        let root3 = expressions.append(
+            Expression::Unary {
+                op: UnaryOperator::Not,
+                expr: expr1,
+            },
+            Default::default(),
+        );
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut types,
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+
+        let res1 = evaluator.evaluate(root1).unwrap();
+        let res2 = evaluator.evaluate(root2).unwrap();
+        let res3 = evaluator.evaluate(root3).unwrap();
+
+        assert_eq!(
+            constants[res1].inner,
+            ConstantInner::Scalar {
+                width: 4,
+                value: ScalarValue::Sint(-4),
+            },
+        );
+
+        assert_eq!(
+            constants[res2].inner,
+            ConstantInner::Scalar {
+                width: 4,
+                value: ScalarValue::Sint(!4),
+            },
+        );
+
+        let res3_inner = &constants[res3].inner;
+
+        match *res3_inner {
+            ConstantInner::Composite { ty, ref components } => {
+                assert_eq!(ty, vec_ty);
+                let mut components_iter = components.iter().copied();
+                assert_eq!(
+                    constants[components_iter.next().unwrap()].inner,
+                    ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Sint(!4),
+                    },
+                );
+                assert_eq!(
+                    constants[components_iter.next().unwrap()].inner,
+                    ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Sint(!8),
+                    },
+                );
+                assert!(components_iter.next().is_none());
+            }
+            ConstantInner::Scalar { .. } => unreachable!("Expected vector"),
+        }
+    }
+
+    #[test]
+    fn cast() {
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+
+        let h = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar {
+                    width: 4,
+                    value: ScalarValue::Sint(4),
+                },
+            },
+            Default::default(),
+        );
+
+        let expr = expressions.append(Expression::Constant(h), Default::default());
+
+        let root = expressions.append(
+            Expression::As {
+                expr,
+                kind: ScalarKind::Bool,
+                convert: Some(crate::BOOL_WIDTH),
+            },
+            Default::default(),
+        );
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut UniqueArena::new(),
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+
+        let res = evaluator.evaluate(root).unwrap();
+
+        assert_eq!(
+            constants[res].inner,
+            ConstantInner::Scalar {
+                width: crate::BOOL_WIDTH,
+                value: ScalarValue::Bool(true),
+            },
+        );
+    }
+
+    #[test]
+    fn access() {
+        let mut types = UniqueArena::new();
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+
+        let matrix_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Matrix {
+                    columns: VectorSize::Bi,
+                    rows: VectorSize::Tri,
+                    width: 4,
+                },
+            },
+            Default::default(),
+        );
+
+        let vec_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Tri,
+                    kind: ScalarKind::Float,
+                    width: 4,
+                },
+            },
+            Default::default(),
+        );
+
+        let mut vec1_components = Vec::with_capacity(3);
+        let mut vec2_components = Vec::with_capacity(3);
+
+        for i in 0..3 {
+            let h = constants.append(
+                Constant {
+                    name: None,
+                    specialization: None,
+                    inner: ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Float(i as f64),
+                    },
+                },
+                Default::default(),
+            );
+
+            vec1_components.push(h)
+        }
+
+        for i in 3..6 {
+            let h = constants.append(
+                Constant {
+                    name: None,
+                    specialization: None,
+                    inner: ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Float(i as f64),
+                    },
+                },
+                Default::default(),
+            );
+
+            vec2_components.push(h)
+        }
+
+        let vec1 = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Composite {
+                    ty: vec_ty,
+                    components: vec1_components,
+                },
+            },
+            Default::default(),
+        );
+
+        let vec2 = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Composite {
+                    ty: vec_ty,
+                    components: vec2_components,
+                },
+            },
+            Default::default(),
+        );
+
+        let h = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Composite {
+                    ty: matrix_ty,
+                    components: vec![vec1, vec2],
+                },
+            },
+            Default::default(),
+        );
+
+        let base = expressions.append(Expression::Constant(h), Default::default());
+        let root1 = expressions.append(
+            Expression::AccessIndex { base, index: 1 },
+            Default::default(),
+        );
+        let root2 = expressions.append(
+            Expression::AccessIndex {
+                base: root1,
+                index: 2,
+            },
+            Default::default(),
+        );
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut types,
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+
+        let res1 = evaluator.evaluate(root1).unwrap();
+        let res2 = evaluator.evaluate(root2).unwrap();
+
+        let res1_inner = &constants[res1].inner;
+
+        match *res1_inner {
+            ConstantInner::Composite { ty, ref components } => {
+                assert_eq!(ty, vec_ty);
+                let mut components_iter = components.iter().copied();
+                assert_eq!(
+                    constants[components_iter.next().unwrap()].inner,
+                    ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Float(3.),
+                    },
+                );
+                assert_eq!(
+                    constants[components_iter.next().unwrap()].inner,
+                    ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Float(4.),
+                    },
+                );
+                assert_eq!(
+                    constants[components_iter.next().unwrap()].inner,
+                    ConstantInner::Scalar {
+                        width: 4,
+                        value: ScalarValue::Float(5.),
+                    },
+                );
+                assert!(components_iter.next().is_none());
+            }
+            ConstantInner::Scalar { .. } => unreachable!("Expected vector"),
+        }
+
+        assert_eq!(
+            constants[res2].inner,
+            ConstantInner::Scalar {
+                width: 4,
+                value: ScalarValue::Float(5.),
+            },
+        );
+    }
+
+    fn evaluate_binary(
+        op: BinaryOperator,
+        left: ScalarValue,
+        right: ScalarValue,
+    ) -> Result<ConstantInner, ConstantEvaluatorError> {
+        evaluate_binary_with_width(op, 4, left, right)
+    }
+
+    fn evaluate_binary_with_width(
+        op: BinaryOperator,
+        width: crate::Bytes,
+        left: ScalarValue,
+        right: ScalarValue,
+    ) -> Result<ConstantInner, ConstantEvaluatorError> {
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+        let mut append_scalar = |value| {
+            let constant = constants.append(
+                Constant {
+                    name: None,
+                    specialization: None,
+                    inner: ConstantInner::Scalar { width, value },
+                },
+                Default::default(),
+            );
+            expressions.append(Expression::Constant(constant), Default::default())
+        };
+        let left = append_scalar(left);
+        let right = append_scalar(right);
+        let root = expressions.append(Expression::Binary { op, left, right }, Default::default());
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut UniqueArena::new(),
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+        let res = evaluator.evaluate(root)?;
+        Ok(constants[res].inner.clone())
+    }
+
+    fn evaluate_with_width(
+        width: crate::Bytes,
+        value: ScalarValue,
+        make_root: impl FnOnce(crate::Handle<Expression>) -> Expression,
+    ) -> Result<ConstantInner, ConstantEvaluatorError> {
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+        let constant = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar { width, value },
+            },
+            Default::default(),
+        );
+        let expr = expressions.append(Expression::Constant(constant), Default::default());
+        let root = expressions.append(make_root(expr), Default::default());
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut UniqueArena::new(),
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+        let res = evaluator.evaluate(root)?;
+        Ok(constants[res].inner.clone())
+    }
+
+    fn scalar(value: ScalarValue) -> ConstantInner {
+        ConstantInner::Scalar { width: 4, value }
+    }
+
+    #[test]
+    fn integer_overflow() {
+        use ScalarValue::{Sint, Uint};
+
+        let max = Sint(i32::MAX as i64);
+        let min = Sint(i32::MIN as i64);
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Add, max, Sint(1)),
+            Ok(scalar(min))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Subtract, Uint(0), Uint(1)),
+            Ok(scalar(Uint(u32::MAX as u64)))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Multiply, max, Sint(2)),
+            Ok(scalar(Sint(-2)))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Divide, min, Sint(-1)),
+            Ok(scalar(min))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::ShiftLeft, Uint(1), Uint(33)),
+            Ok(scalar(Uint(2)))
+        );
+    }
+
+    #[test]
+    fn narrowing_conversion() {
+        use ScalarValue::{Sint, Uint};
+
+        let convert = |value, kind| {
+            evaluate_with_width(8, value, |expr| Expression::As {
+                expr,
+                kind,
+                convert: Some(4),
+            })
+        };
+        assert_eq!(
+            convert(Sint(1 << 32 | 5), ScalarKind::Sint),
+            Ok(scalar(Sint(5)))
+        );
+        assert_eq!(
+            convert(Sint(-1), ScalarKind::Uint),
+            Ok(scalar(Uint(u32::MAX as u64)))
+        );
+        assert_eq!(
+            convert(Uint(u32::MAX as u64 + 2), ScalarKind::Uint),
+            Ok(scalar(Uint(1)))
+        );
+        assert_eq!(
+            convert(Uint(u32::MAX as u64), ScalarKind::Sint),
+            Ok(scalar(Sint(-1)))
+        );
+    }
+
+    #[test]
+    fn wide_bit_functions() {
+        use crate::MathFunction as Mf;
+        use ScalarValue::{Sint, Uint};
+
+        let math = |fun, value| {
+            evaluate_with_width(8, value, |arg| Expression::Math {
+                fun,
+                arg,
+                arg1: None,
+                arg2: None,
+                arg3: None,
+            })
+        };
+        let wide = |value| ConstantInner::Scalar { width: 8, value };
+        assert_eq!(math(Mf::CountOneBits, Sint(-1)), Ok(wide(Sint(64))));
+        assert_eq!(math(Mf::CountOneBits, Uint(1 << 40 | 1)), Ok(wide(Uint(2))));
+        assert_eq!(math(Mf::ReverseBits, Sint(1)), Ok(wide(Sint(i64::MIN))));
+        assert_eq!(math(Mf::ReverseBits, Uint(1)), Ok(wide(Uint(1 << 63))));
+    }
+
+    #[test]
+    fn swizzle_out_of_bounds() {
+        let mut types = UniqueArena::new();
+        let mut expressions = Arena::new();
+        let mut constants = Arena::new();
+
+        let vec2_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Bi,
+                    kind: ScalarKind::Float,
+                    width: 4,
+                },
+            },
+            Default::default(),
+        );
+        let component = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Scalar {
+                    width: 4,
+                    value: ScalarValue::Float(1.0),
+                },
+            },
+            Default::default(),
+        );
+        let vector = constants.append(
+            Constant {
+                name: None,
+                specialization: None,
+                inner: ConstantInner::Composite {
+                    ty: vec2_ty,
+                    components: vec![component, component],
+                },
+            },
+            Default::default(),
+        );
+        let vector = expressions.append(Expression::Constant(vector), Default::default());
+        let root = expressions.append(
+            Expression::Swizzle {
+                size: VectorSize::Bi,
+                vector,
+                pattern: [
+                    crate::SwizzleComponent::X,
+                    crate::SwizzleComponent::Z,
+                    crate::SwizzleComponent::X,
+                    crate::SwizzleComponent::X,
+                ],
+            },
+            Default::default(),
+        );
+
+        let mut evaluator = ConstantEvaluator {
+            types: &mut types,
+            constants: &mut constants,
+            expressions: &expressions,
+        };
+        assert_eq!(
+            evaluator.evaluate(root),
+            Err(ConstantEvaluatorError::SwizzleOutOfBounds(
+                crate::SwizzleComponent::Z
+            ))
+        );
+    }
+
+    #[test]
+    fn division_by_zero() {
+        use ScalarValue::{Float, Sint, Uint};
+
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Divide, Sint(7), Sint(0)),
+            Ok(scalar(Sint(7)))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Modulo, Sint(7), Sint(0)),
+            Ok(scalar(Sint(0)))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Divide, Uint(7), Uint(0)),
+            Ok(scalar(Uint(7)))
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Divide, Float(1.0), Float(0.0)),
+            Err(ConstantEvaluatorError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn half_precision() {
+        use ScalarValue::Float;
+
+        let half = |value| ConstantInner::Scalar { width: 2, value };
+        assert_eq!(
+            evaluate_binary_with_width(BinaryOperator::Add, 2, Float(1.0), Float(0.0001)),
+            Ok(half(Float(1.0)))
+        );
+        assert_eq!(
+            evaluate_binary_with_width(BinaryOperator::Add, 2, Float(0.1), Float(0.2)),
+            Ok(half(Float(0.300048828125)))
+        );
+        assert_eq!(
+            evaluate_binary_with_width(BinaryOperator::Multiply, 2, Float(300.0), Float(300.0)),
+            Err(ConstantEvaluatorError::NotFinite)
+        );
+    }
+
+    #[cfg(feature = "wgsl-in")]
+    #[test]
+    fn fold_function() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            @stage(compute) @workgroup_size(1)
+            fn main() {
+                var x: vec2<i32> = vec2<i32>(2, 3) * (1 + 1);
+                x = x + vec2<i32>(i32(1.5), -1);
+            }
+            ",
+        )
+        .unwrap();
+        super::fold_constants(&mut module);
+
+        let function = &module.entry_points[0].function;
+        let folded = function
+            .expressions
+            .iter()
+            .filter(|&(_, expr)| match *expr {
+                Expression::Binary { .. } | Expression::Compose { .. } | Expression::As { .. } => {
+                    true
+                }
+                _ => false,
+            })
+            .count();
+        // Only the addition involving `x` is left.
+        assert_eq!(folded, 1);
+        assert!(module.constants.iter().any(|(_, constant)| {
+            match constant.inner {
+                ConstantInner::Composite { ref components, .. } => components
+                    .iter()
+                    .map(|&c| &module.constants[c].inner)
+                    .eq([scalar(ScalarValue::Sint(4)), scalar(ScalarValue::Sint(6))].iter()),
+                ConstantInner::Scalar { .. } => false,
+            }
+        }));
+
+        crate::proc::validate(&module);
+    }
+}
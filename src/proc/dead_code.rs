@@ -0,0 +1,476 @@
+//! Removal of dead code within functions.
+//!
+//! Front ends don't try to produce minimal functions: the SPIR-V front end
+//! in particular spills phi instructions to local variables, which are often
+//! stored to and never read again. [`eliminate_dead_code`] removes the
+//! statements that can't be reached, the stores to local variables that are
+//! never read, and the expressions whose values end up unused.
+
+use super::{Mutator, Visitor};
+use crate::arena::{Arena, Handle, HandleMap};
+use crate::{Block, Expression, Function, LocalVariable, Statement};
+use bit_set::BitSet;
+
+/// Remove dead code from every function and entry point of `module`.
+///
+/// Within each function, this removes:
+///
+/// - the statements following a [`Return`], [`Kill`], [`Break`] or
+///   [`Continue`] in the same block, or following a nested block or [`If`]
+///   that always ends with one of those,
+///
+/// - the local variables that are never read, along with the [`Store`]s to
+///   them,
+///
+/// - the expressions whose values aren't used by any remaining statement,
+///   directly or through other expressions. [`Emit`] statements are
+///   shortened accordingly, and dropped once empty.
+///
+/// Named expressions are dropped from [`Function::named_expressions`] along
+/// with the expressions they name. The remaining expressions and local
+/// variables keep their order and spans.
+///
+/// [`Return`]: Statement::Return
+/// [`Kill`]: Statement::Kill
+/// [`Break`]: Statement::Break
+/// [`Continue`]: Statement::Continue
+/// [`If`]: Statement::If
+/// [`Store`]: Statement::Store
+/// [`Emit`]: Statement::Emit
+pub fn eliminate_dead_code(module: &mut crate::Module) {
+    for (_, function) in module.functions.iter_mut() {
+        eliminate_in_function(function);
+    }
+    for ep in module.entry_points.iter_mut() {
+        eliminate_in_function(&mut ep.function);
+    }
+}
+
+fn eliminate_in_function(function: &mut Function) {
+    remove_unreachable(&mut function.body);
+
+    let mut tracer = LiveTracer::new(function);
+    tracer.trace();
+    let LiveTracer {
+        expressions: live_expressions,
+        local_variables: live_locals,
+        ..
+    } = tracer;
+
+    prune_block(
+        &mut function.body,
+        &function.expressions,
+        &live_expressions,
+        &live_locals,
+    );
+
+    let expressions = HandleMap::from_kept(function.expressions.len(), |handle| {
+        live_expressions.contains(handle.index())
+    });
+    let local_variables = HandleMap::from_kept(function.local_variables.len(), |handle| {
+        live_locals.contains(handle.index())
+    });
+    let mut maps = FunctionMaps {
+        expressions: &expressions,
+        local_variables: &local_variables,
+    };
+    function
+        .local_variables
+        .retain_mut(|handle, _| live_locals.contains(handle.index()));
+    function.expressions.retain_mut(|handle, expression| {
+        if !expressions.contains(handle) {
+            return false;
+        }
+        expression.visit_handles_mut(&mut maps);
+        true
+    });
+    function.named_expressions = std::mem::take(&mut function.named_expressions)
+        .into_iter()
+        .filter_map(|(handle, name)| expressions.get(handle).map(|handle| (handle, name)))
+        .collect();
+    function.body.visit_handles_mut(&mut maps);
+}
+
+/// Return `true` if control never reaches the end of `block`.
+fn ends_control_flow(block: &Block) -> bool {
+    let last = match block.last() {
+        Some(last) => last,
+        None => return false,
+    };
+    match *last {
+        Statement::Return { .. } | Statement::Kill | Statement::Break | Statement::Continue => true,
+        Statement::Block(ref block) => ends_control_flow(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => ends_control_flow(accept) && ends_control_flow(reject),
+        _ => false,
+    }
+}
+
+/// Remove the statements of `block` and its nested blocks that follow a
+/// statement ending control flow.
+fn remove_unreachable(block: &mut Block) {
+    let mut index = 0;
+    while index < block.len() {
+        let ends = match block[index] {
+            Statement::Return { .. } | Statement::Kill | Statement::Break | Statement::Continue => {
+                true
+            }
+            Statement::Block(ref mut block) => {
+                remove_unreachable(block);
+                ends_control_flow(block)
+            }
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                remove_unreachable(accept);
+                remove_unreachable(reject);
+                ends_control_flow(accept) && ends_control_flow(reject)
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    remove_unreachable(&mut case.body);
+                }
+                false
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remove_unreachable(body);
+                remove_unreachable(continuing);
+                false
+            }
+            _ => false,
+        };
+        index += 1;
+        if ends {
+            block.cull(index..);
+        }
+    }
+}
+
+/// Return the local variable `pointer` points into, if any.
+fn pointer_local(
+    expressions: &Arena<Expression>,
+    mut pointer: Handle<Expression>,
+) -> Option<Handle<LocalVariable>> {
+    loop {
+        match expressions[pointer] {
+            Expression::LocalVariable(local) => return Some(local),
+            Expression::Access { base, .. } | Expression::AccessIndex { base, .. } => {
+                pointer = base;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Finds the live expressions and local variables of a function.
+///
+/// The operands of stores to a local variable are only live once the local
+/// variable is read, that is once its [`Expression::LocalVariable`] is live.
+struct LiveTracer<'a> {
+    function: &'a Function,
+    expressions: BitSet,
+    local_variables: BitSet,
+    /// The operands of the stores to each local variable.
+    local_stores: Vec<Vec<Handle<Expression>>>,
+    pending: Vec<Handle<Expression>>,
+}
+
+impl<'a> LiveTracer<'a> {
+    fn new(function: &'a Function) -> Self {
+        LiveTracer {
+            function,
+            expressions: BitSet::with_capacity(function.expressions.len()),
+            local_variables: BitSet::with_capacity(function.local_variables.len()),
+            local_stores: vec![Vec::new(); function.local_variables.len()],
+            pending: Vec::new(),
+        }
+    }
+
+    fn trace(&mut self) {
+        let function = self.function;
+        self.collect_uses(&function.body);
+        while let Some(handle) = self.pending.pop() {
+            if !self.expressions.insert(handle.index()) {
+                continue;
+            }
+            let expression = &function.expressions[handle];
+            expression.visit_handles(&mut PendingExpressions(&mut self.pending));
+            if let Expression::LocalVariable(local) = *expression {
+                self.local_variables.insert(local.index());
+                let stores = std::mem::take(&mut self.local_stores[local.index()]);
+                self.pending.extend(stores);
+            }
+        }
+    }
+
+    /// Gather the expressions used by the statements of `block`, except for
+    /// [`Emit`] ranges, and the operands of the stores to local variables.
+    ///
+    /// [`Emit`]: Statement::Emit
+    fn collect_uses(&mut self, block: &Block) {
+        for statement in block.iter() {
+            match *statement {
+                Statement::Emit(_) => {}
+                Statement::Block(ref block) => self.collect_uses(block),
+                Statement::If {
+                    condition,
+                    ref accept,
+                    ref reject,
+                } => {
+                    self.pending.push(condition);
+                    self.collect_uses(accept);
+                    self.collect_uses(reject);
+                }
+                Statement::Switch {
+                    selector,
+                    ref cases,
+                } => {
+                    self.pending.push(selector);
+                    for case in cases.iter() {
+                        self.collect_uses(&case.body);
+                    }
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    self.collect_uses(body);
+                    self.collect_uses(continuing);
+                }
+                Statement::Store { pointer, value } => {
+                    match pointer_local(&self.function.expressions, pointer) {
+                        Some(local) => {
+                            self.local_stores[local.index()].extend_from_slice(&[pointer, value])
+                        }
+                        None => self.pending.extend_from_slice(&[pointer, value]),
+                    }
+                }
+                _ => statement.visit_handles(&mut PendingExpressions(&mut self.pending)),
+            }
+        }
+    }
+}
+
+struct PendingExpressions<'a>(&'a mut Vec<Handle<Expression>>);
+
+impl Visitor for PendingExpressions<'_> {
+    fn visit_expression(&mut self, handle: Handle<Expression>) {
+        self.0.push(handle);
+    }
+}
+
+/// Remove the stores to dead local variables from `block` and its nested
+/// blocks, and shorten its [`Emit`] statements to their live expressions.
+///
+/// [`Emit`]: Statement::Emit
+fn prune_block(
+    block: &mut Block,
+    expressions: &Arena<Expression>,
+    live_expressions: &BitSet,
+    live_locals: &BitSet,
+) {
+    let mut index = 0;
+    while index < block.len() {
+        let keep = match block[index] {
+            Statement::Emit(ref mut range) => {
+                let mut live = range
+                    .clone()
+                    .filter(|handle| live_expressions.contains(handle.index()));
+                match live.next() {
+                    Some(first) => {
+                        let last = live.last().unwrap_or(first);
+                        // The dead expressions in between are removed from
+                        // the arena, so the live ones end up contiguous.
+                        *range = crate::arena::Range::new_from_bounds(first, last);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Statement::Store { pointer, .. } => match pointer_local(expressions, pointer) {
+                Some(local) => live_locals.contains(local.index()),
+                None => true,
+            },
+            Statement::Block(ref mut block) => {
+                prune_block(block, expressions, live_expressions, live_locals);
+                true
+            }
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                prune_block(accept, expressions, live_expressions, live_locals);
+                prune_block(reject, expressions, live_expressions, live_locals);
+                true
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    prune_block(&mut case.body, expressions, live_expressions, live_locals);
+                }
+                true
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                prune_block(body, expressions, live_expressions, live_locals);
+                prune_block(continuing, expressions, live_expressions, live_locals);
+                true
+            }
+            _ => true,
+        };
+        if keep {
+            index += 1;
+        } else {
+            block.cull(index..index + 1);
+        }
+    }
+}
+
+/// The maps adjusting the handles within a function.
+struct FunctionMaps<'a> {
+    expressions: &'a HandleMap<Expression>,
+    local_variables: &'a HandleMap<LocalVariable>,
+}
+
+impl Mutator for FunctionMaps<'_> {
+    fn visit_expression(&mut self, handle: &mut Handle<Expression>) {
+        self.expressions.adjust(handle);
+    }
+
+    fn visit_local_variable(&mut self, handle: &mut Handle<LocalVariable>) {
+        self.local_variables.adjust(handle);
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::eliminate_dead_code;
+    use crate::{Expression, Statement};
+
+    fn eliminate(source: &str) -> crate::Function {
+        let mut module = crate::proc::run_pass(source, eliminate_dead_code);
+        module.entry_points.pop().unwrap().function
+    }
+
+    #[test]
+    fn dead_stores() {
+        let function = eliminate(
+            "
+            struct Output {
+                value: u32;
+            };
+
+            @group(0) @binding(0)
+            var<storage, read_write> output: Output;
+
+            @stage(compute) @workgroup_size(1)
+            fn main(@builtin(local_invocation_index) index: u32) {
+                var unused: u32 = 1u;
+                var written: u32;
+                var kept: u32;
+                written = index * 2u;
+                kept = index + 1u;
+                written = kept;
+                output.value = kept;
+            }
+            ",
+        );
+
+        let names = function
+            .local_variables
+            .iter()
+            .map(|(_, local)| local.name.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["kept"]);
+        let stores = function
+            .body
+            .iter()
+            .filter(|statement| match **statement {
+                Statement::Store { .. } => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(stores, 2);
+        // `index * 2u` is gone along with the store of its value.
+        assert!(!function.expressions.iter().any(|(_, expression)| {
+            match *expression {
+                Expression::Binary {
+                    op: crate::BinaryOperator::Multiply,
+                    ..
+                } => true,
+                _ => false,
+            }
+        }));
+    }
+
+    #[test]
+    fn unreachable_statements() {
+        let function = eliminate(
+            "
+            struct Output {
+                values: array<u32, 4>;
+            };
+
+            @group(0) @binding(0)
+            var<storage, read_write> output: Output;
+
+            @stage(compute) @workgroup_size(1)
+            fn main(@builtin(local_invocation_index) index: u32) {
+                if (index == 0u) {
+                    return;
+                } else {
+                    return;
+                }
+                output.values[index] = index;
+            }
+            ",
+        );
+
+        assert!(!function.body.iter().any(|statement| match *statement {
+            Statement::Store { .. } => true,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn named_expressions() {
+        let function = eliminate(
+            "
+            struct Output {
+                values: array<u32, 4>;
+            };
+
+            @group(0) @binding(0)
+            var<storage, read_write> output: Output;
+
+            @stage(compute) @workgroup_size(1)
+            fn main(@builtin(local_invocation_index) index: u32) {
+                let unused = index * 3u;
+                let used = index + 1u;
+                output.values[used % 4u] = used;
+            }
+            ",
+        );
+
+        let mut names = function
+            .named_expressions
+            .iter()
+            .map(|(&handle, name)| {
+                assert!(handle.index() < function.expressions.len());
+                name.as_str()
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, ["used"]);
+    }
+}
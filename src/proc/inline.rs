@@ -0,0 +1,912 @@
+//! Inlining of function calls.
+//!
+//! Some drivers handle small helper functions poorly, and some back ends
+//! can't express every argument passing convention of the IR: pointer
+//! arguments in particular are awkward in HLSL and GLSL. [`inline`] replaces
+//! [`Statement::Call`]s with the bodies of the called functions, so none of
+//! that reaches the back ends.
+
+use super::Mutator;
+use crate::arena::{Arena, Handle, HandleMap, Range, UniqueArena};
+use crate::{
+    Block, Constant, ConstantInner, Expression, FastHashMap, Function, LocalVariable, ScalarKind,
+    ScalarValue, Span, Statement, Type, TypeInner,
+};
+
+/// Which calls [`inline`] replaces with the body of the called function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InlineMode {
+    /// Inline calls to functions taking pointer arguments, and to functions
+    /// made of at most [`SMALL_FUNCTION_SIZE`] statements and emitted
+    /// expressions.
+    Heuristic,
+    /// Inline every call that can be inlined.
+    All,
+}
+
+/// The size up to which [`InlineMode::Heuristic`] inlines functions, counting
+/// their statements, including nested ones, and emitted expressions.
+pub const SMALL_FUNCTION_SIZE: usize = 16;
+
+/// Replace the calls in the functions and entry points of `module` with the
+/// bodies of the called functions, as selected by `mode`.
+///
+/// The local variables of the called function are added to the caller, and
+/// its expressions are appended to the caller's, with [`FunctionArgument`]
+/// expressions replaced by the arguments of the call. The [`CallResult`]
+/// expression of an inlined call is replaced by the returned value, and every
+/// other expression keeps its order, so handles only ever refer to earlier
+/// expressions.
+///
+/// Functions returning early are inlined within a [`Loop`] that their
+/// [`Return`]s break out of, after storing the returned value to a new local
+/// variable. Functions returning from within a [`Loop`] or [`Switch`] can't
+/// be inlined that way, so calls to them are left alone.
+///
+/// Within a loop, the local variables of an inlined function are initialized
+/// again before its body, to their [`init`] or to their zero value. Calls to
+/// functions with local variables that have no zero value are left alone.
+///
+/// Calls are inlined into functions after the functions they call have had
+/// their own calls inlined. The functions that are no longer called are left
+/// in the module: [`compact`] removes them.
+///
+/// [`FunctionArgument`]: Expression::FunctionArgument
+/// [`CallResult`]: Expression::CallResult
+/// [`Loop`]: Statement::Loop
+/// [`Return`]: Statement::Return
+/// [`Switch`]: Statement::Switch
+/// [`init`]: LocalVariable::init
+/// [`compact`]: super::compact
+pub fn inline(module: &mut crate::Module, mode: InlineMode) {
+    let handles = module
+        .functions
+        .iter()
+        .map(|(handle, _)| handle)
+        .collect::<Vec<_>>();
+
+    // Functions only call the ones before them in the arena, so each one is
+    // complete by the time its callers are processed.
+    let mut inlined = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let mut function = std::mem::take(&mut module.functions[handle]);
+        inline_calls(
+            &mut function,
+            &mut CallCollector {
+                functions: &module.functions,
+                inlined: &inlined,
+                types: &mut module.types,
+                constants: &mut module.constants,
+                calls: Vec::new(),
+            },
+        );
+        inlined.push(should_inline(&function, &module.types, mode));
+        module.functions[handle] = function;
+    }
+
+    for ep in module.entry_points.iter_mut() {
+        inline_calls(
+            &mut ep.function,
+            &mut CallCollector {
+                functions: &module.functions,
+                inlined: &inlined,
+                types: &mut module.types,
+                constants: &mut module.constants,
+                calls: Vec::new(),
+            },
+        );
+    }
+}
+
+fn should_inline(function: &Function, types: &UniqueArena<Type>, mode: InlineMode) -> bool {
+    if !returns_outside_breakable(&function.body, false) {
+        return false;
+    }
+    // The value of a call comes from the trailing `Return`, or from the
+    // local variable early `Return`s store to.
+    if function.result.is_some() && !returns_value(&function.body) {
+        return false;
+    }
+    match mode {
+        InlineMode::All => true,
+        InlineMode::Heuristic => {
+            let has_pointer_argument =
+                function
+                    .arguments
+                    .iter()
+                    .any(|argument| match types[argument.ty].inner {
+                        TypeInner::Pointer { .. } | TypeInner::ValuePointer { .. } => true,
+                        _ => false,
+                    });
+            has_pointer_argument || block_size(&function.body) <= SMALL_FUNCTION_SIZE
+        }
+    }
+}
+
+/// Return `true` if none of the [`Return`]s in `block` is within a [`Loop`]
+/// or [`Switch`], which `breakable` tells `block` itself is.
+///
+/// [`Return`]: Statement::Return
+/// [`Loop`]: Statement::Loop
+/// [`Switch`]: Statement::Switch
+fn returns_outside_breakable(block: &Block, breakable: bool) -> bool {
+    block.iter().all(|statement| match *statement {
+        Statement::Return { .. } => !breakable,
+        Statement::Block(ref block) => returns_outside_breakable(block, breakable),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => {
+            returns_outside_breakable(accept, breakable)
+                && returns_outside_breakable(reject, breakable)
+        }
+        Statement::Switch { ref cases, .. } => cases
+            .iter()
+            .all(|case| returns_outside_breakable(&case.body, true)),
+        Statement::Loop {
+            ref body,
+            ref continuing,
+        } => returns_outside_breakable(body, true) && returns_outside_breakable(continuing, true),
+        _ => true,
+    })
+}
+
+/// Return `true` if `block` holds a [`Return`], at any depth.
+///
+/// [`Return`]: Statement::Return
+fn contains_return(block: &Block) -> bool {
+    !returns_outside_breakable(block, true)
+}
+
+/// Return `true` if the body of a function, `block`, ends with a [`Return`]
+/// of a value, or returns early.
+///
+/// [`Return`]: Statement::Return
+fn returns_value(block: &Block) -> bool {
+    match block.last() {
+        Some(&Statement::Return { value }) => value.is_some(),
+        _ => contains_return(block),
+    }
+}
+
+/// Return a constant holding the zero value of `ty`, or `None` if the type
+/// has none, like images or runtime-sized arrays.
+fn zero_constant(
+    ty: Handle<Type>,
+    types: &mut UniqueArena<Type>,
+    constants: &mut Arena<Constant>,
+) -> Option<Handle<Constant>> {
+    let zero_scalar = |kind, width| ConstantInner::Scalar {
+        width,
+        value: match kind {
+            ScalarKind::Sint => ScalarValue::Sint(0),
+            ScalarKind::Uint => ScalarValue::Uint(0),
+            ScalarKind::Float => ScalarValue::Float(0.0),
+            ScalarKind::Bool => ScalarValue::Bool(false),
+        },
+    };
+    let inner = match types[ty].inner.clone() {
+        TypeInner::Scalar { kind, width } => zero_scalar(kind, width),
+        TypeInner::Vector { size, kind, width } => {
+            let component = append_constant(constants, zero_scalar(kind, width));
+            ConstantInner::Composite {
+                ty,
+                components: vec![component; size as usize],
+            }
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => {
+            let column_ty = types.insert(
+                Type {
+                    name: None,
+                    inner: TypeInner::Vector {
+                        size: rows,
+                        kind: ScalarKind::Float,
+                        width,
+                    },
+                },
+                Span::default(),
+            );
+            let column = zero_constant(column_ty, types, constants)?;
+            ConstantInner::Composite {
+                ty,
+                components: vec![column; columns as usize],
+            }
+        }
+        TypeInner::Array {
+            base,
+            size: crate::ArraySize::Constant(size),
+            ..
+        } => {
+            let length = constants[size].to_array_length()?;
+            let element = zero_constant(base, types, constants)?;
+            ConstantInner::Composite {
+                ty,
+                components: vec![element; length as usize],
+            }
+        }
+        TypeInner::Struct { members, .. } => {
+            let components = members
+                .iter()
+                .map(|member| zero_constant(member.ty, types, constants))
+                .collect::<Option<_>>()?;
+            ConstantInner::Composite { ty, components }
+        }
+        _ => return None,
+    };
+    Some(append_constant(constants, inner))
+}
+
+fn append_constant(constants: &mut Arena<Constant>, inner: ConstantInner) -> Handle<Constant> {
+    constants.fetch_or_append(
+        Constant {
+            name: None,
+            specialization: None,
+            inner,
+        },
+        Span::default(),
+    )
+}
+
+fn block_size(block: &Block) -> usize {
+    block
+        .iter()
+        .map(|statement| match *statement {
+            Statement::Emit(ref range) => range.clone().count(),
+            Statement::Block(ref block) => 1 + block_size(block),
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => 1 + block_size(accept) + block_size(reject),
+            Statement::Switch { ref cases, .. } => {
+                1 + cases
+                    .iter()
+                    .map(|case| block_size(&case.body))
+                    .sum::<usize>()
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+            } => 1 + block_size(body) + block_size(continuing),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// A call to inline.
+struct CallSite {
+    function: Handle<Function>,
+    arguments: Vec<Handle<Expression>>,
+    result: Option<Handle<Expression>>,
+    /// If the call is within a loop, the values the local variables of the
+    /// called function are initialized to again on each iteration.
+    local_inits: Option<Vec<Handle<Constant>>>,
+    span: Span,
+}
+
+/// Collects the calls of a function, in statement order, with the ones to
+/// inline as `Some`.
+struct CallCollector<'a> {
+    functions: &'a Arena<Function>,
+    /// Whether to inline the calls to each function, indexed by handle index.
+    inlined: &'a [bool],
+    /// The types and constants of the module, which the zero values of local
+    /// variables are added to.
+    types: &'a mut UniqueArena<Type>,
+    constants: &'a mut Arena<Constant>,
+    calls: Vec<Option<CallSite>>,
+}
+
+impl CallCollector<'_> {
+    fn collect(&mut self, block: &Block, in_loop: bool) {
+        for (statement, &span) in block.span_iter() {
+            match *statement {
+                Statement::Call {
+                    function,
+                    ref arguments,
+                    result,
+                } => {
+                    let call = self.call_site(function, arguments, result, in_loop, span);
+                    self.calls.push(call);
+                }
+                Statement::Block(ref block) => self.collect(block, in_loop),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    self.collect(accept, in_loop);
+                    self.collect(reject, in_loop);
+                }
+                Statement::Switch { ref cases, .. } => {
+                    for case in cases.iter() {
+                        self.collect(&case.body, in_loop);
+                    }
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    self.collect(body, true);
+                    self.collect(continuing, true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Return the call to inline, or `None` if it is left alone.
+    fn call_site(
+        &mut self,
+        function: Handle<Function>,
+        arguments: &[Handle<Expression>],
+        result: Option<Handle<Expression>>,
+        in_loop: bool,
+        span: Span,
+    ) -> Option<CallSite> {
+        if !self.inlined.get(function.index()).cloned().unwrap_or(false) {
+            return None;
+        }
+        let callee = &self.functions[function];
+        if let Some(result) = result {
+            // The arguments become operands of the inlined expressions,
+            // which take the place of the result.
+            if callee.result.is_none() || arguments.iter().any(|&argument| argument >= result) {
+                return None;
+            }
+        }
+        let local_inits = if in_loop {
+            let mut inits = Vec::with_capacity(callee.local_variables.len());
+            for (_, local) in callee.local_variables.iter() {
+                let init = match local.init {
+                    Some(init) => init,
+                    None => zero_constant(local.ty, self.types, self.constants)?,
+                };
+                inits.push(init);
+            }
+            Some(inits)
+        } else {
+            None
+        };
+        Some(CallSite {
+            function,
+            arguments: arguments.to_vec(),
+            result,
+            local_inits,
+            span,
+        })
+    }
+}
+
+/// Inline the calls of `function` that `collector` selects.
+fn inline_calls(function: &mut Function, collector: &mut CallCollector) {
+    collector.collect(&function.body, false);
+    let calls = std::mem::take(&mut collector.calls);
+    if calls.iter().all(Option::is_none) {
+        return;
+    }
+    let functions = collector.functions;
+
+    let results = calls
+        .iter()
+        .enumerate()
+        .filter_map(|(index, call)| {
+            call.as_ref()
+                .and_then(|call| call.result)
+                .map(|result| (result, index))
+        })
+        .collect::<FastHashMap<_, _>>();
+    let mut bodies = calls.iter().map(|_| None).collect::<Vec<Option<Block>>>();
+
+    // Rebuild the expression arena, with the expressions of each inlined
+    // function in place of the result of the call.
+    let old_expressions = std::mem::take(&mut function.expressions);
+    let mut expressions = HandleMap::new(old_expressions.len());
+    let mut named_expressions = FastHashMap::default();
+    for (handle, expression) in old_expressions.iter() {
+        if let Some(&index) = results.get(&handle) {
+            let call = calls[index].as_ref().unwrap();
+            let (body, value) = inline_call(
+                function,
+                &functions[call.function],
+                call,
+                &expressions,
+                &mut named_expressions,
+            );
+            // `should_inline` only selects functions returning a value on
+            // every path, but leave the call alone otherwise.
+            if let Some(value) = value {
+                expressions.insert(handle, value);
+                bodies[index] = Some(body);
+                continue;
+            }
+        }
+        let mut expression = expression.clone();
+        expression.visit_handles_mut(&mut ExpressionMap(&expressions));
+        let new_handle = function
+            .expressions
+            .append(expression, old_expressions.get_span(handle));
+        expressions.insert(handle, new_handle);
+    }
+
+    // The calls without results only need their arguments.
+    for (call, body) in calls.iter().zip(bodies.iter_mut()) {
+        if let Some(ref call) = *call {
+            if call.result.is_none() {
+                let (inlined_body, _) = inline_call(
+                    function,
+                    &functions[call.function],
+                    call,
+                    &expressions,
+                    &mut named_expressions,
+                );
+                *body = Some(inlined_body);
+            }
+        }
+    }
+
+    for (handle, name) in std::mem::take(&mut function.named_expressions) {
+        if let Some(handle) = expressions.get(handle) {
+            named_expressions.insert(handle, name);
+        }
+    }
+    function.named_expressions = named_expressions;
+
+    let mut rewriter = BlockRewriter {
+        expressions: &expressions,
+        local_variables: None,
+        first_new_expression: 0,
+        returns: None,
+        calls: bodies.into_iter(),
+    };
+    function.body = rewriter.rewrite(&function.body);
+}
+
+/// Append the local variables and expressions of `callee` to `caller` for
+/// `call`, with `caller_expressions` mapping the handles of the caller's
+/// expressions before the call to their new handles.
+///
+/// Return the statements replacing the call, and the expression holding
+/// the returned value, if any.
+fn inline_call(
+    caller: &mut Function,
+    callee: &Function,
+    call: &CallSite,
+    caller_expressions: &HandleMap<Expression>,
+    named_expressions: &mut crate::NamedExpressions,
+) -> (Block, Option<Handle<Expression>>) {
+    let span = call.span;
+    let arguments = call
+        .arguments
+        .iter()
+        .map(|&argument| {
+            caller_expressions
+                .get(argument)
+                .expect("call argument after the call")
+        })
+        .collect::<Vec<_>>();
+
+    let mut local_variables = HandleMap::new(callee.local_variables.len());
+    for (handle, local) in callee.local_variables.iter() {
+        let new_handle = caller
+            .local_variables
+            .append(local.clone(), callee.local_variables.get_span(handle));
+        local_variables.insert(handle, new_handle);
+    }
+
+    let first_new_expression = caller.expressions.len();
+    let mut expressions = HandleMap::new(callee.expressions.len());
+    for (handle, expression) in callee.expressions.iter() {
+        let new_handle = match *expression {
+            Expression::FunctionArgument(index) => arguments[index as usize],
+            _ => {
+                let mut expression = expression.clone();
+                expression.visit_handles_mut(&mut BlockRewriter {
+                    expressions: &expressions,
+                    local_variables: Some(&local_variables),
+                    first_new_expression,
+                    returns: None,
+                    calls: Vec::new().into_iter(),
+                });
+                caller
+                    .expressions
+                    .append(expression, callee.expressions.get_span(handle))
+            }
+        };
+        expressions.insert(handle, new_handle);
+    }
+    for (&handle, name) in callee.named_expressions.iter() {
+        if let Some(new_handle) = expressions.get(handle) {
+            if new_handle.index() >= first_new_expression {
+                named_expressions
+                    .entry(new_handle)
+                    .or_insert_with(|| name.clone());
+            }
+        }
+    }
+
+    let mut body = Block::new();
+    if let Some(ref local_inits) = call.local_inits {
+        for ((handle, _), &init) in callee.local_variables.iter().zip(local_inits.iter()) {
+            let local = local_variables.get(handle).unwrap();
+            let pointer = caller
+                .expressions
+                .append(Expression::LocalVariable(local), span);
+            let value = caller.expressions.append(Expression::Constant(init), span);
+            body.push(Statement::Store { pointer, value }, span);
+        }
+    }
+
+    let mut trimmed_body = callee.body.clone();
+    let mut tail_value = None;
+    if let Some(&Statement::Return { value }) = trimmed_body.last() {
+        tail_value = value;
+        let len = trimmed_body.len();
+        trimmed_body.cull(len - 1..);
+    }
+
+    if contains_return(&trimmed_body) {
+        // Return by storing the value and breaking out of a loop.
+        let result_pointer = callee.result.as_ref().map(|result| {
+            let local = caller.local_variables.append(
+                LocalVariable {
+                    name: None,
+                    ty: result.ty,
+                    init: None,
+                },
+                span,
+            );
+            caller
+                .expressions
+                .append(Expression::LocalVariable(local), span)
+        });
+        let mut rewriter = BlockRewriter {
+            expressions: &expressions,
+            local_variables: Some(&local_variables),
+            first_new_expression,
+            returns: Some(result_pointer),
+            calls: Vec::new().into_iter(),
+        };
+        let mut loop_body = rewriter.rewrite(&callee.body);
+        match loop_body.last() {
+            Some(&Statement::Break) | Some(&Statement::Kill) => {}
+            _ => loop_body.push(Statement::Break, span),
+        }
+        body.push(
+            Statement::Loop {
+                body: loop_body,
+                continuing: Block::new(),
+            },
+            span,
+        );
+
+        let value = result_pointer.map(|pointer| {
+            let load = caller
+                .expressions
+                .append(Expression::Load { pointer }, span);
+            body.push(Statement::Emit(Range::new_from_bounds(load, load)), span);
+            load
+        });
+        (body, value)
+    } else {
+        let mut rewriter = BlockRewriter {
+            expressions: &expressions,
+            local_variables: Some(&local_variables),
+            first_new_expression,
+            returns: None,
+            calls: Vec::new().into_iter(),
+        };
+        let inlined = rewriter.rewrite(&trimmed_body);
+        body.extend_block(inlined);
+        (body, tail_value.and_then(|value| expressions.get(value)))
+    }
+}
+
+/// Adjusts the expression handles of the caller's expressions.
+struct ExpressionMap<'a>(&'a HandleMap<Expression>);
+
+impl Mutator for ExpressionMap<'_> {
+    fn visit_expression(&mut self, handle: &mut Handle<Expression>) {
+        self.0.adjust(handle);
+    }
+}
+
+/// Copies a block of statements into the function calls are inlined into.
+struct BlockRewriter<'a> {
+    expressions: &'a HandleMap<Expression>,
+    /// The map of the local variables, for the body of an inlined function.
+    local_variables: Option<&'a HandleMap<LocalVariable>>,
+    /// The expressions with handles at least this index are emitted by the
+    /// rewritten block, as opposed to the arguments of an inlined call.
+    first_new_expression: usize,
+    /// For the body of a function returning early, the pointer its returned
+    /// value is stored to, if any. Its [`Return`]s become [`Break`]s.
+    ///
+    /// [`Return`]: Statement::Return
+    /// [`Break`]: Statement::Break
+    returns: Option<Option<Handle<Expression>>>,
+    /// The statements replacing each call, in statement order.
+    calls: std::vec::IntoIter<Option<Block>>,
+}
+
+impl Mutator for BlockRewriter<'_> {
+    fn visit_expression(&mut self, handle: &mut Handle<Expression>) {
+        self.expressions.adjust(handle);
+    }
+
+    fn visit_local_variable(&mut self, handle: &mut Handle<LocalVariable>) {
+        if let Some(local_variables) = self.local_variables {
+            local_variables.adjust(handle);
+        }
+    }
+}
+
+impl BlockRewriter<'_> {
+    fn rewrite(&mut self, block: &Block) -> Block {
+        let mut new_block = Block::with_capacity(block.len());
+        for (statement, &span) in block.span_iter() {
+            match *statement {
+                Statement::Emit(ref range) => self.rewrite_emit(range, span, &mut new_block),
+                Statement::Block(ref block) => {
+                    new_block.push(Statement::Block(self.rewrite(block)), span)
+                }
+                Statement::If {
+                    mut condition,
+                    ref accept,
+                    ref reject,
+                } => {
+                    self.visit_expression(&mut condition);
+                    let accept = self.rewrite(accept);
+                    let reject = self.rewrite(reject);
+                    new_block.push(
+                        Statement::If {
+                            condition,
+                            accept,
+                            reject,
+                        },
+                        span,
+                    );
+                }
+                Statement::Switch {
+                    mut selector,
+                    ref cases,
+                } => {
+                    self.visit_expression(&mut selector);
+                    let cases = cases
+                        .iter()
+                        .map(|case| crate::SwitchCase {
+                            value: case.value.clone(),
+                            body: self.rewrite(&case.body),
+                            fall_through: case.fall_through,
+                        })
+                        .collect();
+                    new_block.push(Statement::Switch { selector, cases }, span);
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    let body = self.rewrite(body);
+                    let continuing = self.rewrite(continuing);
+                    new_block.push(Statement::Loop { body, continuing }, span);
+                }
+                Statement::Return { value } if self.returns.is_some() => {
+                    if let (Some(Some(pointer)), Some(mut value)) = (self.returns, value) {
+                        self.visit_expression(&mut value);
+                        new_block.push(Statement::Store { pointer, value }, span);
+                    }
+                    new_block.push(Statement::Break, span);
+                }
+                Statement::Call { .. } => match self.calls.next().flatten() {
+                    Some(inlined) => new_block.extend_block(inlined),
+                    None => {
+                        let mut statement = statement.clone();
+                        statement.visit_handles_mut(self);
+                        new_block.push(statement, span);
+                    }
+                },
+                _ => {
+                    let mut statement = statement.clone();
+                    statement.visit_handles_mut(self);
+                    new_block.push(statement, span);
+                }
+            }
+        }
+        new_block
+    }
+
+    /// Push the [`Emit`] statements covering the new expressions of `range`.
+    ///
+    /// [`Emit`]: Statement::Emit
+    fn rewrite_emit(&self, range: &Range<Expression>, span: Span, block: &mut Block) {
+        let mut current: Option<(Handle<Expression>, Handle<Expression>)> = None;
+        for handle in range.clone() {
+            let new_handle = match self.expressions.get(handle) {
+                Some(new_handle) if new_handle.index() >= self.first_new_expression => new_handle,
+                _ => continue,
+            };
+            current = match current {
+                Some((first, last)) if last.index() + 1 == new_handle.index() => {
+                    Some((first, new_handle))
+                }
+                Some((first, last)) => {
+                    block.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+                    Some((new_handle, new_handle))
+                }
+                None => Some((new_handle, new_handle)),
+            };
+        }
+        if let Some((first, last)) = current {
+            block.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{inline, InlineMode};
+    use crate::{ConstantInner, Expression, ScalarValue, Statement};
+
+    const SOURCE: &str = "
+        fn small(x: f32) -> f32 {
+            return x * 2.0;
+        }
+
+        fn large(x: f32) -> f32 {
+            var y = x;
+            y = y + 1.0;
+            y = y * y;
+            y = y - 3.0;
+            y = y / 4.0;
+            y = y + 5.0;
+            y = y * 6.0;
+            return y;
+        }
+
+        fn sign_of(x: f32) -> f32 {
+            if (x < 0.0) {
+                return -1.0;
+            }
+            return 1.0;
+        }
+
+        @stage(fragment)
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            return vec4<f32>(small(x), large(x), sign_of(x), 1.0);
+        }
+    ";
+
+    fn call_count(block: &crate::Block) -> usize {
+        block
+            .iter()
+            .map(|statement| match *statement {
+                Statement::Call { .. } => 1,
+                Statement::Loop { ref body, .. } => call_count(body),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn inline_source(source: &str, mode: InlineMode) -> crate::Module {
+        crate::proc::run_pass(source, |module| inline(module, mode))
+    }
+
+    #[test]
+    fn heuristic() {
+        let module = inline_source(SOURCE, InlineMode::Heuristic);
+        let body = &module.entry_points[0].function.body;
+        // Only the call to `large` is left.
+        assert_eq!(call_count(body), 1);
+        assert!(body.iter().any(|statement| match *statement {
+            Statement::Call { function, .. } => {
+                module.functions[function].name.as_deref() == Some("large")
+            }
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn all() {
+        let module = inline_source(SOURCE, InlineMode::All);
+        let function = &module.entry_points[0].function;
+        assert_eq!(call_count(&function.body), 0);
+        // `sign_of` returns early, so its result goes through a local.
+        assert!(function.body.iter().any(|statement| match *statement {
+            Statement::Loop { .. } => true,
+            _ => false,
+        }));
+        assert_eq!(function.local_variables.len(), 2);
+    }
+
+    #[test]
+    fn locals_in_loop() {
+        let module = inline_source(
+            "
+            fn accumulate(x: f32) -> f32 {
+                var total: f32;
+                var scale = 2.0;
+                total = total + x * scale;
+                scale = scale + 1.0;
+                return total;
+            }
+
+            @stage(fragment)
+            fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+                var sum = 0.0;
+                var i = 0;
+                loop {
+                    if (i >= 4) {
+                        break;
+                    }
+                    sum = sum + accumulate(x);
+                    i = i + 1;
+                }
+                return vec4<f32>(sum);
+            }
+            ",
+            InlineMode::All,
+        );
+        let function = &module.entry_points[0].function;
+        let body = function
+            .body
+            .iter()
+            .find_map(|statement| match *statement {
+                Statement::Loop { ref body, .. } => Some(body),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(call_count(body), 0);
+
+        // Both locals of `accumulate` are initialized on each iteration,
+        // `total` to zero.
+        let inits = body
+            .iter()
+            .filter_map(|statement| match *statement {
+                Statement::Store { pointer, value } => {
+                    match (&function.expressions[pointer], &function.expressions[value]) {
+                        (&Expression::LocalVariable(local), &Expression::Constant(constant)) => {
+                            Some((local, &module.constants[constant].inner))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(inits.len(), 2);
+        assert_eq!(
+            function.local_variables[inits[0].0].name.as_deref(),
+            Some("total")
+        );
+        assert_eq!(
+            *inits[0].1,
+            ConstantInner::Scalar {
+                width: 4,
+                value: ScalarValue::Float(0.0),
+            }
+        );
+        assert_eq!(
+            function.local_variables[inits[1].0].name.as_deref(),
+            Some("scale")
+        );
+    }
+
+    #[test]
+    fn malformed_call() {
+        let mut module = crate::front::wgsl::parse_str(SOURCE).unwrap();
+        // `small` no longer returns a value, but is still called for one.
+        let (small, _) = module
+            .functions
+            .iter()
+            .find(|&(_, function)| function.name.as_deref() == Some("small"))
+            .unwrap();
+        module.functions[small].result = None;
+        inline(&mut module, InlineMode::All);
+        assert_eq!(call_count(&module.entry_points[0].function.body), 1);
+    }
+}
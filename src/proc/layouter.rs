@@ -202,7 +202,11 @@ impl Layouter {
                         alignment,
                     }
                 }
-                Ti::Image { .. } | Ti::Sampler { .. } => TypeLayout {
+                Ti::Image { .. }
+                | Ti::Sampler { .. }
+                | Ti::BindingArray { .. }
+                | Ti::AccelerationStructure
+                | Ti::RayQuery => TypeLayout {
                     size,
                     alignment: Alignment::new(1).unwrap(),
                 },
@@ -0,0 +1,523 @@
+//! Merging of several modules into one.
+//!
+//! Shaders often share utility functions and resource declarations that live
+//! in separate sources. Rather than concatenating source text, each source
+//! can be translated to a [`Module`] on its own, and [`link`] combines them.
+//!
+//! [`Module`]: crate::Module
+
+use super::{Mutator, Visitor};
+use crate::{
+    arena::{Handle, HandleMap},
+    Constant, ConstantInner, FastHashMap, Function, GlobalVariable, Module, Span, Type, WithSpan,
+};
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum LinkError {
+    #[error("Function '{0}' is defined more than once")]
+    DuplicateFunction(String),
+    #[error("Function '{0}' is declared with conflicting signatures")]
+    ConflictingFunction(String),
+    #[error("Constant '{0}' is declared with conflicting values")]
+    ConflictingConstant(String),
+    #[error("Global variable '{0}' is declared with conflicting types or bindings")]
+    ConflictingGlobalVariable(String),
+    #[error("Entry point '{name}' of the {stage:?} stage is defined more than once")]
+    DuplicateEntryPoint {
+        stage: crate::ShaderStage,
+        name: String,
+    },
+}
+
+/// Combine `modules` into a single module.
+///
+/// Types are deduplicated. Named constants and global variables that are
+/// declared in several modules are merged if the declarations are identical
+/// once their handles are adjusted, and reported as conflicting otherwise.
+/// Functions are merged by name: a named function with an empty body, such as
+/// the ones the GLSL front end produces for prototypes, is a declaration, and
+/// calls to it are resolved to the definition of the same name in any of the
+/// modules. Declarations without a definition are kept.
+///
+/// The functions of the result are ordered so that callees come before their
+/// callers, and every handle is adjusted throughout.
+///
+/// The spans of the returned errors refer to the sources of different
+/// modules, so their descriptions name the index of the module in `modules`.
+pub fn link(modules: &[Module]) -> Result<Module, WithSpan<LinkError>> {
+    let mut linker = Linker::default();
+    for (index, module) in modules.iter().enumerate() {
+        linker.add_module(index, module)?;
+    }
+    linker.finish()
+}
+
+/// A function of one of the linked modules, with all its handles but the
+/// ones to other functions adjusted for the linked module.
+struct LinkedFunction {
+    function: Function,
+    span: Span,
+    module_index: usize,
+}
+
+impl LinkedFunction {
+    fn is_declaration(&self) -> bool {
+        self.function.name.is_some() && self.function.body.is_empty()
+    }
+
+    fn signature_matches(&self, other: &Self) -> bool {
+        let a = &self.function;
+        let b = &other.function;
+        a.arguments.len() == b.arguments.len()
+            && a.arguments
+                .iter()
+                .zip(b.arguments.iter())
+                .all(|(a, b)| a.ty == b.ty)
+            && a.result.as_ref().map(|r| r.ty) == b.result.as_ref().map(|r| r.ty)
+    }
+}
+
+#[derive(Default)]
+struct Linker {
+    module: Module,
+    constant_names: FastHashMap<String, (Handle<Constant>, usize)>,
+    global_names: FastHashMap<String, (Handle<GlobalVariable>, usize)>,
+    /// All the functions of the linked modules, in order. Calls refer to
+    /// them by index until [`Linker::finish`] orders them.
+    functions: Vec<LinkedFunction>,
+    /// The first definition, or failing that the first declaration, of every
+    /// function name.
+    function_names: FastHashMap<String, usize>,
+    /// The index of the module of every entry point of [`Linker::module`].
+    entry_point_modules: Vec<usize>,
+}
+
+/// The handles of a linked module's items in the combined module, indexed
+/// by the items' original handles.
+struct ModuleMap {
+    types: HandleMap<Type>,
+    constants: HandleMap<Constant>,
+    global_variables: HandleMap<GlobalVariable>,
+    /// Indices into [`Linker::functions`], as handles.
+    functions: HandleMap<Function>,
+}
+
+fn module_label(module_index: usize) -> String {
+    format!("in module {}", module_index)
+}
+
+fn entry_point_span(ep: &crate::EntryPoint) -> Span {
+    Span::total_span(ep.function.body.span_iter().map(|(_, &span)| span))
+}
+
+impl Linker {
+    fn add_module(
+        &mut self,
+        module_index: usize,
+        module: &Module,
+    ) -> Result<(), WithSpan<LinkError>> {
+        let mut map = ModuleMap {
+            types: HandleMap::new(module.types.len()),
+            constants: HandleMap::new(module.constants.len()),
+            global_variables: HandleMap::new(module.global_variables.len()),
+            functions: HandleMap::new(module.functions.len()),
+        };
+
+        // Array sizes refer to scalar constants, and composite constants
+        // refer to types, so the scalars go first.
+        for (handle, constant) in module.constants.iter() {
+            if let ConstantInner::Scalar { .. } = constant.inner {
+                let span = module.constants.get_span(handle);
+                let new_handle = self.add_constant(module_index, constant.clone(), span)?;
+                map.constants.insert(handle, new_handle);
+            }
+        }
+
+        for (handle, ty) in module.types.iter() {
+            let mut ty = ty.clone();
+            ty.inner.visit_handles_mut(&mut map);
+            let span = module.types.get_span(handle);
+            map.types.insert(handle, self.module.types.insert(ty, span));
+        }
+
+        for (handle, constant) in module.constants.iter() {
+            if let ConstantInner::Composite { .. } = constant.inner {
+                let mut constant = constant.clone();
+                constant.inner.visit_handles_mut(&mut map);
+                let span = module.constants.get_span(handle);
+                let new_handle = self.add_constant(module_index, constant, span)?;
+                map.constants.insert(handle, new_handle);
+            }
+        }
+
+        for (handle, var) in module.global_variables.iter() {
+            let mut var = var.clone();
+            map.types.adjust(&mut var.ty);
+            map.constants.adjust_option(&mut var.init);
+            let span = module.global_variables.get_span(handle);
+            let new_handle = self.add_global_variable(module_index, var, span)?;
+            map.global_variables.insert(handle, new_handle);
+        }
+
+        let first_function = self.functions.len();
+        for (handle, _) in module.functions.iter() {
+            let index = first_function + handle.index();
+            map.functions.insert(handle, Handle::from_usize(index));
+        }
+        for (handle, function) in module.functions.iter() {
+            let mut function = function.clone();
+            map.adjust_function(&mut function);
+            let linked = LinkedFunction {
+                function,
+                span: module.functions.get_span(handle),
+                module_index,
+            };
+            self.add_function(linked)?;
+        }
+
+        for ep in module.entry_points.iter() {
+            if let Some((other, &other_index)) = self
+                .module
+                .entry_points
+                .iter()
+                .zip(self.entry_point_modules.iter())
+                .find(|&(other, _)| other.stage == ep.stage && other.name == ep.name)
+            {
+                return Err(WithSpan::new(LinkError::DuplicateEntryPoint {
+                    stage: other.stage,
+                    name: other.name.clone(),
+                })
+                .with_span(entry_point_span(other), module_label(other_index))
+                .with_span(entry_point_span(ep), module_label(module_index)));
+            }
+            let mut ep = ep.clone();
+            if let Some(ref mut overrides) = ep.workgroup_size_overrides {
+                for constant in overrides.iter_mut() {
+                    map.constants.adjust_option(constant);
+                }
+            }
+            if let Some(ref mut mesh_info) = ep.mesh_info {
+                map.types.adjust(&mut mesh_info.vertex_output_type);
+                map.types.adjust(&mut mesh_info.primitive_output_type);
+            }
+            map.adjust_function(&mut ep.function);
+            self.module.entry_points.push(ep);
+            self.entry_point_modules.push(module_index);
+        }
+
+        Ok(())
+    }
+
+    fn add_constant(
+        &mut self,
+        module_index: usize,
+        constant: Constant,
+        span: Span,
+    ) -> Result<Handle<Constant>, WithSpan<LinkError>> {
+        let name = match constant.name {
+            Some(ref name) => name.clone(),
+            None => return Ok(self.module.constants.fetch_or_append(constant, span)),
+        };
+        if let Some(&(handle, other_index)) = self.constant_names.get(&name) {
+            if self.module.constants[handle] == constant {
+                return Ok(handle);
+            }
+            return Err(WithSpan::new(LinkError::ConflictingConstant(name))
+                .with_span(
+                    self.module.constants.get_span(handle),
+                    module_label(other_index),
+                )
+                .with_span(span, module_label(module_index)));
+        }
+        let handle = self.module.constants.append(constant, span);
+        self.constant_names.insert(name, (handle, module_index));
+        Ok(handle)
+    }
+
+    fn add_global_variable(
+        &mut self,
+        module_index: usize,
+        var: GlobalVariable,
+        span: Span,
+    ) -> Result<Handle<GlobalVariable>, WithSpan<LinkError>> {
+        let name = match var.name {
+            Some(ref name) => name.clone(),
+            None => return Ok(self.module.global_variables.append(var, span)),
+        };
+        if let Some(&(handle, other_index)) = self.global_names.get(&name) {
+            if self.module.global_variables[handle] == var {
+                return Ok(handle);
+            }
+            let other_span = self.module.global_variables.get_span(handle);
+            return Err(WithSpan::new(LinkError::ConflictingGlobalVariable(name))
+                .with_span(other_span, module_label(other_index))
+                .with_span(span, module_label(module_index)));
+        }
+        let handle = self.module.global_variables.append(var, span);
+        self.global_names.insert(name, (handle, module_index));
+        Ok(handle)
+    }
+
+    fn add_function(&mut self, linked: LinkedFunction) -> Result<(), WithSpan<LinkError>> {
+        let index = self.functions.len();
+        if let Some(ref name) = linked.function.name {
+            match self.function_names.get(name).cloned() {
+                Some(other_index) => {
+                    let other = &self.functions[other_index];
+                    let error = if !other.signature_matches(&linked) {
+                        Some(LinkError::ConflictingFunction(name.clone()))
+                    } else if !other.is_declaration() && !linked.is_declaration() {
+                        Some(LinkError::DuplicateFunction(name.clone()))
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Err(WithSpan::new(error)
+                            .with_span(other.span, module_label(other.module_index))
+                            .with_span(linked.span, module_label(linked.module_index)));
+                    }
+                    if other.is_declaration() && !linked.is_declaration() {
+                        self.function_names.insert(name.clone(), index);
+                    }
+                }
+                None => {
+                    self.function_names.insert(name.clone(), index);
+                }
+            }
+        }
+        self.functions.push(linked);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Module, WithSpan<LinkError>> {
+        let Linker {
+            mut module,
+            functions,
+            function_names,
+            ..
+        } = self;
+
+        // Every function index refers to the function it resolves to.
+        let resolved: Vec<usize> = functions
+            .iter()
+            .enumerate()
+            .map(|(index, linked)| match linked.function.name {
+                Some(ref name) if linked.is_declaration() => function_names[name],
+                _ => index,
+            })
+            .collect();
+
+        // Order the resolved functions so that callees come first.
+        let mut order = Vec::with_capacity(functions.len());
+        let mut visited = vec![false; functions.len()];
+        for index in 0..functions.len() {
+            if resolved[index] == index {
+                visit_function(index, &functions, &resolved, &mut visited, &mut order);
+            }
+        }
+
+        let mut new_handles = vec![Handle::from_usize(0); functions.len()];
+        for (position, &index) in order.iter().enumerate() {
+            new_handles[index] = Handle::from_usize(position);
+        }
+        let mut calls = CallMap(HandleMap::new(functions.len()));
+        for (index, &target) in resolved.iter().enumerate() {
+            calls
+                .0
+                .insert(Handle::from_usize(index), new_handles[target]);
+        }
+
+        let mut functions: Vec<Option<LinkedFunction>> = functions.into_iter().map(Some).collect();
+        for index in order {
+            let linked = functions[index]
+                .take()
+                .expect("function is ordered only once");
+            let mut function = linked.function;
+            calls.adjust_function(&mut function);
+            module.functions.append(function, linked.span);
+        }
+        for ep in module.entry_points.iter_mut() {
+            calls.adjust_function(&mut ep.function);
+        }
+
+        Ok(module)
+    }
+}
+
+fn visit_function(
+    index: usize,
+    functions: &[LinkedFunction],
+    resolved: &[usize],
+    visited: &mut [bool],
+    order: &mut Vec<usize>,
+) {
+    if visited[index] {
+        return;
+    }
+    visited[index] = true;
+    let mut callees = Callees(Vec::new());
+    functions[index].function.body.visit_handles(&mut callees);
+    for callee in callees.0 {
+        visit_function(
+            resolved[callee.index()],
+            functions,
+            resolved,
+            visited,
+            order,
+        );
+    }
+    order.push(index);
+}
+
+impl Mutator for ModuleMap {
+    fn visit_type(&mut self, handle: &mut Handle<Type>) {
+        self.types.adjust(handle);
+    }
+
+    fn visit_constant(&mut self, handle: &mut Handle<Constant>) {
+        self.constants.adjust(handle);
+    }
+
+    fn visit_global_variable(&mut self, handle: &mut Handle<GlobalVariable>) {
+        self.global_variables.adjust(handle);
+    }
+
+    fn visit_function(&mut self, handle: &mut Handle<Function>) {
+        self.functions.adjust(handle);
+    }
+}
+
+impl ModuleMap {
+    fn adjust_function(&mut self, function: &mut Function) {
+        for argument in function.arguments.iter_mut() {
+            self.types.adjust(&mut argument.ty);
+        }
+        if let Some(ref mut result) = function.result {
+            self.types.adjust(&mut result.ty);
+        }
+        for (_, local) in function.local_variables.iter_mut() {
+            self.types.adjust(&mut local.ty);
+            self.constants.adjust_option(&mut local.init);
+        }
+        for (_, expression) in function.expressions.iter_mut() {
+            expression.visit_handles_mut(self);
+        }
+        function.body.visit_handles_mut(self);
+    }
+}
+
+/// The functions called by a block.
+struct Callees(Vec<Handle<Function>>);
+
+impl Visitor for Callees {
+    fn visit_function(&mut self, handle: Handle<Function>) {
+        self.0.push(handle);
+    }
+}
+
+/// The map from the indices of [`Linker::functions`] to the handles of the
+/// functions they resolve to in the linked module.
+struct CallMap(HandleMap<Function>);
+
+impl Mutator for CallMap {
+    fn visit_function(&mut self, handle: &mut Handle<Function>) {
+        self.0.adjust(handle);
+    }
+}
+
+impl CallMap {
+    fn adjust_function(&mut self, function: &mut Function) {
+        for (_, expression) in function.expressions.iter_mut() {
+            expression.visit_handles_mut(self);
+        }
+        function.body.visit_handles_mut(self);
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in", feature = "glsl-in"))]
+mod tests {
+    use super::{link, LinkError};
+    use crate::proc::validate;
+
+    const LIBRARY: &str = "
+        struct Lighting {
+            ambient: vec4<f32>;
+        };
+        @group(0) @binding(0)
+        var<uniform> lighting: Lighting;
+
+        fn shade(color: vec4<f32>) -> vec4<f32> {
+            return color * lighting.ambient;
+        }
+    ";
+
+    #[test]
+    fn resolve_declaration() {
+        let main = crate::front::glsl::Parser::default()
+            .parse(
+                &crate::front::glsl::Options::from(crate::ShaderStage::Fragment),
+                "
+                #version 450
+                layout(location = 0) out vec4 o_color;
+                vec4 shade(vec4 color);
+                void main() { o_color = shade(vec4(1.0)); }
+                ",
+            )
+            .unwrap();
+        let library = crate::front::wgsl::parse_str(LIBRARY).unwrap();
+
+        let linked = link(&[main, library]).unwrap();
+        let names: Vec<_> = linked
+            .functions
+            .iter()
+            .map(|(_, f)| f.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, ["shade", "main"]);
+        validate(&linked);
+    }
+
+    #[test]
+    fn merge_globals() {
+        let a = crate::front::wgsl::parse_str(LIBRARY).unwrap();
+        let b = crate::front::wgsl::parse_str(&LIBRARY.replace("shade", "tint")).unwrap();
+        let linked = link(&[a, b]).unwrap();
+        assert_eq!(linked.global_variables.len(), 1);
+        assert_eq!(linked.functions.len(), 2);
+        validate(&linked);
+    }
+
+    #[test]
+    fn conflicts() {
+        let library = crate::front::wgsl::parse_str(LIBRARY).unwrap();
+        let error = link(&[library.clone(), library]).unwrap_err();
+        assert!(matches!(
+            error.into_inner(),
+            LinkError::DuplicateFunction(ref name) if name == "shade"
+        ));
+
+        let a = crate::front::wgsl::parse_str("let scale: f32 = 1.0;").unwrap();
+        let b = crate::front::wgsl::parse_str("let scale: f32 = 2.0;").unwrap();
+        let error = link(&[a, b]).unwrap_err();
+        assert!(matches!(
+            error.into_inner(),
+            LinkError::ConflictingConstant(ref name) if name == "scale"
+        ));
+
+        let main = crate::front::wgsl::parse_str(
+            "
+            @stage(fragment)
+            fn main() -> @location(0) vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let error = link(&[main.clone(), main]).unwrap_err();
+        #[cfg(feature = "span")]
+        assert_eq!(error.spans().count(), 2);
+        assert!(matches!(
+            error.into_inner(),
+            LinkError::DuplicateEntryPoint { ref name, .. } if name == "main"
+        ));
+    }
+}
@@ -1,18 +1,44 @@
 //! Module processing functionality.
 
+mod bindings;
+pub mod cfg;
+mod common_subexpressions;
+mod compact;
+mod constant_evaluator;
+mod dead_code;
 pub mod index;
+mod inline;
 mod layouter;
+mod link;
 mod namer;
+mod outputs;
+mod overrides;
+mod ray;
 mod terminator;
 mod typifier;
+mod visit;
 
 use std::cmp::PartialEq;
 
+pub use bindings::{remap_bindings, BindingRemapping, RemapError};
+pub use common_subexpressions::eliminate_common_subexpressions;
+pub use compact::{compact, extract_entry_point, extract_entry_point_with_info, ExtractError};
+pub use constant_evaluator::{fold_constants, ConstantEvaluator, ConstantEvaluatorError};
+pub use dead_code::eliminate_dead_code;
 pub use index::{BoundsCheckPolicies, BoundsCheckPolicy, IndexableLength, IndexableLengthError};
+pub use inline::{inline, InlineMode, SMALL_FUNCTION_SIZE};
 pub use layouter::{Alignment, LayoutError, LayoutErrorInner, Layouter, TypeLayout};
+pub use link::{link, LinkError};
 pub use namer::{EntryPointIndex, NameKey, Namer};
+pub use outputs::strip_unused_outputs;
+pub use overrides::{process_overrides, OverrideError, PipelineConstants};
+#[cfg(feature = "wgsl-in")]
+pub(crate) use ray::{generate_ray_desc_type, generate_ray_intersection_type};
+#[cfg(any(feature = "wgsl-in", feature = "wgsl-out"))]
+pub(crate) use ray::{RAY_DESC_NAME, RAY_INTERSECTION_NAME};
 pub use terminator::ensure_block_returns;
 pub use typifier::{ResolveContext, ResolveError, TypeResolution};
+pub use visit::{Mutator, Visitor};
 
 impl From<super::StorageFormat> for super::ScalarKind {
     fn from(format: super::StorageFormat) -> Self {
@@ -123,7 +149,11 @@ impl super::TypeInner {
                 count * stride
             }
             Self::Struct { span, .. } => span,
-            Self::Image { .. } | Self::Sampler { .. } => 0,
+            Self::Image { .. }
+            | Self::Sampler { .. }
+            | Self::BindingArray { .. }
+            | Self::AccelerationStructure
+            | Self::RayQuery => 0,
         })
     }
 
@@ -354,6 +384,28 @@ impl crate::SampleLevel {
     }
 }
 
+impl crate::ShaderStage {
+    /// Return true if this stage runs in workgroups, and thus has a
+    /// [`workgroup_size`](crate::EntryPoint::workgroup_size).
+    pub fn has_workgroups(self) -> bool {
+        match self {
+            Self::Compute | Self::Task | Self::Mesh => true,
+            Self::Vertex | Self::Fragment => false,
+        }
+    }
+}
+
+impl crate::MeshOutputTopology {
+    /// Return the built-in holding the vertex indices of each primitive.
+    pub fn indices_built_in(self) -> crate::BuiltIn {
+        match self {
+            Self::Points => crate::BuiltIn::PointIndex,
+            Self::Lines => crate::BuiltIn::LineIndices,
+            Self::Triangles => crate::BuiltIn::TriangleIndices,
+        }
+    }
+}
+
 impl crate::Constant {
     /// Interpret this constant as an array length, and return it as a `u32`.
     ///
@@ -439,6 +491,27 @@ impl super::SwizzleComponent {
     }
 }
 
+/// Check that `module`, the result of a pass, is valid.
+#[cfg(test)]
+fn validate(module: &crate::Module) {
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(module)
+    .unwrap();
+}
+
+/// Parse `source`, run `pass` on the module and check that the result is
+/// valid.
+#[cfg(all(test, feature = "wgsl-in"))]
+fn run_pass(source: &str, pass: impl FnOnce(&mut crate::Module)) -> crate::Module {
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    pass(&mut module);
+    validate(&module);
+    module
+}
+
 #[test]
 fn test_matrix_size() {
     let constants = crate::Arena::new();
@@ -0,0 +1,212 @@
+//! Removal of the outputs of an entry point that the next stage doesn't read.
+//!
+//! [`valid::check_stage_interface`] reports the outputs of a vertex shader
+//! that aren't inputs of the fragment shader it is paired with. Drivers accept
+//! them, but still compute and pass them between the stages.
+//! [`strip_unused_outputs`] removes them from the entry point.
+//!
+//! [`valid::check_stage_interface`]: crate::valid::check_stage_interface
+
+use super::{Alignment, Layouter};
+use crate::{
+    arena::Handle, Binding, Block, Expression, Module, Statement, StructMember, Type, TypeInner,
+};
+
+/// Remove the outputs of the entry point at index `entry_point` of `module`
+/// whose [`Location`] isn't in `read_locations`.
+///
+/// The locations the next stage reads are the [`inputs`] of its reflection.
+///
+/// The outputs are removed from the structure the entry point returns: a new
+/// structure type without them is added to the module, and each [`Return`]
+/// statement builds a value of that type from the members of the original
+/// value. The original type is kept, as other functions may use it. Outputs
+/// that aren't in a structure are left alone.
+///
+/// Return the number of outputs removed.
+///
+/// [`Location`]: Binding::Location
+/// [`inputs`]: crate::reflect::EntryPointReflection::inputs
+/// [`Return`]: Statement::Return
+pub fn strip_unused_outputs(
+    module: &mut Module,
+    entry_point: usize,
+    read_locations: &[u32],
+) -> usize {
+    let result_ty = match module.entry_points[entry_point].function.result {
+        Some(ref result) if result.binding.is_none() => result.ty,
+        _ => return 0,
+    };
+    let members = match module.types[result_ty].inner {
+        TypeInner::Struct { ref members, .. } => members,
+        _ => return 0,
+    };
+    let kept = members
+        .iter()
+        .enumerate()
+        .filter(|&(_, member)| match member.binding {
+            Some(Binding::Location { location, .. }) => read_locations.contains(&location),
+            _ => true,
+        })
+        .map(|(index, _)| index as u32)
+        .collect::<Vec<_>>();
+    let removed = members.len() - kept.len();
+    if removed == 0 {
+        return 0;
+    }
+
+    let mut layouter = Layouter::default();
+    layouter
+        .update(&module.types, &module.constants)
+        .expect("layout of a valid module");
+    let mut new_members = Vec::with_capacity(kept.len());
+    let mut offset = 0;
+    let mut struct_alignment = Alignment::new(1).unwrap();
+    for &index in kept.iter() {
+        let member = &members[index as usize];
+        let (range, alignment) = layouter.member_placement(offset, member.ty, None, None);
+        new_members.push(StructMember {
+            offset: range.start,
+            ..member.clone()
+        });
+        offset = range.end;
+        struct_alignment = struct_alignment.max(alignment);
+    }
+    let new_ty = module.types.insert(
+        Type {
+            name: module.types[result_ty].name.clone(),
+            inner: TypeInner::Struct {
+                members: new_members,
+                span: Layouter::round_up(struct_alignment, offset),
+            },
+        },
+        module.types.get_span(result_ty),
+    );
+
+    let function = &mut module.entry_points[entry_point].function;
+    if let Some(ref mut result) = function.result {
+        result.ty = new_ty;
+    }
+    let mut rewriter = ReturnRewriter {
+        expressions: &mut function.expressions,
+        kept: &kept,
+        new_ty,
+    };
+    rewriter.rewrite_block(&mut function.body);
+    removed
+}
+
+/// Makes the [`Return`] statements of an entry point return the structure
+/// with only the kept outputs.
+///
+/// [`Return`]: Statement::Return
+struct ReturnRewriter<'a> {
+    expressions: &'a mut crate::Arena<Expression>,
+    /// The indices of the kept members in the original structure.
+    kept: &'a [u32],
+    new_ty: Handle<Type>,
+}
+
+impl ReturnRewriter<'_> {
+    fn rewrite_block(&mut self, block: &mut Block) {
+        let old = std::mem::take(block);
+        let mut new = Block::with_capacity(old.len());
+        for (mut statement, span) in old.span_into_iter() {
+            match statement {
+                Statement::Return {
+                    value: Some(ref mut value),
+                } => {
+                    let start = self.expressions.len();
+                    let components = self
+                        .kept
+                        .iter()
+                        .map(|&member| {
+                            self.expressions.append(
+                                Expression::AccessIndex {
+                                    base: *value,
+                                    index: member,
+                                },
+                                span,
+                            )
+                        })
+                        .collect();
+                    *value = self.expressions.append(
+                        Expression::Compose {
+                            ty: self.new_ty,
+                            components,
+                        },
+                        span,
+                    );
+                    new.push(Statement::Emit(self.expressions.range_from(start)), span);
+                }
+                Statement::Block(ref mut block) => self.rewrite_block(block),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    self.rewrite_block(accept);
+                    self.rewrite_block(reject);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases.iter_mut() {
+                        self.rewrite_block(&mut case.body);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                } => {
+                    self.rewrite_block(body);
+                    self.rewrite_block(continuing);
+                }
+                _ => {}
+            }
+            new.push(statement, span);
+        }
+        *block = new;
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::strip_unused_outputs;
+
+    #[test]
+    fn strip() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            struct VertexOutput {
+                @builtin(position) position: vec4<f32>;
+                @location(0) color: vec4<f32>;
+                @location(1) normal: vec3<f32>;
+                @location(2) uv: vec2<f32>;
+            };
+
+            @stage(vertex)
+            fn main(@location(0) position: vec4<f32>) -> VertexOutput {
+                if (position.x > 0.0) {
+                    return VertexOutput(position, position, position.xyz, position.xy);
+                }
+                var out: VertexOutput;
+                out.position = position;
+                return out;
+            }
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(strip_unused_outputs(&mut module, 0, &[0, 2]), 1);
+        assert_eq!(strip_unused_outputs(&mut module, 0, &[0, 2]), 0);
+        let result_ty = module.entry_points[0].function.result.as_ref().unwrap().ty;
+        let names = match module.types[result_ty].inner {
+            crate::TypeInner::Struct { ref members, .. } => members
+                .iter()
+                .filter_map(|member| member.name.as_deref())
+                .collect(),
+            _ => Vec::new(),
+        };
+        assert_eq!(names, ["position", "color", "uv"]);
+        crate::proc::validate(&module);
+    }
+}
@@ -0,0 +1,297 @@
+//! Specialization of pipeline-overridable constants.
+//!
+//! Specializable constants, produced from WGSL `override` declarations or
+//! SPIR-V `OpSpecConstant*` instructions, have their values provided when
+//! the pipeline is created. Back ends without a notion of specialization
+//! constants can't express them, so [`process_overrides`] bakes the values
+//! into the module instead.
+
+use crate::{arena::Handle, Bytes, FastHashMap, FastHashSet, ScalarKind, ScalarValue};
+use std::collections::HashMap;
+
+/// Values of pipeline-overridable constants, used by [`process_overrides`].
+///
+/// Each key is either the specialization id of an override, written in
+/// decimal (like `"0"`), or the name of the override.
+pub type PipelineConstants = HashMap<String, ScalarValue>;
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum OverrideError {
+    #[error("Pipeline constant {0:?} doesn't match any override")]
+    UnknownPipelineConstant(String),
+    #[error("Override {0:?} is not a scalar")]
+    NonScalarOverride(Handle<crate::Constant>),
+    #[error("Value {value:?} can't be represented by the type of override {constant:?}")]
+    UnrepresentableValue {
+        constant: Handle<crate::Constant>,
+        value: ScalarValue,
+    },
+    #[error("Override {constant:?} is used as a workgroup size of entry point '{entry_point}', but isn't a positive integer")]
+    InvalidWorkgroupSize {
+        constant: Handle<crate::Constant>,
+        entry_point: String,
+    },
+}
+
+/// Replace the value of every specializable constant in `module` by the one
+/// provided in `pipeline_constants`, if any.
+///
+/// Overrides that have no entry in `pipeline_constants` keep their default
+/// value. Afterwards, no constant in `module` is specializable any more, and
+/// the workgroup sizes of the entry points are updated accordingly. This also
+/// makes arrays sized by overrides acceptable to the validator.
+///
+/// Values are converted to the type of the override they are provided for,
+/// as long as that can be done without loss: for example, `Float(4.0)` is
+/// accepted for a `u32` override, but `Float(4.5)` isn't.
+pub fn process_overrides(
+    module: &mut crate::Module,
+    pipeline_constants: &PipelineConstants,
+) -> Result<(), OverrideError> {
+    // Check everything before touching `module`, so that it is left unchanged
+    // if an error is returned.
+    let mut specialized = FastHashMap::default();
+    let mut used_keys = FastHashSet::default();
+
+    for (handle, constant) in module.constants.iter() {
+        let id = match constant.specialization {
+            Some(id) => id,
+            None => continue,
+        };
+        let (kind, width) = match constant.inner {
+            crate::ConstantInner::Scalar { width, ref value } => (value.scalar_kind(), width),
+            crate::ConstantInner::Composite { .. } => {
+                return Err(OverrideError::NonScalarOverride(handle))
+            }
+        };
+
+        let mut specialized_constant = crate::Constant {
+            specialization: None,
+            ..constant.clone()
+        };
+        let entry = pipeline_constants
+            .get_key_value(&id.to_string())
+            .or_else(|| {
+                constant
+                    .name
+                    .as_ref()
+                    .and_then(|name| pipeline_constants.get_key_value(name))
+            });
+        if let Some((key, &value)) = entry {
+            let converted = convert_scalar_value(value, kind, width).ok_or(
+                OverrideError::UnrepresentableValue {
+                    constant: handle,
+                    value,
+                },
+            )?;
+            specialized_constant.inner = crate::ConstantInner::Scalar {
+                width,
+                value: converted,
+            };
+            used_keys.insert(key.as_str());
+        }
+        specialized.insert(handle, specialized_constant);
+    }
+
+    if let Some(key) = pipeline_constants
+        .keys()
+        .find(|key| !used_keys.contains(key.as_str()))
+    {
+        return Err(OverrideError::UnknownPipelineConstant(key.clone()));
+    }
+
+    let mut workgroup_sizes = Vec::new();
+    for (index, ep) in module.entry_points.iter().enumerate() {
+        let overrides = match ep.workgroup_size_overrides {
+            Some(ref overrides) => overrides,
+            None => continue,
+        };
+        let mut workgroup_size = ep.workgroup_size;
+        for (size, &constant) in workgroup_size.iter_mut().zip(overrides.iter()) {
+            let constant = match constant {
+                Some(constant) => constant,
+                None => continue,
+            };
+            let value = specialized
+                .get(&constant)
+                .unwrap_or(&module.constants[constant])
+                .to_array_length();
+            *size = match value {
+                Some(value) if value != 0 => value,
+                _ => {
+                    return Err(OverrideError::InvalidWorkgroupSize {
+                        constant,
+                        entry_point: ep.name.clone(),
+                    })
+                }
+            };
+        }
+        workgroup_sizes.push((index, workgroup_size));
+    }
+
+    for (handle, constant) in specialized {
+        module.constants[handle] = constant;
+    }
+    for (index, workgroup_size) in workgroup_sizes {
+        let ep = &mut module.entry_points[index];
+        ep.workgroup_size = workgroup_size;
+        ep.workgroup_size_overrides = None;
+    }
+
+    Ok(())
+}
+
+/// Convert `value` to a scalar of the given `kind` and `width`, if it can be
+/// represented exactly.
+fn convert_scalar_value(value: ScalarValue, kind: ScalarKind, width: Bytes) -> Option<ScalarValue> {
+    use std::convert::TryFrom;
+
+    // Every integer of this magnitude (and all the smaller ones) is
+    // representable by an `f64`.
+    const F64_INTEGER_BOUND: f64 = 9_223_372_036_854_775_808.0; // 2^63
+
+    let converted = match kind {
+        ScalarKind::Bool => ScalarValue::Bool(match value {
+            ScalarValue::Bool(v) => v,
+            ScalarValue::Sint(v) => v != 0,
+            ScalarValue::Uint(v) => v != 0,
+            ScalarValue::Float(v) => v != 0.0,
+        }),
+        ScalarKind::Sint => {
+            let v = match value {
+                ScalarValue::Bool(v) => v as i64,
+                ScalarValue::Sint(v) => v,
+                ScalarValue::Uint(v) => i64::try_from(v).ok()?,
+                ScalarValue::Float(v) => {
+                    if v.fract() != 0.0 || !(-F64_INTEGER_BOUND..F64_INTEGER_BOUND).contains(&v) {
+                        return None;
+                    }
+                    v as i64
+                }
+            };
+            if width == 4 && i32::try_from(v).is_err() {
+                return None;
+            }
+            ScalarValue::Sint(v)
+        }
+        ScalarKind::Uint => {
+            let v = match value {
+                ScalarValue::Bool(v) => v as u64,
+                ScalarValue::Sint(v) => u64::try_from(v).ok()?,
+                ScalarValue::Uint(v) => v,
+                ScalarValue::Float(v) => {
+                    if v.fract() != 0.0 || !(0.0..2.0 * F64_INTEGER_BOUND).contains(&v) {
+                        return None;
+                    }
+                    v as u64
+                }
+            };
+            if width == 4 && u32::try_from(v).is_err() {
+                return None;
+            }
+            ScalarValue::Uint(v)
+        }
+        ScalarKind::Float => {
+            let v = match value {
+                ScalarValue::Bool(v) => v as u8 as f64,
+                ScalarValue::Sint(v) => v as f64,
+                ScalarValue::Uint(v) => v as f64,
+                ScalarValue::Float(v) => v,
+            };
+            let max = match width {
+                2 => 65504.0,
+                4 => f32::MAX as f64,
+                _ => f64::MAX,
+            };
+            if v.is_nan() || v.abs() > max {
+                return None;
+            }
+            ScalarValue::Float(if width == 4 { v as f32 as f64 } else { v })
+        }
+    };
+    Some(converted)
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{process_overrides, OverrideError, PipelineConstants};
+    use crate::{ConstantInner, ScalarValue};
+
+    fn scalar_value(module: &crate::Module, name: &str) -> ScalarValue {
+        let constant = module
+            .constants
+            .iter()
+            .map(|(_, c)| c)
+            .find(|c| c.name.as_deref() == Some(name))
+            .unwrap();
+        assert_eq!(constant.specialization, None);
+        match constant.inner {
+            ConstantInner::Scalar { value, .. } => value,
+            ConstantInner::Composite { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn specialize() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            @id(1) override scale: f32 = 1.0;
+            override enabled: bool = false;
+            @id(0) override block_size: u32 = 64u;
+            override count: u32 = 4u;
+            var<workgroup> data: array<f32, count>;
+            @stage(compute) @workgroup_size(block_size, 2)
+            fn main() {}
+            ",
+        )
+        .unwrap();
+
+        let mut constants = PipelineConstants::default();
+        constants.insert("0".to_string(), ScalarValue::Float(128.0));
+        constants.insert("count".to_string(), ScalarValue::Sint(16));
+        constants.insert("enabled".to_string(), ScalarValue::Bool(true));
+        process_overrides(&mut module, &constants).unwrap();
+
+        assert_eq!(scalar_value(&module, "block_size"), ScalarValue::Uint(128));
+        assert_eq!(scalar_value(&module, "count"), ScalarValue::Uint(16));
+        assert_eq!(scalar_value(&module, "enabled"), ScalarValue::Bool(true));
+        assert_eq!(scalar_value(&module, "scale"), ScalarValue::Float(1.0));
+        assert_eq!(module.entry_points[0].workgroup_size, [128, 2, 1]);
+        assert!(module.entry_points[0].workgroup_size_overrides.is_none());
+
+        crate::proc::validate(&module);
+    }
+
+    #[test]
+    fn invalid_values() {
+        let source = "
+            override size: u32 = 1u;
+            @stage(compute) @workgroup_size(size)
+            fn main() {}
+        ";
+        let cases = [
+            ("unknown", ScalarValue::Uint(1)),
+            ("size", ScalarValue::Sint(-1)),
+            ("size", ScalarValue::Float(1.5)),
+            ("size", ScalarValue::Uint(0)),
+        ];
+
+        for &(key, value) in cases.iter() {
+            let mut module = crate::front::wgsl::parse_str(source).unwrap();
+            let mut constants = PipelineConstants::default();
+            constants.insert(key.to_string(), value);
+            let error = process_overrides(&mut module, &constants).unwrap_err();
+            assert!(module.entry_points[0].workgroup_size_overrides.is_some());
+            assert!(module
+                .constants
+                .iter()
+                .all(|(_, c)| c.specialization.is_some()));
+            let expected = match value {
+                _ if key == "unknown" => matches!(error, OverrideError::UnknownPipelineConstant(_)),
+                ScalarValue::Uint(0) => matches!(error, OverrideError::InvalidWorkgroupSize { .. }),
+                _ => matches!(error, OverrideError::UnrepresentableValue { .. }),
+            };
+            assert!(expected, "{:?} for {:?}", error, value);
+        }
+    }
+}
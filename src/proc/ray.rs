@@ -0,0 +1,184 @@
+//! Structure types exchanged with ray queries.
+
+use crate::arena::{Handle, UniqueArena};
+
+pub(crate) const RAY_DESC_NAME: &str = "RayDesc";
+pub(crate) const RAY_INTERSECTION_NAME: &str = "RayIntersection";
+
+fn scalar(kind: crate::ScalarKind) -> crate::TypeInner {
+    crate::TypeInner::Scalar {
+        kind,
+        width: match kind {
+            crate::ScalarKind::Bool => crate::BOOL_WIDTH,
+            _ => 4,
+        },
+    }
+}
+
+fn vector(size: crate::VectorSize) -> crate::TypeInner {
+    crate::TypeInner::Vector {
+        size,
+        kind: crate::ScalarKind::Float,
+        width: 4,
+    }
+}
+
+fn transform() -> crate::TypeInner {
+    crate::TypeInner::Matrix {
+        columns: crate::VectorSize::Quad,
+        rows: crate::VectorSize::Tri,
+        width: 4,
+    }
+}
+
+/// Members of the ray descriptor structure, with their offsets.
+fn ray_desc_members() -> Vec<(&'static str, crate::TypeInner, u32)> {
+    use crate::ScalarKind as Sk;
+    vec![
+        ("flags", scalar(Sk::Uint), 0),
+        ("cull_mask", scalar(Sk::Uint), 4),
+        ("tmin", scalar(Sk::Float), 8),
+        ("tmax", scalar(Sk::Float), 12),
+        ("origin", vector(crate::VectorSize::Tri), 16),
+        ("dir", vector(crate::VectorSize::Tri), 32),
+    ]
+}
+
+/// Members of the ray intersection structure, with their offsets.
+fn ray_intersection_members() -> Vec<(&'static str, crate::TypeInner, u32)> {
+    use crate::ScalarKind as Sk;
+    vec![
+        ("kind", scalar(Sk::Uint), 0),
+        ("t", scalar(Sk::Float), 4),
+        ("instance_custom_index", scalar(Sk::Uint), 8),
+        ("instance_id", scalar(Sk::Uint), 12),
+        ("sbt_record_offset", scalar(Sk::Uint), 16),
+        ("geometry_index", scalar(Sk::Uint), 20),
+        ("primitive_index", scalar(Sk::Uint), 24),
+        ("barycentrics", vector(crate::VectorSize::Bi), 32),
+        ("front_face", scalar(Sk::Bool), 40),
+        ("object_to_world", transform(), 48),
+        ("world_to_object", transform(), 112),
+    ]
+}
+
+fn generate_struct(
+    types: &mut UniqueArena<crate::Type>,
+    name: &str,
+    members: Vec<(&'static str, crate::TypeInner, u32)>,
+    span: u32,
+) -> Handle<crate::Type> {
+    let members = members
+        .into_iter()
+        .map(|(name, inner, offset)| crate::StructMember {
+            name: Some(name.to_string()),
+            ty: types.insert(crate::Type { name: None, inner }, Default::default()),
+            binding: None,
+            offset,
+        })
+        .collect();
+    types.insert(
+        crate::Type {
+            name: Some(name.to_string()),
+            inner: crate::TypeInner::Struct { members, span },
+        },
+        Default::default(),
+    )
+}
+
+fn matches_members(
+    inner: &crate::TypeInner,
+    types: &UniqueArena<crate::Type>,
+    expected: Vec<(&'static str, crate::TypeInner, u32)>,
+) -> bool {
+    match *inner {
+        crate::TypeInner::Struct { ref members, .. } => {
+            members.len() == expected.len()
+                && members
+                    .iter()
+                    .zip(expected)
+                    .all(|(member, (_, inner, _))| types[member.ty].inner == inner)
+        }
+        _ => false,
+    }
+}
+
+/// Add the ray descriptor structure to `types`.
+///
+/// See [`Module::generate_ray_desc_type`] for its layout.
+///
+/// [`Module::generate_ray_desc_type`]: crate::Module::generate_ray_desc_type
+pub(crate) fn generate_ray_desc_type(types: &mut UniqueArena<crate::Type>) -> Handle<crate::Type> {
+    generate_struct(types, RAY_DESC_NAME, ray_desc_members(), 48)
+}
+
+/// Add the ray intersection structure to `types`.
+///
+/// See [`Module::generate_ray_intersection_type`] for its layout.
+///
+/// [`Module::generate_ray_intersection_type`]: crate::Module::generate_ray_intersection_type
+pub(crate) fn generate_ray_intersection_type(
+    types: &mut UniqueArena<crate::Type>,
+) -> Handle<crate::Type> {
+    generate_struct(
+        types,
+        RAY_INTERSECTION_NAME,
+        ray_intersection_members(),
+        176,
+    )
+}
+
+impl crate::Module {
+    /// Add the ray descriptor structure to the type arena, if it's not
+    /// there yet, and return its handle.
+    ///
+    /// This is the type of the `descriptor` of a ray query
+    /// [`Initialize`] statement. Its members are:
+    /// - `flags: u32`, the ray flags,
+    /// - `cull_mask: u32`, skipping instances with no bits in common with
+    ///   their own mask,
+    /// - `tmin: f32` and `tmax: f32`, the range of distances along the ray,
+    /// - `origin: vec3<f32>` and `dir: vec3<f32>`.
+    ///
+    /// [`Initialize`]: crate::RayQueryFunction::Initialize
+    pub fn generate_ray_desc_type(&mut self) -> Handle<crate::Type> {
+        generate_ray_desc_type(&mut self.types)
+    }
+
+    /// Add the ray intersection structure to the type arena, if it's not
+    /// there yet, and return its handle.
+    ///
+    /// This is the type of [`RayQueryGetCommittedIntersection`] expressions.
+    /// Its members are:
+    /// - `kind: u32`, 0 if there is no intersection, 1 for a triangle,
+    ///   and 2 for a procedural primitive,
+    /// - `t: f32`, the distance along the ray,
+    /// - `instance_custom_index: u32` and `instance_id: u32`,
+    /// - `sbt_record_offset: u32`, the instance's shader binding table offset,
+    /// - `geometry_index: u32` and `primitive_index: u32`,
+    /// - `barycentrics: vec2<f32>` and `front_face: bool`, for triangles,
+    /// - `object_to_world: mat4x3<f32>` and `world_to_object: mat4x3<f32>`.
+    ///
+    /// [`RayQueryGetCommittedIntersection`]: crate::Expression::RayQueryGetCommittedIntersection
+    pub fn generate_ray_intersection_type(&mut self) -> Handle<crate::Type> {
+        generate_ray_intersection_type(&mut self.types)
+    }
+}
+
+impl crate::TypeInner {
+    /// Return true if this is a structure laid out like the one made by
+    /// [`Module::generate_ray_desc_type`].
+    ///
+    /// [`Module::generate_ray_desc_type`]: crate::Module::generate_ray_desc_type
+    pub fn is_ray_desc(&self, types: &UniqueArena<crate::Type>) -> bool {
+        matches_members(self, types, ray_desc_members())
+    }
+
+    /// Return true if this is a structure laid out like the one made by
+    /// [`Module::generate_ray_intersection_type`].
+    ///
+    /// [`Module::generate_ray_intersection_type`]: crate::Module::generate_ray_intersection_type
+    pub fn is_ray_intersection(&self, types: &UniqueArena<crate::Type>) -> bool {
+        matches_members(self, types, ray_intersection_members())
+    }
+}
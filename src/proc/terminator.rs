@@ -36,8 +36,15 @@ pub fn ensure_block_returns(block: &mut crate::Block) {
         Some(&mut S::Loop { .. })
         | Some(&mut S::Store { .. })
         | Some(&mut S::ImageStore { .. })
+        | Some(&mut S::ImageAtomic { .. })
         | Some(&mut S::Call { .. })
         | Some(&mut S::Atomic { .. })
+        | Some(&mut S::SubgroupBallot { .. })
+        | Some(&mut S::SubgroupElect { .. })
+        | Some(&mut S::SubgroupGather { .. })
+        | Some(&mut S::SubgroupCollectiveOperation { .. })
+        | Some(&mut S::RayQuery { .. })
+        | Some(&mut S::MeshFunction(_))
         | Some(&mut S::Barrier(_))
         | None => block.push(S::Return { value: None }, Default::default()),
     }
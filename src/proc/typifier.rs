@@ -236,7 +236,9 @@ impl<'a> ResolveContext<'a> {
                 // Arrays and matrices can only be indexed dynamically behind a
                 // pointer, but that's a validation error, not a type error, so
                 // go ahead provide a type here.
-                Ti::Array { base, .. } => TypeResolution::Handle(base),
+                Ti::Array { base, .. } | Ti::BindingArray { base, .. } => {
+                    TypeResolution::Handle(base)
+                }
                 Ti::Matrix { rows, width, .. } => TypeResolution::Value(Ti::Vector {
                     size: rows,
                     kind: crate::ScalarKind::Float,
@@ -260,7 +262,9 @@ impl<'a> ResolveContext<'a> {
                 }),
                 Ti::Pointer { base, class } => {
                     TypeResolution::Value(match types[base].inner {
-                        Ti::Array { base, .. } => Ti::Pointer { base, class },
+                        Ti::Array { base, .. } | Ti::BindingArray { base, .. } => {
+                            Ti::Pointer { base, class }
+                        }
                         Ti::Vector {
                             size: _,
                             kind,
@@ -321,7 +325,9 @@ impl<'a> ResolveContext<'a> {
                             width,
                         })
                     }
-                    Ti::Array { base, .. } => TypeResolution::Handle(base),
+                    Ti::Array { base, .. } | Ti::BindingArray { base, .. } => {
+                        TypeResolution::Handle(base)
+                    }
                     Ti::Struct { ref members, .. } => {
                         let member = members
                             .get(index as usize)
@@ -348,7 +354,9 @@ impl<'a> ResolveContext<'a> {
                         base: ty_base,
                         class,
                     } => TypeResolution::Value(match types[ty_base].inner {
-                        Ti::Array { base, .. } => Ti::Pointer { base, class },
+                        Ti::Array { base, .. } | Ti::BindingArray { base, .. } => {
+                            Ti::Pointer { base, class }
+                        }
                         Ti::Vector { size, kind, width } => {
                             if index >= size as u32 {
                                 return Err(ResolveError::OutOfBoundsIndex { expr: base, index });
@@ -642,20 +650,19 @@ impl<'a> ResolveContext<'a> {
                 | crate::BinaryOperator::ShiftLeft
                 | crate::BinaryOperator::ShiftRight => past(left)?.clone(),
             },
-            crate::Expression::AtomicResult {
-                kind,
-                width,
-                comparison,
-            } => {
-                if comparison {
-                    TypeResolution::Value(Ti::Vector {
-                        size: crate::VectorSize::Bi,
-                        kind,
-                        width,
-                    })
-                } else {
-                    TypeResolution::Value(Ti::Scalar { kind, width })
-                }
+            crate::Expression::AtomicResult { ty, .. } => TypeResolution::Handle(ty),
+            crate::Expression::SubgroupBallotResult => TypeResolution::Value(Ti::Vector {
+                size: crate::VectorSize::Quad,
+                kind: crate::ScalarKind::Uint,
+                width: 4,
+            }),
+            crate::Expression::SubgroupOperationResult { ty } => TypeResolution::Handle(ty),
+            crate::Expression::RayQueryProceedResult => TypeResolution::Value(Ti::Scalar {
+                kind: crate::ScalarKind::Bool,
+                width: crate::BOOL_WIDTH,
+            }),
+            crate::Expression::RayQueryGetCommittedIntersection { ty, .. } => {
+                TypeResolution::Handle(ty)
             }
             crate::Expression::Select { accept, .. } => past(accept)?.clone(),
             crate::Expression::Derivative { axis: _, expr } => past(expr)?.clone(),
@@ -880,6 +887,7 @@ impl<'a> ResolveContext<'a> {
                 kind: crate::ScalarKind::Uint,
                 width: 4,
             }),
+            crate::Expression::NonUniform(value) => past(value)?.clone(),
         })
     }
 }
@@ -0,0 +1,670 @@
+//! Enumeration and rewriting of the handles held by IR nodes.
+//!
+//! Passes over a [`Module`] often only care about the handles an
+//! [`Expression`], [`Statement`] or [`TypeInner`] refers to, not about what
+//! each variant means. The [`Visitor`] and [`Mutator`] traits receive those
+//! handles, sorted by the arena they point into, so such passes don't need
+//! to match every variant themselves.
+//!
+//! [`Module`]: crate::Module
+
+use crate::{
+    arena::{Handle, Range},
+    Block, Constant, ConstantInner, Expression, Function, GlobalVariable, LocalVariable, Statement,
+    Type, TypeInner,
+};
+
+/// Receives the handles held by an IR node, through `visit_handles`.
+///
+/// Every method does nothing by default.
+pub trait Visitor {
+    fn visit_type(&mut self, _: Handle<Type>) {}
+    fn visit_constant(&mut self, _: Handle<Constant>) {}
+    fn visit_global_variable(&mut self, _: Handle<GlobalVariable>) {}
+    fn visit_function(&mut self, _: Handle<Function>) {}
+    fn visit_local_variable(&mut self, _: Handle<LocalVariable>) {}
+    fn visit_expression(&mut self, _: Handle<Expression>) {}
+
+    /// Receives the range of a [`Statement::Emit`]. By default, every
+    /// expression of the range is passed to [`visit_expression`].
+    ///
+    /// [`visit_expression`]: Visitor::visit_expression
+    fn visit_expression_range(&mut self, range: &Range<Expression>) {
+        for handle in range.clone() {
+            self.visit_expression(handle);
+        }
+    }
+}
+
+/// Rewrites the handles held by an IR node, through `visit_handles_mut`.
+///
+/// Every method leaves its handle as is by default.
+pub trait Mutator {
+    fn visit_type(&mut self, _: &mut Handle<Type>) {}
+    fn visit_constant(&mut self, _: &mut Handle<Constant>) {}
+    fn visit_global_variable(&mut self, _: &mut Handle<GlobalVariable>) {}
+    fn visit_function(&mut self, _: &mut Handle<Function>) {}
+    fn visit_local_variable(&mut self, _: &mut Handle<LocalVariable>) {}
+    fn visit_expression(&mut self, _: &mut Handle<Expression>) {}
+
+    /// Rewrites the range of a [`Statement::Emit`]. By default, the first
+    /// and last expressions of the range are passed to [`visit_expression`],
+    /// so the expressions in between must stay between them.
+    ///
+    /// [`visit_expression`]: Mutator::visit_expression
+    fn visit_expression_range(&mut self, range: &mut Range<Expression>) {
+        if let Some((mut first, mut last)) = range.first_and_last() {
+            self.visit_expression(&mut first);
+            self.visit_expression(&mut last);
+            *range = Range::new_from_bounds(first, last);
+        }
+    }
+}
+
+// Each of these macros matches the handles of a node with `ref` or `ref mut`
+// patterns depending on `$mutability`, and passes them to `$visitor`, with
+// `$deref` dereferencing them for a `Visitor`. Nested blocks are walked with
+// `$walk`, and iterated with `$iter`.
+
+macro_rules! visit_type_inner {
+    ($inner:expr, $visitor:ident, [$($mutability:tt)*], [$($deref:tt)*]) => {
+        match *$inner {
+            TypeInner::Pointer {
+                ref $($mutability)* base,
+                ..
+            } => $visitor.visit_type($($deref)* base),
+            TypeInner::Array {
+                ref $($mutability)* base,
+                ref $($mutability)* size,
+                ..
+            }
+            | TypeInner::BindingArray {
+                ref $($mutability)* base,
+                ref $($mutability)* size,
+            } => {
+                $visitor.visit_type($($deref)* base);
+                if let crate::ArraySize::Constant(ref $($mutability)* constant) = *size {
+                    $visitor.visit_constant($($deref)* constant);
+                }
+            }
+            TypeInner::Struct {
+                ref $($mutability)* members,
+                ..
+            } => {
+                for member in members {
+                    let crate::StructMember {
+                        ref $($mutability)* ty,
+                        ..
+                    } = *member;
+                    $visitor.visit_type($($deref)* ty);
+                }
+            }
+            TypeInner::Scalar { .. }
+            | TypeInner::Vector { .. }
+            | TypeInner::Matrix { .. }
+            | TypeInner::Atomic { .. }
+            | TypeInner::ValuePointer { .. }
+            | TypeInner::Image { .. }
+            | TypeInner::Sampler { .. }
+            | TypeInner::AccelerationStructure
+            | TypeInner::RayQuery => {}
+        }
+    };
+}
+
+macro_rules! visit_constant_inner {
+    ($inner:expr, $visitor:ident, [$($mutability:tt)*], [$($deref:tt)*]) => {
+        match *$inner {
+            ConstantInner::Scalar { .. } => {}
+            ConstantInner::Composite {
+                ref $($mutability)* ty,
+                ref $($mutability)* components,
+            } => {
+                $visitor.visit_type($($deref)* ty);
+                for component in components {
+                    $visitor.visit_constant($($deref)* component);
+                }
+            }
+        }
+    };
+}
+
+macro_rules! visit_expression {
+    ($expression:expr, $visitor:ident, [$($mutability:tt)*], [$($deref:tt)*]) => {{
+        use crate::Expression as E;
+        match *$expression {
+            E::Access {
+                ref $($mutability)* base,
+                ref $($mutability)* index,
+            } => {
+                $visitor.visit_expression($($deref)* base);
+                $visitor.visit_expression($($deref)* index);
+            }
+            E::AccessIndex {
+                ref $($mutability)* base,
+                ..
+            } => $visitor.visit_expression($($deref)* base),
+            E::Constant(ref $($mutability)* constant) => {
+                $visitor.visit_constant($($deref)* constant)
+            }
+            E::Splat {
+                ref $($mutability)* value,
+                ..
+            } => $visitor.visit_expression($($deref)* value),
+            E::Swizzle {
+                ref $($mutability)* vector,
+                ..
+            } => $visitor.visit_expression($($deref)* vector),
+            E::Compose {
+                ref $($mutability)* ty,
+                ref $($mutability)* components,
+            } => {
+                $visitor.visit_type($($deref)* ty);
+                for component in components {
+                    $visitor.visit_expression($($deref)* component);
+                }
+            }
+            E::FunctionArgument(_)
+            | E::SubgroupBallotResult
+            | E::RayQueryProceedResult => {}
+            E::GlobalVariable(ref $($mutability)* var) => {
+                $visitor.visit_global_variable($($deref)* var)
+            }
+            E::LocalVariable(ref $($mutability)* var) => {
+                $visitor.visit_local_variable($($deref)* var)
+            }
+            E::Load {
+                ref $($mutability)* pointer,
+            } => $visitor.visit_expression($($deref)* pointer),
+            E::ImageSample {
+                ref $($mutability)* image,
+                ref $($mutability)* sampler,
+                ref $($mutability)* coordinate,
+                ref $($mutability)* array_index,
+                ref $($mutability)* offset,
+                ref $($mutability)* level,
+                ref $($mutability)* depth_ref,
+                ..
+            } => {
+                $visitor.visit_expression($($deref)* image);
+                $visitor.visit_expression($($deref)* sampler);
+                $visitor.visit_expression($($deref)* coordinate);
+                if let Some(ref $($mutability)* array_index) = *array_index {
+                    $visitor.visit_expression($($deref)* array_index);
+                }
+                if let Some(ref $($mutability)* offset) = *offset {
+                    $visitor.visit_constant($($deref)* offset);
+                }
+                match *level {
+                    crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                    crate::SampleLevel::Exact(ref $($mutability)* level)
+                    | crate::SampleLevel::Bias(ref $($mutability)* level) => {
+                        $visitor.visit_expression($($deref)* level)
+                    }
+                    crate::SampleLevel::Gradient {
+                        ref $($mutability)* x,
+                        ref $($mutability)* y,
+                    } => {
+                        $visitor.visit_expression($($deref)* x);
+                        $visitor.visit_expression($($deref)* y);
+                    }
+                }
+                if let Some(ref $($mutability)* depth_ref) = *depth_ref {
+                    $visitor.visit_expression($($deref)* depth_ref);
+                }
+            }
+            E::ImageLoad {
+                ref $($mutability)* image,
+                ref $($mutability)* coordinate,
+                ref $($mutability)* array_index,
+                ref $($mutability)* index,
+            } => {
+                $visitor.visit_expression($($deref)* image);
+                $visitor.visit_expression($($deref)* coordinate);
+                if let Some(ref $($mutability)* array_index) = *array_index {
+                    $visitor.visit_expression($($deref)* array_index);
+                }
+                if let Some(ref $($mutability)* index) = *index {
+                    $visitor.visit_expression($($deref)* index);
+                }
+            }
+            E::ImageQuery {
+                ref $($mutability)* image,
+                ref $($mutability)* query,
+            } => {
+                $visitor.visit_expression($($deref)* image);
+                if let crate::ImageQuery::Size {
+                    level: Some(ref $($mutability)* level),
+                } = *query
+                {
+                    $visitor.visit_expression($($deref)* level);
+                }
+            }
+            E::Unary {
+                ref $($mutability)* expr,
+                ..
+            }
+            | E::Derivative {
+                ref $($mutability)* expr,
+                ..
+            }
+            | E::As {
+                ref $($mutability)* expr,
+                ..
+            }
+            | E::Relational {
+                argument: ref $($mutability)* expr,
+                ..
+            }
+            | E::ArrayLength(ref $($mutability)* expr)
+            | E::NonUniform(ref $($mutability)* expr) => {
+                $visitor.visit_expression($($deref)* expr)
+            }
+            E::Binary {
+                ref $($mutability)* left,
+                ref $($mutability)* right,
+                ..
+            } => {
+                $visitor.visit_expression($($deref)* left);
+                $visitor.visit_expression($($deref)* right);
+            }
+            E::Select {
+                ref $($mutability)* condition,
+                ref $($mutability)* accept,
+                ref $($mutability)* reject,
+            } => {
+                $visitor.visit_expression($($deref)* condition);
+                $visitor.visit_expression($($deref)* accept);
+                $visitor.visit_expression($($deref)* reject);
+            }
+            E::Math {
+                ref $($mutability)* arg,
+                ref $($mutability)* arg1,
+                ref $($mutability)* arg2,
+                ref $($mutability)* arg3,
+                ..
+            } => {
+                $visitor.visit_expression($($deref)* arg);
+                if let Some(ref $($mutability)* arg1) = *arg1 {
+                    $visitor.visit_expression($($deref)* arg1);
+                }
+                if let Some(ref $($mutability)* arg2) = *arg2 {
+                    $visitor.visit_expression($($deref)* arg2);
+                }
+                if let Some(ref $($mutability)* arg3) = *arg3 {
+                    $visitor.visit_expression($($deref)* arg3);
+                }
+            }
+            E::CallResult(ref $($mutability)* function) => {
+                $visitor.visit_function($($deref)* function)
+            }
+            E::AtomicResult {
+                ref $($mutability)* ty,
+                ..
+            }
+            | E::SubgroupOperationResult {
+                ref $($mutability)* ty,
+            } => $visitor.visit_type($($deref)* ty),
+            E::RayQueryGetCommittedIntersection {
+                ref $($mutability)* query,
+                ref $($mutability)* ty,
+            } => {
+                $visitor.visit_expression($($deref)* query);
+                $visitor.visit_type($($deref)* ty);
+            }
+        }
+    }};
+}
+
+macro_rules! visit_atomic_function {
+    ($fun:expr, $visitor:ident, [$($mutability:tt)*], [$($deref:tt)*]) => {
+        if let crate::AtomicFunction::Exchange {
+            compare: Some(ref $($mutability)* compare),
+        } = *$fun
+        {
+            $visitor.visit_expression($($deref)* compare);
+        }
+    };
+}
+
+macro_rules! visit_statement {
+    (
+        $statement:expr,
+        $visitor:ident,
+        [$($mutability:tt)*],
+        [$($deref:tt)*],
+        $walk:ident,
+        $iter:ident
+    ) => {{
+        use crate::Statement as S;
+        match *$statement {
+            S::Emit(ref $($mutability)* range) => $visitor.visit_expression_range(range),
+            S::Block(ref $($mutability)* block) => block.$walk($visitor),
+            S::If {
+                ref $($mutability)* condition,
+                ref $($mutability)* accept,
+                ref $($mutability)* reject,
+            } => {
+                $visitor.visit_expression($($deref)* condition);
+                accept.$walk($visitor);
+                reject.$walk($visitor);
+            }
+            S::Switch {
+                ref $($mutability)* selector,
+                ref $($mutability)* cases,
+            } => {
+                $visitor.visit_expression($($deref)* selector);
+                for case in cases.$iter() {
+                    case.body.$walk($visitor);
+                }
+            }
+            S::Loop {
+                ref $($mutability)* body,
+                ref $($mutability)* continuing,
+            } => {
+                body.$walk($visitor);
+                continuing.$walk($visitor);
+            }
+            S::Break | S::Continue | S::Kill | S::Barrier(_) => {}
+            S::Return {
+                value: Some(ref $($mutability)* value),
+            } => $visitor.visit_expression($($deref)* value),
+            S::Return { value: None } => {}
+            S::Store {
+                ref $($mutability)* pointer,
+                ref $($mutability)* value,
+            } => {
+                $visitor.visit_expression($($deref)* pointer);
+                $visitor.visit_expression($($deref)* value);
+            }
+            S::ImageStore {
+                ref $($mutability)* image,
+                ref $($mutability)* coordinate,
+                ref $($mutability)* array_index,
+                ref $($mutability)* value,
+            } => {
+                $visitor.visit_expression($($deref)* image);
+                $visitor.visit_expression($($deref)* coordinate);
+                if let Some(ref $($mutability)* array_index) = *array_index {
+                    $visitor.visit_expression($($deref)* array_index);
+                }
+                $visitor.visit_expression($($deref)* value);
+            }
+            S::ImageAtomic {
+                ref $($mutability)* image,
+                ref $($mutability)* coordinate,
+                ref $($mutability)* array_index,
+                ref $($mutability)* fun,
+                ref $($mutability)* value,
+                ref $($mutability)* result,
+            } => {
+                $visitor.visit_expression($($deref)* image);
+                $visitor.visit_expression($($deref)* coordinate);
+                if let Some(ref $($mutability)* array_index) = *array_index {
+                    $visitor.visit_expression($($deref)* array_index);
+                }
+                visit_atomic_function!(fun, $visitor, [$($mutability)*], [$($deref)*]);
+                $visitor.visit_expression($($deref)* value);
+                $visitor.visit_expression($($deref)* result);
+            }
+            S::Atomic {
+                ref $($mutability)* pointer,
+                ref $($mutability)* fun,
+                ref $($mutability)* value,
+                ref $($mutability)* result,
+            } => {
+                $visitor.visit_expression($($deref)* pointer);
+                visit_atomic_function!(fun, $visitor, [$($mutability)*], [$($deref)*]);
+                $visitor.visit_expression($($deref)* value);
+                $visitor.visit_expression($($deref)* result);
+            }
+            S::Call {
+                ref $($mutability)* function,
+                ref $($mutability)* arguments,
+                ref $($mutability)* result,
+            } => {
+                $visitor.visit_function($($deref)* function);
+                for argument in arguments {
+                    $visitor.visit_expression($($deref)* argument);
+                }
+                if let Some(ref $($mutability)* result) = *result {
+                    $visitor.visit_expression($($deref)* result);
+                }
+            }
+            S::SubgroupBallot {
+                ref $($mutability)* result,
+                ref $($mutability)* predicate,
+            } => {
+                if let Some(ref $($mutability)* predicate) = *predicate {
+                    $visitor.visit_expression($($deref)* predicate);
+                }
+                $visitor.visit_expression($($deref)* result);
+            }
+            S::SubgroupElect {
+                ref $($mutability)* result,
+            } => $visitor.visit_expression($($deref)* result),
+            S::SubgroupGather {
+                ref $($mutability)* mode,
+                ref $($mutability)* argument,
+                ref $($mutability)* result,
+            } => {
+                match *mode {
+                    crate::GatherMode::BroadcastFirst => {}
+                    crate::GatherMode::Broadcast(ref $($mutability)* index)
+                    | crate::GatherMode::Shuffle(ref $($mutability)* index)
+                    | crate::GatherMode::ShuffleDown(ref $($mutability)* index)
+                    | crate::GatherMode::ShuffleUp(ref $($mutability)* index)
+                    | crate::GatherMode::ShuffleXor(ref $($mutability)* index) => {
+                        $visitor.visit_expression($($deref)* index)
+                    }
+                }
+                $visitor.visit_expression($($deref)* argument);
+                $visitor.visit_expression($($deref)* result);
+            }
+            S::SubgroupCollectiveOperation {
+                ref $($mutability)* argument,
+                ref $($mutability)* result,
+                ..
+            } => {
+                $visitor.visit_expression($($deref)* argument);
+                $visitor.visit_expression($($deref)* result);
+            }
+            S::RayQuery {
+                ref $($mutability)* query,
+                ref $($mutability)* fun,
+            } => {
+                $visitor.visit_expression($($deref)* query);
+                match *fun {
+                    crate::RayQueryFunction::Initialize {
+                        ref $($mutability)* acceleration_structure,
+                        ref $($mutability)* descriptor,
+                    } => {
+                        $visitor.visit_expression($($deref)* acceleration_structure);
+                        $visitor.visit_expression($($deref)* descriptor);
+                    }
+                    crate::RayQueryFunction::Proceed {
+                        ref $($mutability)* result,
+                    } => $visitor.visit_expression($($deref)* result),
+                }
+            }
+            S::MeshFunction(ref $($mutability)* fun) => match *fun {
+                crate::MeshFunction::SetMeshOutputs {
+                    ref $($mutability)* vertex_count,
+                    ref $($mutability)* primitive_count,
+                } => {
+                    $visitor.visit_expression($($deref)* vertex_count);
+                    $visitor.visit_expression($($deref)* primitive_count);
+                }
+                crate::MeshFunction::SetVertex {
+                    ref $($mutability)* index,
+                    ref $($mutability)* value,
+                }
+                | crate::MeshFunction::SetPrimitive {
+                    ref $($mutability)* index,
+                    ref $($mutability)* value,
+                } => {
+                    $visitor.visit_expression($($deref)* index);
+                    $visitor.visit_expression($($deref)* value);
+                }
+            },
+        }
+    }};
+}
+
+impl TypeInner {
+    /// Pass the handles this type refers to to `visitor`.
+    pub fn visit_handles<V: Visitor>(&self, visitor: &mut V) {
+        visit_type_inner!(self, visitor, [], [*]);
+    }
+
+    /// Rewrite the handles this type refers to with `mutator`.
+    pub fn visit_handles_mut<M: Mutator>(&mut self, mutator: &mut M) {
+        visit_type_inner!(self, mutator, [mut], []);
+    }
+}
+
+impl ConstantInner {
+    /// Pass the handles this constant refers to to `visitor`.
+    pub fn visit_handles<V: Visitor>(&self, visitor: &mut V) {
+        visit_constant_inner!(self, visitor, [], [*]);
+    }
+
+    /// Rewrite the handles this constant refers to with `mutator`.
+    pub fn visit_handles_mut<M: Mutator>(&mut self, mutator: &mut M) {
+        visit_constant_inner!(self, mutator, [mut], []);
+    }
+}
+
+impl Expression {
+    /// Pass the handles this expression refers to to `visitor`: its
+    /// operands, and the module-scope items and local variables it uses.
+    pub fn visit_handles<V: Visitor>(&self, visitor: &mut V) {
+        visit_expression!(self, visitor, [], [*]);
+    }
+
+    /// Rewrite the handles this expression refers to with `mutator`.
+    pub fn visit_handles_mut<M: Mutator>(&mut self, mutator: &mut M) {
+        visit_expression!(self, mutator, [mut], []);
+    }
+}
+
+impl Statement {
+    /// Pass the handles this statement refers to to `visitor`, including
+    /// the ones of the statements nested in it.
+    pub fn visit_handles<V: Visitor>(&self, visitor: &mut V) {
+        visit_statement!(self, visitor, [], [*], visit_handles, iter);
+    }
+
+    /// Rewrite the handles this statement refers to with `mutator`,
+    /// including the ones of the statements nested in it.
+    pub fn visit_handles_mut<M: Mutator>(&mut self, mutator: &mut M) {
+        visit_statement!(self, mutator, [mut], [], visit_handles_mut, iter_mut);
+    }
+}
+
+impl Block {
+    /// Pass the handles the statements of this block refer to to `visitor`.
+    pub fn visit_handles<V: Visitor>(&self, visitor: &mut V) {
+        for statement in self.iter() {
+            statement.visit_handles(visitor);
+        }
+    }
+
+    /// Rewrite the handles the statements of this block refer to with
+    /// `mutator`.
+    pub fn visit_handles_mut<M: Mutator>(&mut self, mutator: &mut M) {
+        for statement in self.iter_mut() {
+            statement.visit_handles_mut(mutator);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{Mutator, Visitor};
+    use crate::{arena::Handle, Constant, Function, GlobalVariable};
+
+    #[derive(Default)]
+    struct Uses {
+        globals: Vec<Handle<GlobalVariable>>,
+        functions: Vec<Handle<Function>>,
+    }
+
+    impl Visitor for Uses {
+        fn visit_global_variable(&mut self, handle: Handle<GlobalVariable>) {
+            self.globals.push(handle);
+        }
+        fn visit_function(&mut self, handle: Handle<Function>) {
+            self.functions.push(handle);
+        }
+    }
+
+    struct ReplaceConstant(Handle<Constant>, Handle<Constant>);
+
+    impl Mutator for ReplaceConstant {
+        fn visit_constant(&mut self, handle: &mut Handle<Constant>) {
+            if *handle == self.0 {
+                *handle = self.1;
+            }
+        }
+    }
+
+    #[test]
+    fn visit_function() {
+        let module = crate::front::wgsl::parse_str(
+            "
+            var<private> counter: u32;
+            fn bump() {
+                counter = counter + 1u;
+            }
+            @stage(compute) @workgroup_size(1)
+            fn main() {
+                if (counter < 4u) {
+                    bump();
+                }
+            }
+            ",
+        )
+        .unwrap();
+        let (bump, _) = module.functions.iter().next().unwrap();
+        let function = &module.entry_points[0].function;
+
+        let mut uses = Uses::default();
+        function.body.visit_handles(&mut uses);
+        assert_eq!(uses.functions, [bump]);
+
+        let mut uses = Uses::default();
+        for (_, expression) in function.expressions.iter() {
+            expression.visit_handles(&mut uses);
+        }
+        assert_eq!(uses.globals.len(), 1);
+    }
+
+    #[test]
+    fn mutate_expressions() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            fn five() -> u32 {
+                return 2u + 3u;
+            }
+            ",
+        )
+        .unwrap();
+        let mut constants = module.constants.iter().map(|(handle, _)| handle);
+        let first = constants.next().unwrap();
+        let second = constants.next().unwrap();
+        let mut replace = ReplaceConstant(second, first);
+
+        let function = module.functions.iter_mut().next().unwrap().1;
+        for (_, expression) in function.expressions.iter_mut() {
+            expression.visit_handles_mut(&mut replace);
+        }
+        assert!(function
+            .expressions
+            .iter()
+            .all(|(_, expression)| match *expression {
+                crate::Expression::Constant(constant) => constant == first,
+                _ => true,
+            }));
+    }
+}
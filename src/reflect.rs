@@ -0,0 +1,694 @@
+/*!
+Reflection of the resources and interfaces of a module's entry points.
+
+Back ends report what they need to map their output to the pipeline, like the
+`ReflectionInfo` of the GLSL back end. A [`ModuleReflection`] describes the
+module itself instead: for each entry point, the resources it binds, the
+locations of its inputs and outputs, its push constants and its workgroup
+size. With the `serialize` feature, it can be written out with [`serde`].
+
+[`serde`]: https://serde.rs
+*/
+
+use crate::{
+    proc::{LayoutError, Layouter},
+    valid::{FunctionInfo, GlobalUse, ModuleInfo, ShaderStages},
+    Binding, Bytes, Handle, ImageClass, ImageDimension, Interpolation, ResourceBinding, Sampling,
+    ScalarKind, ShaderStage, StorageAccess, StorageClass, TypeInner, VectorSize,
+};
+
+/// Reflection of all the entry points of a module.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct ModuleReflection {
+    /// The entry points, in the order of [`Module::entry_points`].
+    ///
+    /// [`Module::entry_points`]: crate::Module::entry_points
+    pub entry_points: Vec<EntryPointReflection>,
+}
+
+/// Reflection of an entry point.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct EntryPointReflection {
+    pub name: String,
+    pub stage: ShaderStage,
+    /// The resources the entry point uses, ordered by group and binding.
+    pub resources: Vec<Resource>,
+    /// The inputs of the entry point with a [`Location`] binding, ordered by
+    /// location.
+    ///
+    /// For a vertex shader, these are the vertex attributes.
+    ///
+    /// [`Location`]: Binding::Location
+    pub inputs: Vec<InterfaceVariable>,
+    /// The outputs of the entry point with a [`Location`] binding, ordered by
+    /// location.
+    ///
+    /// For a fragment shader, these are the color attachments.
+    ///
+    /// [`Location`]: Binding::Location
+    pub outputs: Vec<InterfaceVariable>,
+    /// The push constants the entry point uses.
+    pub push_constants: Vec<PushConstantRange>,
+    /// The workgroup size of compute, task and mesh shaders.
+    ///
+    /// This is `None` if the workgroup size depends on overrides, which are
+    /// only known once [`proc::process_overrides`] is run.
+    ///
+    /// [`proc::process_overrides`]: crate::proc::process_overrides
+    pub workgroup_size: Option<[u32; 3]>,
+}
+
+/// A global variable bound to a resource of the pipeline.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Resource {
+    /// The name of the global variable, if any.
+    pub name: Option<String>,
+    pub binding: ResourceBinding,
+    pub ty: ResourceType,
+    /// The number of resources in a binding array, or `None` for a single
+    /// resource.
+    pub array_length: Option<ArrayLength>,
+    /// The size in bytes of a buffer, or of each buffer in a binding array.
+    ///
+    /// If the buffer ends with a runtime-sized array, this counts a single
+    /// element of it: this is the minimum size of the buffer. This is `None`
+    /// for resources that aren't buffers.
+    pub size: Option<u32>,
+    /// How the entry point uses the resource.
+    pub usage: GlobalUse,
+}
+
+/// The kind of a [`Resource`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ResourceType {
+    /// A uniform buffer.
+    Uniform,
+    /// A storage buffer.
+    Storage {
+        access: StorageAccess,
+    },
+    /// A texture or storage image.
+    Image {
+        dim: ImageDimension,
+        arrayed: bool,
+        class: ImageClass,
+    },
+    Sampler {
+        comparison: bool,
+    },
+    AccelerationStructure,
+}
+
+/// The number of resources in a binding array.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ArrayLength {
+    Constant(u32),
+    /// The length is set by the pipeline.
+    Dynamic,
+}
+
+/// An input or output of an entry point with a [`Location`] binding.
+///
+/// [`Location`]: Binding::Location
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct InterfaceVariable {
+    /// The name of the argument or structure member, if any.
+    pub name: Option<String>,
+    pub location: u32,
+    /// The blend source index, for dual-source blending.
+    pub blend_src: Option<u32>,
+    pub interpolation: Option<Interpolation>,
+    pub sampling: Option<Sampling>,
+    pub format: Format,
+}
+
+/// The type of an [`InterfaceVariable`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Format {
+    pub kind: ScalarKind,
+    pub width: Bytes,
+    /// The number of components of a vector, or `None` for a scalar.
+    pub size: Option<VectorSize>,
+}
+
+/// A global variable in the [`PushConstant`] storage class.
+///
+/// [`PushConstant`]: StorageClass::PushConstant
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct PushConstantRange {
+    /// The name of the global variable, if any.
+    pub name: Option<String>,
+    /// The size of the range in bytes, starting at offset zero.
+    pub size: u32,
+}
+
+/// The resources bound in a group, for a set of entry points.
+///
+/// See [`ModuleReflection::bind_group_layouts`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct BindGroupLayout {
+    pub group: u32,
+    /// The bindings of the group, in order.
+    pub entries: Vec<BindGroupLayoutEntry>,
+}
+
+/// A binding of a [`BindGroupLayout`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct BindGroupLayoutEntry {
+    pub binding: u32,
+    /// The stages of the entry points using the binding.
+    pub visibility: ShaderStages,
+    pub ty: ResourceType,
+    pub array_length: Option<ArrayLength>,
+    /// The largest [`size`] of the resources at this binding.
+    ///
+    /// [`size`]: Resource::size
+    pub size: Option<u32>,
+    /// How the entry points use the binding, together.
+    pub usage: GlobalUse,
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum ReflectionError {
+    #[error(transparent)]
+    Layouter(#[from] LayoutError),
+    #[error("Binding array {0:?} has an invalid length")]
+    InvalidArrayLength(Handle<crate::GlobalVariable>),
+}
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BindGroupLayoutError {
+    #[error("Entry point index {0} is out of range")]
+    InvalidEntryPoint(usize),
+    #[error("Resources bound at {binding:?} have different types: {first:?} and {second:?}")]
+    ConflictingTypes {
+        binding: ResourceBinding,
+        first: ResourceType,
+        second: ResourceType,
+    },
+    #[error("Binding arrays at {binding:?} have different lengths: {first:?} and {second:?}")]
+    ConflictingArrayLengths {
+        binding: ResourceBinding,
+        first: Option<ArrayLength>,
+        second: Option<ArrayLength>,
+    },
+}
+
+impl ModuleReflection {
+    /// Reflect the entry points of `module`, given its validation `info`.
+    pub fn new(module: &crate::Module, info: &ModuleInfo) -> Result<Self, ReflectionError> {
+        let mut layouter = Layouter::default();
+        layouter.update(&module.types, &module.constants)?;
+
+        let entry_points = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .map(|(index, ep)| {
+                reflect_entry_point(module, &layouter, ep, info.get_entry_point(index))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ModuleReflection { entry_points })
+    }
+
+    /// Merge the resources of the entry points at the given indices into
+    /// bind group layouts, ordered by group.
+    ///
+    /// Resources of different entry points bound at the same
+    /// [`ResourceBinding`] share an entry, visible to all their stages. They
+    /// must have the same [`ResourceType`], including the access of storage
+    /// buffers and images, and the same array length.
+    pub fn bind_group_layouts(
+        &self,
+        entry_points: &[usize],
+    ) -> Result<Vec<BindGroupLayout>, BindGroupLayoutError> {
+        let mut entries =
+            std::collections::BTreeMap::<ResourceBinding, BindGroupLayoutEntry>::new();
+        for &index in entry_points {
+            let ep = self
+                .entry_points
+                .get(index)
+                .ok_or(BindGroupLayoutError::InvalidEntryPoint(index))?;
+            let stage = match ep.stage {
+                ShaderStage::Vertex => ShaderStages::VERTEX,
+                ShaderStage::Fragment => ShaderStages::FRAGMENT,
+                ShaderStage::Compute => ShaderStages::COMPUTE,
+                ShaderStage::Task => ShaderStages::TASK,
+                ShaderStage::Mesh => ShaderStages::MESH,
+            };
+            for resource in ep.resources.iter() {
+                let entry = entries.entry(resource.binding.clone()).or_insert_with(|| {
+                    BindGroupLayoutEntry {
+                        binding: resource.binding.binding,
+                        visibility: ShaderStages::empty(),
+                        ty: resource.ty,
+                        array_length: resource.array_length,
+                        size: resource.size,
+                        usage: GlobalUse::empty(),
+                    }
+                });
+                if entry.ty != resource.ty {
+                    return Err(BindGroupLayoutError::ConflictingTypes {
+                        binding: resource.binding.clone(),
+                        first: entry.ty,
+                        second: resource.ty,
+                    });
+                }
+                if entry.array_length != resource.array_length {
+                    return Err(BindGroupLayoutError::ConflictingArrayLengths {
+                        binding: resource.binding.clone(),
+                        first: entry.array_length,
+                        second: resource.array_length,
+                    });
+                }
+                entry.visibility |= stage;
+                entry.size = entry.size.max(resource.size);
+                entry.usage |= resource.usage;
+            }
+        }
+
+        let mut layouts = Vec::<BindGroupLayout>::new();
+        for (binding, entry) in entries {
+            match layouts.last_mut() {
+                Some(layout) if layout.group == binding.group => layout.entries.push(entry),
+                _ => layouts.push(BindGroupLayout {
+                    group: binding.group,
+                    entries: vec![entry],
+                }),
+            }
+        }
+        Ok(layouts)
+    }
+}
+
+fn reflect_entry_point(
+    module: &crate::Module,
+    layouter: &Layouter,
+    ep: &crate::EntryPoint,
+    info: &FunctionInfo,
+) -> Result<EntryPointReflection, ReflectionError> {
+    let mut resources = Vec::new();
+    let mut push_constants = Vec::new();
+    for (handle, var) in module.global_variables.iter() {
+        let usage = info[handle];
+        if usage.is_empty() {
+            continue;
+        }
+        if var.class == StorageClass::PushConstant {
+            push_constants.push(PushConstantRange {
+                name: var.name.clone(),
+                size: layouter[var.ty].size,
+            });
+            continue;
+        }
+        let binding = match var.binding {
+            Some(ref binding) => binding.clone(),
+            None => continue,
+        };
+        let (base, array_length) = match module.types[var.ty].inner {
+            TypeInner::BindingArray { base, size } => {
+                let length = match size {
+                    crate::ArraySize::Constant(constant) => ArrayLength::Constant(
+                        module.constants[constant]
+                            .to_array_length()
+                            .ok_or(ReflectionError::InvalidArrayLength(handle))?,
+                    ),
+                    crate::ArraySize::Dynamic => ArrayLength::Dynamic,
+                };
+                (base, Some(length))
+            }
+            _ => (var.ty, None),
+        };
+        let ty = match (var.class, &module.types[base].inner) {
+            (StorageClass::Uniform, _) => ResourceType::Uniform,
+            (StorageClass::Storage { access }, _) => ResourceType::Storage { access },
+            (
+                StorageClass::Handle,
+                &TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                },
+            ) => ResourceType::Image {
+                dim,
+                arrayed,
+                class,
+            },
+            (StorageClass::Handle, &TypeInner::Sampler { comparison }) => {
+                ResourceType::Sampler { comparison }
+            }
+            (StorageClass::Handle, &TypeInner::AccelerationStructure) => {
+                ResourceType::AccelerationStructure
+            }
+            _ => continue,
+        };
+        let size = match ty {
+            ResourceType::Uniform | ResourceType::Storage { .. } => Some(layouter[base].size),
+            _ => None,
+        };
+        resources.push(Resource {
+            name: var.name.clone(),
+            binding,
+            ty,
+            array_length,
+            size,
+            usage,
+        });
+    }
+    resources.sort_by(|a, b| a.binding.cmp(&b.binding));
+
+    let mut inputs = Vec::new();
+    for argument in ep.function.arguments.iter() {
+        collect_locations(
+            module,
+            argument.name.as_ref(),
+            argument.ty,
+            argument.binding.as_ref(),
+            &mut inputs,
+        );
+    }
+    let mut outputs = Vec::new();
+    if let Some(ref result) = ep.function.result {
+        collect_locations(
+            module,
+            None,
+            result.ty,
+            result.binding.as_ref(),
+            &mut outputs,
+        );
+    }
+
+    let is_overridden = match ep.workgroup_size_overrides {
+        Some(ref overrides) => overrides.iter().any(Option::is_some),
+        None => false,
+    };
+    let workgroup_size = match ep.stage {
+        ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh if !is_overridden => {
+            Some(ep.workgroup_size)
+        }
+        _ => None,
+    };
+
+    Ok(EntryPointReflection {
+        name: ep.name.clone(),
+        stage: ep.stage,
+        resources,
+        inputs,
+        outputs,
+        push_constants,
+        workgroup_size,
+    })
+}
+
+/// Add to `variables` the [`Location`] bindings of an argument or result of
+/// an entry point, or of the members of its type if it is a structure.
+///
+/// The variables are kept sorted by location.
+///
+/// [`Location`]: Binding::Location
+fn collect_locations(
+    module: &crate::Module,
+    name: Option<&String>,
+    ty: Handle<crate::Type>,
+    binding: Option<&Binding>,
+    variables: &mut Vec<InterfaceVariable>,
+) {
+    match binding {
+        Some(&Binding::Location {
+            location,
+            interpolation,
+            sampling,
+            blend_src,
+        }) => {
+            let format = match module.types[ty].inner {
+                TypeInner::Scalar { kind, width } => Format {
+                    kind,
+                    width,
+                    size: None,
+                },
+                TypeInner::Vector { size, kind, width } => Format {
+                    kind,
+                    width,
+                    size: Some(size),
+                },
+                _ => return,
+            };
+            let variable = InterfaceVariable {
+                name: name.cloned(),
+                location,
+                blend_src,
+                interpolation,
+                sampling,
+                format,
+            };
+            let index = variables
+                .iter()
+                .position(|other| (other.location, other.blend_src) > (location, blend_src))
+                .unwrap_or(variables.len());
+            variables.insert(index, variable);
+        }
+        Some(&Binding::BuiltIn(_)) => {}
+        None => {
+            if let TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                for member in members.iter() {
+                    collect_locations(
+                        module,
+                        member.name.as_ref(),
+                        member.ty,
+                        member.binding.as_ref(),
+                        variables,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{
+        ArrayLength, BindGroupLayoutError, ModuleReflection, ReflectionError, ResourceType,
+    };
+    use crate::valid::{GlobalUse, ShaderStages};
+
+    fn reflect(source: &str) -> ModuleReflection {
+        let module = crate::front::wgsl::parse_str(source).unwrap();
+        let info = crate::valid::Validator::new(
+            crate::valid::ValidationFlags::all(),
+            crate::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .unwrap();
+        ModuleReflection::new(&module, &info).unwrap()
+    }
+
+    #[test]
+    fn resources_and_interface() {
+        let reflection = reflect(
+            "
+            struct Globals {
+                transform: mat4x4<f32>;
+                tint: vec4<f32>;
+            };
+
+            struct Particles {
+                positions: array<vec4<f32>>;
+            };
+
+            struct VertexOutput {
+                @builtin(position) position: vec4<f32>;
+                @location(1) color: vec4<f32>;
+                @location(0) @interpolate(flat) index: u32;
+            };
+
+            @group(0) @binding(0) var<uniform> globals: Globals;
+            @group(1) @binding(2) var textures: binding_array<texture_2d<f32>, 4>;
+            @group(1) @binding(1) var linear: sampler;
+            @group(2) @binding(0) var<storage, read_write> particles: Particles;
+
+            @stage(vertex)
+            fn vs(@location(0) position: vec3<f32>, @builtin(vertex_index) index: u32) -> VertexOutput {
+                return VertexOutput(globals.transform * vec4<f32>(position, 1.0), globals.tint, index);
+            }
+
+            @stage(fragment)
+            fn fs(input: VertexOutput) -> @location(0) vec4<f32> {
+                return textureSample(textures[input.index], linear, vec2<f32>(0.5)) * input.color;
+            }
+
+            @stage(compute) @workgroup_size(64)
+            fn cs(@builtin(global_invocation_id) id: vec3<u32>) {
+                particles.positions[id.x] = vec4<f32>(1.0);
+            }
+            ",
+        );
+        let (vs, fs, cs) = match reflection.entry_points[..] {
+            [ref vs, ref fs, ref cs] => (vs, fs, cs),
+            ref other => unreachable!("{:?}", other),
+        };
+
+        assert_eq!(vs.resources.len(), 1);
+        assert_eq!(vs.resources[0].ty, ResourceType::Uniform);
+        assert_eq!(vs.resources[0].size, Some(80));
+        assert_eq!(vs.resources[0].usage, GlobalUse::READ);
+        assert_eq!(vs.inputs.len(), 1);
+        assert_eq!(vs.inputs[0].name.as_deref(), Some("position"));
+        assert_eq!(vs.inputs[0].format.size, Some(crate::VectorSize::Tri));
+        let locations = vs.outputs.iter().map(|output| output.location);
+        assert_eq!(locations.collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(vs.workgroup_size, None);
+
+        // Resources are ordered by binding, not by declaration.
+        let names = fs.resources.iter().map(|res| res.name.as_deref().unwrap());
+        assert_eq!(names.collect::<Vec<_>>(), ["linear", "textures"]);
+        assert_eq!(fs.resources[1].array_length, Some(ArrayLength::Constant(4)));
+        assert_eq!(fs.resources[1].size, None);
+        assert_eq!(fs.inputs, vs.outputs);
+        assert_eq!(fs.outputs[0].format.kind, crate::ScalarKind::Float);
+
+        assert_eq!(cs.resources.len(), 1);
+        assert_eq!(cs.resources[0].size, Some(16));
+        assert_eq!(cs.resources[0].usage, GlobalUse::WRITE);
+        assert_eq!(cs.workgroup_size, Some([64, 1, 1]));
+    }
+
+    #[test]
+    fn bind_group_layouts() {
+        let reflection = reflect(
+            "
+            struct Data {
+                values: array<f32, 4>;
+            };
+
+            struct Params {
+                value: vec4<f32>;
+            };
+
+            @group(0) @binding(0) var<uniform> scale: Params;
+            @group(1) @binding(0) var<storage, read> input: Data;
+            @group(1) @binding(0) var<storage, read_write> output: Data;
+            @group(0) @binding(1) var<uniform> offset: Params;
+
+            @stage(vertex)
+            fn vs() -> @builtin(position) vec4<f32> {
+                return scale.value * input.values[0];
+            }
+
+            @stage(fragment)
+            fn fs() -> @location(0) vec4<f32> {
+                return scale.value + offset.value;
+            }
+
+            @stage(compute) @workgroup_size(1)
+            fn cs() {
+                output.values[0] = 1.0;
+            }
+            ",
+        );
+
+        let layouts = reflection.bind_group_layouts(&[0, 1]).unwrap();
+        let groups = layouts.iter().map(|layout| layout.group);
+        assert_eq!(groups.collect::<Vec<_>>(), [0, 1]);
+        let visibility = layouts[0].entries.iter().map(|entry| entry.visibility);
+        assert_eq!(
+            visibility.collect::<Vec<_>>(),
+            [
+                ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ShaderStages::FRAGMENT
+            ]
+        );
+        assert_eq!(layouts[1].entries[0].size, Some(16));
+        assert_eq!(layouts, reflection.bind_group_layouts(&[1, 0]).unwrap());
+
+        let read_write = ResourceType::Storage {
+            access: crate::StorageAccess::LOAD | crate::StorageAccess::STORE,
+        };
+        let layouts = reflection.bind_group_layouts(&[2]).unwrap();
+        assert_eq!(layouts[0].entries[0].ty, read_write);
+        assert_eq!(layouts[0].entries[0].usage, GlobalUse::WRITE);
+
+        match reflection.bind_group_layouts(&[0, 2]) {
+            Err(BindGroupLayoutError::ConflictingTypes {
+                binding, second, ..
+            }) => {
+                assert_eq!(binding.group, 1);
+                assert_eq!(second, read_write);
+            }
+            other => unreachable!("{:?}", other),
+        }
+        assert_eq!(
+            reflection.bind_group_layouts(&[3]),
+            Err(BindGroupLayoutError::InvalidEntryPoint(3))
+        );
+    }
+
+    #[test]
+    fn overridden_workgroup_size() {
+        let reflection = reflect(
+            "
+            override block_size: u32 = 64u;
+
+            @stage(compute) @workgroup_size(block_size)
+            fn main() {}
+            ",
+        );
+        assert_eq!(reflection.entry_points[0].workgroup_size, None);
+    }
+
+    #[test]
+    fn invalid_array_length() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+            @group(0) @binding(1) var linear: sampler;
+
+            @stage(fragment)
+            fn main() -> @location(0) vec4<f32> {
+                return textureSample(textures[0], linear, vec2<f32>(0.5));
+            }
+            ",
+        )
+        .unwrap();
+        let info = crate::valid::Validator::new(
+            crate::valid::ValidationFlags::all(),
+            crate::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .unwrap();
+
+        let (textures, _) = module.global_variables.iter().next().unwrap();
+        let (length, _) = module
+            .constants
+            .iter()
+            .find(|&(_, constant)| constant.to_array_length() == Some(4))
+            .unwrap();
+        module.constants.get_mut(length).inner = crate::ConstantInner::Scalar {
+            width: 4,
+            value: crate::ScalarValue::Sint(-1),
+        };
+        assert_eq!(
+            ModuleReflection::new(&module, &info),
+            Err(ReflectionError::InvalidArrayLength(textures))
+        );
+    }
+}
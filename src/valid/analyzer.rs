@@ -169,10 +169,19 @@ enum GlobalOrArgument {
 }
 
 impl crate::Expression {
-    fn to_global_or_argument(&self) -> Result<GlobalOrArgument, ExpressionError> {
+    fn to_global_or_argument(
+        &self,
+        expression_arena: &Arena<crate::Expression>,
+    ) -> Result<GlobalOrArgument, ExpressionError> {
         Ok(match *self {
             crate::Expression::GlobalVariable(var) => GlobalOrArgument::Global(var),
             crate::Expression::FunctionArgument(i) => GlobalOrArgument::Argument(i),
+            // an element of a binding array
+            crate::Expression::Access { base, .. }
+            | crate::Expression::AccessIndex { base, .. } => match expression_arena[base] {
+                crate::Expression::GlobalVariable(var) => GlobalOrArgument::Global(var),
+                _ => return Err(ExpressionError::ExpectedGlobalOrArgument),
+            },
             _ => return Err(ExpressionError::ExpectedGlobalOrArgument),
         })
     }
@@ -322,7 +331,7 @@ impl FunctionInfo {
                 GlobalOrArgument::Argument(i) => {
                     let handle = arguments[i as usize];
                     expression_arena[handle]
-                        .to_global_or_argument()
+                        .to_global_or_argument(expression_arena)
                         .map_err(|error| {
                             FunctionError::Expression { handle, error }
                                 .with_span_handle(handle, expression_arena)
@@ -335,7 +344,7 @@ impl FunctionInfo {
                 GlobalOrArgument::Argument(i) => {
                     let handle = arguments[i as usize];
                     expression_arena[handle]
-                        .to_global_or_argument()
+                        .to_global_or_argument(expression_arena)
                         .map_err(|error| {
                             FunctionError::Expression { handle, error }
                                 .with_span_handle(handle, expression_arena)
@@ -473,8 +482,10 @@ impl FunctionInfo {
                 level,
                 depth_ref,
             } => {
-                let image_storage = expression_arena[image].to_global_or_argument()?;
-                let sampler_storage = expression_arena[sampler].to_global_or_argument()?;
+                let image_storage =
+                    expression_arena[image].to_global_or_argument(expression_arena)?;
+                let sampler_storage =
+                    expression_arena[sampler].to_global_or_argument(expression_arena)?;
 
                 match (image_storage, sampler_storage) {
                     (GlobalOrArgument::Global(image), GlobalOrArgument::Global(sampler)) => {
@@ -588,14 +599,29 @@ impl FunctionInfo {
 
                 info.uniformity.clone()
             }
-            E::AtomicResult { .. } => Uniformity {
+            E::AtomicResult { .. }
+            | E::SubgroupBallotResult
+            | E::SubgroupOperationResult { .. }
+            | E::RayQueryProceedResult => Uniformity {
                 non_uniform_result: Some(handle),
                 requirements: UniformityRequirements::empty(),
             },
+            E::RayQueryGetCommittedIntersection { query, ty: _ } => Uniformity {
+                non_uniform_result: self.add_ref(query),
+                requirements: UniformityRequirements::empty(),
+            },
             E::ArrayLength(expr) => Uniformity {
                 non_uniform_result: self.add_ref_impl(expr, GlobalUse::QUERY),
                 requirements: UniformityRequirements::empty(),
             },
+            // explicitly marked as non-uniform, regardless of the value
+            E::NonUniform(value) => {
+                let _ = self.add_ref(value);
+                Uniformity {
+                    non_uniform_result: Some(handle),
+                    requirements: UniformityRequirements::empty(),
+                }
+            }
         };
 
         let ty = resolve_context.resolve(expression, |h| {
@@ -795,6 +821,22 @@ impl FunctionInfo {
                     //Note: the result is validated by the Validator, not here
                     self.process_call(info, arguments, expression_arena)?
                 }
+                S::ImageAtomic {
+                    image,
+                    coordinate,
+                    array_index,
+                    fun: _,
+                    value,
+                    result: _,
+                } => {
+                    let _ = self.add_ref_impl(image, GlobalUse::READ | GlobalUse::WRITE);
+                    if let Some(expr) = array_index {
+                        let _ = self.add_ref(expr);
+                    }
+                    let _ = self.add_ref(coordinate);
+                    let _ = self.add_ref(value);
+                    FunctionUniformity::new()
+                }
                 S::Atomic {
                     pointer,
                     ref fun,
@@ -808,6 +850,69 @@ impl FunctionInfo {
                     }
                     FunctionUniformity::new()
                 }
+                S::SubgroupBallot {
+                    result: _,
+                    predicate,
+                } => {
+                    if let Some(predicate) = predicate {
+                        let _ = self.add_ref(predicate);
+                    }
+                    FunctionUniformity::new()
+                }
+                S::SubgroupElect { result: _ } => FunctionUniformity::new(),
+                S::SubgroupGather {
+                    mode,
+                    argument,
+                    result: _,
+                } => {
+                    let _ = self.add_ref(argument);
+                    match mode {
+                        crate::GatherMode::BroadcastFirst => {}
+                        crate::GatherMode::Broadcast(index)
+                        | crate::GatherMode::Shuffle(index)
+                        | crate::GatherMode::ShuffleDown(index)
+                        | crate::GatherMode::ShuffleUp(index)
+                        | crate::GatherMode::ShuffleXor(index) => {
+                            let _ = self.add_ref(index);
+                        }
+                    }
+                    FunctionUniformity::new()
+                }
+                S::SubgroupCollectiveOperation {
+                    op: _,
+                    collective_op: _,
+                    argument,
+                    result: _,
+                } => {
+                    let _ = self.add_ref(argument);
+                    FunctionUniformity::new()
+                }
+                S::RayQuery { query, ref fun } => {
+                    let _ = self.add_ref(query);
+                    if let crate::RayQueryFunction::Initialize {
+                        acceleration_structure,
+                        descriptor,
+                    } = *fun
+                    {
+                        let _ = self.add_ref(acceleration_structure);
+                        let _ = self.add_ref(descriptor);
+                    }
+                    FunctionUniformity::new()
+                }
+                S::MeshFunction(ref fun) => {
+                    match *fun {
+                        crate::MeshFunction::SetMeshOutputs {
+                            vertex_count: a,
+                            primitive_count: b,
+                        }
+                        | crate::MeshFunction::SetVertex { index: a, value: b }
+                        | crate::MeshFunction::SetPrimitive { index: a, value: b } => {
+                            let _ = self.add_ref(a);
+                            let _ = self.add_ref(b);
+                        }
+                    }
+                    FunctionUniformity::new()
+                }
             };
 
             disruptor = disruptor.or(uniformity.exit_disruptor());
@@ -33,6 +33,8 @@ pub enum ExpressionError {
     InvalidPointerType(Handle<crate::Expression>),
     #[error("Array length of {0:?} can't be done")]
     InvalidArrayType(Handle<crate::Expression>),
+    #[error("Non-uniform marker of {0:?} can't be done, it's not an integer scalar")]
+    InvalidNonUniformType(Handle<crate::Expression>),
     #[error("Splatting {0:?} can't be done")]
     InvalidSplatType(Handle<crate::Expression>),
     #[error("Swizzling {0:?} can't be done")]
@@ -113,8 +115,16 @@ pub enum ExpressionError {
     WrongArgumentCount(crate::MathFunction),
     #[error("Argument [{1}] to {0:?} as expression {2:?} has an invalid type.")]
     InvalidArgumentType(crate::MathFunction, u32, Handle<crate::Expression>),
-    #[error("Atomic result type can't be {0:?} of {1} bytes")]
-    InvalidAtomicResultType(crate::ScalarKind, crate::Bytes),
+    #[error("Atomic result type {0:?} is invalid")]
+    InvalidAtomicResultType(Handle<crate::Type>),
+    #[error("Subgroup result type {0:?} doesn't exist")]
+    InvalidSubgroupResultType(Handle<crate::Type>),
+    #[error("Expression {0:?} is not a pointer to a ray query")]
+    InvalidRayQueryType(Handle<crate::Expression>),
+    #[error("Ray intersection type {0:?} doesn't have the expected members")]
+    InvalidRayIntersectionType(Handle<crate::Type>),
+    #[error("Non-uniform indexing of the binding array {0:?} requires {1:?}")]
+    MissingCapabilities(Handle<crate::Expression>, super::Capabilities),
 }
 
 #[cfg(feature = "validate")]
@@ -138,6 +148,38 @@ impl<'a> ExpressionTypeResolver<'a> {
     }
 }
 
+/// Return the type of the image or sampler referred to by `expr`.
+///
+/// Elements of binding arrays are resolved to the array's base type.
+#[cfg(feature = "validate")]
+pub(super) fn resolve_handle_type(
+    expr: Handle<crate::Expression>,
+    expressions: &crate::Arena<crate::Expression>,
+    arguments: &[crate::FunctionArgument],
+    global_vars: &crate::Arena<crate::GlobalVariable>,
+    types: &UniqueArena<crate::Type>,
+) -> Result<Handle<crate::Type>, ExpressionError> {
+    match expressions[expr] {
+        crate::Expression::GlobalVariable(var_handle) => Ok(global_vars[var_handle].ty),
+        crate::Expression::FunctionArgument(i) => arguments
+            .get(i as usize)
+            .map(|arg| arg.ty)
+            .ok_or(ExpressionError::ExpectedGlobalVariable),
+        crate::Expression::Access { base, .. } | crate::Expression::AccessIndex { base, .. } => {
+            match expressions[base] {
+                crate::Expression::GlobalVariable(var_handle) => {
+                    match types[global_vars[var_handle].ty].inner {
+                        crate::TypeInner::BindingArray { base, .. } => Ok(base),
+                        _ => Err(ExpressionError::ExpectedGlobalVariable),
+                    }
+                }
+                _ => Err(ExpressionError::ExpectedGlobalVariable),
+            }
+        }
+        _ => Err(ExpressionError::ExpectedGlobalVariable),
+    }
+}
+
 #[cfg(feature = "validate")]
 impl super::Validator {
     pub(super) fn validate_expression(
@@ -162,7 +204,7 @@ impl super::Validator {
                 let base_type = resolver.resolve(base)?;
                 // See the documentation for `Expression::Access`.
                 let dynamic_indexing_restricted = match *base_type {
-                    Ti::Vector { .. } => false,
+                    Ti::Vector { .. } | Ti::BindingArray { .. } => false,
                     Ti::Matrix { .. } | Ti::Array { .. } => true,
                     Ti::Pointer { .. } | Ti::ValuePointer { size: Some(_), .. } => false,
                     ref other => {
@@ -190,6 +232,13 @@ impl super::Validator {
                 {
                     return Err(ExpressionError::IndexMustBeConstant(base));
                 }
+                if info[index].uniformity.non_uniform_result.is_some() {
+                    if let Some(required) = base_type.binding_array_non_uniform_capability(module) {
+                        if !self.capabilities.contains(required) {
+                            return Err(ExpressionError::MissingCapabilities(base, required));
+                        }
+                    }
+                }
 
                 // If we know both the length and the index, we can do the
                 // bounds check now.
@@ -240,6 +289,11 @@ impl super::Validator {
                             ..
                         } => module.constants[handle].to_array_length().unwrap(),
                         Ti::Array { .. } => !0, // can't statically know, but need run-time checks
+                        Ti::BindingArray {
+                            size: crate::ArraySize::Constant(handle),
+                            ..
+                        } => module.constants[handle].to_array_length().unwrap(),
+                        Ti::BindingArray { .. } => !0,
                         Ti::Pointer { base, .. } if top_level => {
                             resolve_index_limit(module, top, &module.types[base].inner, false)?
                         }
@@ -344,20 +398,20 @@ impl super::Validator {
                 depth_ref,
             } => {
                 // check the validity of expressions
-                let image_ty = match function.expressions[image] {
-                    crate::Expression::GlobalVariable(var_handle) => {
-                        module.global_variables[var_handle].ty
-                    }
-                    crate::Expression::FunctionArgument(i) => function.arguments[i as usize].ty,
-                    _ => return Err(ExpressionError::ExpectedGlobalVariable),
-                };
-                let sampler_ty = match function.expressions[sampler] {
-                    crate::Expression::GlobalVariable(var_handle) => {
-                        module.global_variables[var_handle].ty
-                    }
-                    crate::Expression::FunctionArgument(i) => function.arguments[i as usize].ty,
-                    _ => return Err(ExpressionError::ExpectedGlobalVariable),
-                };
+                let image_ty = resolve_handle_type(
+                    image,
+                    &function.expressions,
+                    &function.arguments,
+                    &module.global_variables,
+                    &module.types,
+                )?;
+                let sampler_ty = resolve_handle_type(
+                    sampler,
+                    &function.expressions,
+                    &function.arguments,
+                    &module.global_variables,
+                    &module.types,
+                )?;
                 let comparison = match module.types[sampler_ty].inner {
                     Ti::Sampler { comparison } => comparison,
                     _ => return Err(ExpressionError::ExpectedSamplerType(sampler_ty)),
@@ -539,13 +593,13 @@ impl super::Validator {
                 array_index,
                 index,
             } => {
-                let ty = match function.expressions[image] {
-                    crate::Expression::GlobalVariable(var_handle) => {
-                        module.global_variables[var_handle].ty
-                    }
-                    crate::Expression::FunctionArgument(i) => function.arguments[i as usize].ty,
-                    _ => return Err(ExpressionError::ExpectedGlobalVariable),
-                };
+                let ty = resolve_handle_type(
+                    image,
+                    &function.expressions,
+                    &function.arguments,
+                    &module.global_variables,
+                    &module.types,
+                )?;
                 match module.types[ty].inner {
                     Ti::Image {
                         class,
@@ -594,13 +648,13 @@ impl super::Validator {
                 ShaderStages::all()
             }
             E::ImageQuery { image, query } => {
-                let ty = match function.expressions[image] {
-                    crate::Expression::GlobalVariable(var_handle) => {
-                        module.global_variables[var_handle].ty
-                    }
-                    crate::Expression::FunctionArgument(i) => function.arguments[i as usize].ty,
-                    _ => return Err(ExpressionError::ExpectedGlobalVariable),
-                };
+                let ty = resolve_handle_type(
+                    image,
+                    &function.expressions,
+                    &function.arguments,
+                    &module.global_variables,
+                    &module.types,
+                )?;
                 match module.types[ty].inner {
                     Ti::Image { class, arrayed, .. } => {
                         let can_level = match class {
@@ -1365,19 +1419,69 @@ impl super::Validator {
                 ShaderStages::all()
             }
             E::CallResult(function) => other_infos[function.index()].available_stages,
-            E::AtomicResult {
-                kind,
-                width,
-                comparison: _,
-            } => {
-                let good = match kind {
-                    crate::ScalarKind::Uint | crate::ScalarKind::Sint => {
-                        self.check_width(kind, width)
-                    }
+            E::AtomicResult { ty, comparison } => {
+                let is_atomic_scalar = |ty: Handle<crate::Type>| match resolver.types.get_handle(ty)
+                {
+                    Some(&crate::Type {
+                        inner: Ti::Scalar { kind, width },
+                        ..
+                    }) => match kind {
+                        crate::ScalarKind::Uint | crate::ScalarKind::Sint => {
+                            self.check_width(kind, width)
+                        }
+                        _ => false,
+                    },
                     _ => false,
                 };
+                let good = if comparison {
+                    // The result of a compare-exchange is an `(old_value, exchanged)` pair.
+                    match resolver.types.get_handle(ty) {
+                        Some(&crate::Type {
+                            inner: Ti::Struct { ref members, .. },
+                            ..
+                        }) => match *members.as_slice() {
+                            [ref old_value, ref exchanged] => {
+                                is_atomic_scalar(old_value.ty)
+                                    && resolver.types[exchanged.ty].inner
+                                        == Ti::Scalar {
+                                            kind: crate::ScalarKind::Bool,
+                                            width: crate::BOOL_WIDTH,
+                                        }
+                            }
+                            _ => false,
+                        },
+                        _ => false,
+                    }
+                } else {
+                    is_atomic_scalar(ty)
+                };
                 if !good {
-                    return Err(ExpressionError::InvalidAtomicResultType(kind, width));
+                    return Err(ExpressionError::InvalidAtomicResultType(ty));
+                }
+                ShaderStages::all()
+            }
+            E::SubgroupBallotResult => ShaderStages::all(),
+            E::SubgroupOperationResult { ty } => {
+                if resolver.types.get_handle(ty).is_none() {
+                    return Err(ExpressionError::InvalidSubgroupResultType(ty));
+                }
+                ShaderStages::all()
+            }
+            E::RayQueryProceedResult => ShaderStages::all(),
+            E::RayQueryGetCommittedIntersection { query, ty } => {
+                match *resolver.resolve(query)? {
+                    Ti::Pointer { base, .. } => match resolver.types.get_handle(base) {
+                        Some(&crate::Type {
+                            inner: Ti::RayQuery,
+                            ..
+                        }) => {}
+                        _ => return Err(ExpressionError::InvalidRayQueryType(query)),
+                    },
+                    _ => return Err(ExpressionError::InvalidRayQueryType(query)),
+                }
+                match resolver.types.get_handle(ty) {
+                    Some(ty_ref) if ty_ref.inner.is_ray_intersection(resolver.types) => {}
+                    _ => return Err(ExpressionError::InvalidRayIntersectionType(ty)),
                 }
                 ShaderStages::all()
             }
@@ -1398,6 +1502,20 @@ impl super::Validator {
                     return Err(ExpressionError::InvalidArrayType(expr));
                 }
             },
+            E::NonUniform(value) => match *resolver.resolve(value)? {
+                Ti::Scalar {
+                    kind: Sk::Sint,
+                    width: _,
+                }
+                | Ti::Scalar {
+                    kind: Sk::Uint,
+                    width: _,
+                } => ShaderStages::all(),
+                ref other => {
+                    log::error!("Non-uniform marker of {:?}", other);
+                    return Err(ExpressionError::InvalidNonUniformType(value));
+                }
+            },
         };
         Ok(stages)
     }
@@ -2,6 +2,8 @@
 use crate::arena::{Arena, UniqueArena};
 use crate::arena::{BadHandle, Handle};
 
+#[cfg(feature = "validate")]
+use super::expression::resolve_handle_type;
 use super::{
     analyzer::{UniformityDisruptor, UniformityRequirements},
     ExpressionError, FunctionInfo, ModuleInfo,
@@ -53,6 +55,38 @@ pub enum AtomicError {
     ResultAlreadyInScope(Handle<crate::Expression>),
     #[error("Result type for {0:?} doesn't match the statement")]
     ResultTypeMismatch(Handle<crate::Expression>),
+    #[error("Image {0:?} is not a 32-bit integer storage image")]
+    InvalidImage(Handle<crate::Expression>),
+    #[error("Function {0:?} is not supported on images")]
+    UnsupportedImageFunction(crate::AtomicFunction),
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum SubgroupError {
+    #[error("Operand {0:?} has invalid type.")]
+    InvalidOperand(Handle<crate::Expression>),
+    #[error("Operation {0:?} can't be used with {1:?}")]
+    InvalidOperation(crate::SubgroupOperation, crate::CollectiveOperation),
+    #[error("Result expression {0:?} has already been introduced earlier")]
+    ResultAlreadyInScope(Handle<crate::Expression>),
+    #[error("Result type for {0:?} doesn't match the statement")]
+    ResultTypeMismatch(Handle<crate::Expression>),
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum RayQueryError {
+    #[error("Expression {0:?} is not a pointer to a ray query")]
+    InvalidQuery(Handle<crate::Expression>),
+    #[error("Acceleration structure {0:?} is not a matching expression")]
+    InvalidAccelerationStructure(Handle<crate::Expression>),
+    #[error("Ray descriptor {0:?} doesn't have the expected members")]
+    InvalidDescriptor(Handle<crate::Expression>),
+    #[error("Result expression {0:?} has already been introduced earlier")]
+    ResultAlreadyInScope(Handle<crate::Expression>),
+    #[error("Result expression {0:?} is not a ray query proceed result")]
+    InvalidResult(Handle<crate::Expression>),
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -125,6 +159,8 @@ pub enum FunctionError {
     },
     #[error("Image store parameters are invalid")]
     InvalidImageStore(#[source] ExpressionError),
+    #[error("Image atomic parameters are invalid")]
+    InvalidImageAtomic(#[source] ExpressionError),
     #[error("Call to {function:?} is invalid")]
     InvalidCall {
         function: Handle<crate::Function>,
@@ -133,6 +169,14 @@ pub enum FunctionError {
     },
     #[error("Atomic operation is invalid")]
     InvalidAtomic(#[from] AtomicError),
+    #[error("Subgroup operation is invalid")]
+    InvalidSubgroup(#[from] SubgroupError),
+    #[error("Ray query is invalid")]
+    InvalidRayQuery(#[from] RayQueryError),
+    #[error("Mesh output index or count {0:?} is not an unsigned integer scalar")]
+    InvalidMeshFunctionOperand(Handle<crate::Expression>),
+    #[error("Capability {0:?} is required")]
+    MissingCapability(super::Capabilities),
     #[error(
         "Required uniformity of control flow for {0:?} in {1:?} is not fulfilled because of {2:?}"
     )]
@@ -358,13 +402,25 @@ impl super::Validator {
                 .with_span_handle(result, context.expressions)
                 .into_other());
         }
-        match context.expressions[result] {
-            //TODO: support atomic result with comparison
-            crate::Expression::AtomicResult {
-                kind,
-                width,
-                comparison: false,
-            } if kind == ptr_kind && width == ptr_width => {}
+        let is_compare_exchange =
+            matches!(*fun, crate::AtomicFunction::Exchange { compare: Some(_) });
+        let value_ty = match context.expressions[result] {
+            crate::Expression::AtomicResult { ty, comparison }
+                if comparison == is_compare_exchange =>
+            {
+                match context.types[ty].inner {
+                    // the shape of the structure is checked by the expression validator
+                    crate::TypeInner::Struct { ref members, .. } if comparison => {
+                        members.first().map(|member| member.ty)
+                    }
+                    _ => Some(ty),
+                }
+            }
+            _ => None,
+        };
+        match value_ty.map(|ty| &context.types[ty].inner) {
+            Some(&crate::TypeInner::Scalar { kind, width })
+                if kind == ptr_kind && width == ptr_width => {}
             _ => {
                 return Err(AtomicError::ResultTypeMismatch(result)
                     .with_span_handle(result, context.expressions)
@@ -374,6 +430,374 @@ impl super::Validator {
         Ok(())
     }
 
+    /// Validate the image, coordinate and array index of a texel access, and
+    /// return the class of the image. Errors are wrapped with `error`.
+    #[cfg(feature = "validate")]
+    fn validate_image_texel(
+        &self,
+        image: Handle<crate::Expression>,
+        coordinate: Handle<crate::Expression>,
+        array_index: Option<Handle<crate::Expression>>,
+        context: &BlockContext,
+        error: fn(ExpressionError) -> FunctionError,
+    ) -> Result<crate::ImageClass, WithSpan<FunctionError>> {
+        let _ = context.get_expression(image).map_err(|e| e.with_span())?;
+        let image_ty = match resolve_handle_type(
+            image,
+            context.expressions,
+            &[],
+            context.global_vars,
+            context.types,
+        ) {
+            Ok(ty) => ty,
+            Err(err) => return Err(error(err).with_span_handle(image, context.expressions)),
+        };
+
+        match context.types[image_ty].inner {
+            crate::TypeInner::Image {
+                class,
+                arrayed,
+                dim,
+            } => {
+                match context
+                    .resolve_type(coordinate, &self.valid_expression_set)?
+                    .image_storage_coordinates()
+                {
+                    Some(coord_dim) if coord_dim == dim => {}
+                    _ => {
+                        return Err(error(ExpressionError::InvalidImageCoordinateType(
+                            dim, coordinate,
+                        ))
+                        .with_span_handle(coordinate, context.expressions));
+                    }
+                };
+                if arrayed != array_index.is_some() {
+                    return Err(error(ExpressionError::InvalidImageArrayIndex)
+                        .with_span_handle(coordinate, context.expressions));
+                }
+                if let Some(expr) = array_index {
+                    match *context.resolve_type(expr, &self.valid_expression_set)? {
+                        crate::TypeInner::Scalar {
+                            kind: crate::ScalarKind::Sint,
+                            width: _,
+                        } => {}
+                        _ => {
+                            return Err(error(ExpressionError::InvalidImageArrayIndexType(expr))
+                                .with_span_handle(expr, context.expressions));
+                        }
+                    }
+                }
+                Ok(class)
+            }
+            _ => Err(error(ExpressionError::ExpectedImageType(image_ty))
+                .with_span()
+                .with_handle(image_ty, context.types)
+                .with_handle(image, context.expressions)),
+        }
+    }
+
+    #[cfg(feature = "validate")]
+    #[allow(clippy::too_many_arguments)]
+    fn validate_image_atomic(
+        &mut self,
+        image: Handle<crate::Expression>,
+        coordinate: Handle<crate::Expression>,
+        array_index: Option<Handle<crate::Expression>>,
+        fun: &crate::AtomicFunction,
+        value: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        let class = self.validate_image_texel(
+            image,
+            coordinate,
+            array_index,
+            context,
+            FunctionError::InvalidImageAtomic,
+        )?;
+        let kind = match class {
+            crate::ImageClass::Storage {
+                format: crate::StorageFormat::R32Uint,
+                ..
+            } => crate::ScalarKind::Uint,
+            crate::ImageClass::Storage {
+                format: crate::StorageFormat::R32Sint,
+                ..
+            } => crate::ScalarKind::Sint,
+            _ => {
+                return Err(AtomicError::InvalidImage(image)
+                    .with_span_handle(image, context.expressions)
+                    .into_other())
+            }
+        };
+
+        if let crate::AtomicFunction::Exchange { compare: Some(_) } = *fun {
+            return Err(AtomicError::UnsupportedImageFunction(*fun)
+                .with_span_handle(image, context.expressions)
+                .into_other());
+        }
+
+        let value_ty = crate::TypeInner::Scalar { kind, width: 4 };
+        if *context.resolve_type(value, &self.valid_expression_set)? != value_ty {
+            return Err(AtomicError::InvalidOperand(value)
+                .with_span_handle(value, context.expressions)
+                .into_other());
+        }
+
+        if self.valid_expression_set.insert(result.index()) {
+            self.valid_expression_list.push(result);
+        } else {
+            return Err(AtomicError::ResultAlreadyInScope(result)
+                .with_span_handle(result, context.expressions)
+                .into_other());
+        }
+        match context.expressions[result] {
+            crate::Expression::AtomicResult {
+                ty,
+                comparison: false,
+            } if context.types[ty].inner == value_ty => Ok(()),
+            _ => Err(AtomicError::ResultTypeMismatch(result)
+                .with_span_handle(result, context.expressions)
+                .into_other()),
+        }
+    }
+
+    #[cfg(feature = "validate")]
+    fn validate_subgroup_result(
+        &mut self,
+        result: Handle<crate::Expression>,
+        expected: &crate::TypeInner,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        if self.valid_expression_set.insert(result.index()) {
+            self.valid_expression_list.push(result);
+        } else {
+            return Err(SubgroupError::ResultAlreadyInScope(result)
+                .with_span_handle(result, context.expressions)
+                .into_other());
+        }
+        let good = match context.expressions[result] {
+            crate::Expression::SubgroupBallotResult => {
+                *expected
+                    == crate::TypeInner::Vector {
+                        size: crate::VectorSize::Quad,
+                        kind: crate::ScalarKind::Uint,
+                        width: 4,
+                    }
+            }
+            crate::Expression::SubgroupOperationResult { ty } => {
+                context.types[ty].inner == *expected
+            }
+            _ => false,
+        };
+        if !good {
+            return Err(SubgroupError::ResultTypeMismatch(result)
+                .with_span_handle(result, context.expressions)
+                .into_other());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "validate")]
+    fn validate_subgroup_gather(
+        &mut self,
+        mode: &crate::GatherMode,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        match *mode {
+            crate::GatherMode::BroadcastFirst => {}
+            crate::GatherMode::Broadcast(index)
+            | crate::GatherMode::Shuffle(index)
+            | crate::GatherMode::ShuffleDown(index)
+            | crate::GatherMode::ShuffleUp(index)
+            | crate::GatherMode::ShuffleXor(index) => {
+                match *context.resolve_type(index, &self.valid_expression_set)? {
+                    crate::TypeInner::Scalar {
+                        kind: crate::ScalarKind::Uint,
+                        width: 4,
+                    } => {}
+                    ref other => {
+                        log::error!("Subgroup gather index type {:?}", other);
+                        return Err(SubgroupError::InvalidOperand(index)
+                            .with_span_handle(index, context.expressions)
+                            .into_other());
+                    }
+                }
+            }
+        }
+
+        let argument_inner = context.resolve_type(argument, &self.valid_expression_set)?;
+        match *argument_inner {
+            crate::TypeInner::Scalar { .. } | crate::TypeInner::Vector { .. } => {}
+            ref other => {
+                log::error!("Subgroup gather operand type {:?}", other);
+                return Err(SubgroupError::InvalidOperand(argument)
+                    .with_span_handle(argument, context.expressions)
+                    .into_other());
+            }
+        }
+
+        self.validate_subgroup_result(result, argument_inner, context)
+    }
+
+    #[cfg(feature = "validate")]
+    fn validate_subgroup_collective_operation(
+        &mut self,
+        op: crate::SubgroupOperation,
+        collective_op: crate::CollectiveOperation,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        use crate::{ScalarKind as Sk, SubgroupOperation as So};
+
+        let argument_inner = context.resolve_type(argument, &self.valid_expression_set)?;
+        let kind = match *argument_inner {
+            crate::TypeInner::Scalar { kind, .. } | crate::TypeInner::Vector { kind, .. } => kind,
+            ref other => {
+                log::error!("Subgroup operand type {:?}", other);
+                return Err(SubgroupError::InvalidOperand(argument)
+                    .with_span_handle(argument, context.expressions)
+                    .into_other());
+            }
+        };
+        // scans are only available for addition and multiplication on all targets
+        match (op, collective_op) {
+            (_, crate::CollectiveOperation::Reduce) | (So::Add, _) | (So::Mul, _) => {}
+            _ => {
+                return Err(SubgroupError::InvalidOperation(op, collective_op)
+                    .with_span_handle(argument, context.expressions)
+                    .into_other());
+            }
+        }
+        let good = match op {
+            So::All | So::Any => {
+                *argument_inner
+                    == crate::TypeInner::Scalar {
+                        kind: Sk::Bool,
+                        width: crate::BOOL_WIDTH,
+                    }
+            }
+            So::Add | So::Mul | So::Min | So::Max => kind != Sk::Bool,
+            So::And | So::Or | So::Xor => kind != Sk::Float,
+        };
+        if !good {
+            return Err(SubgroupError::InvalidOperand(argument)
+                .with_span_handle(argument, context.expressions)
+                .into_other());
+        }
+
+        self.validate_subgroup_result(result, argument_inner, context)
+    }
+
+    #[cfg(feature = "validate")]
+    fn validate_ray_query(
+        &mut self,
+        query: Handle<crate::Expression>,
+        fun: &crate::RayQueryFunction,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        match *context.resolve_type(query, &self.valid_expression_set)? {
+            crate::TypeInner::Pointer { base, .. }
+                if context.types[base].inner == crate::TypeInner::RayQuery => {}
+            ref other => {
+                log::error!("Ray query on type {:?}", other);
+                return Err(RayQueryError::InvalidQuery(query)
+                    .with_span_handle(query, context.expressions)
+                    .into_other());
+            }
+        }
+
+        match *fun {
+            crate::RayQueryFunction::Initialize {
+                acceleration_structure,
+                descriptor,
+            } => {
+                match *context.resolve_type(acceleration_structure, &self.valid_expression_set)? {
+                    crate::TypeInner::AccelerationStructure => {}
+                    ref other => {
+                        log::error!("Acceleration structure of type {:?}", other);
+                        return Err(RayQueryError::InvalidAccelerationStructure(
+                            acceleration_structure,
+                        )
+                        .with_span_handle(acceleration_structure, context.expressions)
+                        .into_other());
+                    }
+                }
+                let desc_inner = context.resolve_type(descriptor, &self.valid_expression_set)?;
+                if !desc_inner.is_ray_desc(context.types) {
+                    log::error!("Ray descriptor of type {:?}", desc_inner);
+                    return Err(RayQueryError::InvalidDescriptor(descriptor)
+                        .with_span_handle(descriptor, context.expressions)
+                        .into_other());
+                }
+            }
+            crate::RayQueryFunction::Proceed { result } => {
+                if self.valid_expression_set.insert(result.index()) {
+                    self.valid_expression_list.push(result);
+                } else {
+                    return Err(RayQueryError::ResultAlreadyInScope(result)
+                        .with_span_handle(result, context.expressions)
+                        .into_other());
+                }
+                match context.expressions[result] {
+                    crate::Expression::RayQueryProceedResult => {}
+                    _ => {
+                        return Err(RayQueryError::InvalidResult(result)
+                            .with_span_handle(result, context.expressions)
+                            .into_other())
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "validate")]
+    fn validate_mesh_operand(
+        &self,
+        operand: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        match *context.resolve_type(operand, &self.valid_expression_set)? {
+            crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Uint,
+                width: _,
+            } => Ok(()),
+            _ => Err(FunctionError::InvalidMeshFunctionOperand(operand)
+                .with_span_handle(operand, context.expressions)),
+        }
+    }
+
+    /// Validate the operands of a mesh function.
+    ///
+    /// The vertex and primitive values are checked against the entry point's
+    /// [`MeshStageInfo`](crate::MeshStageInfo) by the interface validation.
+    #[cfg(feature = "validate")]
+    fn validate_mesh_function(
+        &self,
+        fun: &crate::MeshFunction,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        match *fun {
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count,
+                primitive_count,
+            } => {
+                self.validate_mesh_operand(vertex_count, context)?;
+                self.validate_mesh_operand(primitive_count, context)?;
+            }
+            crate::MeshFunction::SetVertex { index, value }
+            | crate::MeshFunction::SetPrimitive { index, value } => {
+                self.validate_mesh_operand(index, context)?;
+                let _ = context.resolve_type(value, &self.valid_expression_set)?;
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "validate")]
     fn validate_block_impl(
         &mut self,
@@ -575,7 +999,9 @@ impl super::Validator {
                     finished = true;
                 }
                 S::Barrier(_) => {
-                    stages &= super::ShaderStages::COMPUTE;
+                    stages &= super::ShaderStages::COMPUTE
+                        | super::ShaderStages::TASK
+                        | super::ShaderStages::MESH;
                 }
                 S::Store { pointer, value } => {
                     let mut current = pointer;
@@ -651,83 +1077,24 @@ impl super::Validator {
                     array_index,
                     value,
                 } => {
-                    //Note: this code uses a lot of `FunctionError::InvalidImageStore`,
-                    // and could probably be refactored.
-                    let var = match *context.get_expression(image).map_err(|e| e.with_span())? {
-                        crate::Expression::GlobalVariable(var_handle) => {
-                            &context.global_vars[var_handle]
-                        }
-                        _ => {
-                            return Err(FunctionError::InvalidImageStore(
-                                ExpressionError::ExpectedGlobalVariable,
-                            )
-                            .with_span_handle(image, context.expressions))
-                        }
-                    };
-
-                    let value_ty = match context.types[var.ty].inner {
-                        Ti::Image {
-                            class,
-                            arrayed,
-                            dim,
-                        } => {
-                            match context
-                                .resolve_type(coordinate, &self.valid_expression_set)?
-                                .image_storage_coordinates()
-                            {
-                                Some(coord_dim) if coord_dim == dim => {}
-                                _ => {
-                                    return Err(FunctionError::InvalidImageStore(
-                                        ExpressionError::InvalidImageCoordinateType(
-                                            dim, coordinate,
-                                        ),
-                                    )
-                                    .with_span_handle(coordinate, context.expressions));
-                                }
-                            };
-                            if arrayed != array_index.is_some() {
-                                return Err(FunctionError::InvalidImageStore(
-                                    ExpressionError::InvalidImageArrayIndex,
-                                )
-                                .with_span_handle(coordinate, context.expressions));
-                            }
-                            if let Some(expr) = array_index {
-                                match *context.resolve_type(expr, &self.valid_expression_set)? {
-                                    Ti::Scalar {
-                                        kind: crate::ScalarKind::Sint,
-                                        width: _,
-                                    } => {}
-                                    _ => {
-                                        return Err(FunctionError::InvalidImageStore(
-                                            ExpressionError::InvalidImageArrayIndexType(expr),
-                                        )
-                                        .with_span_handle(expr, context.expressions));
-                                    }
-                                }
-                            }
-                            match class {
-                                crate::ImageClass::Storage { format, .. } => {
-                                    crate::TypeInner::Vector {
-                                        kind: format.into(),
-                                        size: crate::VectorSize::Quad,
-                                        width: 4,
-                                    }
-                                }
-                                _ => {
-                                    return Err(FunctionError::InvalidImageStore(
-                                        ExpressionError::InvalidImageClass(class),
-                                    )
-                                    .with_span_handle(image, context.expressions));
-                                }
-                            }
-                        }
+                    let class = self.validate_image_texel(
+                        image,
+                        coordinate,
+                        array_index,
+                        context,
+                        FunctionError::InvalidImageStore,
+                    )?;
+                    let value_ty = match class {
+                        crate::ImageClass::Storage { format, .. } => crate::TypeInner::Vector {
+                            kind: format.into(),
+                            size: crate::VectorSize::Quad,
+                            width: 4,
+                        },
                         _ => {
                             return Err(FunctionError::InvalidImageStore(
-                                ExpressionError::ExpectedImageType(var.ty),
+                                ExpressionError::InvalidImageClass(class),
                             )
-                            .with_span()
-                            .with_handle(var.ty, context.types)
-                            .with_handle(image, context.expressions))
+                            .with_span_handle(image, context.expressions));
                         }
                     };
 
@@ -736,6 +1103,34 @@ impl super::Validator {
                             .with_span_handle(value, context.expressions));
                     }
                 }
+                S::ImageAtomic { .. }
+                    if !self
+                        .capabilities
+                        .contains(super::Capabilities::TEXTURE_ATOMIC) =>
+                {
+                    return Err(FunctionError::MissingCapability(
+                        super::Capabilities::TEXTURE_ATOMIC,
+                    )
+                    .with_span_static(span, "image atomic"));
+                }
+                S::ImageAtomic {
+                    image,
+                    coordinate,
+                    array_index,
+                    ref fun,
+                    value,
+                    result,
+                } => {
+                    self.validate_image_atomic(
+                        image,
+                        coordinate,
+                        array_index,
+                        fun,
+                        value,
+                        result,
+                        context,
+                    )?;
+                }
                 S::Call {
                     function,
                     ref arguments,
@@ -757,6 +1152,90 @@ impl super::Validator {
                 } => {
                     self.validate_atomic(pointer, fun, value, result, context)?;
                 }
+                S::SubgroupBallot { .. }
+                | S::SubgroupElect { .. }
+                | S::SubgroupGather { .. }
+                | S::SubgroupCollectiveOperation { .. }
+                    if !self.capabilities.contains(super::Capabilities::SUBGROUP) =>
+                {
+                    return Err(
+                        FunctionError::MissingCapability(super::Capabilities::SUBGROUP)
+                            .with_span_static(span, "subgroup operation"),
+                    );
+                }
+                S::RayQuery { .. }
+                    if !self.capabilities.contains(super::Capabilities::RAY_QUERY) =>
+                {
+                    return Err(
+                        FunctionError::MissingCapability(super::Capabilities::RAY_QUERY)
+                            .with_span_static(span, "ray query"),
+                    );
+                }
+                S::RayQuery { query, ref fun } => {
+                    self.validate_ray_query(query, fun, context)?;
+                }
+                S::MeshFunction(_)
+                    if !self.capabilities.contains(super::Capabilities::MESH_SHADER) =>
+                {
+                    return Err(
+                        FunctionError::MissingCapability(super::Capabilities::MESH_SHADER)
+                            .with_span_static(span, "mesh function"),
+                    );
+                }
+                S::MeshFunction(ref fun) => {
+                    self.validate_mesh_function(fun, context)?;
+                    stages &= super::ShaderStages::MESH;
+                }
+                S::SubgroupBallot { result, predicate } => {
+                    if let Some(predicate) = predicate {
+                        match *context.resolve_type(predicate, &self.valid_expression_set)? {
+                            Ti::Scalar {
+                                kind: crate::ScalarKind::Bool,
+                                width: _,
+                            } => {}
+                            ref other => {
+                                log::error!("Subgroup ballot predicate type {:?}", other);
+                                return Err(SubgroupError::InvalidOperand(predicate)
+                                    .with_span_handle(predicate, context.expressions)
+                                    .into_other());
+                            }
+                        }
+                    }
+                    let ballot = Ti::Vector {
+                        size: crate::VectorSize::Quad,
+                        kind: crate::ScalarKind::Uint,
+                        width: 4,
+                    };
+                    self.validate_subgroup_result(result, &ballot, context)?;
+                }
+                S::SubgroupElect { result } => {
+                    let elected = Ti::Scalar {
+                        kind: crate::ScalarKind::Bool,
+                        width: crate::BOOL_WIDTH,
+                    };
+                    self.validate_subgroup_result(result, &elected, context)?;
+                }
+                S::SubgroupGather {
+                    ref mode,
+                    argument,
+                    result,
+                } => {
+                    self.validate_subgroup_gather(mode, argument, result, context)?;
+                }
+                S::SubgroupCollectiveOperation {
+                    op,
+                    collective_op,
+                    argument,
+                    result,
+                } => {
+                    self.validate_subgroup_collective_operation(
+                        op,
+                        collective_op,
+                        argument,
+                        result,
+                        context,
+                    )?;
+                }
             }
         }
         Ok(BlockInfo { stages, finished })
@@ -1,6 +1,6 @@
 use super::{
     analyzer::{FunctionInfo, GlobalUse},
-    Capabilities, Disalignment, FunctionError, ModuleInfo,
+    Capabilities, Disalignment, FunctionError, LimitError, ModuleInfo,
 };
 use crate::arena::{Handle, UniqueArena};
 
@@ -51,6 +51,14 @@ pub enum VaryingError {
     DuplicateBuiltIn(crate::BuiltIn),
     #[error("Capability {0:?} is not supported")]
     UnsupportedCapability(Capabilities),
+    #[error("Blend source indices are only valid on fragment shader outputs")]
+    InvalidBlendSrcStage,
+    #[error("Blend source index {blend_src} at location {location} is invalid")]
+    InvalidBlendSrc { location: u32, blend_src: u32 },
+    #[error(
+        "Dual-source blending needs exactly two outputs at location 0, with blend sources 0 and 1"
+    )]
+    InvalidDualSourceBlending,
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -63,6 +71,8 @@ pub enum EntryPointError {
     UnexpectedWorkgroupSize,
     #[error("Workgroup size is out of range")]
     OutOfRangeWorkgroupSize,
+    #[error("Workgroup size override {0:?} is not a specializable integer constant")]
+    InvalidWorkgroupSizeOverride(Handle<crate::Constant>),
     #[error("Uses operations forbidden at this stage")]
     ForbiddenStageOperations,
     #[error("Global variable {0:?} is used incorrectly as {1:?}")]
@@ -75,8 +85,28 @@ pub enum EntryPointError {
     Result(#[from] VaryingError),
     #[error("Location {location} onterpolation of an integer has to be flat")]
     InvalidIntegerInterpolation { location: u32 },
+    #[error("Capability {0:?} is required")]
+    MissingCapability(Capabilities),
+    #[error("Mesh output declaration is missing")]
+    MissingMeshInfo,
+    #[error("Mesh output declaration is not applicable")]
+    UnexpectedMeshInfo,
+    #[error("Mesh vertex output varying error")]
+    MeshVertexOutput(#[source] VaryingError),
+    #[error("Mesh primitive output varying error")]
+    MeshPrimitiveOutput(#[source] VaryingError),
+    #[error("Mesh primitive output is missing the {0:?} built-in")]
+    MissingPrimitiveIndices(crate::BuiltIn),
+    #[error("Mesh output value {0:?} doesn't match the declared output type")]
+    InvalidMeshOutputValue(Handle<crate::Expression>),
+    #[error("Task shader result must be the mesh task size built-in")]
+    InvalidTaskResult,
+    #[error("Mesh shader can't return a value")]
+    UnexpectedMeshResult,
     #[error(transparent)]
     Function(#[from] FunctionError),
+    #[error(transparent)]
+    Limit(#[from] LimitError),
 }
 
 #[cfg(feature = "validate")]
@@ -97,7 +127,9 @@ struct VaryingContext<'a> {
     output: bool,
     types: &'a UniqueArena<crate::Type>,
     location_mask: &'a mut BitSet,
-    built_in_mask: u32,
+    built_ins: &'a mut crate::FastHashSet<crate::BuiltIn>,
+    /// Bit `i` is set if an output has blend source index `i`.
+    blend_src_mask: u32,
     capabilities: Capabilities,
 }
 
@@ -110,11 +142,14 @@ impl VaryingContext<'_> {
         let ty_inner = &self.types[self.ty].inner;
         match *binding {
             crate::Binding::BuiltIn(built_in) => {
-                let bit = 1 << built_in as u32;
-                if self.built_in_mask & bit != 0 {
+                // Qualifiers don't make a built-in any less of a duplicate.
+                let canonical = match built_in {
+                    Bi::Position { .. } => Bi::Position { invariant: false },
+                    other => other,
+                };
+                if !self.built_ins.insert(canonical) {
                     return Err(VaryingError::DuplicateBuiltIn(built_in));
                 }
-                self.built_in_mask |= bit;
 
                 let width = 4;
                 let (visible, type_good) = match built_in {
@@ -127,7 +162,7 @@ impl VaryingContext<'_> {
                             },
                     ),
                     Bi::ClipDistance | Bi::CullDistance => (
-                        self.stage == St::Vertex && self.output,
+                        (self.stage == St::Vertex || self.stage == St::Mesh) && self.output,
                         match *ty_inner {
                             Ti::Array { base, .. } => {
                                 self.types[base].inner
@@ -140,18 +175,18 @@ impl VaryingContext<'_> {
                         },
                     ),
                     Bi::PointSize => (
-                        self.stage == St::Vertex && self.output,
+                        (self.stage == St::Vertex || self.stage == St::Mesh) && self.output,
                         *ty_inner
                             == Ti::Scalar {
                                 kind: Sk::Float,
                                 width,
                             },
                     ),
-                    Bi::Position => (
+                    Bi::Position { .. } => (
                         match self.stage {
-                            St::Vertex => self.output,
+                            St::Vertex | St::Mesh => self.output,
                             St::Fragment => !self.output,
-                            St::Compute => false,
+                            St::Compute | St::Task => false,
                         },
                         *ty_inner
                             == Ti::Vector {
@@ -163,7 +198,7 @@ impl VaryingContext<'_> {
                     Bi::ViewIndex => (
                         match self.stage {
                             St::Vertex | St::Fragment => !self.output,
-                            St::Compute => false,
+                            St::Compute | St::Task | St::Mesh => false,
                         },
                         *ty_inner
                             == Ti::Scalar {
@@ -219,19 +254,70 @@ impl VaryingContext<'_> {
                             },
                     ),
                     Bi::LocalInvocationIndex => (
-                        self.stage == St::Compute && !self.output,
+                        self.stage.has_workgroups() && !self.output,
                         *ty_inner
                             == Ti::Scalar {
                                 kind: Sk::Uint,
                                 width,
                             },
                     ),
+                    Bi::SubgroupSize | Bi::SubgroupInvocationId => {
+                        if !self.capabilities.contains(Capabilities::SUBGROUP) {
+                            return Err(VaryingError::UnsupportedCapability(
+                                Capabilities::SUBGROUP,
+                            ));
+                        }
+                        (
+                            (self.stage == St::Compute || self.stage == St::Fragment)
+                                && !self.output,
+                            *ty_inner
+                                == Ti::Scalar {
+                                    kind: Sk::Uint,
+                                    width,
+                                },
+                        )
+                    }
                     Bi::GlobalInvocationId
                     | Bi::LocalInvocationId
                     | Bi::WorkGroupId
                     | Bi::WorkGroupSize
                     | Bi::NumWorkGroups => (
-                        self.stage == St::Compute && !self.output,
+                        self.stage.has_workgroups() && !self.output,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Tri,
+                                kind: Sk::Uint,
+                                width,
+                            },
+                    ),
+                    Bi::MeshTaskSize => (
+                        self.stage == St::Task && self.output,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Tri,
+                                kind: Sk::Uint,
+                                width,
+                            },
+                    ),
+                    Bi::PointIndex => (
+                        self.stage == St::Mesh && self.output,
+                        *ty_inner
+                            == Ti::Scalar {
+                                kind: Sk::Uint,
+                                width,
+                            },
+                    ),
+                    Bi::LineIndices => (
+                        self.stage == St::Mesh && self.output,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Bi,
+                                kind: Sk::Uint,
+                                width,
+                            },
+                    ),
+                    Bi::TriangleIndices => (
+                        self.stage == St::Mesh && self.output,
                         *ty_inner
                             == Ti::Vector {
                                 size: Vs::Tri,
@@ -239,6 +325,14 @@ impl VaryingContext<'_> {
                                 width,
                             },
                     ),
+                    Bi::CullPrimitive => (
+                        self.stage == St::Mesh && self.output,
+                        *ty_inner
+                            == Ti::Scalar {
+                                kind: Sk::Bool,
+                                width: crate::BOOL_WIDTH,
+                            },
+                    ),
                 };
 
                 if !visible {
@@ -253,13 +347,39 @@ impl VaryingContext<'_> {
                 location,
                 interpolation,
                 sampling,
+                blend_src,
             } => {
-                if !self.location_mask.insert(location as usize) {
+                if let Some(blend_src) = blend_src {
+                    if !self
+                        .capabilities
+                        .contains(Capabilities::DUAL_SOURCE_BLENDING)
+                    {
+                        return Err(VaryingError::UnsupportedCapability(
+                            Capabilities::DUAL_SOURCE_BLENDING,
+                        ));
+                    }
+                    if self.stage != crate::ShaderStage::Fragment || !self.output {
+                        return Err(VaryingError::InvalidBlendSrcStage);
+                    }
+                    if location != 0 || blend_src > 1 {
+                        return Err(VaryingError::InvalidBlendSrc {
+                            location,
+                            blend_src,
+                        });
+                    }
+                    // Both blend sources are at location 0, so they are
+                    // kept out of `location_mask`.
+                    let bit = 1 << blend_src;
+                    if self.blend_src_mask & bit != 0 {
+                        return Err(VaryingError::BindingCollision { location });
+                    }
+                    self.blend_src_mask |= bit;
+                } else if !self.location_mask.insert(location as usize) {
                     return Err(VaryingError::BindingCollision { location });
                 }
 
                 let needs_interpolation = match self.stage {
-                    crate::ShaderStage::Vertex => self.output,
+                    crate::ShaderStage::Vertex | crate::ShaderStage::Mesh => self.output,
                     crate::ShaderStage::Fragment => !self.output,
                     _ => false,
                 };
@@ -323,6 +443,130 @@ impl VaryingContext<'_> {
 }
 
 impl super::Validator {
+    #[cfg(feature = "validate")]
+    fn validate_mesh_info(
+        &mut self,
+        mesh_info: &crate::MeshStageInfo,
+        module: &crate::Module,
+    ) -> Result<(), WithSpan<EntryPointError>> {
+        use crate::BuiltIn as Bi;
+
+        // Vertex and primitive outputs share the location space.
+        self.location_mask.clear();
+        let mut built_ins = crate::FastHashSet::default();
+        let mut ctx = VaryingContext {
+            ty: mesh_info.vertex_output_type,
+            stage: crate::ShaderStage::Mesh,
+            output: true,
+            types: &module.types,
+            location_mask: &mut self.location_mask,
+            built_ins: &mut built_ins,
+            blend_src_mask: 0,
+            capabilities: self.capabilities,
+        };
+        ctx.validate(None)
+            .map_err_inner(|e| EntryPointError::MeshVertexOutput(e).with_span())?;
+        for &built_in in [
+            Bi::PointIndex,
+            Bi::LineIndices,
+            Bi::TriangleIndices,
+            Bi::CullPrimitive,
+        ]
+        .iter()
+        {
+            if ctx.built_ins.contains(&built_in) {
+                let error = VaryingError::InvalidBuiltInStage(built_in);
+                return Err(EntryPointError::MeshVertexOutput(error).with_span());
+            }
+        }
+
+        ctx.ty = mesh_info.primitive_output_type;
+        ctx.built_ins.clear();
+        ctx.validate(None)
+            .map_err_inner(|e| EntryPointError::MeshPrimitiveOutput(e).with_span())?;
+        for &built_in in [
+            Bi::Position { invariant: false },
+            Bi::PointSize,
+            Bi::ClipDistance,
+            Bi::CullDistance,
+        ]
+        .iter()
+        {
+            if ctx.built_ins.contains(&built_in) {
+                let error = VaryingError::InvalidBuiltInStage(built_in);
+                return Err(EntryPointError::MeshPrimitiveOutput(error).with_span());
+            }
+        }
+        let indices = mesh_info.topology.indices_built_in();
+        if !ctx.built_ins.contains(&indices) {
+            return Err(EntryPointError::MissingPrimitiveIndices(indices).with_span());
+        }
+
+        Ok(())
+    }
+
+    /// Check that the values written by the mesh functions in `block`, and in
+    /// the functions it calls, match the output types of `mesh_info`.
+    #[cfg(feature = "validate")]
+    fn validate_mesh_block(
+        &self,
+        block: &crate::Block,
+        info: &FunctionInfo,
+        mesh_info: &crate::MeshStageInfo,
+        module: &crate::Module,
+        mod_info: &ModuleInfo,
+    ) -> Result<(), EntryPointError> {
+        use crate::Statement as S;
+        for statement in block.iter() {
+            match *statement {
+                S::Block(ref block) => {
+                    self.validate_mesh_block(block, info, mesh_info, module, mod_info)?
+                }
+                S::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    self.validate_mesh_block(accept, info, mesh_info, module, mod_info)?;
+                    self.validate_mesh_block(reject, info, mesh_info, module, mod_info)?;
+                }
+                S::Switch { ref cases, .. } => {
+                    for case in cases.iter() {
+                        self.validate_mesh_block(&case.body, info, mesh_info, module, mod_info)?;
+                    }
+                }
+                S::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    self.validate_mesh_block(body, info, mesh_info, module, mod_info)?;
+                    self.validate_mesh_block(continuing, info, mesh_info, module, mod_info)?;
+                }
+                S::Call { function, .. } => {
+                    self.validate_mesh_block(
+                        &module.functions[function].body,
+                        &mod_info[function],
+                        mesh_info,
+                        module,
+                        mod_info,
+                    )?;
+                }
+                S::MeshFunction(crate::MeshFunction::SetVertex { value, .. })
+                    if info[value].ty.handle() != Some(mesh_info.vertex_output_type) =>
+                {
+                    return Err(EntryPointError::InvalidMeshOutputValue(value));
+                }
+                S::MeshFunction(crate::MeshFunction::SetPrimitive { value, .. })
+                    if info[value].ty.handle() != Some(mesh_info.primitive_output_type) =>
+                {
+                    return Err(EntryPointError::InvalidMeshOutputValue(value));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "validate")]
     pub(super) fn validate_global_var(
         &self,
@@ -332,9 +576,14 @@ impl super::Validator {
         use super::TypeFlags;
 
         log::debug!("var {:?}", var);
+        // The requirements of binding arrays are those of their elements.
+        let (inner_ty, is_binding_array) = match types[var.ty].inner {
+            crate::TypeInner::BindingArray { base, .. } => (base, true),
+            _ => (var.ty, false),
+        };
         let type_info = self
             .types
-            .get(var.ty.index())
+            .get(inner_ty.index())
             .ok_or(GlobalVariableError::InvalidType)?;
 
         let (required_type_flags, is_resource) = match var.class {
@@ -376,12 +625,14 @@ impl super::Validator {
         };
 
         let is_handle = var.class == crate::StorageClass::Handle;
-        let good_type = match types[var.ty].inner {
+        let good_type = match types[inner_ty].inner {
             crate::TypeInner::Struct { .. } => !is_handle,
-            crate::TypeInner::Image { .. } | crate::TypeInner::Sampler { .. } => is_handle,
+            crate::TypeInner::Image { .. }
+            | crate::TypeInner::Sampler { .. }
+            | crate::TypeInner::AccelerationStructure => is_handle,
             _ => false,
         };
-        if is_resource && !good_type {
+        if (is_resource && !good_type) || (is_binding_array && !is_resource) {
             return Err(GlobalVariableError::InvalidType);
         }
 
@@ -411,7 +662,14 @@ impl super::Validator {
         }
 
         #[cfg(feature = "validate")]
-        if ep.stage == crate::ShaderStage::Compute {
+        if (ep.stage == crate::ShaderStage::Task || ep.stage == crate::ShaderStage::Mesh)
+            && !self.capabilities.contains(Capabilities::MESH_SHADER)
+        {
+            return Err(EntryPointError::MissingCapability(Capabilities::MESH_SHADER).with_span());
+        }
+
+        #[cfg(feature = "validate")]
+        if ep.stage.has_workgroups() {
             if ep
                 .workgroup_size
                 .iter()
@@ -419,7 +677,33 @@ impl super::Validator {
             {
                 return Err(EntryPointError::OutOfRangeWorkgroupSize.with_span());
             }
-        } else if ep.workgroup_size != [0; 3] {
+            for &handle in ep.workgroup_size_overrides.iter().flatten().flatten() {
+                let valid = match module.constants.try_get(handle) {
+                    Ok(&crate::Constant {
+                        specialization: Some(_),
+                        inner:
+                            crate::ConstantInner::Scalar {
+                                value: crate::ScalarValue::Uint(_),
+                                width: 4,
+                            },
+                        ..
+                    })
+                    | Ok(&crate::Constant {
+                        specialization: Some(_),
+                        inner:
+                            crate::ConstantInner::Scalar {
+                                value: crate::ScalarValue::Sint(_),
+                                width: 4,
+                            },
+                        ..
+                    }) => true,
+                    _ => false,
+                };
+                if !valid {
+                    return Err(EntryPointError::InvalidWorkgroupSizeOverride(handle).with_span());
+                }
+            }
+        } else if ep.workgroup_size != [0; 3] || ep.workgroup_size_overrides.is_some() {
             return Err(EntryPointError::UnexpectedWorkgroupSize.with_span());
         }
 
@@ -435,15 +719,47 @@ impl super::Validator {
                 crate::ShaderStage::Vertex => ShaderStages::VERTEX,
                 crate::ShaderStage::Fragment => ShaderStages::FRAGMENT,
                 crate::ShaderStage::Compute => ShaderStages::COMPUTE,
+                crate::ShaderStage::Task => ShaderStages::TASK,
+                crate::ShaderStage::Mesh => ShaderStages::MESH,
             };
 
             if !info.available_stages.contains(stage_bit) {
                 return Err(EntryPointError::ForbiddenStageOperations.with_span());
             }
+
+            match (ep.stage, ep.mesh_info.as_ref()) {
+                (crate::ShaderStage::Mesh, None) => {
+                    return Err(EntryPointError::MissingMeshInfo.with_span());
+                }
+                (crate::ShaderStage::Mesh, Some(mesh_info)) => {
+                    if ep.function.result.is_some() {
+                        return Err(EntryPointError::UnexpectedMeshResult.with_span());
+                    }
+                    self.validate_mesh_info(mesh_info, module)?;
+                    self.validate_mesh_block(&ep.function.body, &info, mesh_info, module, mod_info)
+                        .map_err(|e| e.with_span())?;
+                }
+                (_, Some(_)) => {
+                    return Err(EntryPointError::UnexpectedMeshInfo.with_span());
+                }
+                (crate::ShaderStage::Task, None) => {
+                    let good_result = match ep.function.result {
+                        Some(crate::FunctionResult {
+                            binding: Some(crate::Binding::BuiltIn(crate::BuiltIn::MeshTaskSize)),
+                            ..
+                        }) => true,
+                        _ => false,
+                    };
+                    if !good_result {
+                        return Err(EntryPointError::InvalidTaskResult.with_span());
+                    }
+                }
+                (_, None) => {}
+            }
         }
 
         self.location_mask.clear();
-        let mut argument_built_ins = 0;
+        let mut argument_built_ins = crate::FastHashSet::default();
         // TODO: add span info to function arguments
         for (index, fa) in ep.function.arguments.iter().enumerate() {
             let mut ctx = VaryingContext {
@@ -452,27 +768,36 @@ impl super::Validator {
                 output: false,
                 types: &module.types,
                 location_mask: &mut self.location_mask,
-                built_in_mask: argument_built_ins,
+                built_ins: &mut argument_built_ins,
+                blend_src_mask: 0,
                 capabilities: self.capabilities,
             };
             ctx.validate(fa.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Argument(index as u32, e).with_span())?;
-            argument_built_ins = ctx.built_in_mask;
         }
 
         self.location_mask.clear();
         if let Some(ref fr) = ep.function.result {
+            let mut result_built_ins = crate::FastHashSet::default();
             let mut ctx = VaryingContext {
                 ty: fr.ty,
                 stage: ep.stage,
                 output: true,
                 types: &module.types,
                 location_mask: &mut self.location_mask,
-                built_in_mask: 0,
+                built_ins: &mut result_built_ins,
+                blend_src_mask: 0,
                 capabilities: self.capabilities,
             };
             ctx.validate(fr.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Result(e).with_span())?;
+            if ctx.blend_src_mask != 0
+                && (ctx.blend_src_mask != 0x3 || !ctx.location_mask.is_empty())
+            {
+                return Err(
+                    EntryPointError::Result(VaryingError::InvalidDualSourceBlending).with_span(),
+                );
+            }
         }
 
         for bg in self.bind_group_masks.iter_mut() {
@@ -486,11 +811,16 @@ impl super::Validator {
                 continue;
             }
 
+            // binding arrays are used the same way as their elements
+            let element_ty = match module.types[var.ty].inner {
+                crate::TypeInner::BindingArray { base, .. } => base,
+                _ => var.ty,
+            };
             let allowed_usage = match var.class {
                 crate::StorageClass::Function => unreachable!(),
                 crate::StorageClass::Uniform => GlobalUse::READ | GlobalUse::QUERY,
                 crate::StorageClass::Storage { access } => storage_usage(access),
-                crate::StorageClass::Handle => match module.types[var.ty].inner {
+                crate::StorageClass::Handle => match module.types[element_ty].inner {
                     crate::TypeInner::Image {
                         class: crate::ImageClass::Storage { access, .. },
                         ..
@@ -522,6 +852,10 @@ impl super::Validator {
             }
         }
 
+        #[cfg(feature = "validate")]
+        self.validate_entry_point_limits(ep, module, &info)
+            .map_err(WithSpan::into_other)?;
+
         Ok(info)
     }
 }
@@ -0,0 +1,295 @@
+#[cfg(feature = "validate")]
+use super::{
+    stage_interface::{collect_varyings, entry_point_span},
+    FunctionInfo, Validator,
+};
+#[cfg(feature = "validate")]
+use crate::span::{AddSpan as _, WithSpan};
+#[cfg(feature = "validate")]
+use std::collections::BTreeMap;
+
+/// Limits of the device a module is meant to run on.
+///
+/// The [`Validator`] rejects the entry points going over any of these, as
+/// pipeline creation would. The default limits are as large as possible, so
+/// that only the limits that are set are checked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Limits {
+    /// Number of bind groups: the groups of the resources an entry point uses
+    /// must be lower.
+    pub max_bind_groups: u32,
+    /// Number of bindings in a bind group: the bindings of the resources an
+    /// entry point uses must be lower.
+    pub max_bindings_per_bind_group: u32,
+    /// Number of locations passed between stages: the locations of the
+    /// outputs of vertex shaders and the inputs of fragment shaders must be
+    /// lower.
+    pub max_inter_stage_locations: u32,
+    /// Size of a workgroup in each dimension.
+    ///
+    /// Dimensions set by overrides are only checked once
+    /// [`proc::process_overrides`] has replaced them with their values.
+    ///
+    /// [`proc::process_overrides`]: crate::proc::process_overrides
+    pub max_workgroup_size: [u32; 3],
+    /// Number of invocations in a workgroup. Dimensions set by overrides count
+    /// as one until they are processed.
+    pub max_workgroup_invocations: u32,
+    /// Size in bytes of the [`WorkGroup`] variables an entry point uses.
+    ///
+    /// [`WorkGroup`]: crate::StorageClass::WorkGroup
+    pub max_workgroup_storage_size: u32,
+    /// Size in bytes of the push constants an entry point uses.
+    pub max_push_constant_size: u32,
+    /// Number of sampled and depth textures an entry point uses, counting each
+    /// element of binding arrays. Binding arrays without a size go over any
+    /// limit but the default one.
+    pub max_sampled_textures_per_stage: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bind_groups: u32::MAX,
+            max_bindings_per_bind_group: u32::MAX,
+            max_inter_stage_locations: u32::MAX,
+            max_workgroup_size: [u32::MAX; 3],
+            max_workgroup_invocations: u32::MAX,
+            max_workgroup_storage_size: u32::MAX,
+            max_push_constant_size: u32::MAX,
+            max_sampled_textures_per_stage: u32::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum LimitError {
+    #[error("Bind group {group} of {var:?} is over the limit of {limit} groups")]
+    BindGroup {
+        var: crate::Handle<crate::GlobalVariable>,
+        group: u32,
+        limit: u32,
+    },
+    #[error("Binding {binding} of {var:?} is over the limit of {limit} bindings per group")]
+    Binding {
+        var: crate::Handle<crate::GlobalVariable>,
+        binding: u32,
+        limit: u32,
+    },
+    #[error("Location {location} is over the limit of {limit} inter-stage locations")]
+    InterStageLocation { location: u32, limit: u32 },
+    #[error("Workgroup size {size:?} is over the limit of {limit:?}")]
+    WorkgroupSize { size: [u32; 3], limit: [u32; 3] },
+    #[error("Workgroup of {invocations} invocations is over the limit of {limit}")]
+    WorkgroupInvocations { invocations: u64, limit: u32 },
+    #[error("Workgroup storage of {size} bytes is over the limit of {limit}")]
+    WorkgroupStorageSize { size: u32, limit: u32 },
+    #[error("Push constants of {size} bytes are over the limit of {limit}")]
+    PushConstantSize { size: u32, limit: u32 },
+    #[error("{count} sampled textures are over the limit of {limit}")]
+    SampledTextures { count: u32, limit: u32 },
+    #[error("Binding array {var:?} has an unbounded number of sampled textures, over the limit of {limit}")]
+    UnboundedSampledTextures {
+        var: crate::Handle<crate::GlobalVariable>,
+        limit: u32,
+    },
+    #[error("Binding array {0:?} has an invalid length")]
+    InvalidBindingArrayLength(crate::Handle<crate::GlobalVariable>),
+}
+
+#[cfg(feature = "validate")]
+impl Validator {
+    /// Check the resources, varyings and workgroup size of `ep`, whose
+    /// function is described by `info`, against the limits of the validator.
+    pub(super) fn validate_entry_point_limits(
+        &self,
+        ep: &crate::EntryPoint,
+        module: &crate::Module,
+        info: &FunctionInfo,
+    ) -> Result<(), WithSpan<LimitError>> {
+        let limits = &self.limits;
+        let ep_span = entry_point_span(ep);
+
+        if ep.stage.has_workgroups() {
+            // Dimensions set by overrides hold placeholders until the overrides
+            // are processed, so they count as one.
+            let mut size = ep.workgroup_size;
+            if let Some(ref overrides) = ep.workgroup_size_overrides {
+                for (size, constant) in size.iter_mut().zip(overrides.iter()) {
+                    if constant.is_some() {
+                        *size = 1;
+                    }
+                }
+            }
+            if size
+                .iter()
+                .zip(limits.max_workgroup_size.iter())
+                .any(|(&size, &limit)| size > limit)
+            {
+                return Err(LimitError::WorkgroupSize {
+                    size,
+                    limit: limits.max_workgroup_size,
+                }
+                .with_span_static(ep_span, "entry point"));
+            }
+            let invocations = size.iter().map(|&size| size as u64).product::<u64>();
+            if invocations > limits.max_workgroup_invocations as u64 {
+                return Err(LimitError::WorkgroupInvocations {
+                    invocations,
+                    limit: limits.max_workgroup_invocations,
+                }
+                .with_span_static(ep_span, "entry point"));
+            }
+        }
+
+        let mut varyings = BTreeMap::new();
+        match ep.stage {
+            crate::ShaderStage::Vertex => {
+                if let Some(ref result) = ep.function.result {
+                    collect_varyings(
+                        module,
+                        None,
+                        result.ty,
+                        result.binding.as_ref(),
+                        ep_span,
+                        &mut varyings,
+                    );
+                }
+            }
+            crate::ShaderStage::Fragment => {
+                for argument in ep.function.arguments.iter() {
+                    collect_varyings(
+                        module,
+                        argument.name.as_ref(),
+                        argument.ty,
+                        argument.binding.as_ref(),
+                        ep_span,
+                        &mut varyings,
+                    );
+                }
+            }
+            _ => {}
+        }
+        if let Some((&location, varying)) = varyings.iter().next_back() {
+            if location >= limits.max_inter_stage_locations {
+                return Err(WithSpan::new(LimitError::InterStageLocation {
+                    location,
+                    limit: limits.max_inter_stage_locations,
+                })
+                .with_span(varying.span, varying.describe("varying")));
+            }
+        }
+
+        let mut workgroup_storage_size = 0u32;
+        let mut push_constant_size = 0u32;
+        let mut sampled_textures = 0u32;
+        for (handle, var) in module.global_variables.iter() {
+            if info[handle].is_empty() {
+                continue;
+            }
+            let span = module.global_variables.get_span(handle);
+
+            if let Some(ref binding) = var.binding {
+                if binding.group >= limits.max_bind_groups {
+                    return Err(LimitError::BindGroup {
+                        var: handle,
+                        group: binding.group,
+                        limit: limits.max_bind_groups,
+                    }
+                    .with_span_static(span, "resource"));
+                }
+                if binding.binding >= limits.max_bindings_per_bind_group {
+                    return Err(LimitError::Binding {
+                        var: handle,
+                        binding: binding.binding,
+                        limit: limits.max_bindings_per_bind_group,
+                    }
+                    .with_span_static(span, "resource"));
+                }
+            }
+
+            match var.class {
+                crate::StorageClass::WorkGroup => {
+                    let layout = &self.layouter[var.ty];
+                    workgroup_storage_size =
+                        crate::proc::Layouter::round_up(layout.alignment, workgroup_storage_size)
+                            .saturating_add(layout.size);
+                    if workgroup_storage_size > limits.max_workgroup_storage_size {
+                        return Err(LimitError::WorkgroupStorageSize {
+                            size: workgroup_storage_size,
+                            limit: limits.max_workgroup_storage_size,
+                        }
+                        .with_span_static(span, "workgroup variable"));
+                    }
+                }
+                crate::StorageClass::PushConstant => {
+                    push_constant_size =
+                        push_constant_size.saturating_add(self.layouter[var.ty].size);
+                    if push_constant_size > limits.max_push_constant_size {
+                        return Err(LimitError::PushConstantSize {
+                            size: push_constant_size,
+                            limit: limits.max_push_constant_size,
+                        }
+                        .with_span_static(span, "push constant"));
+                    }
+                }
+                crate::StorageClass::Handle => {
+                    // `None` stands for an unbounded binding array.
+                    let (base, count) = match module.types[var.ty].inner {
+                        crate::TypeInner::BindingArray { base, size } => {
+                            let count = match size {
+                                crate::ArraySize::Constant(size) => Some(
+                                    module.constants[size].to_array_length().ok_or_else(|| {
+                                        LimitError::InvalidBindingArrayLength(handle)
+                                            .with_span_static(span, "resource")
+                                    })?,
+                                ),
+                                crate::ArraySize::Dynamic => None,
+                            };
+                            (base, count)
+                        }
+                        _ => (var.ty, Some(1)),
+                    };
+                    match module.types[base].inner {
+                        crate::TypeInner::Image {
+                            class: crate::ImageClass::Sampled { .. },
+                            ..
+                        }
+                        | crate::TypeInner::Image {
+                            class: crate::ImageClass::Depth { .. },
+                            ..
+                        } => {
+                            let count = match count {
+                                Some(count) => count,
+                                None if limits.max_sampled_textures_per_stage == u32::MAX => {
+                                    continue
+                                }
+                                None => {
+                                    return Err(LimitError::UnboundedSampledTextures {
+                                        var: handle,
+                                        limit: limits.max_sampled_textures_per_stage,
+                                    }
+                                    .with_span_static(span, "texture"))
+                                }
+                            };
+                            sampled_textures = sampled_textures.saturating_add(count);
+                            if sampled_textures > limits.max_sampled_textures_per_stage {
+                                return Err(LimitError::SampledTextures {
+                                    count: sampled_textures,
+                                    limit: limits.max_sampled_textures_per_stage,
+                                }
+                                .with_span_static(span, "texture"));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@ mod compose;
 mod expression;
 mod function;
 mod interface;
+mod limits;
+mod stage_interface;
 mod r#type;
 
 #[cfg(feature = "validate")]
@@ -25,7 +27,9 @@ pub use compose::ComposeError;
 pub use expression::ExpressionError;
 pub use function::{CallError, FunctionError, LocalVariableError};
 pub use interface::{EntryPointError, GlobalVariableError, VaryingError};
+pub use limits::{LimitError, Limits};
 pub use r#type::{Disalignment, TypeError, TypeFlags};
+pub use stage_interface::{check_stage_interface, StageInterfaceError};
 
 bitflags::bitflags! {
     /// Validation flags.
@@ -62,13 +66,36 @@ bitflags::bitflags! {
     #[derive(Default)]
     #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
     #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
-    pub struct Capabilities: u8 {
+    pub struct Capabilities: u16 {
         /// Support for `StorageClass:PushConstant`.
         const PUSH_CONSTANT = 0x1;
         /// Float values with width = 8.
         const FLOAT64 = 0x2;
         /// Support for `Builtin:PrimitiveIndex`.
         const PRIMITIVE_INDEX = 0x4;
+        /// Float values with width = 2.
+        const FLOAT16 = 0x8;
+        /// Integer values with width = 8, including 64-bit atomics.
+        const INT64 = 0x10;
+        /// Support for subgroup operations and the `Builtin::SubgroupSize` and
+        /// `Builtin::SubgroupInvocationId` built-ins.
+        const SUBGROUP = 0x20;
+        /// Support for non-uniform indexing of binding arrays of sampled
+        /// textures and storage buffers.
+        const SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING = 0x40;
+        /// Support for non-uniform indexing of binding arrays of uniform
+        /// buffers and storage textures.
+        const UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING = 0x80;
+        /// Support for non-uniform indexing of binding arrays of samplers.
+        const SAMPLER_NON_UNIFORM_INDEXING = 0x100;
+        /// Support for acceleration structures and ray queries.
+        const RAY_QUERY = 0x200;
+        /// Support for task and mesh shader stages.
+        const MESH_SHADER = 0x400;
+        /// Support for dual-source blending fragment outputs.
+        const DUAL_SOURCE_BLENDING = 0x800;
+        /// Support for atomic operations on storage images.
+        const TEXTURE_ATOMIC = 0x1000;
     }
 }
 
@@ -80,6 +107,8 @@ bitflags::bitflags! {
         const VERTEX = 0x1;
         const FRAGMENT = 0x2;
         const COMPUTE = 0x4;
+        const TASK = 0x8;
+        const MESH = 0x10;
     }
 }
 
@@ -102,6 +131,7 @@ impl ops::Index<Handle<crate::Function>> for ModuleInfo {
 pub struct Validator {
     flags: ValidationFlags,
     capabilities: Capabilities,
+    limits: Limits,
     types: Vec<r#type::TypeInfo>,
     layouter: Layouter,
     location_mask: BitSet,
@@ -182,10 +212,45 @@ impl crate::TypeInner {
             | Self::Pointer { .. }
             | Self::ValuePointer { .. }
             | Self::Struct { .. } => true,
-            Self::Array { .. } | Self::Image { .. } | Self::Sampler { .. } => false,
+            Self::Array { .. }
+            | Self::Image { .. }
+            | Self::Sampler { .. }
+            | Self::BindingArray { .. }
+            | Self::AccelerationStructure
+            | Self::RayQuery => false,
         }
     }
 
+    /// If `self` is a binding array, or a pointer to one, return the capability
+    /// required to index it with a non-uniform index.
+    #[cfg(feature = "validate")]
+    fn binding_array_non_uniform_capability(&self, module: &crate::Module) -> Option<Capabilities> {
+        let (base, class) = match *self {
+            Self::BindingArray { base, .. } => (base, crate::StorageClass::Handle),
+            Self::Pointer { base, class } => match module.types[base].inner {
+                Self::BindingArray { base, .. } => (base, class),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(match module.types[base].inner {
+            Self::Image {
+                class: crate::ImageClass::Storage { .. },
+                ..
+            } => Capabilities::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+            Self::Image { .. } => {
+                Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+            }
+            Self::Sampler { .. } => Capabilities::SAMPLER_NON_UNIFORM_INDEXING,
+            _ => match class {
+                crate::StorageClass::Storage { .. } => {
+                    Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+                }
+                _ => Capabilities::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+            },
+        })
+    }
+
     /// Return the `ImageDimension` for which `self` is an appropriate coordinate.
     #[cfg(feature = "validate")]
     fn image_storage_coordinates(&self) -> Option<crate::ImageDimension> {
@@ -215,6 +280,7 @@ impl Validator {
         Validator {
             flags,
             capabilities,
+            limits: Limits::default(),
             types: Vec::new(),
             layouter: Layouter::default(),
             location_mask: BitSet::new(),
@@ -225,6 +291,12 @@ impl Validator {
         }
     }
 
+    /// Set the device limits the entry points must fit in.
+    pub fn limits(&mut self, limits: Limits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
     #[cfg(feature = "validate")]
     fn validate_constant(
         &self,
@@ -0,0 +1,321 @@
+use crate::{span::WithSpan, Binding, Interpolation, Sampling, Span, TypeInner};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum StageInterfaceError {
+    #[error("Input at location {0} isn't an output of the previous stage")]
+    MissingOutput(u32),
+    #[error("Output at location {0} isn't an input of the next stage")]
+    UnusedOutput(u32),
+    #[error("Varying at location {location} is output as {output:?}, but input as {input:?}")]
+    TypeMismatch {
+        location: u32,
+        output: TypeInner,
+        input: TypeInner,
+    },
+    #[error("Varying at location {location} is output with interpolation {output:?}, but input with {input:?}")]
+    InterpolationMismatch {
+        location: u32,
+        output: Option<Interpolation>,
+        input: Option<Interpolation>,
+    },
+    #[error("Varying at location {location} is output with sampling {output:?}, but input with {input:?}")]
+    SamplingMismatch {
+        location: u32,
+        output: Option<Sampling>,
+        input: Option<Sampling>,
+    },
+}
+
+/// An input or output of an entry point with a [`Location`] binding.
+///
+/// [`Location`]: Binding::Location
+pub(super) struct Varying<'a> {
+    interpolation: Option<Interpolation>,
+    sampling: Option<Sampling>,
+    ty: &'a TypeInner,
+    /// The name of the argument or structure member.
+    pub name: Option<&'a str>,
+    /// The span of the structure declaring the varying, or of the entry point
+    /// if it is declared directly on an argument or the result.
+    pub span: Span,
+}
+
+impl Varying<'_> {
+    /// Describe the varying as an `output`, `input` or `varying`, with its
+    /// name.
+    pub fn describe(&self, kind: &str) -> String {
+        match self.name {
+            Some(name) => format!("{} `{}`", kind, name),
+            None => kind.to_string(),
+        }
+    }
+}
+
+/// The span of the entry point `ep`.
+///
+/// Entry points have no span of their own: this covers the statements of
+/// their body.
+pub(super) fn entry_point_span(ep: &crate::EntryPoint) -> Span {
+    Span::total_span(ep.function.body.span_iter().map(|(_, &span)| span))
+}
+
+/// Add to `varyings` the [`Location`] bindings of an argument or result of an
+/// entry point, or of the members of its type if it is a structure.
+///
+/// [`Location`]: Binding::Location
+pub(super) fn collect_varyings<'a>(
+    module: &'a crate::Module,
+    name: Option<&'a String>,
+    ty: crate::Handle<crate::Type>,
+    binding: Option<&Binding>,
+    span: Span,
+    varyings: &mut BTreeMap<u32, Varying<'a>>,
+) {
+    match binding {
+        Some(&Binding::Location {
+            location,
+            interpolation,
+            sampling,
+            ..
+        }) => {
+            varyings.insert(
+                location,
+                Varying {
+                    interpolation,
+                    sampling,
+                    ty: &module.types[ty].inner,
+                    name: name.map(String::as_str),
+                    span,
+                },
+            );
+        }
+        Some(&Binding::BuiltIn(_)) => {}
+        None => {
+            if let TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                for member in members.iter() {
+                    collect_varyings(
+                        module,
+                        member.name.as_ref(),
+                        member.ty,
+                        member.binding.as_ref(),
+                        module.types.get_span(ty),
+                        varyings,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Check that the outputs of the entry point `output` match the inputs of
+/// `input`, the entry point of the next stage of a pipeline.
+///
+/// This is usually a vertex and a fragment shader. The entry points may come
+/// from different modules, `output_module` and `input_module`, which are
+/// expected to be valid.
+///
+/// Every input with a [`Location`] binding must be an output of the same type,
+/// interpolation and sampling. Outputs that aren't inputs are reported as
+/// [`UnusedOutput`], which pipelines usually allow:
+/// [`proc::strip_unused_outputs`] removes them.
+///
+/// Return all the mismatches found, ordered by location. Their spans point to
+/// the structures declaring the varyings, in either module, or to the entry
+/// point declaring them on an argument or its result. They are described as
+/// an output or an input, with the name of the varying.
+///
+/// [`Location`]: Binding::Location
+/// [`UnusedOutput`]: StageInterfaceError::UnusedOutput
+/// [`proc::strip_unused_outputs`]: crate::proc::strip_unused_outputs
+pub fn check_stage_interface(
+    output_module: &crate::Module,
+    output: &crate::EntryPoint,
+    input_module: &crate::Module,
+    input: &crate::EntryPoint,
+) -> Vec<WithSpan<StageInterfaceError>> {
+    let mut outputs = BTreeMap::new();
+    if let Some(ref result) = output.function.result {
+        collect_varyings(
+            output_module,
+            None,
+            result.ty,
+            result.binding.as_ref(),
+            entry_point_span(output),
+            &mut outputs,
+        );
+    }
+    let mut inputs = BTreeMap::new();
+    for argument in input.function.arguments.iter() {
+        collect_varyings(
+            input_module,
+            argument.name.as_ref(),
+            argument.ty,
+            argument.binding.as_ref(),
+            entry_point_span(input),
+            &mut inputs,
+        );
+    }
+
+    let locations = outputs
+        .keys()
+        .chain(inputs.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let mut errors = Vec::new();
+    for location in locations {
+        let (out, inp) = match (outputs.get(&location), inputs.get(&location)) {
+            (Some(out), Some(inp)) => (out, inp),
+            (Some(out), None) => {
+                errors.push(
+                    WithSpan::new(StageInterfaceError::UnusedOutput(location))
+                        .with_span(out.span, out.describe("output")),
+                );
+                continue;
+            }
+            (None, Some(inp)) => {
+                errors.push(
+                    WithSpan::new(StageInterfaceError::MissingOutput(location))
+                        .with_span(inp.span, inp.describe("input")),
+                );
+                continue;
+            }
+            (None, None) => continue,
+        };
+        let error = if out.ty != inp.ty {
+            StageInterfaceError::TypeMismatch {
+                location,
+                output: out.ty.clone(),
+                input: inp.ty.clone(),
+            }
+        } else if out.interpolation != inp.interpolation {
+            StageInterfaceError::InterpolationMismatch {
+                location,
+                output: out.interpolation,
+                input: inp.interpolation,
+            }
+        } else if out.sampling != inp.sampling {
+            StageInterfaceError::SamplingMismatch {
+                location,
+                output: out.sampling,
+                input: inp.sampling,
+            }
+        } else {
+            continue;
+        };
+        errors.push(
+            WithSpan::new(error)
+                .with_span(out.span, out.describe("output"))
+                .with_span(inp.span, inp.describe("input")),
+        );
+    }
+    errors
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::{check_stage_interface, StageInterfaceError};
+
+    const VERTEX: &str = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>;
+            @location(0) color: vec4<f32>;
+            @location(1) normal: vec3<f32>;
+            @location(2) @interpolate(flat) index: f32;
+            @location(3) uv: vec2<f32>;
+        };
+
+        @stage(vertex)
+        fn main(@location(0) position: vec4<f32>) -> VertexOutput {
+            return VertexOutput(position, position, position.xyz, 1.0, position.xy);
+        }
+    ";
+
+    #[test]
+    fn mismatches() {
+        let vertex = crate::front::wgsl::parse_str(VERTEX).unwrap();
+        let fragment = crate::front::wgsl::parse_str(
+            "
+            @stage(fragment)
+            fn main(
+                @location(0) color: vec4<f32>,
+                @location(2) index: f32,
+                @location(3) uv: vec3<f32>,
+                @location(4) extra: f32,
+            ) -> @location(0) vec4<f32> {
+                return color;
+            }
+            ",
+        )
+        .unwrap();
+
+        let errors = check_stage_interface(
+            &vertex,
+            &vertex.entry_points[0],
+            &fragment,
+            &fragment.entry_points[0],
+        )
+        .into_iter()
+        .map(|error| error.into_inner())
+        .collect::<Vec<_>>();
+        assert!(matches!(
+            errors[..],
+            [
+                StageInterfaceError::UnusedOutput(1),
+                StageInterfaceError::InterpolationMismatch { location: 2, .. },
+                StageInterfaceError::TypeMismatch { location: 3, .. },
+                StageInterfaceError::MissingOutput(4),
+            ]
+        ));
+    }
+
+    #[cfg(feature = "span")]
+    #[test]
+    fn spans() {
+        let vertex = crate::front::wgsl::parse_str(
+            "
+            @stage(vertex)
+            fn main(@location(0) position: vec4<f32>) -> @location(0) vec4<f32> {
+                return position;
+            }
+            ",
+        )
+        .unwrap();
+        let fragment = crate::front::wgsl::parse_str(
+            "
+            @stage(fragment)
+            fn main(@location(1) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return vec4<f32>(uv, 0.0, 1.0);
+            }
+            ",
+        )
+        .unwrap();
+
+        let errors = check_stage_interface(
+            &vertex,
+            &vertex.entry_points[0],
+            &fragment,
+            &fragment.entry_points[0],
+        );
+        assert_eq!(errors.len(), 2);
+        let labels = errors
+            .iter()
+            .map(|error| {
+                let &(span, ref label) = error.spans().next().unwrap();
+                assert!(span.is_defined());
+                label.as_str()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["output", "input `uv`"]);
+
+        let vertex = crate::front::wgsl::parse_str(VERTEX).unwrap();
+        let errors = check_stage_interface(
+            &vertex,
+            &vertex.entry_points[0],
+            &fragment,
+            &fragment.entry_points[0],
+        );
+        let label = errors[0].spans().next().map(|span| span.1.as_str());
+        assert_eq!(label, Some("output `color`"));
+    }
+}
@@ -78,6 +78,8 @@ pub enum TypeError {
     InvalidWidth(crate::ScalarKind, crate::Bytes),
     #[error("The {0:?} scalar width {1} is not supported for an atomic")]
     InvalidAtomicWidth(crate::ScalarKind, crate::Bytes),
+    #[error("Capability {0:?} is required")]
+    MissingCapability(Capabilities),
     #[error("The base handle {0:?} can not be resolved")]
     UnresolvedBase(Handle<crate::Type>),
     #[error("Invalid type for pointer target {0:?}")]
@@ -91,6 +93,8 @@ pub enum TypeError {
     InvalidData(Handle<crate::Type>),
     #[error("Base type {0:?} for the array is invalid")]
     InvalidArrayBaseType(Handle<crate::Type>),
+    #[error("Base type {0:?} for the binding array is invalid")]
+    InvalidBindingArrayBaseType(Handle<crate::Type>),
     #[error("The constant {0:?} can not be used for an array size")]
     InvalidArraySizeConstant(Handle<crate::Constant>),
     #[error("The constant {0:?} is specialized, and cannot be used as an array size")]
@@ -145,6 +149,71 @@ fn check_member_layout(
     };
 }
 
+/// Validate the `size` of an [`Array`] or [`BindingArray`], returning the
+/// flags it contributes to the type.
+///
+/// [`Array`]: crate::TypeInner::Array
+/// [`BindingArray`]: crate::TypeInner::BindingArray
+fn validate_array_size(
+    size: crate::ArraySize,
+    constants: &Arena<crate::Constant>,
+) -> Result<TypeFlags, TypeError> {
+    Ok(match size {
+        crate::ArraySize::Constant(const_handle) => {
+            let constant = constants.try_get(const_handle)?;
+            let length_is_positive = match *constant {
+                crate::Constant {
+                    specialization: Some(_),
+                    ..
+                } => {
+                    // Many of our back ends don't seem to support
+                    // specializable array lengths. If you want to try to make
+                    // this work, be sure to address all uses of
+                    // `Constant::to_array_length`, which ignores
+                    // specialization.
+                    return Err(TypeError::UnsupportedSpecializedArrayLength(const_handle));
+                }
+                crate::Constant {
+                    inner:
+                        crate::ConstantInner::Scalar {
+                            width: _,
+                            value: crate::ScalarValue::Uint(length),
+                        },
+                    ..
+                } => length > 0,
+                // Accept a signed integer size to avoid
+                // requiring an explicit uint
+                // literal. Type inference should make
+                // this unnecessary.
+                crate::Constant {
+                    inner:
+                        crate::ConstantInner::Scalar {
+                            width: _,
+                            value: crate::ScalarValue::Sint(length),
+                        },
+                    ..
+                } => length > 0,
+                _ => {
+                    log::warn!("Array size {:?}", constant);
+                    return Err(TypeError::InvalidArraySizeConstant(const_handle));
+                }
+            };
+
+            if !length_is_positive {
+                return Err(TypeError::NonPositiveArrayLength(const_handle));
+            }
+
+            TypeFlags::SIZED | TypeFlags::ARGUMENT
+        }
+        crate::ArraySize::Dynamic => {
+            // Non-SIZED types may only appear as the last element of a structure.
+            // This is enforced by checks for SIZED-ness for all compound types,
+            // and a special case for structs.
+            TypeFlags::empty()
+        }
+    })
+}
+
 #[derive(Clone, Debug)]
 pub(super) struct TypeInfo {
     pub flags: TypeFlags,
@@ -176,9 +245,13 @@ impl super::Validator {
         match kind {
             crate::ScalarKind::Bool => width == crate::BOOL_WIDTH,
             crate::ScalarKind::Float => {
-                width == 4 || (width == 8 && self.capabilities.contains(Capabilities::FLOAT64))
+                width == 4
+                    || (width == 8 && self.capabilities.contains(Capabilities::FLOAT64))
+                    || (width == 2 && self.capabilities.contains(Capabilities::FLOAT16))
+            }
+            crate::ScalarKind::Sint | crate::ScalarKind::Uint => {
+                width == 4 || (width == 8 && self.capabilities.contains(Capabilities::INT64))
             }
-            crate::ScalarKind::Sint | crate::ScalarKind::Uint => width == 4,
         }
     }
 
@@ -247,7 +320,10 @@ impl super::Validator {
             Ti::Atomic { kind, width } => {
                 let good = match kind {
                     crate::ScalarKind::Bool | crate::ScalarKind::Float => false,
-                    crate::ScalarKind::Sint | crate::ScalarKind::Uint => width == 4,
+                    crate::ScalarKind::Sint | crate::ScalarKind::Uint => {
+                        width == 4
+                            || (width == 8 && self.capabilities.contains(Capabilities::INT64))
+                    }
                 };
                 if !good {
                     return Err(TypeError::InvalidAtomicWidth(kind, width));
@@ -374,62 +450,7 @@ impl super::Validator {
                     Err(e) => Err(e),
                 };
 
-                let sized_flag = match size {
-                    crate::ArraySize::Constant(const_handle) => {
-                        let constant = constants.try_get(const_handle)?;
-                        let length_is_positive = match *constant {
-                            crate::Constant {
-                                specialization: Some(_),
-                                ..
-                            } => {
-                                // Many of our back ends don't seem to support
-                                // specializable array lengths. If you want to try to make
-                                // this work, be sure to address all uses of
-                                // `Constant::to_array_length`, which ignores
-                                // specialization.
-                                return Err(TypeError::UnsupportedSpecializedArrayLength(
-                                    const_handle,
-                                ));
-                            }
-                            crate::Constant {
-                                inner:
-                                    crate::ConstantInner::Scalar {
-                                        width: _,
-                                        value: crate::ScalarValue::Uint(length),
-                                    },
-                                ..
-                            } => length > 0,
-                            // Accept a signed integer size to avoid
-                            // requiring an explicit uint
-                            // literal. Type inference should make
-                            // this unnecessary.
-                            crate::Constant {
-                                inner:
-                                    crate::ConstantInner::Scalar {
-                                        width: _,
-                                        value: crate::ScalarValue::Sint(length),
-                                    },
-                                ..
-                            } => length > 0,
-                            _ => {
-                                log::warn!("Array size {:?}", constant);
-                                return Err(TypeError::InvalidArraySizeConstant(const_handle));
-                            }
-                        };
-
-                        if !length_is_positive {
-                            return Err(TypeError::NonPositiveArrayLength(const_handle));
-                        }
-
-                        TypeFlags::SIZED | TypeFlags::ARGUMENT
-                    }
-                    crate::ArraySize::Dynamic => {
-                        // Non-SIZED types may only appear as the last element of a structure.
-                        // This is enforced by checks for SIZED-ness for all compound types,
-                        // and a special case for structs.
-                        TypeFlags::empty()
-                    }
-                };
+                let sized_flag = validate_array_size(size, constants)?;
 
                 let base_mask = TypeFlags::COPY | TypeFlags::HOST_SHARED | TypeFlags::INTERFACE;
                 TypeInfo {
@@ -535,6 +556,37 @@ impl super::Validator {
                 ti
             }
             Ti::Image { .. } | Ti::Sampler { .. } => TypeInfo::new(TypeFlags::ARGUMENT, 0),
+            Ti::AccelerationStructure => {
+                if !self.capabilities.contains(Capabilities::RAY_QUERY) {
+                    return Err(TypeError::MissingCapability(Capabilities::RAY_QUERY));
+                }
+                TypeInfo::new(TypeFlags::ARGUMENT, 0)
+            }
+            Ti::RayQuery => {
+                if !self.capabilities.contains(Capabilities::RAY_QUERY) {
+                    return Err(TypeError::MissingCapability(Capabilities::RAY_QUERY));
+                }
+                // Ray queries live in local variables, but can't be copied.
+                TypeInfo::new(TypeFlags::DATA | TypeFlags::SIZED, 0)
+            }
+            Ti::BindingArray { base, size } => {
+                if base >= handle {
+                    return Err(TypeError::UnresolvedBase(base));
+                }
+                match types[base].inner {
+                    Ti::Image { .. } | Ti::Sampler { .. } | Ti::Struct { .. } => {}
+                    _ => return Err(TypeError::InvalidBindingArrayBaseType(base)),
+                }
+                let base_info = &self.types[base.index()];
+                // The `ARGUMENT` flag of a sized array doesn't apply to
+                // binding arrays, which can only be global variables.
+                let sized_flag = validate_array_size(size, constants)? & TypeFlags::SIZED;
+                TypeInfo {
+                    flags: (base_info.flags & TypeFlags::HOST_SHARED) | sized_flag,
+                    uniform_layout: base_info.uniform_layout,
+                    storage_layout: base_info.storage_layout,
+                }
+            }
         })
     }
 }
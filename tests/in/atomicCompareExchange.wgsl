@@ -0,0 +1,25 @@
+struct Counters {
+    signed_value: atomic<i32>;
+    unsigned_value: atomic<u32>;
+};
+
+@group(0) @binding(0)
+var<storage, read_write> counters: Counters;
+
+@stage(compute) @workgroup_size(1)
+fn main() {
+    var exchanged: bool = false;
+    loop {
+        if (exchanged) {
+            break;
+        }
+        let old = atomicLoad(&counters.signed_value);
+        let result = atomicCompareExchangeWeak(&counters.signed_value, old, old + 1);
+        exchanged = result.exchanged;
+    }
+
+    let unsigned_result = atomicCompareExchangeWeak(&counters.unsigned_value, 1u, 2u);
+    if (unsigned_result.exchanged) {
+        atomicStore(&counters.unsigned_value, unsigned_result.old_value + 1u);
+    }
+}
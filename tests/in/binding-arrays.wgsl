@@ -0,0 +1,38 @@
+struct UniformIndex {
+    index: u32;
+};
+
+@group(0) @binding(0)
+var texture_array: binding_array<texture_2d<f32>, 5>;
+@group(0) @binding(1)
+var sampler_array: binding_array<sampler, 5>;
+@group(0) @binding(2)
+var storage_array: binding_array<texture_storage_2d<rgba32float, write>, 5>;
+@group(0) @binding(3)
+var<uniform> uni: UniformIndex;
+
+struct FragmentIn {
+    @location(0) @interpolate(flat) index: u32;
+};
+
+@stage(fragment)
+fn main(fragment_in: FragmentIn) -> @location(0) vec4<f32> {
+    let uniform_index = uni.index;
+    let non_uniform_index = nonuniform(fragment_in.index);
+
+    var v1 = vec4<f32>(0.0);
+    let uv = vec2<f32>(0.0);
+    let pix = vec2<i32>(0);
+
+    v1 = v1 + textureSample(texture_array[0], sampler_array[0], uv);
+    v1 = v1 + textureSample(texture_array[uniform_index], sampler_array[uniform_index], uv);
+    v1 = v1 + textureSample(texture_array[non_uniform_index], sampler_array[non_uniform_index], uv);
+
+    v1 = v1 + textureLoad(texture_array[uniform_index], pix, 0);
+    v1 = v1 + textureLoad(texture_array[non_uniform_index], pix, 0);
+
+    textureStore(storage_array[uniform_index], pix, v1);
+    textureStore(storage_array[non_uniform_index], pix, v1);
+
+    return v1;
+}
@@ -0,0 +1,39 @@
+struct UniformIndex {
+    index: u32;
+};
+
+struct Foo { x: u32; };
+
+@group(0) @binding(0)
+var<storage, read> storage_array: binding_array<Foo, 1>;
+@group(0) @binding(1)
+var<uniform> uniform_array: binding_array<Foo, 2>;
+@group(0) @binding(2)
+var<storage, read_write> unbounded_array: binding_array<Foo>;
+@group(0) @binding(10)
+var<uniform> uni: UniformIndex;
+
+struct FragmentIn {
+    @location(0) @interpolate(flat) index: u32;
+};
+
+@stage(fragment)
+fn main(fragment_in: FragmentIn) -> @location(0) u32 {
+    let uniform_index = uni.index;
+    let non_uniform_index = nonuniform(fragment_in.index);
+
+    var u1 = 0u;
+
+    u1 = u1 + storage_array[0].x;
+    u1 = u1 + storage_array[uniform_index].x;
+    u1 = u1 + storage_array[non_uniform_index].x;
+
+    u1 = u1 + uniform_array[0].x;
+    u1 = u1 + uniform_array[uniform_index].x;
+    u1 = u1 + uniform_array[non_uniform_index].x;
+
+    unbounded_array[uniform_index].x = u1;
+    unbounded_array[non_uniform_index].x = u1;
+
+    return u1;
+}
@@ -0,0 +1,28 @@
+struct Light {
+    position: vec4<f32>;
+    color: vec4<f32>;
+};
+
+@group(0) @binding(0)
+var<uniform> light: Light;
+
+var<private> total: vec3<f32>;
+
+fn shade(normal: vec3<f32>, position: vec3<f32>) -> vec3<f32> {
+    let direction = normalize(light.position.xyz - position);
+    let diffuse = max(0.0, dot(normal, normalize(light.position.xyz - position)));
+    total = total + diffuse * light.color.xyz;
+    let ambient = total * 0.1;
+    return total + ambient + light.color.xyz * dot(direction, normal);
+}
+
+@stage(fragment)
+fn main(@location(0) normal: vec3<f32>, @location(1) position: vec3<f32>) -> @location(0) vec4<f32> {
+    var color = shade(normal, position);
+    if (light.color.w > 0.0) {
+        color = color * light.color.w;
+    } else {
+        color = color * -light.color.w;
+    }
+    return vec4<f32>(color, light.color.w);
+}
@@ -0,0 +1,50 @@
+// Only the items reachable from `main` remain after compaction.
+
+struct Unused {
+    a: vec4<f32>;
+    b: array<u32, 8>;
+};
+
+struct Light {
+    color: vec4<f32>;
+    intensity: f32;
+};
+
+struct Lights {
+    data: array<Light>;
+};
+
+let unused_constant: vec2<i32> = vec2<i32>(1, 2);
+let light_count: u32 = 4u;
+
+var<private> unused_global: Unused;
+
+@group(0) @binding(0)
+var<uniform> unused_uniform: Light;
+
+@group(0) @binding(1)
+var<storage> lights: Lights;
+
+var<private> weights: array<f32, light_count>;
+
+fn unused_function(x: f32) -> f32 {
+    return x * 2.0;
+}
+
+fn shade(index: u32) -> vec4<f32> {
+    let light = lights.data[index];
+    return light.color * light.intensity * weights[index];
+}
+
+fn unused_caller() -> vec4<f32> {
+    return shade(0u) * unused_function(1.0);
+}
+
+@stage(fragment)
+fn main() -> @location(0) vec4<f32> {
+    var color = vec4<f32>(0.0);
+    for (var i = 0u; i < light_count; i = i + 1u) {
+        color = color + shade(i);
+    }
+    return color;
+}
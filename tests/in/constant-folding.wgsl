@@ -0,0 +1,22 @@
+// Expressions on constants are folded by `proc::fold_constants`.
+
+let scale: f32 = 2.0;
+
+struct Output {
+    values: array<vec4<f32>>;
+};
+
+@group(0) @binding(0)
+var<storage, read_write> output: Output;
+
+@stage(compute) @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let wrapped = 2147483647 + 1;
+    let quotient = 7 / 0;
+    let remainder = 7u % 0u;
+    let shifted = 1u << 33u;
+    let truncated = i32(-2.75);
+    let v = vec4<f32>(vec2<f32>(1.0, 2.0) * scale, sqrt(16.0), f32(wrapped + quotient));
+    let s = v.zyx + vec3<f32>(f32(remainder + shifted));
+    output.values[id.x] = vec4<f32>(s, f32(truncated)) * vec4<f32>(v.w);
+}
@@ -0,0 +1,31 @@
+// Dead code removed by `proc::eliminate_dead_code`.
+
+struct Output {
+    values: array<u32, 4>;
+};
+
+@group(0) @binding(0)
+var<storage, read_write> output: Output;
+
+fn select_value(flag: bool, value: u32) -> u32 {
+    // Phi-like spill: only the final store is read.
+    var result: u32 = 0u;
+    var spilled: u32;
+    spilled = value * 2u;
+    if (flag) {
+        result = value;
+        return result;
+    } else {
+        return 0u;
+    }
+    result = spilled;
+    return result;
+}
+
+@stage(compute) @workgroup_size(1)
+fn main(@builtin(local_invocation_index) index: u32) {
+    let unused = index * 3u;
+    var never_read: u32 = index;
+    never_read = never_read + 1u;
+    output.values[index % 4u] = select_value(index > 1u, index);
+}
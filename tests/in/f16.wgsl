@@ -0,0 +1,48 @@
+enable f16;
+
+struct UniformCompatible {
+    val_f32: f32;
+    val_f16: f16;
+    val_f16_2: vec2<f16>;
+    val_f16_4: vec4<f16>;
+    final_value: f16;
+};
+
+struct StorageCompatible {
+    val_f16_mat: mat2x2<f16>;
+    val_f16_array: array<f16, 2>;
+};
+
+@group(0) @binding(0)
+var<uniform> input_uniform: UniformCompatible;
+
+@group(0) @binding(1)
+var<storage, read_write> output: UniformCompatible;
+
+@group(0) @binding(2)
+var<storage, read_write> output_matrices: StorageCompatible;
+
+let constant_variable: f16 = 15.2h;
+
+fn f16_function(x: f16) -> f16 {
+    var val: f16 = constant_variable;
+    // A number too big for f16
+    val = val + -33344.0h;
+    // Constructing an f16 from an AbstractInt
+    val = val + val - f16(5.0);
+
+    output.val_f32 = f32(val);
+    output.val_f16 = input_uniform.val_f16 + x;
+    output.val_f16_2 = input_uniform.val_f16_2 + vec2<f16>(val);
+    output.val_f16_4 = input_uniform.val_f16_4 * vec4<f16>(x, 1h, 0.5h, val);
+
+    output_matrices.val_f16_mat = mat2x2<f16>(vec2<f16>(1h, 0h), vec2<f16>(0h, val));
+    output_matrices.val_f16_array[1] = f16(input_uniform.val_f32);
+
+    return val;
+}
+
+@stage(compute) @workgroup_size(1)
+fn main() {
+    output.final_value = f16_function(2h);
+}
@@ -0,0 +1,7 @@
+#version 450 core
+
+invariant gl_Position;
+
+void main() {
+    gl_Position = vec4(1.0);
+}
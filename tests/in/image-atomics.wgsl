@@ -0,0 +1,19 @@
+@group(0) @binding(0)
+var image: texture_storage_2d<r32uint, read_write>;
+@group(0) @binding(1)
+var image_array: texture_storage_2d_array<r32sint, read_write>;
+
+@stage(compute) @workgroup_size(1)
+fn main(@builtin(local_invocation_id) id: vec3<u32>) {
+    let coord = vec2<i32>(id.xy);
+    let a = textureAtomicAdd(image, coord, 1u);
+    let b = textureAtomicSub(image, coord, 2u);
+    let c = textureAtomicMin(image, coord, a);
+    let d = textureAtomicMax(image, coord, b);
+    let e = textureAtomicExchange(image, coord, c + d);
+    let f = textureAtomicAnd(image_array, coord, 0, 3);
+    let g = textureAtomicOr(image_array, coord, 1, f);
+    let h = textureAtomicXor(image_array, coord, 2, g);
+    let loaded = textureLoad(image_array, coord, 0);
+    textureStore(image, coord, vec4<u32>(e + u32(h + loaded.x)));
+}
@@ -0,0 +1,41 @@
+// Calls inlined by `proc::inline`.
+
+struct Output {
+    values: array<u32, 4>;
+};
+
+@group(0) @binding(0)
+var<storage, read_write> output: Output;
+
+fn increment(counter: ptr<function, u32>, amount: u32) {
+    *counter = *counter + amount;
+}
+
+fn clamp_index(index: u32) -> u32 {
+    if (index > 3u) {
+        return 3u;
+    }
+    return index;
+}
+
+fn accumulate(count: u32) -> u32 {
+    var total: u32 = 0u;
+    var i: u32 = 0u;
+    loop {
+        if (i >= count) {
+            break;
+        }
+        increment(&total, i);
+        i = i + 1u;
+    }
+    return total;
+}
+
+@stage(compute) @workgroup_size(1)
+fn main(@builtin(local_invocation_index) index: u32) {
+    var sum: u32 = 0u;
+    for (var j: u32 = 0u; j < 2u; j = j + 1u) {
+        increment(&sum, accumulate(index));
+    }
+    output.values[clamp_index(index)] = sum;
+}
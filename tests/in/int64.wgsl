@@ -0,0 +1,43 @@
+struct Values {
+    val_i64: i64;
+    val_u64: u64;
+    val_i64_2: vec2<i64>;
+    val_u64_4: vec4<u64>;
+};
+
+struct Atomics {
+    counter: atomic<u64>;
+    signed_counter: atomic<i64>;
+};
+
+@group(0) @binding(0)
+var<uniform> input: Values;
+
+@group(0) @binding(1)
+var<storage, read_write> output: Values;
+
+@group(0) @binding(2)
+var<storage, read_write> atomics: Atomics;
+
+let big_constant: i64 = -9000000000li;
+
+fn i64_function(x: i64) -> i64 {
+    var val: i64 = big_constant;
+    val = val + 20li * x;
+    val = val - i64(input.val_u64);
+
+    output.val_u64 = u64(val) + 0x100000000lu;
+    output.val_i64_2 = input.val_i64_2 + vec2<i64>(val);
+    output.val_u64_4 = input.val_u64_4 * vec4<u64>(u64(x), 1lu, 2lu, input.val_u64);
+
+    return val;
+}
+
+@stage(compute) @workgroup_size(1)
+fn main() {
+    output.val_i64 = i64_function(3li);
+
+    // Metal only supports 64-bit min and max
+    let old = atomicMax(&atomics.counter, 1lu);
+    atomicMax(&atomics.signed_counter, i64(old));
+}
@@ -0,0 +1,19 @@
+@id(0) override has_point_light: bool = true;
+@id(1200) override specular_param: f32 = 2.3;
+@id(1300) override gain: f32 = 1.1;
+override width: f32 = 0.0;
+override depth: f32 = 2.0;
+override height: f32 = 4.6;
+override block_size: u32 = 64u;
+
+var<workgroup> shared_area: array<f32, 16>;
+
+@stage(compute) @workgroup_size(block_size)
+fn main(@builtin(local_invocation_index) index: u32) {
+    var gain_x_10: f32 = gain * 10.0;
+    var area: f32 = width * height;
+    if (has_point_light) {
+        area = area * depth * specular_param;
+    }
+    shared_area[index % 16u] = area * gain_x_10;
+}
@@ -0,0 +1,25 @@
+struct Camera {
+    view_proj: mat4x4<f32>;
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+@group(1) @binding(2)
+var albedo: texture_2d<f32>;
+
+@group(1) @binding(5)
+var albedo_sampler: sampler;
+
+@group(3) @binding(1)
+var shadow: texture_depth_2d;
+
+@group(3) @binding(4)
+var shadow_sampler: sampler_comparison;
+
+@stage(fragment)
+fn main(@location(0) position: vec3<f32>, @location(1) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let light = camera.view_proj * vec4<f32>(position, 1.0);
+    let visibility = textureSampleCompare(shadow, shadow_sampler, light.xy, light.z);
+    return textureSample(albedo, albedo_sampler, uv) * visibility;
+}
@@ -0,0 +1,34 @@
+@stage(compute) @workgroup_size(128)
+fn main(
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(subgroup_size) subgroup_size: u32,
+    @builtin(subgroup_invocation_id) subgroup_invocation_id: u32,
+) {
+    subgroupBarrier();
+
+    subgroupBallot((subgroup_invocation_id & 1u) == 1u);
+    subgroupBallot();
+
+    subgroupAll(subgroup_invocation_id != 0u);
+    subgroupAny(subgroup_invocation_id == 0u);
+    subgroupAdd(subgroup_invocation_id);
+    subgroupMul(subgroup_invocation_id);
+    subgroupMin(subgroup_invocation_id);
+    subgroupMax(subgroup_invocation_id);
+    subgroupAnd(subgroup_invocation_id);
+    subgroupOr(subgroup_invocation_id);
+    subgroupXor(subgroup_invocation_id);
+    subgroupExclusiveAdd(subgroup_invocation_id);
+    subgroupExclusiveMul(subgroup_invocation_id);
+    subgroupInclusiveAdd(subgroup_invocation_id);
+    subgroupInclusiveMul(subgroup_invocation_id);
+
+    subgroupBroadcastFirst(local_index);
+    subgroupBroadcast(local_index, 4u);
+    subgroupShuffle(local_index, subgroup_size - 1u - subgroup_invocation_id);
+    subgroupShuffleDown(local_index, 1u);
+    subgroupShuffleUp(local_index, 1u);
+    subgroupShuffleXor(local_index, subgroup_size - 1u);
+
+    let elected = subgroupElect();
+}
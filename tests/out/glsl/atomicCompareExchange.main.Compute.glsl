@@ -0,0 +1,46 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct Counters {
+    int signed_value;
+    uint unsigned_value;
+};
+struct atomic_compare_exchange_result_i32_ {
+    int old_value;
+    bool exchanged;
+};
+struct atomic_compare_exchange_result_u32_ {
+    uint old_value;
+    bool exchanged;
+};
+layout(std430) buffer Counters_block_0Compute { Counters _group_0_binding_0_cs; };
+
+
+void main() {
+    bool exchanged = false;
+    while(true) {
+        bool _e3 = exchanged;
+        if (_e3) {
+            break;
+        }
+        int old = _group_0_binding_0_cs.signed_value;
+        atomic_compare_exchange_result_i32_ _e9;
+        _e9.old_value = atomicCompSwap(_group_0_binding_0_cs.signed_value, old, (old + 1));
+        _e9.exchanged = (_e9.old_value == old);
+        exchanged = _e9.exchanged;
+    }
+    atomic_compare_exchange_result_u32_ _e14;
+    _e14.old_value = atomicCompSwap(_group_0_binding_0_cs.unsigned_value, 1u, 2u);
+    _e14.exchanged = (_e14.old_value == 1u);
+    if (_e14.exchanged) {
+        _group_0_binding_0_cs.unsigned_value = (_e14.old_value + 1u);
+        return;
+    } else {
+        return;
+    }
+}
+
@@ -0,0 +1,18 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(std430) buffer Output_block_0Compute {
+    vec4 values[];
+} _group_0_binding_0_cs;
+
+
+void main() {
+    uvec3 id = gl_GlobalInvocationID;
+    _group_0_binding_0_cs.values[id.x] = (vec4(6.0, 6.0, 4.0, -2.0) * vec4(vec4(2.0, 4.0, 4.0, -2147483648.0).w));
+    return;
+}
+
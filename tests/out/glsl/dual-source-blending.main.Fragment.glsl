@@ -0,0 +1,17 @@
+#version 330 core
+struct FragmentOutput {
+    vec4 color;
+    vec4 mask;
+};
+layout(location = 0, index = 0) out vec4 _fs2p_location0_index0;
+layout(location = 0, index = 1) out vec4 _fs2p_location0_index1;
+
+void main() {
+    vec4 position = gl_FragCoord;
+    float coverage = fract(position.x);
+    FragmentOutput _tmp_return = FragmentOutput(vec4(0.5, 0.0, 0.0, 1.0), vec4(coverage));
+    _fs2p_location0_index0 = _tmp_return.color;
+    _fs2p_location0_index1 = _tmp_return.mask;
+    return;
+}
+
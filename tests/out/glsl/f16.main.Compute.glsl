@@ -0,0 +1,56 @@
+#version 450 core
+#extension GL_ARB_compute_shader : require
+#extension GL_ARB_shader_storage_buffer_object : require
+#extension GL_EXT_shader_explicit_arithmetic_types_float16 : require
+#extension GL_EXT_shader_16bit_storage : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct UniformCompatible {
+    float val_f32_;
+    float16_t val_f16_;
+    f16vec2 val_f16_2_;
+    f16vec4 val_f16_4_;
+    float16_t final_value;
+};
+struct StorageCompatible {
+    f16mat2x2 val_f16_mat;
+    float16_t val_f16_array[2];
+};
+uniform UniformCompatible_block_0Compute { UniformCompatible _group_0_binding_0_cs; };
+
+layout(std430) buffer UniformCompatible_block_1Compute { UniformCompatible _group_0_binding_1_cs; };
+
+layout(std430) buffer StorageCompatible_block_2Compute { StorageCompatible _group_0_binding_2_cs; };
+
+
+float16_t f16_function(float16_t x) {
+    float16_t val = 15.199999809265137hf;
+    float16_t _e6 = val;
+    val = (_e6 + -33344.0hf);
+    float16_t _e9 = val;
+    float16_t _e10 = val;
+    val = ((_e9 + _e10) - float16_t(5.0));
+    float16_t _e16 = val;
+    _group_0_binding_1_cs.val_f32_ = float(_e16);
+    float16_t _e20 = _group_0_binding_0_cs.val_f16_;
+    _group_0_binding_1_cs.val_f16_ = (_e20 + x);
+    f16vec2 _e24 = _group_0_binding_0_cs.val_f16_2_;
+    float16_t _e25 = val;
+    _group_0_binding_1_cs.val_f16_2_ = (_e24 + f16vec2(_e25));
+    f16vec4 _e30 = _group_0_binding_0_cs.val_f16_4_;
+    float16_t _e33 = val;
+    _group_0_binding_1_cs.val_f16_4_ = (_e30 * f16vec4(x, 1.0hf, 0.5hf, _e33));
+    float16_t _e41 = val;
+    _group_0_binding_2_cs.val_f16_mat = f16mat2x2(f16vec2(1.0hf, 0.0hf), f16vec2(0.0hf, _e41));
+    float _e48 = _group_0_binding_0_cs.val_f32_;
+    _group_0_binding_2_cs.val_f16_array[1] = float16_t(_e48);
+    float16_t _e50 = val;
+    return _e50;
+}
+
+void main() {
+    float16_t _e6 = f16_function(2.0hf);
+    _group_0_binding_1_cs.final_value = _e6;
+    return;
+}
+
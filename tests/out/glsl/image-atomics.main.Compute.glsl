@@ -0,0 +1,25 @@
+#version 430 core
+#extension GL_ARB_compute_shader : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(r32ui) uniform highp uimage2D _group_0_binding_0_cs;
+
+layout(r32i) uniform highp iimage2DArray _group_0_binding_1_cs;
+
+
+void main() {
+    uvec3 id = gl_LocalInvocationID;
+    ivec2 coord = ivec2(id.xy);
+    uint _e6 = imageAtomicAdd(_group_0_binding_0_cs, coord, 1u);
+    uint _e8 = imageAtomicAdd(_group_0_binding_0_cs, coord, -2u);
+    uint _e9 = imageAtomicMin(_group_0_binding_0_cs, coord, _e6);
+    uint _e10 = imageAtomicMax(_group_0_binding_0_cs, coord, _e8);
+    uint _e12 = imageAtomicExchange(_group_0_binding_0_cs, coord, (_e9 + _e10));
+    int _e15 = imageAtomicAnd(_group_0_binding_1_cs, ivec3(coord, 0), 3);
+    int _e17 = imageAtomicOr(_group_0_binding_1_cs, ivec3(coord, 1), _e15);
+    int _e19 = imageAtomicXor(_group_0_binding_1_cs, ivec3(coord, 2), _e17);
+    ivec4 loaded = imageLoad(_group_0_binding_1_cs, ivec3(coord, 0));
+    imageStore(_group_0_binding_0_cs, coord, uvec4((_e12 + uint((_e19 + loaded.x)))));
+    return;
+}
+
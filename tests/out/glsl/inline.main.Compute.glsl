@@ -0,0 +1,63 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct Output {
+    uint values[4];
+};
+layout(std430) buffer Output_block_0Compute { Output _group_0_binding_0_cs; };
+
+
+void main() {
+    uint index = gl_LocalInvocationIndex;
+    uint sum = 0u;
+    uint j = 0u;
+    uint total = 0u;
+    uint i = 0u;
+    uint local = 0u;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+        uint _e7 = j;
+        j = (_e7 + 1u);
+        }
+        loop_init = false;
+        uint _e4 = j;
+        if ((_e4 < 2u)) {
+        } else {
+            break;
+        }
+        total = 0u;
+        i = 0u;
+        while(true) {
+            uint _e12 = i;
+            if ((_e12 >= index)) {
+                break;
+            }
+            uint _e14 = i;
+            uint _e19 = total;
+            total = (_e19 + _e14);
+            uint _e15 = i;
+            i = (_e15 + 1u);
+        }
+        uint _e18 = total;
+        uint _e33 = sum;
+        sum = (_e33 + _e18);
+    }
+    while(true) {
+        if ((index > 3u)) {
+            local = 3u;
+            break;
+        }
+        local = index;
+        break;
+    }
+    uint _e30 = local;
+    uint _e32 = sum;
+    _group_0_binding_0_cs.values[_e30] = _e32;
+    return;
+}
+
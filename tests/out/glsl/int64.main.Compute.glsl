@@ -0,0 +1,51 @@
+#version 450 core
+#extension GL_ARB_compute_shader : require
+#extension GL_ARB_shader_storage_buffer_object : require
+#extension GL_ARB_gpu_shader_int64 : require
+#extension GL_EXT_shader_atomic_int64 : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct Values {
+    int64_t val_i64_;
+    uint64_t val_u64_;
+    i64vec2 val_i64_2_;
+    u64vec4 val_u64_4_;
+};
+struct Atomics {
+    uint64_t counter;
+    int64_t signed_counter;
+};
+uniform Values_block_0Compute { Values _group_0_binding_0_cs; };
+
+layout(std430) buffer Values_block_1Compute { Values _group_0_binding_1_cs; };
+
+layout(std430) buffer Atomics_block_2Compute { Atomics _group_0_binding_2_cs; };
+
+
+int64_t i64_function(int64_t x) {
+    int64_t val = -9000000000l;
+    int64_t _e6 = val;
+    val = (_e6 + (20l * x));
+    int64_t _e10 = val;
+    uint64_t _e12 = _group_0_binding_0_cs.val_u64_;
+    val = (_e10 - int64_t(_e12));
+    int64_t _e16 = val;
+    _group_0_binding_1_cs.val_u64_ = (uint64_t(_e16) + 4294967296ul);
+    i64vec2 _e22 = _group_0_binding_0_cs.val_i64_2_;
+    int64_t _e23 = val;
+    _group_0_binding_1_cs.val_i64_2_ = (_e22 + i64vec2(_e23));
+    u64vec4 _e28 = _group_0_binding_0_cs.val_u64_4_;
+    uint64_t _e33 = _group_0_binding_0_cs.val_u64_;
+    _group_0_binding_1_cs.val_u64_4_ = (_e28 * u64vec4(uint64_t(x), 1ul, 2ul, _e33));
+    int64_t _e36 = val;
+    return _e36;
+}
+
+void main() {
+    int64_t _e6 = i64_function(3l);
+    _group_0_binding_1_cs.val_i64_ = _e6;
+    uint64_t _e9 = atomicMax(_group_0_binding_2_cs.counter, 1ul);
+    int64_t _e12 = atomicMax(_group_0_binding_2_cs.signed_counter, int64_t(_e9));
+    return;
+}
+
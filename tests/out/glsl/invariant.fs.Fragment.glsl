@@ -0,0 +1,9 @@
+#version 330 core
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    vec4 position = gl_FragCoord;
+    _fs2p_location0 = position;
+    return;
+}
+
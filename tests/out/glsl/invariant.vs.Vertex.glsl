@@ -0,0 +1,8 @@
+#version 330 core
+invariant gl_Position;
+
+void main() {
+    gl_Position = vec4(0.0);
+    return;
+}
+
@@ -0,0 +1,26 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 32, local_size_y = 1, local_size_z = 1) in;
+
+shared float shared_area[16];
+
+
+void main() {
+    uint index = gl_LocalInvocationIndex;
+    float gain_x_10_ = 0.0;
+    float area = 0.0;
+    gain_x_10_ = (2.0 * 10.0);
+    area = (3.0 * 4.599999904632568);
+    if (false) {
+        float _e14 = area;
+        area = ((_e14 * 2.0) * 2.299999952316284);
+    }
+    float _e20 = area;
+    float _e21 = gain_x_10_;
+    shared_area[(index % 16u)] = (_e20 * _e21);
+    return;
+}
+
@@ -0,0 +1,40 @@
+
+struct Counters {
+    int signed_value;
+    uint unsigned_value;
+};
+
+struct atomic_compare_exchange_result_i32_ {
+    int old_value;
+    bool exchanged;
+};
+
+struct atomic_compare_exchange_result_u32_ {
+    uint old_value;
+    bool exchanged;
+};
+
+RWByteAddressBuffer counters : register(u0);
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    bool exchanged = false;
+
+    while(true) {
+        bool _expr3 = exchanged;
+        if (_expr3) {
+            break;
+        }
+        int old = asint(counters.Load(0));
+        atomic_compare_exchange_result_i32_ _e9; counters.InterlockedCompareExchange(0, old, (old + 1), _e9.old_value); _e9.exchanged = (_e9.old_value == old);
+        exchanged = _e9.exchanged;
+    }
+    atomic_compare_exchange_result_u32_ _e14; counters.InterlockedCompareExchange(4, 1u, 2u, _e14.old_value); _e14.exchanged = (_e14.old_value == 1u);
+    if (_e14.exchanged) {
+        counters.Store(4, asuint((_e14.old_value + 1u)));
+        return;
+    } else {
+        return;
+    }
+}
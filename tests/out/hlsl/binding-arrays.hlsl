@@ -0,0 +1,50 @@
+
+struct UniformIndex {
+    uint index;
+};
+
+struct FragmentIn {
+    nointerpolation uint index : LOC0;
+};
+
+Texture2D<float4> texture_array[5] : register(t0);
+SamplerState sampler_array[5] : register(s1);
+RWTexture2D<float4> storage_array[5] : register(u2);
+cbuffer uni : register(b3) { UniformIndex uni; }
+
+struct FragmentInput_main {
+    uint index : LOC0;
+};
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    FragmentIn fragment_in = { fragmentinput_main.index };
+    float4 v1_ = (float4)0;
+
+    uint uniform_index = uni.index;
+    uint non_uniform_index = NonUniformResourceIndex(fragment_in.index);
+    v1_ = float4(0.0.xxxx);
+    float2 uv = float2(0.0.xx);
+    int2 pix = int2(0.xx);
+    float4 _expr16 = v1_;
+    float4 _expr21 = texture_array[0].Sample(sampler_array[0], uv);
+    v1_ = (_expr16 + _expr21);
+    float4 _expr23 = v1_;
+    float4 _expr26 = texture_array[uniform_index].Sample(sampler_array[uniform_index], uv);
+    v1_ = (_expr23 + _expr26);
+    float4 _expr28 = v1_;
+    float4 _expr31 = texture_array[non_uniform_index].Sample(sampler_array[non_uniform_index], uv);
+    v1_ = (_expr28 + _expr31);
+    float4 _expr33 = v1_;
+    float4 _expr36 = texture_array[uniform_index].Load(int3(pix, 0));
+    v1_ = (_expr33 + _expr36);
+    float4 _expr38 = v1_;
+    float4 _expr41 = texture_array[non_uniform_index].Load(int3(pix, 0));
+    v1_ = (_expr38 + _expr41);
+    float4 _expr44 = v1_;
+    storage_array[uniform_index][pix] = _expr44;
+    float4 _expr46 = v1_;
+    storage_array[non_uniform_index][pix] = _expr46;
+    float4 _expr47 = v1_;
+    return _expr47;
+}
@@ -0,0 +1,54 @@
+
+struct UniformIndex {
+    uint index;
+};
+
+struct Foo {
+    uint x;
+};
+
+struct FragmentIn {
+    nointerpolation uint index : LOC0;
+};
+
+ByteAddressBuffer storage_array[1] : register(t0);
+ConstantBuffer<Foo> uniform_array[2] : register(b1);
+RWByteAddressBuffer unbounded_array[] : register(u2);
+cbuffer uni : register(b10) { UniformIndex uni; }
+
+struct FragmentInput_main {
+    uint index : LOC0;
+};
+
+uint main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    FragmentIn fragment_in = { fragmentinput_main.index };
+    uint u1_ = 0u;
+
+    uint uniform_index = uni.index;
+    uint non_uniform_index = NonUniformResourceIndex(fragment_in.index);
+    uint _expr11 = u1_;
+    uint _expr15 = asuint(storage_array[0].Load(0));
+    u1_ = (_expr11 + _expr15);
+    uint _expr17 = u1_;
+    uint _expr20 = asuint(storage_array[uniform_index].Load(0));
+    u1_ = (_expr17 + _expr20);
+    uint _expr22 = u1_;
+    uint _expr25 = asuint(storage_array[non_uniform_index].Load(0));
+    u1_ = (_expr22 + _expr25);
+    uint _expr27 = u1_;
+    uint _expr31 = uniform_array[0].x;
+    u1_ = (_expr27 + _expr31);
+    uint _expr33 = u1_;
+    uint _expr36 = uniform_array[uniform_index].x;
+    u1_ = (_expr33 + _expr36);
+    uint _expr38 = u1_;
+    uint _expr41 = uniform_array[non_uniform_index].x;
+    u1_ = (_expr38 + _expr41);
+    uint _expr45 = u1_;
+    unbounded_array[uniform_index].Store(0, asuint(_expr45));
+    uint _expr48 = u1_;
+    unbounded_array[non_uniform_index].Store(0, asuint(_expr48));
+    uint _expr49 = u1_;
+    return _expr49;
+}
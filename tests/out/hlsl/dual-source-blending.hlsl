@@ -0,0 +1,24 @@
+
+struct FragmentOutput {
+    linear float4 color : SV_Target0;
+    linear float4 mask : SV_Target1;
+};
+
+struct FragmentInput_main {
+    float4 position_1 : SV_Position;
+};
+
+FragmentOutput ConstructFragmentOutput(float4 arg0, float4 arg1) {
+    FragmentOutput ret;
+    ret.color = arg0;
+    ret.mask = arg1;
+    return ret;
+}
+
+FragmentOutput main(FragmentInput_main fragmentinput_main)
+{
+    float4 position = fragmentinput_main.position_1;
+    float coverage = frac(position.x);
+    const FragmentOutput fragmentoutput = ConstructFragmentOutput(float4(0.5, 0.0, 0.0, 1.0), float4(coverage.xxxx));
+    return fragmentoutput;
+}
@@ -0,0 +1,57 @@
+static const float16_t constant_variable = 15.199999809265137;
+
+struct UniformCompatible {
+    float val_f32_;
+    float16_t val_f16_;
+    float16_t2 val_f16_2_;
+    float16_t4 val_f16_4_;
+    float16_t final_value;
+};
+
+struct StorageCompatible {
+    row_major float16_t2x2 val_f16_mat;
+    float16_t val_f16_array[2];
+};
+
+cbuffer input_uniform : register(b0) { UniformCompatible input_uniform; }
+RWByteAddressBuffer output : register(u1);
+RWByteAddressBuffer output_matrices : register(u2);
+
+float16_t f16_function(float16_t x)
+{
+    float16_t val = constant_variable;
+
+    float16_t _expr6 = val;
+    val = (_expr6 + -33344.0);
+    float16_t _expr9 = val;
+    float16_t _expr10 = val;
+    val = ((_expr9 + _expr10) - float16_t(5.0));
+    float16_t _expr16 = val;
+    output.Store(0, asuint(float(_expr16)));
+    float16_t _expr20 = input_uniform.val_f16_;
+    output.Store<float16_t>(4, (_expr20 + x));
+    float16_t2 _expr24 = input_uniform.val_f16_2_;
+    float16_t _expr25 = val;
+    output.Store<float16_t2>(8, (_expr24 + float16_t2(_expr25.xx)));
+    float16_t4 _expr30 = input_uniform.val_f16_4_;
+    float16_t _expr33 = val;
+    output.Store<float16_t4>(16, (_expr30 * float16_t4(x, 1.0, 0.5, _expr33)));
+    float16_t _expr41 = val;
+    {
+        float16_t2x2 _value2 = float16_t2x2(float16_t2(1.0, 0.0), float16_t2(0.0, _expr41));
+        output_matrices.Store<float16_t2>(0+0, _value2[0]);
+        output_matrices.Store<float16_t2>(0+4, _value2[1]);
+    }
+    float _expr48 = input_uniform.val_f32_;
+    output_matrices.Store<float16_t>(2+8, float16_t(_expr48));
+    float16_t _expr50 = val;
+    return _expr50;
+}
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    const float16_t _e6 = f16_function(2.0);
+    output.Store<float16_t>(24, _e6);
+    return;
+}
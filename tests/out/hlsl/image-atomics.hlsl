@@ -0,0 +1,20 @@
+
+RWTexture2D<uint> image : register(u0);
+RWTexture2DArray<int> image_array : register(u1);
+
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_GroupThreadID)
+{
+    int2 coord = int2(id.xy);
+    uint _e6; InterlockedAdd(image[coord], 1u, _e6);
+    uint _e8; InterlockedAdd(image[coord], -2u, _e8);
+    uint _e9; InterlockedMin(image[coord], _e6, _e9);
+    uint _e10; InterlockedMax(image[coord], _e8, _e10);
+    uint _e12; InterlockedExchange(image[coord], (_e9 + _e10), _e12);
+    int _e15; InterlockedAnd(image_array[int3(coord, 0)], 3, _e15);
+    int _e17; InterlockedOr(image_array[int3(coord, 1)], _e15, _e17);
+    int _e19; InterlockedXor(image_array[int3(coord, 2)], _e17, _e19);
+    int4 loaded = int4(image_array.Load(int3(coord, 0)), 0, 0, 1);
+    image[coord] = (uint4((_e12 + uint((_e19 + loaded.x))).xxxx)).x;
+    return;
+}
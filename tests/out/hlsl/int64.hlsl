@@ -0,0 +1,48 @@
+static const int64_t big_constant = -9000000000L;
+
+struct Values {
+    int64_t val_i64_;
+    uint64_t val_u64_;
+    int64_t2 val_i64_2_;
+    uint64_t4 val_u64_4_;
+};
+
+struct Atomics {
+    uint64_t counter;
+    int64_t signed_counter;
+};
+
+cbuffer input : register(b0) { Values input; }
+RWByteAddressBuffer output : register(u1);
+RWByteAddressBuffer atomics : register(u2);
+
+int64_t i64_function(int64_t x)
+{
+    int64_t val = big_constant;
+
+    int64_t _expr6 = val;
+    val = (_expr6 + (20L * x));
+    int64_t _expr10 = val;
+    uint64_t _expr12 = input.val_u64_;
+    val = (_expr10 - int64_t(_expr12));
+    int64_t _expr16 = val;
+    output.Store<uint64_t>(8, (uint64_t(_expr16) + 4294967296uL));
+    int64_t2 _expr22 = input.val_i64_2_;
+    int64_t _expr23 = val;
+    output.Store<int64_t2>(16, (_expr22 + int64_t2(_expr23.xx)));
+    uint64_t4 _expr28 = input.val_u64_4_;
+    uint64_t _expr33 = input.val_u64_;
+    output.Store<uint64_t4>(32, (_expr28 * uint64_t4(uint64_t(x), 1uL, 2uL, _expr33)));
+    int64_t _expr36 = val;
+    return _expr36;
+}
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    const int64_t _e6 = i64_function(3L);
+    output.Store<int64_t>(0, _e6);
+    uint64_t _e9; atomics.InterlockedMax64(0, 1uL, _e9);
+    int64_t _e12; atomics.InterlockedMax64(8, int64_t(_e9), _e12);
+    return;
+}
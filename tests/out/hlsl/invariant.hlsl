@@ -0,0 +1,15 @@
+
+struct FragmentInput_fs {
+    precise float4 position_1 : SV_Position;
+};
+
+precise float4 vs() : SV_Position
+{
+    return float4(0.0.xxxx);
+}
+
+float4 fs(FragmentInput_fs fragmentinput_fs) : SV_Target0
+{
+    float4 position = fragmentinput_fs.position_1;
+    return position;
+}
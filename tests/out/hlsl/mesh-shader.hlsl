@@ -0,0 +1,60 @@
+
+struct VertexOutput {
+    float4 position : SV_Position;
+    linear float4 color : LOC0;
+};
+
+struct PrimitiveOutput {
+    uint3 indices;
+    bool cull : SV_CullPrimitive;
+    nointerpolation uint id : LOC1;
+};
+
+struct NagaTaskPayload {
+    uint dummy;
+};
+groupshared NagaTaskPayload _naga_task_payload;
+
+struct PrimitiveOutput_ms_main {
+    uint id : LOC1;
+    bool cull : SV_CullPrimitive;
+};
+
+[numthreads(1, 1, 1)]
+void ts_main()
+{
+    uint3 _e3 = uint3(1u, 1u, 1u);
+    DispatchMesh(_e3.x, _e3.y, _e3.z, _naga_task_payload);
+    return;
+}
+
+VertexOutput ConstructVertexOutput(float4 arg0, float4 arg1) {
+    VertexOutput ret;
+    ret.position = arg0;
+    ret.color = arg1;
+    return ret;
+}
+
+PrimitiveOutput ConstructPrimitiveOutput(uint3 arg0, bool arg1, uint arg2) {
+    PrimitiveOutput ret;
+    ret.indices = arg0;
+    ret.cull = arg1;
+    ret.id = arg2;
+    return ret;
+}
+
+[numthreads(1, 1, 1)]
+[outputtopology("triangle")]
+void ms_main(uint3 wg_id : SV_GroupID, out vertices VertexOutput vertices[3], out indices uint3 indices[1], out primitives PrimitiveOutput_ms_main primitiveoutput_ms_main[1])
+{
+    SetMeshOutputCounts(3u, 1u);
+    float4 color = float4(1.0, 0.0, 0.0, 1.0);
+    vertices[0u] = ConstructVertexOutput(float4(0.0, 1.0, 0.0, 1.0), color);
+    vertices[1u] = ConstructVertexOutput(float4(-1.0, -1.0, 0.0, 1.0), color);
+    vertices[2u] = ConstructVertexOutput(float4(1.0, -1.0, 0.0, 1.0), color);
+    PrimitiveOutput _e36 = ConstructPrimitiveOutput(uint3(0u, 1u, 2u), false, wg_id.x);
+    indices[0u] = _e36.indices;
+    primitiveoutput_ms_main[0u].id = _e36.id;
+    primitiveoutput_ms_main[0u].cull = _e36.cull;
+    return;
+}
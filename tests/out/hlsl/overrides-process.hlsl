@@ -0,0 +1,27 @@
+static const bool has_point_light = false;
+static const float specular_param = 2.299999952316284;
+static const float gain = 2.0;
+static const float width = 3.0;
+static const float depth = 2.0;
+static const float height = 4.599999904632568;
+static const uint block_size = 32;
+
+groupshared float shared_area[16];
+
+[numthreads(32, 1, 1)]
+void main(uint index : SV_GroupIndex)
+{
+    float gain_x_10_ = (float)0;
+    float area = (float)0;
+
+    gain_x_10_ = (gain * 10.0);
+    area = (width * height);
+    if (has_point_light) {
+        float _expr14 = area;
+        area = ((_expr14 * depth) * specular_param);
+    }
+    float _expr20 = area;
+    float _expr21 = gain_x_10_;
+    shared_area[(index % 16u)] = (_expr20 * _expr21);
+    return;
+}
@@ -0,0 +1,98 @@
+
+struct Output {
+    uint visible;
+    float3 normal;
+};
+
+struct RayIntersection {
+    uint kind;
+    float t;
+    uint instance_custom_index;
+    uint instance_id;
+    uint sbt_record_offset;
+    uint geometry_index;
+    uint primitive_index;
+    float2 barycentrics;
+    bool front_face;
+    row_major float3x4 object_to_world;
+    row_major float3x4 world_to_object;
+};
+
+struct RayDesc_ {
+    uint flags;
+    uint cull_mask;
+    float tmin;
+    float tmax;
+    float3 origin;
+    float3 dir;
+};
+
+RayIntersection GetCommittedIntersection(RayQuery<RAY_FLAG_NONE> rq) {
+    RayIntersection ret = (RayIntersection)0;
+    ret.kind = rq.CommittedStatus();
+    ret.t = rq.CommittedRayT();
+    ret.instance_custom_index = rq.CommittedInstanceID();
+    ret.instance_id = rq.CommittedInstanceIndex();
+    ret.sbt_record_offset = rq.CommittedInstanceContributionToHitGroupIndex();
+    ret.geometry_index = rq.CommittedGeometryIndex();
+    ret.primitive_index = rq.CommittedPrimitiveIndex();
+    ret.object_to_world = rq.CommittedObjectToWorld3x4();
+    ret.world_to_object = rq.CommittedWorldToObject3x4();
+    if (ret.kind == COMMITTED_TRIANGLE_HIT) {
+        ret.barycentrics = rq.CommittedTriangleBarycentrics();
+        ret.front_face = rq.CommittedTriangleFrontFace();
+    }
+    return ret;
+}
+
+RayDesc RayDescFromRayDesc_(RayDesc_ arg0) {
+    RayDesc ret = (RayDesc)0;
+    ret.Origin = arg0.origin;
+    ret.TMin = arg0.tmin;
+    ret.Direction = arg0.dir;
+    ret.TMax = arg0.tmax;
+    return ret;
+}
+
+RaytracingAccelerationStructure acc_struct : register(t0);
+RWByteAddressBuffer output : register(u1);
+
+float3 get_torus_normal(float3 world_point, RayIntersection intersection)
+{
+    float3 local_point = mul(float4(world_point, 1.0), intersection.world_to_object);
+    float2 point_on_guiding_line = (normalize(local_point.xy) * 2.4000000953674316);
+    float3 world_point_on_guiding_line = mul(float4(point_on_guiding_line, 0.0, 1.0), intersection.object_to_world);
+    return normalize((world_point - world_point_on_guiding_line));
+}
+
+RayDesc_ ConstructRayDesc_(uint arg0, uint arg1, float arg2, float arg3, float3 arg4, float3 arg5) {
+    RayDesc_ ret;
+    ret.flags = arg0;
+    ret.cull_mask = arg1;
+    ret.tmin = arg2;
+    ret.tmax = arg3;
+    ret.origin = arg4;
+    ret.dir = arg5;
+    return ret;
+}
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    RayQuery<RAY_FLAG_NONE> rq;
+
+    float3 dir = float3(0.0, 1.0, 0.0);
+    RayDesc_ _e13 = ConstructRayDesc_(4u, 255u, 0.10000000149011612, 100.0, float3(0.0.xxx), dir);
+    rq.TraceRayInline(acc_struct, _e13.flags, _e13.cull_mask, RayDescFromRayDesc_(_e13));
+    while(true) {
+        const bool _e14 = rq.Proceed();
+        if (!_e14) {
+            break;
+        }
+    }
+    RayIntersection intersection_1 = GetCommittedIntersection(rq);
+    output.Store(0, asuint(uint((intersection_1.kind == 0u))));
+    const float3 _e25 = get_torus_normal((dir * intersection_1.t), intersection_1);
+    output.Store3(16, asuint(_e25));
+    return;
+}
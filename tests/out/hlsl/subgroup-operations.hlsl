@@ -0,0 +1,28 @@
+
+[numthreads(128, 1, 1)]
+void main(uint local_index : SV_GroupIndex)
+{
+    const uint4 _e7 = WaveActiveBallot(((WaveGetLaneIndex() & 1u) == 1u));
+    const uint4 _e8 = WaveActiveBallot(true);
+    const bool _e11 = WaveActiveAllTrue((WaveGetLaneIndex() != 0u));
+    const bool _e14 = WaveActiveAnyTrue((WaveGetLaneIndex() == 0u));
+    const uint _e15 = WaveActiveSum(WaveGetLaneIndex());
+    const uint _e16 = WaveActiveProduct(WaveGetLaneIndex());
+    const uint _e17 = WaveActiveMin(WaveGetLaneIndex());
+    const uint _e18 = WaveActiveMax(WaveGetLaneIndex());
+    const uint _e19 = WaveActiveBitAnd(WaveGetLaneIndex());
+    const uint _e20 = WaveActiveBitOr(WaveGetLaneIndex());
+    const uint _e21 = WaveActiveBitXor(WaveGetLaneIndex());
+    const uint _e22 = WavePrefixSum(WaveGetLaneIndex());
+    const uint _e23 = WavePrefixProduct(WaveGetLaneIndex());
+    const uint _e24 = WavePrefixSum(WaveGetLaneIndex()) + WaveGetLaneIndex();
+    const uint _e25 = WavePrefixProduct(WaveGetLaneIndex()) * WaveGetLaneIndex();
+    const uint _e26 = WaveReadLaneFirst(local_index);
+    const uint _e28 = WaveReadLaneAt(local_index, 4u);
+    const uint _e32 = WaveReadLaneAt(local_index, ((WaveGetLaneCount() - 1u) - WaveGetLaneIndex()));
+    const uint _e34 = WaveReadLaneAt(local_index, WaveGetLaneIndex() + 1u);
+    const uint _e36 = WaveReadLaneAt(local_index, WaveGetLaneIndex() - 1u);
+    const uint _e39 = WaveReadLaneAt(local_index, WaveGetLaneIndex() ^ (WaveGetLaneCount() - 1u));
+    const bool _e40 = WaveIsFirstLane();
+    return;
+}
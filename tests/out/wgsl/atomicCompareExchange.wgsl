@@ -0,0 +1,29 @@
+struct Counters {
+    signed_value: atomic<i32>;
+    unsigned_value: atomic<u32>;
+};
+
+@group(0) @binding(0) 
+var<storage, read_write> counters: Counters;
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main() {
+    var exchanged: bool = false;
+
+    loop {
+        let _e3 = exchanged;
+        if (_e3) {
+            break;
+        }
+        let old = atomicLoad((&counters.signed_value));
+        let _e9 = atomicCompareExchangeWeak((&counters.signed_value), old, (old + 1));
+        exchanged = _e9.exchanged;
+    }
+    let _e14 = atomicCompareExchangeWeak((&counters.unsigned_value), 1u, 2u);
+    if (_e14.exchanged) {
+        atomicStore((&counters.unsigned_value), (_e14.old_value + 1u));
+        return;
+    } else {
+        return;
+    }
+}
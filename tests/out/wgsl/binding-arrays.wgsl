@@ -0,0 +1,48 @@
+struct UniformIndex {
+    index: u32;
+};
+
+struct FragmentIn {
+    @location(0) index: u32;
+};
+
+@group(0) @binding(0) 
+var texture_array: binding_array<texture_2d<f32>,5>;
+@group(0) @binding(1) 
+var sampler_array: binding_array<sampler,5>;
+@group(0) @binding(2) 
+var storage_array: binding_array<texture_storage_2d<rgba32float,write>,5>;
+@group(0) @binding(3) 
+var<uniform> uni: UniformIndex;
+
+@stage(fragment) 
+fn main(fragment_in: FragmentIn) -> @location(0) vec4<f32> {
+    var v1_: vec4<f32>;
+
+    let uniform_index = uni.index;
+    let non_uniform_index = nonuniform(fragment_in.index);
+    v1_ = vec4<f32>(0.0);
+    let uv = vec2<f32>(0.0);
+    let pix = vec2<i32>(0);
+    let _e16 = v1_;
+    let _e21 = textureSample(texture_array[0], sampler_array[0], uv);
+    v1_ = (_e16 + _e21);
+    let _e23 = v1_;
+    let _e26 = textureSample(texture_array[uniform_index], sampler_array[uniform_index], uv);
+    v1_ = (_e23 + _e26);
+    let _e28 = v1_;
+    let _e31 = textureSample(texture_array[non_uniform_index], sampler_array[non_uniform_index], uv);
+    v1_ = (_e28 + _e31);
+    let _e33 = v1_;
+    let _e36 = textureLoad(texture_array[uniform_index], pix, 0);
+    v1_ = (_e33 + _e36);
+    let _e38 = v1_;
+    let _e41 = textureLoad(texture_array[non_uniform_index], pix, 0);
+    v1_ = (_e38 + _e41);
+    let _e44 = v1_;
+    textureStore(storage_array[uniform_index], pix, _e44);
+    let _e46 = v1_;
+    textureStore(storage_array[non_uniform_index], pix, _e46);
+    let _e47 = v1_;
+    return _e47;
+}
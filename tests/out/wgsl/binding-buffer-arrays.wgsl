@@ -0,0 +1,52 @@
+struct UniformIndex {
+    index: u32;
+};
+
+struct Foo {
+    x: u32;
+};
+
+struct FragmentIn {
+    @location(0) index: u32;
+};
+
+@group(0) @binding(0) 
+var<storage> storage_array: binding_array<Foo,1>;
+@group(0) @binding(1) 
+var<uniform> uniform_array: binding_array<Foo,2>;
+@group(0) @binding(2) 
+var<storage, read_write> unbounded_array: binding_array<Foo>;
+@group(0) @binding(10) 
+var<uniform> uni: UniformIndex;
+
+@stage(fragment) 
+fn main(fragment_in: FragmentIn) -> @location(0) u32 {
+    var u1_: u32 = 0u;
+
+    let uniform_index = uni.index;
+    let non_uniform_index = nonuniform(fragment_in.index);
+    let _e11 = u1_;
+    let _e15 = storage_array[0].x;
+    u1_ = (_e11 + _e15);
+    let _e17 = u1_;
+    let _e20 = storage_array[uniform_index].x;
+    u1_ = (_e17 + _e20);
+    let _e22 = u1_;
+    let _e25 = storage_array[non_uniform_index].x;
+    u1_ = (_e22 + _e25);
+    let _e27 = u1_;
+    let _e31 = uniform_array[0].x;
+    u1_ = (_e27 + _e31);
+    let _e33 = u1_;
+    let _e36 = uniform_array[uniform_index].x;
+    u1_ = (_e33 + _e36);
+    let _e38 = u1_;
+    let _e41 = uniform_array[non_uniform_index].x;
+    u1_ = (_e38 + _e41);
+    let _e45 = u1_;
+    unbounded_array[uniform_index].x = _e45;
+    let _e48 = u1_;
+    unbounded_array[non_uniform_index].x = _e48;
+    let _e49 = u1_;
+    return _e49;
+}
@@ -0,0 +1,39 @@
+struct Light {
+    position: vec4<f32>;
+    color: vec4<f32>;
+};
+
+@group(0) @binding(0) 
+var<uniform> light: Light;
+var<private> total: vec3<f32>;
+
+fn shade(normal_1: vec3<f32>, position_1: vec3<f32>) -> vec3<f32> {
+    let _e5 = light.position;
+    let direction = normalize((_e5.xyz - position_1));
+    let diffuse = max(0.0, dot(normal_1, direction));
+    let _e12 = total;
+    let _e14 = light.color;
+    let _e15 = _e14.xyz;
+    total = (_e12 + (diffuse * _e15));
+    let _e18 = total;
+    let ambient = (_e18 * 0.10000000149011612);
+    return ((_e18 + ambient) + (_e15 * dot(direction, normal_1)));
+}
+
+@stage(fragment) 
+fn main(@location(0) normal: vec3<f32>, @location(1) position: vec3<f32>) -> @location(0) vec4<f32> {
+    var color: vec3<f32>;
+
+    let _e3 = shade(normal, position);
+    color = _e3;
+    let _e7 = light.color.w;
+    if ((_e7 > 0.0)) {
+        let _e10 = color;
+        color = (_e10 * _e7);
+    } else {
+        let _e12 = color;
+        color = (_e12 * -(_e7));
+    }
+    let _e15 = color;
+    return vec4<f32>(_e15, _e7);
+}
@@ -0,0 +1,45 @@
+struct Light {
+    color: vec4<f32>;
+    intensity: f32;
+};
+
+struct Lights {
+    data: array<Light>;
+};
+
+let light_count: u32 = 4u;
+
+@group(0) @binding(1) 
+var<storage> lights: Lights;
+var<private> weights: array<f32,light_count>;
+
+fn shade(index: u32) -> vec4<f32> {
+    let light = lights.data[index];
+    let _e10 = weights[index];
+    return ((light.color * light.intensity) * _e10);
+}
+
+@stage(fragment) 
+fn main() -> @location(0) vec4<f32> {
+    var color: vec4<f32>;
+    var i: u32 = 0u;
+
+    color = vec4<f32>(0.0);
+    loop {
+        let _e5 = i;
+        if ((_e5 < light_count)) {
+        } else {
+            break;
+        }
+        let _e10 = color;
+        let _e11 = i;
+        let _e12 = shade(_e11);
+        color = (_e10 + _e12);
+        continuing {
+            let _e7 = i;
+            i = (_e7 + 1u);
+        }
+    }
+    let _e14 = color;
+    return _e14;
+}
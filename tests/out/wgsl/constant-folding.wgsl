@@ -0,0 +1,14 @@
+struct Output {
+    values: array<vec4<f32>>;
+};
+
+let scale: f32 = 2.0;
+
+@group(0) @binding(0) 
+var<storage, read_write> output: Output;
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output.values[id.x] = (vec4<f32>(6.0, 6.0, 4.0, -2.0) * vec4<f32>(vec4<f32>(2.0, 4.0, 4.0, -2147483648.0).w));
+    return;
+}
@@ -0,0 +1,25 @@
+struct Output {
+    values: array<u32,4>;
+};
+
+@group(0) @binding(0) 
+var<storage, read_write> output: Output;
+
+fn select_value(flag: bool, value: u32) -> u32 {
+    var result: u32 = 0u;
+
+    if (flag) {
+        result = value;
+        let _e3 = result;
+        return _e3;
+    } else {
+        return 0u;
+    }
+}
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main(@builtin(local_invocation_index) index: u32) {
+    let _e8 = select_value((index > 1u), index);
+    output.values[(index % 4u)] = _e8;
+    return;
+}
@@ -0,0 +1,12 @@
+enable dual_source_blending;
+
+struct FragmentOutput {
+    @location(0) @blend_src(0) color: vec4<f32>;
+    @location(0) @blend_src(1) mask: vec4<f32>;
+};
+
+@stage(fragment) 
+fn main(@builtin(position) position: vec4<f32>) -> FragmentOutput {
+    let coverage = fract(position.x);
+    return FragmentOutput(vec4<f32>(0.5, 0.0, 0.0, 1.0), vec4<f32>(coverage));
+}
@@ -0,0 +1,56 @@
+enable f16;
+
+struct UniformCompatible {
+    val_f32_: f32;
+    val_f16_: f16;
+    val_f16_2_: vec2<f16>;
+    val_f16_4_: vec4<f16>;
+    final_value: f16;
+};
+
+struct StorageCompatible {
+    val_f16_mat: mat2x2<f16>;
+    val_f16_array: array<f16,2>;
+};
+
+let constant_variable: f16 = 15.199999809265137h;
+
+@group(0) @binding(0) 
+var<uniform> input_uniform: UniformCompatible;
+@group(0) @binding(1) 
+var<storage, read_write> output: UniformCompatible;
+@group(0) @binding(2) 
+var<storage, read_write> output_matrices: StorageCompatible;
+
+fn f16_function(x: f16) -> f16 {
+    var val: f16 = constant_variable;
+
+    let _e6 = val;
+    val = (_e6 + -33344.0h);
+    let _e9 = val;
+    let _e10 = val;
+    val = ((_e9 + _e10) - f16(5.0));
+    let _e16 = val;
+    output.val_f32_ = f32(_e16);
+    let _e20 = input_uniform.val_f16_;
+    output.val_f16_ = (_e20 + x);
+    let _e24 = input_uniform.val_f16_2_;
+    let _e25 = val;
+    output.val_f16_2_ = (_e24 + vec2<f16>(_e25));
+    let _e30 = input_uniform.val_f16_4_;
+    let _e33 = val;
+    output.val_f16_4_ = (_e30 * vec4<f16>(x, 1.0h, 0.5h, _e33));
+    let _e41 = val;
+    output_matrices.val_f16_mat = mat2x2<f16>(vec2<f16>(1.0h, 0.0h), vec2<f16>(0.0h, _e41));
+    let _e48 = input_uniform.val_f32_;
+    output_matrices.val_f16_array[1] = f16(_e48);
+    let _e50 = val;
+    return _e50;
+}
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main() {
+    let _e6 = f16_function(2.0h);
+    output.final_value = _e6;
+    return;
+}
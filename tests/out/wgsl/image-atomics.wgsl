@@ -0,0 +1,20 @@
+@group(0) @binding(0) 
+var image: texture_storage_2d<r32uint,read_write>;
+@group(0) @binding(1) 
+var image_array: texture_storage_2d_array<r32sint,read_write>;
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main(@builtin(local_invocation_id) id: vec3<u32>) {
+    let coord = vec2<i32>(id.xy);
+    let _e6 = textureAtomicAdd(image, coord, 1u);
+    let _e8 = textureAtomicSub(image, coord, 2u);
+    let _e9 = textureAtomicMin(image, coord, _e6);
+    let _e10 = textureAtomicMax(image, coord, _e8);
+    let _e12 = textureAtomicExchange(image, coord, (_e9 + _e10));
+    let _e15 = textureAtomicAnd(image_array, coord, 0, 3);
+    let _e17 = textureAtomicOr(image_array, coord, 1, _e15);
+    let _e19 = textureAtomicXor(image_array, coord, 2, _e17);
+    let loaded = textureLoad(image_array, coord, 0);
+    textureStore(image, coord, vec4<u32>((_e12 + u32((_e19 + loaded.x)))));
+    return;
+}
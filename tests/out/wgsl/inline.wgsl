@@ -0,0 +1,55 @@
+struct Output {
+    values: array<u32,4>;
+};
+
+@group(0) @binding(0) 
+var<storage, read_write> output: Output;
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main(@builtin(local_invocation_index) index: u32) {
+    var sum: u32 = 0u;
+    var j: u32 = 0u;
+    var total: u32 = 0u;
+    var i: u32 = 0u;
+    var local: u32;
+
+    loop {
+        let _e4 = j;
+        if ((_e4 < 2u)) {
+        } else {
+            break;
+        }
+        total = 0u;
+        i = 0u;
+        loop {
+            let _e12 = i;
+            if ((_e12 >= index)) {
+                break;
+            }
+            let _e14 = i;
+            let _e19 = total;
+            total = (_e19 + _e14);
+            let _e15 = i;
+            i = (_e15 + 1u);
+        }
+        let _e18 = total;
+        let _e33 = sum;
+        sum = (_e33 + _e18);
+        continuing {
+            let _e7 = j;
+            j = (_e7 + 1u);
+        }
+    }
+    loop {
+        if ((index > 3u)) {
+            local = 3u;
+            break;
+        }
+        local = index;
+        break;
+    }
+    let _e30 = local;
+    let _e32 = sum;
+    output.values[_e30] = _e32;
+    return;
+}
@@ -0,0 +1,49 @@
+struct Values {
+    val_i64_: i64;
+    val_u64_: u64;
+    val_i64_2_: vec2<i64>;
+    val_u64_4_: vec4<u64>;
+};
+
+struct Atomics {
+    counter: atomic<u64>;
+    signed_counter: atomic<i64>;
+};
+
+let big_constant: i64 = -9000000000li;
+
+@group(0) @binding(0) 
+var<uniform> input: Values;
+@group(0) @binding(1) 
+var<storage, read_write> output: Values;
+@group(0) @binding(2) 
+var<storage, read_write> atomics: Atomics;
+
+fn i64_function(x: i64) -> i64 {
+    var val: i64 = big_constant;
+
+    let _e6 = val;
+    val = (_e6 + (20li * x));
+    let _e10 = val;
+    let _e12 = input.val_u64_;
+    val = (_e10 - i64(_e12));
+    let _e16 = val;
+    output.val_u64_ = (u64(_e16) + 4294967296lu);
+    let _e22 = input.val_i64_2_;
+    let _e23 = val;
+    output.val_i64_2_ = (_e22 + vec2<i64>(_e23));
+    let _e28 = input.val_u64_4_;
+    let _e33 = input.val_u64_;
+    output.val_u64_4_ = (_e28 * vec4<u64>(u64(x), 1lu, 2lu, _e33));
+    let _e36 = val;
+    return _e36;
+}
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main() {
+    let _e6 = i64_function(3li);
+    output.val_i64_ = _e6;
+    let _e9 = atomicMax((&atomics.counter), 1lu);
+    let _e12 = atomicMax((&atomics.signed_counter), i64(_e9));
+    return;
+}
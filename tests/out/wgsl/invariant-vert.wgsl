@@ -0,0 +1,17 @@
+struct VertexOutput {
+    @builtin(position) @invariant member: vec4<f32>;
+};
+
+var<private> gl_Position: vec4<f32>;
+
+fn main_1() {
+    gl_Position = vec4<f32>(1.0);
+    return;
+}
+
+@stage(vertex) 
+fn main() -> VertexOutput {
+    main_1();
+    let _e2 = gl_Position;
+    return VertexOutput(_e2);
+}
@@ -0,0 +1,9 @@
+@stage(vertex) 
+fn vs() -> @builtin(position) @invariant vec4<f32> {
+    return vec4<f32>(0.0);
+}
+
+@stage(fragment) 
+fn fs(@builtin(position) @invariant position: vec4<f32>) -> @location(0) vec4<f32> {
+    return position;
+}
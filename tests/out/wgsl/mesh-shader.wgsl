@@ -0,0 +1,26 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>;
+    @location(0) color: vec4<f32>;
+};
+
+struct PrimitiveOutput {
+    @builtin(triangle_indices) indices: vec3<u32>;
+    @builtin(cull_primitive) cull: bool;
+    @location(1) id: u32;
+};
+
+@stage(task) @workgroup_size(1, 1, 1) 
+fn ts_main() -> @builtin(mesh_task_size) vec3<u32> {
+    return vec3<u32>(1u, 1u, 1u);
+}
+
+@stage(mesh) @workgroup_size(1, 1, 1) @output_topology(triangles) @vertex_output(VertexOutput, 3) @primitive_output(PrimitiveOutput, 1) 
+fn ms_main(@builtin(workgroup_id) wg_id: vec3<u32>) {
+    setMeshOutputs(3u, 1u);
+    let color = vec4<f32>(1.0, 0.0, 0.0, 1.0);
+    setVertex(0u, VertexOutput(vec4<f32>(0.0, 1.0, 0.0, 1.0), color));
+    setVertex(1u, VertexOutput(vec4<f32>(-1.0, -1.0, 0.0, 1.0), color));
+    setVertex(2u, VertexOutput(vec4<f32>(1.0, -1.0, 0.0, 1.0), color));
+    setPrimitive(0u, PrimitiveOutput(vec3<u32>(0u, 1u, 2u), false, wg_id.x));
+    return;
+}
@@ -0,0 +1,29 @@
+@id(0) override has_point_light: bool = true;
+
+@id(1200) override specular_param: f32 = 2.299999952316284;
+
+@id(1300) override gain: f32 = 1.100000023841858;
+
+@id(1) override width: f32 = 0.0;
+
+@id(2) override depth: f32 = 2.0;
+
+@id(3) override height: f32 = 4.599999904632568;
+
+@id(4) override block_size: u32 = 64u;
+
+@stage(compute) @workgroup_size(block_size, 1, 1) 
+fn main() {
+    var gain_x_10_: f32;
+    var area: f32;
+
+    gain_x_10_ = (gain * 10.0);
+    area = (width * height);
+    if (has_point_light) {
+        let _e12 = area;
+        area = ((_e12 * depth) * specular_param);
+        return;
+    } else {
+        return;
+    }
+}
@@ -0,0 +1,35 @@
+struct Output {
+    visible: u32;
+    normal: vec3<f32>;
+};
+
+@group(0) @binding(0) 
+var acc_struct: acceleration_structure;
+@group(0) @binding(1) 
+var<storage, read_write> output: Output;
+
+fn get_torus_normal(world_point: vec3<f32>, intersection: RayIntersection) -> vec3<f32> {
+    let local_point = (intersection.world_to_object * vec4<f32>(world_point, 1.0));
+    let point_on_guiding_line = (normalize(local_point.xy) * 2.4000000953674316);
+    let world_point_on_guiding_line = (intersection.object_to_world * vec4<f32>(point_on_guiding_line, 0.0, 1.0));
+    return normalize((world_point - world_point_on_guiding_line));
+}
+
+@stage(compute) @workgroup_size(1, 1, 1) 
+fn main() {
+    var rq: ray_query;
+
+    let dir = vec3<f32>(0.0, 1.0, 0.0);
+    rayQueryInitialize((&rq), acc_struct, RayDesc(4u, 255u, 0.10000000149011612, 100.0, vec3<f32>(0.0), dir));
+    loop {
+        let _e14 = rayQueryProceed((&rq));
+        if (!(_e14)) {
+            break;
+        }
+    }
+    let intersection_1 = rayQueryGetCommittedIntersection((&rq));
+    output.visible = u32((intersection_1.kind == 0u));
+    let _e25 = get_torus_normal((dir * intersection_1.t), intersection_1);
+    output.normal = _e25;
+    return;
+}
@@ -0,0 +1,23 @@
+struct Camera {
+    view_proj: mat4x4<f32>;
+};
+
+@group(0) @binding(0) 
+var<uniform> camera: Camera;
+@group(0) @binding(10) 
+var albedo: texture_2d<f32>;
+@group(0) @binding(13) 
+var albedo_sampler: sampler;
+@group(0) @binding(25) 
+var shadow: texture_depth_2d;
+@group(0) @binding(28) 
+var shadow_sampler: sampler_comparison;
+
+@stage(fragment) 
+fn main(@location(0) position: vec3<f32>, @location(1) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let _e8 = camera.view_proj;
+    let light = (_e8 * vec4<f32>(position, 1.0));
+    let visibility = textureSampleCompare(shadow, shadow_sampler, light.xy, light.z);
+    let _e15 = textureSample(albedo, albedo_sampler, uv);
+    return (_e15 * visibility);
+}
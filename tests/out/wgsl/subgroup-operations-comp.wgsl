@@ -0,0 +1,109 @@
+var<private> gl_SubgroupInvocationID: u32;
+var<private> gl_LocalInvocationIndex: u32;
+var<private> gl_SubgroupSize: u32;
+
+fn main_1() {
+    var ballot: vec4<u32>;
+    var all_set: bool;
+    var any_set: bool;
+    var sum: u32;
+    var product: u32;
+    var minimum: u32;
+    var maximum: u32;
+    var and_all: u32;
+    var or_all: u32;
+    var xor_all: u32;
+    var exclusive_sum: u32;
+    var exclusive_product: u32;
+    var inclusive_sum: u32;
+    var inclusive_product: u32;
+    var first: u32;
+    var broadcast: u32;
+    var shuffled: u32;
+    var shuffled_down: u32;
+    var shuffled_up: u32;
+    var shuffled_xor: u32;
+    var elected: bool;
+
+    let _e1 = gl_SubgroupInvocationID;
+    let _e6 = gl_SubgroupInvocationID;
+    let _e11 = subgroupBallot(((_e6 & 1u) == 1u));
+    ballot = _e11;
+    let _e13 = gl_SubgroupInvocationID;
+    let _e16 = gl_SubgroupInvocationID;
+    let _e19 = subgroupAll((_e16 != 0u));
+    all_set = _e19;
+    let _e21 = gl_SubgroupInvocationID;
+    let _e24 = gl_SubgroupInvocationID;
+    let _e27 = subgroupAny((_e24 == 0u));
+    any_set = _e27;
+    let _e30 = gl_SubgroupInvocationID;
+    let _e31 = subgroupAdd(_e30);
+    sum = _e31;
+    let _e34 = gl_SubgroupInvocationID;
+    let _e35 = subgroupMul(_e34);
+    product = _e35;
+    let _e38 = gl_SubgroupInvocationID;
+    let _e39 = subgroupMin(_e38);
+    minimum = _e39;
+    let _e42 = gl_SubgroupInvocationID;
+    let _e43 = subgroupMax(_e42);
+    maximum = _e43;
+    let _e46 = gl_SubgroupInvocationID;
+    let _e47 = subgroupAnd(_e46);
+    and_all = _e47;
+    let _e50 = gl_SubgroupInvocationID;
+    let _e51 = subgroupOr(_e50);
+    or_all = _e51;
+    let _e54 = gl_SubgroupInvocationID;
+    let _e55 = subgroupXor(_e54);
+    xor_all = _e55;
+    let _e58 = gl_SubgroupInvocationID;
+    let _e59 = subgroupExclusiveAdd(_e58);
+    exclusive_sum = _e59;
+    let _e62 = gl_SubgroupInvocationID;
+    let _e63 = subgroupExclusiveMul(_e62);
+    exclusive_product = _e63;
+    let _e66 = gl_SubgroupInvocationID;
+    let _e67 = subgroupInclusiveAdd(_e66);
+    inclusive_sum = _e67;
+    let _e70 = gl_SubgroupInvocationID;
+    let _e71 = subgroupInclusiveMul(_e70);
+    inclusive_product = _e71;
+    let _e75 = gl_LocalInvocationIndex;
+    let _e76 = subgroupBroadcastFirst(_e75);
+    first = _e76;
+    let _e80 = gl_LocalInvocationIndex;
+    let _e82 = subgroupBroadcast(_e80, 4u);
+    broadcast = _e82;
+    let _e86 = gl_SubgroupSize;
+    let _e89 = gl_SubgroupInvocationID;
+    let _e91 = gl_LocalInvocationIndex;
+    let _e92 = gl_SubgroupSize;
+    let _e95 = gl_SubgroupInvocationID;
+    let _e97 = subgroupShuffle(_e91, ((_e92 - 1u) - _e95));
+    shuffled = _e97;
+    let _e101 = gl_LocalInvocationIndex;
+    let _e103 = subgroupShuffleDown(_e101, 1u);
+    shuffled_down = _e103;
+    let _e107 = gl_LocalInvocationIndex;
+    let _e109 = subgroupShuffleUp(_e107, 1u);
+    shuffled_up = _e109;
+    let _e112 = gl_SubgroupSize;
+    let _e115 = gl_LocalInvocationIndex;
+    let _e116 = gl_SubgroupSize;
+    let _e119 = subgroupShuffleXor(_e115, (_e116 - 1u));
+    shuffled_xor = _e119;
+    let _e121 = subgroupElect();
+    elected = _e121;
+    return;
+}
+
+@stage(compute) @workgroup_size(128, 1, 1) 
+fn main(@builtin(subgroup_invocation_id) param: u32, @builtin(local_invocation_index) param_1: u32, @builtin(subgroup_size) param_2: u32) {
+    gl_SubgroupInvocationID = param;
+    gl_LocalInvocationIndex = param_1;
+    gl_SubgroupSize = param_2;
+    main_1();
+    return;
+}
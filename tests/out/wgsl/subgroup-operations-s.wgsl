@@ -0,0 +1,41 @@
+var<private> global: u32;
+var<private> global_1: u32;
+var<private> global_2: u32;
+
+fn function_() {
+    let _e13 = global;
+    let _e14 = global_1;
+    let _e15 = global_2;
+    subgroupBarrier();
+    let _e18 = subgroupBallot(((_e15 & 1u) == 1u));
+    let _e19 = subgroupBallot(true);
+    let _e21 = subgroupAll((_e15 != 0u));
+    let _e23 = subgroupAny((_e15 == 0u));
+    let _e24 = subgroupAdd(_e15);
+    let _e25 = subgroupMul(_e15);
+    let _e26 = subgroupMin(_e15);
+    let _e27 = subgroupMax(_e15);
+    let _e28 = subgroupAnd(_e15);
+    let _e29 = subgroupOr(_e15);
+    let _e30 = subgroupXor(_e15);
+    let _e31 = subgroupExclusiveAdd(_e15);
+    let _e32 = subgroupExclusiveMul(_e15);
+    let _e33 = subgroupInclusiveAdd(_e15);
+    let _e34 = subgroupInclusiveMul(_e15);
+    let _e35 = subgroupBroadcastFirst(_e13);
+    let _e36 = subgroupBroadcast(_e13, 4u);
+    let _e39 = subgroupShuffle(_e13, ((_e14 - 1u) - _e15));
+    let _e40 = subgroupShuffleDown(_e13, 1u);
+    let _e41 = subgroupShuffleUp(_e13, 1u);
+    let _e43 = subgroupShuffleXor(_e13, (_e14 - 1u));
+    let _e44 = subgroupElect();
+    return;
+}
+
+@stage(compute) @workgroup_size(128, 1, 1) 
+fn main(@builtin(local_invocation_index) param: u32, @builtin(subgroup_size) param_1: u32, @builtin(subgroup_invocation_id) param_2: u32) {
+    global = param;
+    global_1 = param_1;
+    global_2 = param_2;
+    function_();
+}
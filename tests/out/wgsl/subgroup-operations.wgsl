@@ -0,0 +1,27 @@
+@stage(compute) @workgroup_size(128, 1, 1) 
+fn main(@builtin(local_invocation_index) local_index: u32, @builtin(subgroup_size) subgroup_size: u32, @builtin(subgroup_invocation_id) subgroup_invocation_id: u32) {
+    subgroupBarrier();
+    let _e7 = subgroupBallot(((subgroup_invocation_id & 1u) == 1u));
+    let _e8 = subgroupBallot();
+    let _e11 = subgroupAll((subgroup_invocation_id != 0u));
+    let _e14 = subgroupAny((subgroup_invocation_id == 0u));
+    let _e15 = subgroupAdd(subgroup_invocation_id);
+    let _e16 = subgroupMul(subgroup_invocation_id);
+    let _e17 = subgroupMin(subgroup_invocation_id);
+    let _e18 = subgroupMax(subgroup_invocation_id);
+    let _e19 = subgroupAnd(subgroup_invocation_id);
+    let _e20 = subgroupOr(subgroup_invocation_id);
+    let _e21 = subgroupXor(subgroup_invocation_id);
+    let _e22 = subgroupExclusiveAdd(subgroup_invocation_id);
+    let _e23 = subgroupExclusiveMul(subgroup_invocation_id);
+    let _e24 = subgroupInclusiveAdd(subgroup_invocation_id);
+    let _e25 = subgroupInclusiveMul(subgroup_invocation_id);
+    let _e26 = subgroupBroadcastFirst(local_index);
+    let _e28 = subgroupBroadcast(local_index, 4u);
+    let _e32 = subgroupShuffle(local_index, ((subgroup_size - 1u) - subgroup_invocation_id));
+    let _e34 = subgroupShuffleDown(local_index, 1u);
+    let _e36 = subgroupShuffleUp(local_index, 1u);
+    let _e39 = subgroupShuffleXor(local_index, (subgroup_size - 1u));
+    let _e40 = subgroupElect();
+    return;
+}
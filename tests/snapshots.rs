@@ -17,6 +17,7 @@ bitflags::bitflags! {
         const DOT = 0x20;
         const HLSL = 0x40;
         const WGSL = 0x80;
+        const REFLECTION = 0x100;
     }
 }
 
@@ -76,10 +77,26 @@ struct Parameters {
     hlsl: naga::back::hlsl::Options,
     #[serde(default)]
     wgsl: WgslOutParameters,
+    #[cfg(feature = "deserialize")]
+    #[serde(default)]
+    pipeline_constants: naga::proc::PipelineConstants,
+    #[serde(default)]
+    compact: bool,
+    #[serde(default)]
+    fold_constants: bool,
+    #[serde(default)]
+    eliminate_dead_code: bool,
+    #[serde(default)]
+    inline: bool,
+    #[serde(default)]
+    eliminate_common_subexpressions: bool,
+    #[cfg(feature = "deserialize")]
+    #[serde(default)]
+    remap_bindings: Option<naga::proc::BindingRemapping>,
 }
 
 #[allow(unused_variables)]
-fn check_targets(module: &naga::Module, name: &str, targets: Targets) {
+fn check_targets(module: &mut naga::Module, name: &str, targets: Targets) {
     let root = env!("CARGO_MANIFEST_DIR");
     let params = match fs::read_to_string(format!("{}/{}/{}.param.ron", root, BASE_DIR_IN, name)) {
         Ok(string) => ron::de::from_str(&string).expect("Couldn't parse param file"),
@@ -92,6 +109,31 @@ fn check_targets(module: &naga::Module, name: &str, targets: Targets) {
         naga::valid::Capabilities::empty()
     };
 
+    #[cfg(feature = "deserialize")]
+    if !params.pipeline_constants.is_empty() {
+        naga::proc::process_overrides(module, &params.pipeline_constants).unwrap();
+    }
+    if params.fold_constants {
+        naga::proc::fold_constants(module);
+    }
+    if params.inline {
+        naga::proc::inline(module, naga::proc::InlineMode::All);
+    }
+    if params.eliminate_common_subexpressions {
+        naga::proc::eliminate_common_subexpressions(module);
+    }
+    if params.eliminate_dead_code {
+        naga::proc::eliminate_dead_code(module);
+    }
+    if params.compact {
+        naga::proc::compact(module);
+    }
+    #[cfg(feature = "deserialize")]
+    if let Some(ref remapping) = params.remap_bindings {
+        naga::proc::remap_bindings(module, remapping).unwrap();
+    }
+    let module = &*module;
+
     let dest = PathBuf::from(root).join(BASE_DIR_OUT);
 
     #[cfg(feature = "serialize")]
@@ -114,6 +156,12 @@ fn check_targets(module: &naga::Module, name: &str, targets: Targets) {
             let string = ron::ser::to_string_pretty(&info, config).unwrap();
             fs::write(dest.join(format!("analysis/{}.info.ron", name)), string).unwrap();
         }
+        if targets.contains(Targets::REFLECTION) {
+            let reflection = naga::reflect::ModuleReflection::new(module, &info).unwrap();
+            let config = ron::ser::PrettyConfig::default().new_line("\n".to_string());
+            let string = ron::ser::to_string_pretty(&reflection, config).unwrap();
+            fs::write(dest.join(format!("reflection/{}.ron", name)), string).unwrap();
+        }
     }
 
     #[cfg(all(feature = "deserialize", feature = "spv-out"))]
@@ -343,7 +391,9 @@ fn write_output_hlsl(
                 )
                 .unwrap();
             }
-            naga::ShaderStage::Compute => {
+            // Task and mesh shaders are validated like compute shaders,
+            // the profile tells them apart.
+            naga::ShaderStage::Compute | naga::ShaderStage::Task | naga::ShaderStage::Mesh => {
                 write!(
                     compute_str,
                     "{}:{}_{} ",
@@ -416,7 +466,12 @@ fn convert_wgsl() {
         ),
         (
             "boids",
-            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+            Targets::SPIRV
+                | Targets::METAL
+                | Targets::GLSL
+                | Targets::HLSL
+                | Targets::WGSL
+                | Targets::REFLECTION,
         ),
         (
             "skybox",
@@ -433,14 +488,19 @@ fn convert_wgsl() {
         ),
         (
             "shadow",
-            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+            Targets::SPIRV
+                | Targets::METAL
+                | Targets::GLSL
+                | Targets::HLSL
+                | Targets::WGSL
+                | Targets::REFLECTION,
         ),
         (
             "image",
             Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL | Targets::GLSL,
         ),
         ("extra", Targets::SPIRV | Targets::METAL | Targets::WGSL),
-        ("push-constants", Targets::GLSL),
+        ("push-constants", Targets::GLSL | Targets::REFLECTION),
         (
             "operators",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
@@ -458,6 +518,10 @@ fn convert_wgsl() {
             "access",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
+        (
+            "atomicCompareExchange",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
         ("pointers", Targets::SPIRV | Targets::WGSL),
         (
             "control-flow",
@@ -470,7 +534,7 @@ fn convert_wgsl() {
         //TODO: GLSL https://github.com/gfx-rs/naga/issues/874
         (
             "interface",
-            Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL,
+            Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL | Targets::REFLECTION,
         ),
         (
             "globals",
@@ -490,6 +554,64 @@ fn convert_wgsl() {
             "math-functions",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
+        (
+            "f16",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "int64",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        ("overrides", Targets::IR | Targets::ANALYSIS | Targets::WGSL),
+        (
+            "overrides-process",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL,
+        ),
+        (
+            "binding-arrays",
+            Targets::IR
+                | Targets::ANALYSIS
+                | Targets::SPIRV
+                | Targets::METAL
+                | Targets::HLSL
+                | Targets::WGSL
+                | Targets::REFLECTION,
+        ),
+        (
+            "binding-buffer-arrays",
+            Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "subgroup-operations",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "ray-query",
+            Targets::IR | Targets::ANALYSIS | Targets::SPIRV | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "dual-source-blending",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "invariant",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        ("compact", Targets::IR | Targets::WGSL),
+        ("constant-folding", Targets::WGSL | Targets::GLSL),
+        ("dead-code", Targets::WGSL),
+        ("inline", Targets::WGSL | Targets::GLSL),
+        ("common-subexpressions", Targets::WGSL),
+        ("remap-bindings", Targets::SPIRV | Targets::WGSL),
+        (
+            "image-atomics",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        // No SPIR-V: `rspirv` can't parse `SPV_EXT_mesh_shader` yet.
+        (
+            "mesh-shader",
+            Targets::IR | Targets::ANALYSIS | Targets::METAL | Targets::HLSL | Targets::WGSL,
+        ),
     ];
 
     for &(name, targets) in inputs.iter() {
@@ -498,7 +620,7 @@ fn convert_wgsl() {
         let file = fs::read_to_string(format!("{}/{}/{}.wgsl", root, BASE_DIR_IN, name))
             .expect("Couldn't find wgsl file");
         match naga::front::wgsl::parse_str(&file) {
-            Ok(module) => check_targets(&module, name, targets),
+            Ok(mut module) => check_targets(&mut module, name, targets),
             Err(e) => panic!("{}", e.emit_to_string(&file)),
         }
     }
@@ -509,7 +631,7 @@ fn convert_spv(name: &str, adjust_coordinate_space: bool, targets: Targets) {
     let _ = env_logger::try_init();
 
     let root = env!("CARGO_MANIFEST_DIR");
-    let module = naga::front::spv::parse_u8_slice(
+    let mut module = naga::front::spv::parse_u8_slice(
         &fs::read(format!("{}/{}/spv/{}.spv", root, BASE_DIR_IN, name))
             .expect("Couldn't find spv file"),
         &naga::front::spv::Options {
@@ -519,10 +641,10 @@ fn convert_spv(name: &str, adjust_coordinate_space: bool, targets: Targets) {
         },
     )
     .unwrap();
-    check_targets(&module, name, targets);
+    check_targets(&mut module, name, targets);
     naga::valid::Validator::new(
         naga::valid::ValidationFlags::all(),
-        naga::valid::Capabilities::empty(),
+        naga::valid::Capabilities::all(),
     )
     .validate(&module)
     .unwrap();
@@ -553,6 +675,7 @@ fn convert_spv_all() {
         Targets::HLSL | Targets::WGSL | Targets::METAL,
     );
     convert_spv("degrees", false, Targets::empty());
+    convert_spv("subgroup-operations-s", false, Targets::WGSL);
 }
 
 #[cfg(feature = "glsl-in")]
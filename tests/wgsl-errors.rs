@@ -285,6 +285,57 @@ fn unknown_shader_stage() {
     );
 }
 
+#[test]
+fn unknown_enable_extension() {
+    check(
+        r#"
+            enable f32;
+        "#,
+        r#"error: unknown enable-extension `f32`
+  ┌─ wgsl:2:20
+  │
+2 │             enable f32;
+  │                    ^^^ unknown extension
+
+"#,
+    );
+}
+
+#[test]
+fn f16_not_enabled() {
+    check(
+        r#"
+            var<private> a: vec2<f16>;
+        "#,
+        r#"error: `vec2<f16>` requires the `f16` extension to be enabled
+  ┌─ wgsl:2:29
+  │
+2 │             var<private> a: vec2<f16>;
+  │                             ^^^^^^^^^ extension not enabled
+  │
+  = note: add `enable f16;` at the top of the shader
+
+"#,
+    );
+
+    check(
+        r#"
+            fn main() {
+                let a = 1.0h;
+            }
+        "#,
+        r#"error: `1.0h` requires the `f16` extension to be enabled
+  ┌─ wgsl:3:25
+  │
+3 │                 let a = 1.0h;
+  │                         ^^^^ extension not enabled
+  │
+  = note: add `enable f16;` at the top of the shader
+
+"#,
+    );
+}
+
 #[test]
 fn unknown_ident() {
     check(
@@ -421,6 +472,22 @@ fn inconsistent_binding() {
     );
 }
 
+#[test]
+fn invariant_non_position() {
+    check(
+        r#"
+        fn foo(@builtin(vertex_index) @invariant x: u32) {}
+        "#,
+        r#"error: input/output binding is not consistent
+  ┌─ wgsl:2:16
+  │
+2 │         fn foo(@builtin(vertex_index) @invariant x: u32) {}
+  │                ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ input/output binding is not consistent
+
+"#,
+    );
+}
+
 #[test]
 fn unknown_local_function() {
     check(
@@ -789,6 +856,20 @@ macro_rules! check_validation_error {
 }
 
 fn validation_error(source: &str) -> Result<naga::valid::ModuleInfo, naga::valid::ValidationError> {
+    validation_error_with(
+        source,
+        naga::valid::Capabilities::empty(),
+        naga::valid::Limits::default(),
+    )
+}
+
+/// Like [`validation_error`], with a validator that has `capabilities` and
+/// checks `limits`.
+fn validation_error_with(
+    source: &str,
+    capabilities: naga::valid::Capabilities,
+    limits: naga::valid::Limits,
+) -> Result<naga::valid::ModuleInfo, naga::valid::ValidationError> {
     let module = match naga::front::wgsl::parse_str(source) {
         Ok(module) => module,
         Err(err) => {
@@ -796,12 +877,10 @@ fn validation_error(source: &str) -> Result<naga::valid::ModuleInfo, naga::valid
             panic!("{}", err.emit_to_string(source));
         }
     };
-    naga::valid::Validator::new(
-        naga::valid::ValidationFlags::all(),
-        naga::valid::Capabilities::empty(),
-    )
-    .validate(&module)
-    .map_err(|e| e.into_inner()) // TODO: Add tests for spans, too?
+    naga::valid::Validator::new(naga::valid::ValidationFlags::all(), capabilities)
+        .limits(limits)
+        .validate(&module)
+        .map_err(|e| e.into_inner()) // TODO: Add tests for spans, too?
 }
 
 #[test]
@@ -838,6 +917,100 @@ fn invalid_arrays() {
     }
 }
 
+#[test]
+fn invalid_f16_width() {
+    check_validation_error! {
+        "
+            enable f16;
+            var<private> a: f16;
+        ":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidWidth(naga::ScalarKind::Float, 2),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_int64_width() {
+    check_validation_error! {
+        "var<private> a: i64;":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidWidth(naga::ScalarKind::Sint, 8),
+            ..
+        })
+    }
+
+    check_validation_error! {
+        "var<private> a: vec2<u64>;":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidWidth(naga::ScalarKind::Uint, 8),
+            ..
+        })
+    }
+}
+
+#[test]
+fn override_id_redefinition() {
+    check(
+        r###"@id(1) override a: f32 = 1.0;
+@id(1) override b: f32 = 2.0;"###,
+        r###"error: override id `1` is used more than once
+  ┌─ wgsl:1:5
+  │
+1 │ @id(1) override a: f32 = 1.0;
+  │     ^ previous use of the override id
+2 │ @id(1) override b: f32 = 2.0;
+  │     ^ redefinition of the override id
+
+"###,
+    );
+}
+
+#[test]
+fn missing_override_initializer() {
+    check(
+        "override size: u32;",
+        r###"error: override `size` needs an initializer
+  ┌─ wgsl:1:10
+  │
+1 │ override size: u32;
+  │          ^^^^ definition of `size`
+  │
+  = note: overrides without a default value are not supported yet
+
+"###,
+    );
+}
+
+#[test]
+fn non_scalar_override() {
+    check(
+        "override dir: vec2<f32> = vec2<f32>(1.0, 0.0);",
+        r###"error: override `dir` must have a scalar type
+  ┌─ wgsl:1:10
+  │
+1 │ override dir: vec2<f32> = vec2<f32>(1.0, 0.0);
+  │          ^^^ definition of `dir`
+
+"###,
+    );
+}
+
+#[test]
+fn missing_subgroup_capability() {
+    check_validation_error! {
+        "fn sum(x: u32) -> u32 { return subgroupAdd(x); }",
+        "fn elect() -> bool { return subgroupElect(); }":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::MissingCapability(
+                naga::valid::Capabilities::SUBGROUP
+            ),
+            ..
+        })
+    }
+}
+
 #[test]
 fn invalid_structs() {
     check_validation_error! {
@@ -1287,3 +1460,476 @@ fn wrong_access_mode() {
             if name == "store"
     }
 }
+
+#[test]
+fn non_uniform_binding_array_index() {
+    // Indexing a binding array with a function argument, which the uniformity
+    // analysis treats as non-uniform, or with an index marked as non-uniform,
+    // requires a capability.
+    check_validation_error! {
+        "
+            @group(0) @binding(0)
+            var textures: binding_array<texture_2d<f32>, 4>;
+
+            fn fetch(i: u32) -> vec4<f32> {
+                return textureLoad(textures[i], vec2<i32>(0), 0);
+            }
+        ",
+        "
+            @group(0) @binding(0)
+            var textures: binding_array<texture_2d<f32>, 4>;
+
+            fn fetch() -> vec4<f32> {
+                return textureLoad(textures[nonuniform(1u)], vec2<i32>(0), 0);
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                error: naga::valid::FunctionError::Expression {
+                    error: naga::valid::ExpressionError::MissingCapabilities(_, capabilities),
+                    ..
+                },
+                ..
+            },
+        )
+            if *capabilities == naga::valid::Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+    }
+}
+
+#[test]
+fn invalid_non_uniform_marker() {
+    check_validation_error! {
+        "
+            fn main() {
+                let x = nonuniform(1.0);
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                error: naga::valid::FunctionError::Expression {
+                    error: naga::valid::ExpressionError::InvalidNonUniformType(_),
+                    ..
+                },
+                ..
+            },
+        )
+    }
+}
+
+#[test]
+fn ray_query_capability() {
+    check_validation_error! {
+        "
+            @group(0) @binding(0)
+            var acc_struct: acceleration_structure;
+        ",
+        "
+            fn main() {
+                var rq: ray_query;
+            }
+        ":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::MissingCapability(naga::valid::Capabilities::RAY_QUERY),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_ray_query_pointer() {
+    check(
+        "
+            fn main() {
+                var x: u32;
+                let proceed = rayQueryProceed(&x);
+            }
+        ",
+        r###"error: ray query operation is done on a pointer to a non-ray-query
+  ┌─ wgsl:4:47
+  │
+4 │                 let proceed = rayQueryProceed(&x);
+  │                                               ^^ ray query pointer is invalid
+
+"###,
+    );
+}
+
+#[test]
+fn mesh_shader_capability() {
+    check_validation_error! {
+        "
+            @stage(task) @workgroup_size(1)
+            fn main() -> @builtin(mesh_task_size) vec3<u32> {
+                return vec3<u32>(1u);
+            }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Task,
+            error: naga::valid::EntryPointError::MissingCapability(
+                naga::valid::Capabilities::MESH_SHADER
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn missing_mesh_output_topology() {
+    check(
+        "
+            struct Vertex {
+                @builtin(position) position: vec4<f32>;
+            };
+            struct Primitive {
+                @builtin(point_index) index: u32;
+            };
+            @stage(mesh) @workgroup_size(1) @vertex_output(Vertex, 1) @primitive_output(Primitive, 1)
+            fn main() {}
+        ",
+        r###"error: mesh entry point needs a 'output_topology' attribute
+  ┌─ wgsl:8:20
+  │
+8 │             @stage(mesh) @workgroup_size(1) @vertex_output(Vertex, 1) @primitive_output(Primitive, 1)
+  │                    ^^^^ mesh entry point
+
+"###,
+    );
+}
+
+#[test]
+fn blend_src_not_enabled() {
+    check(
+        "
+            struct FragmentOutput {
+                @location(0) @blend_src(0) color: vec4<f32>;
+            };
+        ",
+        r###"error: `blend_src` requires the `dual_source_blending` extension to be enabled
+  ┌─ wgsl:3:31
+  │
+3 │                 @location(0) @blend_src(0) color: vec4<f32>;
+  │                               ^^^^^^^^^ extension not enabled
+  │
+  = note: add `enable dual_source_blending;` at the top of the shader
+
+"###,
+    );
+}
+
+#[test]
+fn dual_source_blending() {
+    check_validation_error! {
+        "
+            enable dual_source_blending;
+            struct FragmentOutput {
+                @location(0) @blend_src(0) color: vec4<f32>;
+                @location(0) @blend_src(1) mask: vec4<f32>;
+            };
+            @stage(fragment)
+            fn main() -> FragmentOutput {
+                return FragmentOutput(vec4<f32>(0.0), vec4<f32>(1.0));
+            }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Fragment,
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::UnsupportedCapability(
+                    naga::valid::Capabilities::DUAL_SOURCE_BLENDING
+                ),
+            ),
+            ..
+        })
+    }
+
+    let validate = |source: &str| {
+        validation_error_with(
+            source,
+            naga::valid::Capabilities::DUAL_SOURCE_BLENDING,
+            naga::valid::Limits::default(),
+        )
+    };
+
+    // a single blend source
+    let error = validate(
+        "
+            enable dual_source_blending;
+            @stage(fragment)
+            fn main() -> @location(0) @blend_src(1) vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        ",
+    );
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::InvalidDualSourceBlending
+            ),
+            ..
+        })
+    ));
+
+    // a blend source at another location
+    let error = validate(
+        "
+            enable dual_source_blending;
+            struct FragmentOutput {
+                @location(1) @blend_src(0) color: vec4<f32>;
+                @location(1) @blend_src(1) mask: vec4<f32>;
+            };
+            @stage(fragment)
+            fn main() -> FragmentOutput {
+                return FragmentOutput(vec4<f32>(0.0), vec4<f32>(1.0));
+            }
+        ",
+    );
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::InvalidBlendSrc {
+                    location: 1,
+                    blend_src: 0,
+                }
+            ),
+            ..
+        })
+    ));
+
+    // a blend source on a vertex output
+    let error = validate(
+        "
+            enable dual_source_blending;
+            struct VertexOutput {
+                @builtin(position) position: vec4<f32>;
+                @location(0) @blend_src(0) color: vec4<f32>;
+            };
+            @stage(vertex)
+            fn main() -> VertexOutput {
+                return VertexOutput(vec4<f32>(0.0), vec4<f32>(1.0));
+            }
+        ",
+    );
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::InvalidBlendSrcStage
+            ),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn image_atomics() {
+    check_validation_error! {
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r32uint, read_write>;
+            fn main() {
+                let x = textureAtomicAdd(image, vec2<i32>(0), 1u);
+            }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::MissingCapability(
+                naga::valid::Capabilities::TEXTURE_ATOMIC
+            ),
+            ..
+        })
+    }
+
+    let validate = |source: &str| {
+        validation_error_with(
+            source,
+            naga::valid::Capabilities::TEXTURE_ATOMIC,
+            naga::valid::Limits::default(),
+        )
+    };
+
+    // not a 32-bit integer format
+    let error = validate(
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<rgba32uint, read_write>;
+            fn main() {
+                let x = textureAtomicAdd(image, vec2<i32>(0), 1u);
+            }
+        ",
+    );
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::InvalidAtomic(_),
+            ..
+        })
+    ));
+
+    // a value of the wrong kind
+    let error = validate(
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r32sint, read_write>;
+            fn main() {
+                let x = textureAtomicMax(image, vec2<i32>(0), 1u);
+            }
+        ",
+    );
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::InvalidAtomic(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn limits() {
+    let validate = |limits: naga::valid::Limits, source: &str| {
+        validation_error_with(source, naga::valid::Capabilities::PUSH_CONSTANT, limits)
+    };
+    let defaults = naga::valid::Limits::default();
+
+    let resources = "
+        struct Constants {
+            scale: vec4<f32>;
+            offset: vec4<f32>;
+        };
+        var<push_constant> constants: Constants;
+        var<workgroup> shared_a: array<f32, 64>;
+        var<workgroup> shared_b: array<vec4<f32>, 16>;
+        @group(1) @binding(4) var textures: binding_array<texture_2d<f32>, 3>;
+        @group(0) @binding(0) var depth: texture_depth_2d;
+        @group(0) @binding(1) var unused: texture_2d<f32>;
+
+        @stage(compute) @workgroup_size(8, 8, 2)
+        fn main() {
+            shared_a[0] = constants.scale.x;
+            shared_b[0] = vec4<f32>(f32(textureNumLevels(textures[0])), f32(textureNumLevels(depth)), 0.0, 0.0);
+        }
+    ";
+    let varyings = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>;
+            @location(0) color: vec4<f32>;
+            @location(5) uv: vec2<f32>;
+        };
+        @stage(vertex)
+        fn main() -> VertexOutput {
+            return VertexOutput(vec4<f32>(0.0), vec4<f32>(1.0), vec2<f32>(0.0));
+        }
+    ";
+    let overridden = "
+        override block_size: u32 = 64u;
+
+        @stage(compute) @workgroup_size(block_size, 4)
+        fn main() {}
+    ";
+    let unbounded = "
+        @group(0) @binding(0) var textures: binding_array<texture_2d<f32>>;
+
+        @stage(compute) @workgroup_size(1)
+        fn main() {
+            let levels = textureNumLevels(textures[0]);
+        }
+    ";
+
+    assert!(validate(defaults, resources).is_ok());
+    assert!(validate(defaults, varyings).is_ok());
+    assert!(validate(defaults, unbounded).is_ok());
+    // Overridden dimensions are only checked once the overrides are processed.
+    assert!(validate(
+        naga::valid::Limits {
+            max_workgroup_size: [1, 4, 1],
+            max_workgroup_invocations: 4,
+            ..defaults
+        },
+        overridden,
+    )
+    .is_ok());
+    assert!(validate(
+        naga::valid::Limits {
+            max_bind_groups: 2,
+            max_bindings_per_bind_group: 5,
+            max_inter_stage_locations: 6,
+            max_workgroup_size: [8, 8, 2],
+            max_workgroup_invocations: 128,
+            max_workgroup_storage_size: 512,
+            max_push_constant_size: 32,
+            max_sampled_textures_per_stage: 4,
+        },
+        resources,
+    )
+    .is_ok());
+
+    macro_rules! check_limit {
+        ( $limits:expr, $source:ident : $pattern:pat ) => {
+            match validate($limits, $source) {
+                Err(naga::valid::ValidationError::EntryPoint {
+                    error: naga::valid::EntryPointError::Limit($pattern),
+                    ..
+                }) => {}
+                other => panic!("unexpected result: {:?}", other),
+            }
+        };
+    }
+
+    check_limit! {
+        naga::valid::Limits { max_bind_groups: 1, ..defaults }, resources:
+        naga::valid::LimitError::BindGroup { group: 1, limit: 1, .. }
+    }
+    check_limit! {
+        naga::valid::Limits { max_bindings_per_bind_group: 4, ..defaults }, resources:
+        naga::valid::LimitError::Binding { binding: 4, limit: 4, .. }
+    }
+    check_limit! {
+        naga::valid::Limits { max_inter_stage_locations: 5, ..defaults }, varyings:
+        naga::valid::LimitError::InterStageLocation { location: 5, limit: 5 }
+    }
+    check_limit! {
+        naga::valid::Limits { max_workgroup_size: [8, 8, 1], ..defaults }, resources:
+        naga::valid::LimitError::WorkgroupSize { size: [8, 8, 2], .. }
+    }
+    check_limit! {
+        naga::valid::Limits { max_workgroup_invocations: 64, ..defaults }, resources:
+        naga::valid::LimitError::WorkgroupInvocations { invocations: 128, limit: 64 }
+    }
+    check_limit! {
+        naga::valid::Limits { max_workgroup_storage_size: 511, ..defaults }, resources:
+        naga::valid::LimitError::WorkgroupStorageSize { size: 512, limit: 511 }
+    }
+    check_limit! {
+        naga::valid::Limits { max_push_constant_size: 16, ..defaults }, resources:
+        naga::valid::LimitError::PushConstantSize { size: 32, limit: 16 }
+    }
+    check_limit! {
+        naga::valid::Limits { max_sampled_textures_per_stage: 3, ..defaults }, resources:
+        naga::valid::LimitError::SampledTextures { count: 4, limit: 3 }
+    }
+    check_limit! {
+        naga::valid::Limits { max_workgroup_size: [1, 2, 1], ..defaults }, overridden:
+        naga::valid::LimitError::WorkgroupSize { size: [1, 4, 1], .. }
+    }
+    check_limit! {
+        naga::valid::Limits { max_sampled_textures_per_stage: 16, ..defaults }, unbounded:
+        naga::valid::LimitError::UnboundedSampledTextures { limit: 16, .. }
+    }
+
+    // Limits of the entry point itself point to its body.
+    #[cfg(feature = "span")]
+    {
+        let module = naga::front::wgsl::parse_str(resources).unwrap();
+        let error = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::PUSH_CONSTANT,
+        )
+        .limits(naga::valid::Limits {
+            max_workgroup_invocations: 64,
+            ..defaults
+        })
+        .validate(&module)
+        .unwrap_err();
+        assert!(error.spans().any(|&(span, _)| span.is_defined()));
+    }
+}